@@ -0,0 +1,34 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use keyv_bench::{connect, Driver, Workload, URL_VAR};
+use tokio::runtime::Runtime;
+
+fn workloads(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("Failed to start the Tokio runtime");
+    let url = std::env::var(URL_VAR).ok();
+    let store = url
+        .as_deref()
+        .and_then(|url| url.split_once(':'))
+        .map_or("inmemory", |(scheme, _)| scheme);
+
+    let mut group = c.benchmark_group(store);
+    group.throughput(Throughput::Elements(1));
+    for workload in Workload::ALL {
+        let driver = runtime.block_on(async {
+            let keyv = connect(url.as_deref())
+                .await
+                .unwrap_or_else(|e| panic!("Failed to connect to the store: {}", e));
+            Driver::new(keyv, workload)
+                .await
+                .unwrap_or_else(|e| panic!("Failed to seed {}: {}", workload.name(), e))
+        });
+        group.bench_function(workload.name(), |b| {
+            b.to_async(&runtime).iter(|| async {
+                driver.step().await.unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, workloads);
+criterion_main!(benches);
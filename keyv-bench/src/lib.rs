@@ -0,0 +1,191 @@
+//! Standard workloads for benchmarking keyv adapters.
+//!
+//! `benches/workloads.rs` runs every `Workload` with criterion against the store named
+//! by the `KEYV_BENCH_URL` environment variable, or against an `InMemoryStore` if it
+//! is unset, so releases and backends can be compared on the same operations:
+//!
+//! ```sh
+//! KEYV_BENCH_URL=redis://localhost:6379 cargo bench -p keyv-bench --features redis
+//! ```
+//!
+//! Each adapter is behind the feature of the same name in `keyv`.
+//!
+//! Operations are drawn from a fixed pseudo-random sequence, so every run issues the
+//! same reads and writes in the same order.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "mongo")]
+use keyv::adapter::mongodb::MongoStoreBuilder;
+#[cfg(feature = "mysql")]
+use keyv::adapter::mysql::MySqlStoreBuilder;
+#[cfg(feature = "postgres")]
+use keyv::adapter::postgres::PostgresStoreBuilder;
+#[cfg(feature = "redis")]
+use keyv::adapter::redis::RedisStoreBuilder;
+#[cfg(feature = "sqlite")]
+use keyv::adapter::sqlite::SqliteStoreBuilder;
+use keyv::{Keyv, KeyvError, StoreError};
+use serde_json::Value;
+
+/// Environment variable holding the URL of the store to benchmark.
+pub const URL_VAR: &str = "KEYV_BENCH_URL";
+
+/// Namespace the benchmark keys are written under, so they stay apart from other data
+/// in the store.
+pub const NAMESPACE: &str = "keyv-bench";
+
+/// A mix of operations run against a store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Workload {
+    /// 95% reads of small values.
+    ReadHeavy,
+    /// 95% writes of small values.
+    WriteHeavy,
+    /// As many reads as writes of small values.
+    Mixed,
+    /// As many reads as writes of 256 KiB values.
+    LargeValues,
+}
+
+impl Workload {
+    /// Every workload, in the order they are benchmarked.
+    pub const ALL: [Workload; 4] = [
+        Workload::ReadHeavy,
+        Workload::WriteHeavy,
+        Workload::Mixed,
+        Workload::LargeValues,
+    ];
+
+    /// Returns the name the workload is reported under.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Workload::ReadHeavy => "read-heavy",
+            Workload::WriteHeavy => "write-heavy",
+            Workload::Mixed => "mixed",
+            Workload::LargeValues => "large-values",
+        }
+    }
+
+    /// Returns the percentage of operations that are reads.
+    pub fn read_percent(&self) -> u64 {
+        match self {
+            Workload::ReadHeavy => 95,
+            Workload::WriteHeavy => 5,
+            Workload::Mixed | Workload::LargeValues => 50,
+        }
+    }
+
+    /// Returns the size, in bytes, of the values written.
+    pub fn value_size(&self) -> usize {
+        match self {
+            Workload::LargeValues => 256 * 1024,
+            _ => 128,
+        }
+    }
+
+    /// Returns the number of distinct keys read and written.
+    pub fn key_space(&self) -> u64 {
+        match self {
+            Workload::LargeValues => 64,
+            _ => 1_000,
+        }
+    }
+}
+
+/// Opens the store at `url`, or an in-memory store if `url` is `None`, and returns a
+/// handle on the `NAMESPACE` namespace.
+///
+/// The scheme of `url` selects the adapter: `sqlite`, `postgres`, `mysql`, `redis` or
+/// `mongodb`, as accepted by the adapter builders, provided the adapter's feature is
+/// enabled.
+pub async fn connect(url: Option<&str>) -> Result<Keyv, KeyvError> {
+    let Some(url) = url else {
+        return Ok(Keyv::default().namespace(NAMESPACE));
+    };
+    let scheme = url
+        .split_once(':')
+        .map(|(scheme, _)| scheme.to_ascii_lowercase());
+    let keyv: Result<Keyv, KeyvError> = match scheme.as_deref() {
+        #[cfg(feature = "sqlite")]
+        Some("sqlite") => {
+            Ok(Keyv::try_new(SqliteStoreBuilder::new().uri(url).build().await?).await?)
+        }
+        #[cfg(feature = "postgres")]
+        Some("postgres" | "postgresql") => {
+            Ok(Keyv::try_new(PostgresStoreBuilder::new().uri(url).build().await?).await?)
+        }
+        #[cfg(feature = "mysql")]
+        Some("mysql" | "mariadb") => {
+            Ok(Keyv::try_new(MySqlStoreBuilder::new().uri(url).build().await?).await?)
+        }
+        #[cfg(feature = "redis")]
+        Some("redis" | "rediss" | "redis+unix" | "unix") => {
+            Ok(Keyv::try_new(RedisStoreBuilder::new().uri(url).build().await?).await?)
+        }
+        #[cfg(feature = "mongo")]
+        Some("mongodb" | "mongodb+srv") => {
+            Ok(Keyv::try_new(MongoStoreBuilder::new().uri(url).build().await?).await?)
+        }
+        _ => Err(StoreError::invalid_configuration(
+            URL_VAR,
+            "The URL scheme must be one of the enabled adapters: sqlite, postgres, mysql, \
+             redis, mongodb",
+        )
+        .into()),
+    };
+    Ok(keyv?.namespace(NAMESPACE))
+}
+
+/// Runs the operations of a workload one at a time.
+pub struct Driver {
+    keyv: Keyv,
+    workload: Workload,
+    value: Value,
+    state: AtomicU64,
+}
+
+impl Driver {
+    /// Seeds every key of the workload's key space, so that reads hit, and returns a
+    /// driver at the start of the operation sequence.
+    pub async fn new(keyv: Keyv, workload: Workload) -> Result<Self, KeyvError> {
+        let value = Value::String("x".repeat(workload.value_size()));
+        let entries: Vec<(String, &Value)> = (0..workload.key_space())
+            .map(|i| (key(i), &value))
+            .collect();
+        keyv.set_many(&entries, None).await?;
+        Ok(Self {
+            keyv,
+            workload,
+            value,
+            state: AtomicU64::new(0),
+        })
+    }
+
+    /// Returns the handle the workload runs on.
+    pub fn keyv(&self) -> &Keyv {
+        &self.keyv
+    }
+
+    /// Runs the next operation of the sequence, a `get` or a `set`.
+    pub async fn step(&self) -> Result<(), KeyvError> {
+        // A 64-bit linear congruential generator, for a sequence that does not depend
+        // on the platform or on a seed.
+        let state = self
+            .state
+            .fetch_add(1, Ordering::Relaxed)
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let key = key((state >> 33) % self.workload.key_space());
+        if (state >> 17) % 100 < self.workload.read_percent() {
+            self.keyv.get(&key).await?;
+        } else {
+            self.keyv.set(&key, &self.value).await?;
+        }
+        Ok(())
+    }
+}
+
+fn key(i: u64) -> String {
+    format!("key:{}", i)
+}
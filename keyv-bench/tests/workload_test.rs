@@ -0,0 +1,42 @@
+use keyv::{KeyvError, StoreError};
+use keyv_bench::{connect, Driver, Workload};
+
+#[tokio::test]
+async fn test_workloads_run_in_memory() {
+    for workload in Workload::ALL {
+        let keyv = connect(None).await.unwrap().with_stats();
+        let driver = Driver::new(keyv, workload).await.unwrap();
+        for _ in 0..200 {
+            driver.step().await.unwrap();
+        }
+
+        let stats = driver.keyv().stats().unwrap();
+        assert_eq!(stats.misses(), 0, "{}", workload.name());
+        let reads = stats.hits();
+        let expected = 2 * workload.read_percent();
+        assert!(
+            reads.abs_diff(expected) <= 20,
+            "{} read {} times",
+            workload.name(),
+            reads
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_connect_rejects_unknown_schemes() {
+    assert!(matches!(
+        connect(Some("memcached://localhost:11211")).await,
+        Err(KeyvError::StoreError(
+            StoreError::InvalidConfiguration { .. }
+        ))
+    ));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_connect_sqlite() {
+    let keyv = connect(Some("sqlite::memory:")).await.unwrap();
+    let driver = Driver::new(keyv, Workload::Mixed).await.unwrap();
+    driver.step().await.unwrap();
+}
@@ -0,0 +1,102 @@
+use serde_json::{json, Map, Value};
+
+/// Number of buckets contexts are spread over when rolling out a flag.
+const BUCKETS: u64 = 10_000;
+
+/// A feature flag, as stored under its key.
+///
+/// Flags are stored as JSON: `true` or `false` for a boolean flag, `{"rollout": 25.0}`
+/// for a percentage rollout, and `{"variants": {"blue": 1, "green": 3}}` or a bare
+/// variant name such as `"blue"` for a variant flag.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Flag {
+    /// On or off for every context.
+    Boolean(bool),
+    /// On for the given percentage of contexts.
+    Rollout(f64),
+    /// On for every context, each being assigned one of the variants in proportion to
+    /// its weight.
+    Variants(Vec<(String, u64)>),
+}
+
+impl Flag {
+    /// Parses a stored flag, returning `None` if `value` has none of the flag shapes.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(enabled) => Some(Flag::Boolean(*enabled)),
+            Value::String(variant) => Some(Flag::Variants(vec![(variant.clone(), 1)])),
+            Value::Object(object) => {
+                if let Some(rollout) = object.get("rollout") {
+                    return rollout.as_f64().map(Flag::Rollout);
+                }
+                let variants = object
+                    .get("variants")?
+                    .as_object()?
+                    .iter()
+                    .map(|(name, weight)| Some((name.clone(), weight.as_u64()?)))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Flag::Variants(variants))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the JSON the flag is stored as.
+    pub fn to_value(&self) -> Value {
+        match self {
+            Flag::Boolean(enabled) => json!(enabled),
+            Flag::Rollout(percentage) => json!({ "rollout": percentage }),
+            Flag::Variants(variants) => {
+                let variants: Map<String, Value> = variants
+                    .iter()
+                    .map(|(name, weight)| (name.clone(), json!(weight)))
+                    .collect();
+                json!({ "variants": variants })
+            }
+        }
+    }
+
+    /// Returns whether the flag called `name` is on for `context`.
+    pub(crate) fn is_enabled(&self, name: &str, context: &str) -> bool {
+        match self {
+            Flag::Boolean(enabled) => *enabled,
+            Flag::Rollout(percentage) => {
+                let bucket = hash(name, context) % BUCKETS;
+                (bucket as f64) < percentage * (BUCKETS / 100) as f64
+            }
+            Flag::Variants(_) => true,
+        }
+    }
+
+    /// Returns the variant of the flag called `name` assigned to `context`, if any.
+    pub(crate) fn variant(&self, name: &str, context: &str) -> Option<&str> {
+        let Flag::Variants(variants) = self else {
+            return None;
+        };
+        let total: u64 = variants.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut point = hash(name, context) % total;
+        for (variant, weight) in variants {
+            if point < *weight {
+                return Some(variant);
+            }
+            point -= weight;
+        }
+        None
+    }
+}
+
+/// Hashes `context` for the flag `name`, placing it in rollouts and variants.
+///
+/// Uses 64-bit FNV-1a rather than the standard library hasher, whose output may change
+/// between releases, so that every process places a context the same way.
+fn hash(name: &str, context: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in name.bytes().chain([0]).chain(context.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use futures::TryStreamExt;
+use tokio::sync::Mutex;
+
+use super::Flag;
+use crate::{
+    store::{validate_key, Store, DEFAULT_SCAN_BATCH_SIZE},
+    KeyvError,
+};
+
+/// How long flags are served from the local copy before being read again by default.
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+struct Snapshot {
+    flags: HashMap<String, Flag>,
+    loaded_at: Option<Instant>,
+}
+
+/// Feature flags stored under a namespace, returned by `Keyv::flags`.
+///
+/// The flag `name` lives under the key `<namespace>:<name>`; see `Flag` for the stored
+/// shapes. All flags of the namespace are read at once and served from a local copy,
+/// read again once it is older than the refresh interval. If reading fails, the
+/// previous copy keeps being served.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{flags::Flag, Keyv};
+/// # async {
+/// let keyv = Keyv::default();
+/// keyv.set("flags:new_checkout", true).await.unwrap();
+///
+/// let flags = keyv.flags("flags");
+/// assert!(flags.is_enabled("new_checkout", "user:42").await.unwrap());
+/// # };
+/// ```
+pub struct Flags {
+    store: Arc<dyn Store>,
+    namespace: String,
+    refresh_interval: Duration,
+    snapshot: Mutex<Snapshot>,
+}
+
+impl Flags {
+    pub(crate) fn new(store: Arc<dyn Store>, namespace: &str) -> Self {
+        Self {
+            store,
+            namespace: namespace.to_string(),
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            snapshot: Mutex::new(Snapshot::default()),
+        }
+    }
+
+    /// Sets how long flags are served from the local copy before being read again.
+    pub fn refresh_interval(mut self, interval: Duration) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Returns whether the flag `name` is on for `context`, an identifier of the
+    /// subject such as a user id. Missing flags are off.
+    pub async fn is_enabled(&self, name: &str, context: &str) -> Result<bool, KeyvError> {
+        Ok(self
+            .flag(name)
+            .await?
+            .is_some_and(|flag| flag.is_enabled(name, context)))
+    }
+
+    /// Returns the variant of the flag `name` assigned to `context`, or `None` if the
+    /// flag is missing or has no variants.
+    pub async fn variant(&self, name: &str, context: &str) -> Result<Option<String>, KeyvError> {
+        Ok(self
+            .flag(name)
+            .await?
+            .and_then(|flag| flag.variant(name, context).map(str::to_string)))
+    }
+
+    /// Returns the flag `name`, or `None` if it is missing.
+    pub async fn flag(&self, name: &str) -> Result<Option<Flag>, KeyvError> {
+        let mut snapshot = self.snapshot.lock().await;
+        let stale = snapshot
+            .loaded_at
+            .is_none_or(|loaded_at| loaded_at.elapsed() >= self.refresh_interval);
+        if stale {
+            match self.load().await {
+                Ok(flags) => {
+                    *snapshot = Snapshot {
+                        flags,
+                        loaded_at: Some(Instant::now()),
+                    }
+                }
+                Err(e) if snapshot.loaded_at.is_some() => {
+                    log::warn!("Failed to refresh the flags of '{}': {}", self.namespace, e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(snapshot.flags.get(name).cloned())
+    }
+
+    /// Stores `flag` under `name`. Other processes see it once their copy refreshes;
+    /// this one right away.
+    pub async fn set(&self, name: &str, flag: Flag) -> Result<(), KeyvError> {
+        let key = format!("{}:{}", self.namespace, name);
+        validate_key(&key)?;
+        self.store.set(&key, flag.to_value(), None).await?;
+        self.snapshot
+            .lock()
+            .await
+            .flags
+            .insert(name.to_string(), flag);
+        Ok(())
+    }
+
+    /// Drops the local copy, so the next check reads the flags again.
+    pub async fn invalidate(&self) {
+        *self.snapshot.lock().await = Snapshot::default();
+    }
+
+    async fn load(&self) -> Result<HashMap<String, Flag>, KeyvError> {
+        let prefix = format!("{}:", self.namespace);
+        let batches: Vec<_> = self
+            .store
+            .scan_entries(Some(&prefix), DEFAULT_SCAN_BATCH_SIZE)
+            .try_collect()
+            .await?;
+
+        let mut flags = HashMap::new();
+        for entry in batches.into_iter().flatten() {
+            let name = &entry.key[prefix.len()..];
+            match Flag::from_value(&entry.value) {
+                Some(flag) => {
+                    flags.insert(name.to_string(), flag);
+                }
+                None => log::warn!("Ignoring the malformed flag '{}'", entry.key),
+            }
+        }
+        Ok(flags)
+    }
+}
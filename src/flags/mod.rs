@@ -0,0 +1,5 @@
+mod flag;
+pub use flag::*;
+
+mod flags;
+pub use flags::*;
@@ -0,0 +1,130 @@
+use std::{sync::Arc, time::Duration};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{
+    store::{validate_key, Store},
+    KeyvError,
+};
+
+/// Prefix of the keys idempotency records are stored under.
+const IDEMPOTENCY_PREFIX: &str = "idempotency";
+
+/// What is known about a request carrying a given idempotency key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdempotencyState {
+    /// An earlier request reserved the key and is still being handled.
+    InProgress,
+    /// An earlier request completed with this response.
+    Completed(Value),
+}
+
+/// Records of the requests handled under client-supplied idempotency keys, returned by
+/// `Keyv::idempotency`.
+///
+/// A handler calls `begin` before doing any work: the first request to do so reserves
+/// the key, while retries learn that the request is in progress or get the stored
+/// response to replay. Reservations are atomic, so concurrent retries of the same
+/// request never run twice.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use keyv::Keyv;
+/// # async {
+/// let keyv = Keyv::default();
+/// let payments = keyv.idempotency(Duration::from_secs(24 * 60 * 60));
+///
+/// match payments.begin("9f3c").await.unwrap() {
+///     None => {
+///         // First time this key is seen: charge the card, then record the response.
+///         payments.complete("9f3c", "charged").await.unwrap();
+///     }
+///     Some(_state) => {
+///         // A retry: answer with 409 while in progress or replay the response.
+///     }
+/// }
+/// # };
+/// ```
+#[derive(Clone)]
+pub struct Idempotency {
+    store: Arc<dyn Store>,
+    ttl: Duration,
+    reservation_timeout: Duration,
+}
+
+impl Idempotency {
+    pub(crate) fn new(store: Arc<dyn Store>, ttl: Duration) -> Self {
+        Self {
+            store,
+            ttl,
+            reservation_timeout: ttl,
+        }
+    }
+
+    /// Releases reservations that were never completed after `timeout`, e.g. because
+    /// the handler crashed, so that a retry can run. Defaults to the retention TTL.
+    pub fn reservation_timeout(mut self, timeout: Duration) -> Self {
+        self.reservation_timeout = timeout;
+        self
+    }
+
+    /// Reserves `key` for the request about to be handled.
+    ///
+    /// Returns `None` if this call reserved the key, or the state left by an earlier
+    /// request with the same key.
+    pub async fn begin(&self, key: &str) -> Result<Option<IdempotencyState>, KeyvError> {
+        let record_key = self.record_key(key)?;
+        let reserved = self
+            .store
+            .set_if_absent(
+                &record_key,
+                json!({ "status": "in_progress" }),
+                Some(self.reservation_timeout),
+            )
+            .await?;
+        if reserved {
+            return Ok(None);
+        }
+        // A record expiring between the two calls reads as still in progress.
+        Ok(Some(
+            self.lookup(key)
+                .await?
+                .unwrap_or(IdempotencyState::InProgress),
+        ))
+    }
+
+    /// Records `response` as the outcome of the request reserved with `key`, keeping it
+    /// for the retention TTL.
+    pub async fn complete<T: Serialize>(&self, key: &str, response: T) -> Result<(), KeyvError> {
+        let record_key = self.record_key(key)?;
+        let record = json!({ "status": "completed", "response": response });
+        Ok(self.store.set(&record_key, record, Some(self.ttl)).await?)
+    }
+
+    /// Drops the record of `key`, so that the next request with it runs again. Meant for
+    /// handlers that failed in a way the client may retry.
+    pub async fn release(&self, key: &str) -> Result<(), KeyvError> {
+        let record_key = self.record_key(key)?;
+        Ok(self.store.remove(&record_key).await?)
+    }
+
+    /// Returns the state of the request with `key`, or `None` if no request holds it.
+    pub async fn lookup(&self, key: &str) -> Result<Option<IdempotencyState>, KeyvError> {
+        let record_key = self.record_key(key)?;
+        let Some(mut record) = self.store.get(&record_key).await? else {
+            return Ok(None);
+        };
+        Ok(Some(match record.get("status").and_then(Value::as_str) {
+            Some("completed") => IdempotencyState::Completed(record["response"].take()),
+            _ => IdempotencyState::InProgress,
+        }))
+    }
+
+    fn record_key(&self, key: &str) -> Result<String, KeyvError> {
+        validate_key(key)?;
+        Ok(format!("{}:{}", IDEMPOTENCY_PREFIX, key))
+    }
+}
@@ -0,0 +1,2 @@
+mod idempotency;
+pub use idempotency::*;
@@ -0,0 +1,2 @@
+mod node;
+pub use node::*;
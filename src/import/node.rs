@@ -0,0 +1,280 @@
+use std::time::SystemTime;
+
+use serde_json::Value;
+
+use crate::{
+    store::expiry::{now_millis, system_time_from_millis},
+    Store, StoreError,
+};
+
+/// Prefix Node keyv puts before every key, its default `keyv` namespace and a colon.
+///
+/// Stores created with another namespace use `<namespace>:`. `@keyv/redis` 4 and later
+/// separate the namespace with two colons, as in `keyv::`.
+pub const NODE_KEYV_PREFIX: &str = "keyv:";
+
+/// Default name of the table `@keyv/sqlite` stores its entries in.
+pub const NODE_KEYV_SQLITE_TABLE: &str = "keyv";
+
+/// Number of entries read from the dump at a time.
+const IMPORT_BATCH_SIZE: usize = 500;
+
+/// An entry decoded from a Node keyv dump.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeEntry {
+    /// The key, without the Node keyv prefix.
+    pub key: String,
+    /// The stored value.
+    pub value: Value,
+    /// When the entry expires, or `None` if it never does.
+    pub expires_at: Option<SystemTime>,
+}
+
+/// The result of importing a Node keyv dump.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Number of entries written to the target store.
+    pub imported: u64,
+    /// Number of entries left out because they had already expired.
+    pub expired: u64,
+    /// Number of entries left out because they were not in the JSON envelope of Node
+    /// keyv.
+    pub invalid: u64,
+}
+
+/// Decodes a raw Node keyv entry, as stored by `@keyv/sqlite` and `@keyv/redis`.
+///
+/// Node keyv stores every value as a JSON envelope `{"value": ..., "expires": ...}`,
+/// `expires` being milliseconds since the Unix epoch or `null`, serialized with
+/// `json-buffer`. Strings that `json-buffer` escaped with a leading colon are
+/// unescaped; Node `Buffer`s are kept as their `:base64:` encoded string.
+///
+/// # Returns
+/// - `Ok(Some(NodeEntry))` with the decoded entry, which may have expired.
+/// - `Ok(None)` if `raw_key` does not start with `prefix`.
+/// - `Err(StoreError)` if `raw_value` is not a Node keyv envelope.
+pub fn decode_node_entry(
+    prefix: &str,
+    raw_key: &str,
+    raw_value: &str,
+) -> Result<Option<NodeEntry>, StoreError> {
+    let Some(key) = raw_key.strip_prefix(prefix) else {
+        return Ok(None);
+    };
+    let invalid = || StoreError::QueryError(format!("Invalid Node keyv entry `{}`", raw_key));
+
+    let envelope: Value = serde_json::from_str(raw_value)
+        .map_err(|e| StoreError::SerializationError { source: e })?;
+    let Value::Object(mut envelope) = envelope else {
+        return Err(invalid());
+    };
+    let mut value = envelope.remove("value").ok_or_else(invalid)?;
+    unescape_json_buffer(&mut value);
+    let expires_at = match envelope.get("expires") {
+        None | Some(Value::Null) => None,
+        Some(expires) => Some(expires.as_i64().ok_or_else(invalid)?),
+    };
+
+    Ok(Some(NodeEntry {
+        key: key.to_string(),
+        value,
+        expires_at: expires_at.map(system_time_from_millis),
+    }))
+}
+
+/// Reverts the escaping `json-buffer` applies to strings starting with a colon.
+fn unescape_json_buffer(value: &mut Value) {
+    match value {
+        Value::String(s) if s.starts_with(':') && !s.starts_with(":base64:") => {
+            s.remove(0);
+        }
+        Value::Array(values) => values.iter_mut().for_each(unescape_json_buffer),
+        Value::Object(values) => values.values_mut().for_each(unescape_json_buffer),
+        _ => {}
+    }
+}
+
+/// Imports raw Node keyv entries, given as `(key, value)` pairs as stored by Node keyv,
+/// into `target`, preserving expirations.
+///
+/// Entries whose key does not start with `prefix` are ignored, and expired or invalid
+/// entries are counted in the report but not written.
+///
+/// # Examples
+///
+/// ```rust
+/// # use keyv::{adapter::inmemory::InMemoryStore, import::{import_node_entries, NODE_KEYV_PREFIX}, Store};
+/// # async {
+/// let target = InMemoryStore::new();
+/// let rows = [("keyv:user:1", r#"{"value":"alice","expires":null}"#)];
+///
+/// let report = import_node_entries(&target, NODE_KEYV_PREFIX, rows).await.unwrap();
+/// assert_eq!(report.imported, 1);
+/// assert_eq!(target.get("user:1").await.unwrap(), Some("alice".into()));
+/// # };
+/// ```
+pub async fn import_node_entries<S, I, K, V>(
+    target: &S,
+    prefix: &str,
+    rows: I,
+) -> Result<ImportReport, StoreError>
+where
+    S: Store + ?Sized,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut report = ImportReport::default();
+    import_rows(target, prefix, rows, &mut report).await?;
+    Ok(report)
+}
+
+async fn import_rows<S, I, K, V>(
+    target: &S,
+    prefix: &str,
+    rows: I,
+    report: &mut ImportReport,
+) -> Result<(), StoreError>
+where
+    S: Store + ?Sized,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let now = system_time_from_millis(now_millis());
+    for (raw_key, raw_value) in rows {
+        let entry = match decode_node_entry(prefix, raw_key.as_ref(), raw_value.as_ref()) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => continue,
+            Err(e) => {
+                log::warn!("Skipping Node keyv entry {}: {}", raw_key.as_ref(), e);
+                report.invalid += 1;
+                continue;
+            }
+        };
+        match entry.expires_at {
+            Some(expires_at) if expires_at <= now => {
+                report.expired += 1;
+                continue;
+            }
+            Some(expires_at) => {
+                target
+                    .set_until(&entry.key, entry.value, expires_at)
+                    .await?
+            }
+            None => target.set(&entry.key, entry.value, None).await?,
+        }
+        report.imported += 1;
+    }
+    Ok(())
+}
+
+/// Imports the entries of a database written by Node `@keyv/sqlite` into `target`,
+/// preserving expirations.
+///
+/// `table` is the table of the Node store, `NODE_KEYV_SQLITE_TABLE` unless it was
+/// configured otherwise, with its `key` and `value` columns. Entries are read in key
+/// order and in batches, so the database can be opened read-only while Node still
+/// writes to it; entries written during the import may be missed.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use keyv::{adapter::{inmemory::InMemoryStore, sqlite::SqlitePool}, import::*};
+/// # async {
+/// let pool = SqlitePool::connect("sqlite://cache.sqlite?mode=ro").await.unwrap();
+/// let target = InMemoryStore::new();
+/// let report = import_node_sqlite(&pool, NODE_KEYV_SQLITE_TABLE, NODE_KEYV_PREFIX, &target)
+///     .await
+///     .unwrap();
+/// println!("imported {} entries", report.imported);
+/// # };
+/// ```
+#[cfg(feature = "sqlite")]
+pub async fn import_node_sqlite<S: Store + ?Sized>(
+    pool: &sqlx::SqlitePool,
+    table: &str,
+    prefix: &str,
+    target: &S,
+) -> Result<ImportReport, StoreError> {
+    let query = format!(
+        "SELECT key, value FROM \"{}\" WHERE key > ? ORDER BY key LIMIT ?",
+        table.replace('"', "\"\"")
+    );
+    let mut report = ImportReport::default();
+    let mut after = String::new();
+    loop {
+        let rows: Vec<(String, String)> = sqlx::query_as(&query)
+            .bind(&after)
+            .bind(IMPORT_BATCH_SIZE as i64)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| StoreError::QueryError(e.to_string()))?;
+        let Some((last, _)) = rows.last() else {
+            return Ok(report);
+        };
+        after = last.clone();
+        import_rows(target, prefix, rows, &mut report).await?;
+    }
+}
+
+/// Imports the entries of a Redis database written by Node `@keyv/redis` into
+/// `target`, preserving expirations.
+///
+/// The keys starting with `prefix` are listed with `SCAN` and read in batches. Keys
+/// that expire or are removed while the import runs are skipped.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use keyv::{adapter::inmemory::InMemoryStore, import::*};
+/// # async {
+/// let client = redis::Client::open("redis://localhost:6379").unwrap();
+/// let target = InMemoryStore::new();
+/// let report = import_node_redis(&client, NODE_KEYV_PREFIX, &target).await.unwrap();
+/// println!("imported {} entries", report.imported);
+/// # };
+/// ```
+#[cfg(feature = "redis")]
+pub async fn import_node_redis<S: Store + ?Sized>(
+    client: &redis::Client,
+    prefix: &str,
+    target: &S,
+) -> Result<ImportReport, StoreError> {
+    let mut conn = client
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
+    let pattern = format!("{}*", crate::adapter::redis::escape_glob(prefix));
+    let mut report = ImportReport::default();
+    let mut cursor = 0u64;
+    loop {
+        let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("MATCH")
+            .arg(&pattern)
+            .arg("COUNT")
+            .arg(IMPORT_BATCH_SIZE)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| StoreError::QueryError(e.to_string()))?;
+        if !keys.is_empty() {
+            // Keys holding something other than a string, such as the key set older
+            // versions of `@keyv/redis` keep, are read as nil and skipped.
+            let values: Vec<Option<String>> = redis::cmd("MGET")
+                .arg(&keys)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| StoreError::QueryError(e.to_string()))?;
+            let rows = keys
+                .into_iter()
+                .zip(values)
+                .filter_map(|(key, value)| Some((key, value?)));
+            import_rows(target, prefix, rows, &mut report).await?;
+        }
+        if next == 0 {
+            return Ok(report);
+        }
+        cursor = next;
+    }
+}
@@ -0,0 +1,194 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    adapter::inmemory::InMemoryStore, layer::concurrency::ConcurrencyMode, ChangeFeedBackend,
+    Generator, KeyHasher, RetryPolicy, Store,
+};
+
+#[cfg(feature = "transform")]
+use crate::layer::transform::{Serializer, Transformer};
+
+use super::{ClearPolicy, ExpiryPolicy, Keyv, KeyvError};
+
+/// Builds a `Keyv` handle, created with `Keyv::builder()`.
+///
+/// Collects the store, the layers wrapped around it and the handle's settings, then
+/// initializes the store once in `build`. Every option has a `Keyv::with_*` equivalent;
+/// the builder only saves applying them in the right order.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use keyv::{adapter::inmemory::InMemoryStore, layer::concurrency::ConcurrencyMode, Keyv, RetryPolicy};
+/// # async {
+/// let keyv = Keyv::builder()
+///     .store(InMemoryStore::new())
+///     .namespace("app")
+///     .default_ttl(Duration::from_secs(300))
+///     .concurrency_limit(32, ConcurrencyMode::Queue)
+///     .retry(RetryPolicy::new(5))
+///     .stats()
+///     .build()
+///     .await
+///     .unwrap();
+/// # };
+/// ```
+#[derive(Default)]
+pub struct KeyvBuilder {
+    store: Option<Arc<dyn Store>>,
+    retry: Option<RetryPolicy>,
+    concurrency_limit: Option<(usize, ConcurrencyMode)>,
+    stats: bool,
+    namespace: Option<String>,
+    change_feed: Option<Arc<dyn ChangeFeedBackend>>,
+    default_ttl: Option<Duration>,
+    expiry_policy: ExpiryPolicy,
+    clear_policy: ClearPolicy,
+    key_hasher: Option<Arc<dyn KeyHasher>>,
+    generator: Option<Arc<dyn Generator>>,
+    #[cfg(feature = "transform")]
+    serializer: Option<Arc<dyn Serializer>>,
+    #[cfg(feature = "transform")]
+    transformers: Vec<Arc<dyn Transformer>>,
+}
+
+impl KeyvBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the store the handle reads and writes. Defaults to an `InMemoryStore`.
+    pub fn store<S: Store + 'static>(mut self, store: S) -> Self {
+        self.store = Some(Arc::new(store));
+        self
+    }
+
+    /// Retries the store's initialization with `policy` in `build`.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Limits the number of concurrent store operations, see
+    /// `Keyv::with_concurrency_limit`.
+    pub fn concurrency_limit(mut self, max_in_flight: usize, mode: ConcurrencyMode) -> Self {
+        self.concurrency_limit = Some((max_in_flight, mode));
+        self
+    }
+
+    /// Collects hit/miss counters and latencies, see `Keyv::with_stats`.
+    pub fn stats(mut self) -> Self {
+        self.stats = true;
+        self
+    }
+
+    /// Places every key of the handle under `namespace`, see `Keyv::namespace`.
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.to_string());
+        self
+    }
+
+    /// Reads `Keyv::changes` from the change feed of `feed`, see `Keyv::with_change_feed`.
+    pub fn change_feed<B: ChangeFeedBackend + 'static>(mut self, feed: B) -> Self {
+        self.change_feed = Some(Arc::new(feed));
+        self
+    }
+
+    /// Expires values written without an explicit TTL after `ttl`, see
+    /// `Keyv::with_default_ttl`.
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the expiry policy, see `Keyv::with_expiry_policy`.
+    pub fn expiry_policy(mut self, policy: ExpiryPolicy) -> Self {
+        self.expiry_policy = policy;
+        self
+    }
+
+    /// Sets the clear policy, see `Keyv::with_clear_policy`.
+    pub fn clear_policy(mut self, policy: ClearPolicy) -> Self {
+        self.clear_policy = policy;
+        self
+    }
+
+    /// Sets the hasher `Keyv::key_for` derives keys with, see `Keyv::with_key_hasher`.
+    pub fn key_hasher<H: KeyHasher + 'static>(mut self, hasher: H) -> Self {
+        self.key_hasher = Some(Arc::new(hasher));
+        self
+    }
+
+    /// Sets the generator lease tokens are drawn from, see `Keyv::with_generator`.
+    pub fn generator<G: Generator + 'static>(mut self, generator: G) -> Self {
+        self.generator = Some(Arc::new(generator));
+        self
+    }
+
+    /// Appends `transformer` to the pipeline values pass through, see
+    /// `Keyv::with_transformers`. Transformers are applied in the order they are added.
+    #[cfg(feature = "transform")]
+    pub fn transformer<T: Transformer + 'static>(mut self, transformer: T) -> Self {
+        self.transformers.push(Arc::new(transformer));
+        self
+    }
+
+    /// Serializes values with `serializer` instead of JSON, see `Keyv::with_serializer`.
+    #[cfg(feature = "transform")]
+    pub fn serializer<S: Serializer + 'static>(mut self, serializer: S) -> Self {
+        self.serializer = Some(Arc::new(serializer));
+        self
+    }
+
+    /// Initializes the store and returns the configured handle.
+    ///
+    /// The layers are stacked with the concurrency limit closest to the store and the
+    /// statistics above it, so the recorded latencies include the time spent waiting for
+    /// a slot. The namespace is applied next, and the value pipeline wraps them all.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KeyvError` if the store fails to initialize, after the retries allowed
+    /// by the `retry` policy.
+    pub async fn build(self) -> Result<Keyv, KeyvError> {
+        let store = self.store.unwrap_or_else(|| Arc::new(InMemoryStore::new()));
+        match &self.retry {
+            Some(policy) => policy.run(|| store.initialize()).await?,
+            None => store.initialize().await?,
+        }
+
+        let mut keyv = Keyv::from_store(store);
+        #[cfg(feature = "transform")]
+        {
+            if let Some(serializer) = self.serializer {
+                keyv = keyv.with_serializer(serializer);
+            }
+            keyv = keyv.with_transformers(self.transformers);
+        }
+        if let Some((max_in_flight, mode)) = self.concurrency_limit {
+            keyv = keyv.with_concurrency_limit(max_in_flight, mode);
+        }
+        if self.stats {
+            keyv = keyv.with_stats();
+        }
+        if let Some(feed) = self.change_feed {
+            keyv = keyv.with_change_feed(feed);
+        }
+        if let Some(namespace) = &self.namespace {
+            keyv = keyv.namespace(namespace);
+        }
+        if let Some(ttl) = self.default_ttl {
+            keyv = keyv.with_default_ttl(ttl);
+        }
+        if let Some(hasher) = self.key_hasher {
+            keyv = keyv.with_key_hasher(hasher);
+        }
+        if let Some(generator) = self.generator {
+            keyv = keyv.with_generator(generator);
+        }
+        Ok(keyv
+            .with_expiry_policy(self.expiry_policy)
+            .with_clear_policy(self.clear_policy))
+    }
+}
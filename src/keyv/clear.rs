@@ -0,0 +1,37 @@
+use std::time::{Duration, Instant};
+
+/// How long a `ConfirmToken` stays valid after it is issued.
+pub(crate) const CONFIRM_TOKEN_TTL: Duration = Duration::from_secs(60);
+
+/// Whether a `Keyv` handle may wipe its store, set with `Keyv::with_clear_policy`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClearPolicy {
+    /// `clear` wipes the store right away.
+    #[default]
+    Allow,
+    /// `clear` is refused; the store can only be wiped with `clear_confirmed` and a
+    /// token from `clear_dry_run`.
+    RequireConfirmation,
+    /// The store cannot be wiped through this handle at all.
+    Deny,
+}
+
+/// Proof that the caller looked at what `clear` would remove, issued by
+/// `Keyv::clear_dry_run`.
+///
+/// A token is only accepted by the handle that issued it, within a minute.
+#[derive(Debug)]
+pub struct ConfirmToken {
+    pub(crate) handle: usize,
+    pub(crate) issued_at: Instant,
+}
+
+/// What clearing the store would remove, returned by `Keyv::clear_dry_run`.
+#[derive(Debug)]
+pub struct ClearPreview {
+    /// Number of entries that would be removed, or `None` if the store cannot count
+    /// them.
+    pub entries: Option<u64>,
+    /// Token to pass to `Keyv::clear_confirmed` to go ahead.
+    pub token: ConfirmToken,
+}
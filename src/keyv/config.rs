@@ -0,0 +1,68 @@
+use std::{sync::Arc, time::Duration};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::sync::watch;
+
+use crate::store::{Store, StoreError};
+
+use super::KeyvError;
+
+/// How often a watched configuration is read again by default.
+pub(crate) const DEFAULT_CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Reads the configuration stored under `key` and keeps the returned receiver up to
+/// date by reading it again every `interval` in a background task.
+///
+/// The task ends once every receiver has been dropped. A configuration that goes
+/// missing or stops deserializing is logged and the last good one is kept.
+pub(crate) async fn watch_config<T>(
+    store: Arc<dyn Store>,
+    key: &str,
+    interval: Duration,
+) -> Result<watch::Receiver<T>, KeyvError>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    let raw = store.get(key).await?.ok_or(StoreError::NotFound)?;
+    let config =
+        T::deserialize(&raw).map_err(|source| StoreError::SerializationError { source })?;
+    let (sender, receiver) = watch::channel(config);
+
+    let key = key.to_string();
+    tokio::spawn(async move {
+        let mut last: Value = raw;
+        loop {
+            tokio::select! {
+                _ = sender.closed() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+            let raw = match store.get(&key).await {
+                Ok(Some(raw)) => raw,
+                Ok(None) => {
+                    log::warn!(
+                        "The configuration '{}' is missing, keeping the last one",
+                        key
+                    );
+                    continue;
+                }
+                Err(e) => {
+                    log::warn!("Failed to read the configuration '{}': {}", key, e);
+                    continue;
+                }
+            };
+            if raw == last {
+                continue;
+            }
+            match T::deserialize(&raw) {
+                Ok(config) => {
+                    sender.send_replace(config);
+                    last = raw;
+                }
+                Err(e) => log::warn!("Ignoring the invalid configuration '{}': {}", key, e),
+            }
+        }
+    });
+
+    Ok(receiver)
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use crate::store::expiry::now_millis;
+
+use super::{Keyv, KeyvError};
+
+/// Prefix of the keys counters are stored under.
+const COUNTER_PREFIX: &str = "counter";
+
+/// A named integer counter, returned by `Keyv::counter`.
+///
+/// The count lives under the key `counter:<name>` and is updated with atomic
+/// increments, so concurrent processes sharing a store never lose updates. With a fixed
+/// window every window gets its own key, `counter:<name>:<window start>`, that expires
+/// when the window ends, so the count starts over each window without any cleanup.
+pub struct Counter<'a> {
+    keyv: &'a Keyv,
+    name: String,
+    window: Option<Duration>,
+}
+
+impl<'a> Counter<'a> {
+    pub(crate) fn new(keyv: &'a Keyv, name: &str) -> Self {
+        Self {
+            keyv,
+            name: name.to_string(),
+            window: None,
+        }
+    }
+
+    /// Resets the count at the start of every `window`, e.g. each minute.
+    ///
+    /// Windows are aligned to the Unix epoch, so every process sharing the store counts
+    /// into the same window.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// Adds one to the count and returns the result.
+    pub async fn incr(&self) -> Result<i64, KeyvError> {
+        self.incr_by(1).await
+    }
+
+    /// Adds `delta`, which may be negative, to the count and returns the result.
+    pub async fn incr_by(&self, delta: i64) -> Result<i64, KeyvError> {
+        let (key, ttl) = self.current();
+        self.keyv.increment(&key, delta, ttl).await
+    }
+
+    /// Returns the count, or zero if nothing has been counted in the current window.
+    pub async fn get(&self) -> Result<i64, KeyvError> {
+        let (key, _) = self.current();
+        Ok(self
+            .keyv
+            .get(&key)
+            .await?
+            .and_then(|value| value.as_i64())
+            .unwrap_or_default())
+    }
+
+    /// Resets the count of the current window to zero.
+    pub async fn reset(&self) -> Result<(), KeyvError> {
+        let (key, _) = self.current();
+        self.keyv.remove(&key).await
+    }
+
+    /// Returns the key of the current window and the time left until it ends.
+    fn current(&self) -> (String, Option<Duration>) {
+        let Some(window) = self.window else {
+            return (format!("{}:{}", COUNTER_PREFIX, self.name), None);
+        };
+        let window = i64::try_from(window.as_millis()).unwrap_or(i64::MAX).max(1);
+        let now = now_millis();
+        let start = now - now.rem_euclid(window);
+        let remaining = Duration::from_millis((start + window - now) as u64);
+        (
+            format!("{}:{}:{}", COUNTER_PREFIX, self.name, start),
+            Some(remaining),
+        )
+    }
+}
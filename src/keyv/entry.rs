@@ -0,0 +1,47 @@
+use serde_json::Value;
+
+/// The result of a lookup that tells a stored `null` apart from a missing key.
+///
+/// Returned by `Keyv::get_entry`. Storing `None` or `()` writes a JSON `null`, which
+/// `Keyv::get` returns as `Some(Value::Null)`; deserializing that into an `Option<T>`
+/// yields `None` and makes a cached "no result" look like a cache miss.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Entry {
+    /// The key does not exist or has expired.
+    Missing,
+    /// The key holds a JSON `null`.
+    Null,
+    /// The key holds a non-null value.
+    Value(Value),
+}
+
+impl Entry {
+    /// Returns `true` if the key does not exist or has expired.
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Entry::Missing)
+    }
+
+    /// Returns `true` if the key exists, including when it holds `null`.
+    pub fn is_present(&self) -> bool {
+        !self.is_missing()
+    }
+
+    /// Returns the stored value, `Value::Null` for `Entry::Null`, or `None` if missing.
+    pub fn into_value(self) -> Option<Value> {
+        match self {
+            Entry::Missing => None,
+            Entry::Null => Some(Value::Null),
+            Entry::Value(value) => Some(value),
+        }
+    }
+}
+
+impl From<Option<Value>> for Entry {
+    fn from(value: Option<Value>) -> Self {
+        match value {
+            None => Entry::Missing,
+            Some(Value::Null) => Entry::Null,
+            Some(value) => Entry::Value(value),
+        }
+    }
+}
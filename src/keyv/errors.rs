@@ -6,4 +6,19 @@ use crate::store::StoreError;
 pub enum KeyvError {
     #[error("Store error: {0}")]
     StoreError(#[from] StoreError),
+
+    #[error("Clearing the store requires a token from `clear_dry_run`")]
+    ClearNotConfirmed,
+
+    #[error("Clearing the store is disabled on this handle")]
+    ClearDisabled,
+
+    #[error("The confirmation token has expired or was issued by another handle")]
+    InvalidConfirmToken,
+
+    #[error("The value expired {0:?} ago")]
+    Expired(std::time::Duration),
+
+    #[error("Loader error: {0}")]
+    LoaderError(#[source] Box<dyn std::error::Error + Send + Sync>),
 }
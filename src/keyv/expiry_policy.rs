@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+/// When entries written through a `Keyv` handle expire, set with
+/// `Keyv::with_expiry_policy`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExpiryPolicy {
+    /// Entries expire after the TTL they were written with, if any.
+    #[default]
+    TimeToLive,
+    /// Entries expire once they have not been read or written for the given duration.
+    ///
+    /// Writes without an explicit TTL expire after the idle duration, and every read
+    /// resets the expiration through `Store::get_and_touch`. Every bundled adapter
+    /// does this atomically, e.g. with `GETEX` on Redis and `UPDATE ... RETURNING` on
+    /// Postgres and SQLite; custom stores without an override read and rewrite the
+    /// entry.
+    TimeToIdle(Duration),
+}
+
+impl ExpiryPolicy {
+    /// Returns the idle duration reads extend entries by, if any.
+    pub(crate) fn idle_timeout(self) -> Option<Duration> {
+        match self {
+            ExpiryPolicy::TimeToLive => None,
+            ExpiryPolicy::TimeToIdle(idle) => Some(idle),
+        }
+    }
+}
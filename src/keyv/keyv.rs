@@ -1,11 +1,41 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
 
-use serde::Serialize;
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{json, Value};
+use tokio::sync::watch;
 
-use crate::{adapter::inmemory::InMemoryStore, store::Store};
+use crate::{
+    adapter::inmemory::InMemoryStore,
+    flags::Flags,
+    idempotency::Idempotency,
+    layer::{
+        concurrency::{ConcurrencyLimitStore, ConcurrencyMode},
+        key_codec::{KeyCodec, KeyCodecStore, PrefixCodec},
+        stats::{LatencyReport, Stats, StatsStore},
+    },
+    leader::Campaign,
+    store::{
+        validate_key, BatchOp, Capabilities, Change, ChangeFeedBackend, EvictionPriority,
+        Generator, KeyHasher, Metadata, ScanEntry, SipKeyHasher, Store, StoreError, Usage,
+        UuidV7Generator, Version, DEFAULT_CHANGES_LIMIT, DEFAULT_SCAN_BATCH_SIZE,
+    },
+};
 
-use super::KeyvError;
+#[cfg(feature = "transform")]
+use crate::layer::transform::{Serializer, TransformStore, Transformer};
+
+use super::{
+    clear::CONFIRM_TOKEN_TTL,
+    config::{watch_config, DEFAULT_CONFIG_POLL_INTERVAL},
+    ClearPolicy, ClearPreview, ConfirmToken, Counter, Entry, ExpiryPolicy, KeyvBuilder, KeyvError,
+    Lease, Pipeline, ReadThrough, StaleRead,
+};
 
 /// Async Key-Value Store Interface
 ///
@@ -57,6 +87,48 @@ use super::KeyvError;
 /// ```
 pub struct Keyv {
     store: Arc<dyn Store>,
+    stats: Option<Arc<Stats>>,
+    clear_policy: ClearPolicy,
+    key_hasher: Arc<dyn KeyHasher>,
+    generator: Arc<dyn Generator>,
+    default_ttl: Option<Duration>,
+    expiry_policy: ExpiryPolicy,
+    namespaced: bool,
+    change_feed: Option<Arc<dyn ChangeFeedBackend>>,
+    #[cfg(feature = "transform")]
+    values: Values,
+}
+
+/// The value pipeline of a handle, kept apart from its other layers so that a
+/// namespaced handle can replace the one it inherits.
+#[cfg(feature = "transform")]
+#[derive(Clone)]
+struct Values {
+    /// The handle's store, with every layer but the value pipeline.
+    store: Arc<dyn Store>,
+    serializer: Option<Arc<dyn Serializer>>,
+    transformers: Vec<Arc<dyn Transformer>>,
+    /// The stats the pipeline records value sizes into.
+    stats: Option<Arc<Stats>>,
+}
+
+#[cfg(feature = "transform")]
+impl Values {
+    /// Returns the store with the value pipeline on top, or the bare store if the
+    /// pipeline is empty.
+    fn build(&self) -> Arc<dyn Store> {
+        if self.serializer.is_none() && self.transformers.is_empty() {
+            return self.store.clone();
+        }
+        let mut store = TransformStore::new(self.store.clone());
+        if let Some(serializer) = &self.serializer {
+            store = store.serializer(serializer.clone());
+        }
+        if let Some(stats) = &self.stats {
+            store = store.stats(stats.clone());
+        }
+        Arc::new(store.transformers(self.transformers.clone()))
+    }
 }
 
 impl Keyv {
@@ -103,9 +175,417 @@ impl Keyv {
     /// ```
     pub async fn try_new<S: Store + 'static>(store: S) -> Result<Self, KeyvError> {
         store.initialize().await?;
-        Ok(Self {
-            store: Arc::new(store),
-        })
+        Ok(Self::from_store(Arc::new(store)))
+    }
+
+    /// Initializes `store` and returns a handle placing every key under `namespace`.
+    ///
+    /// Use it when several components share a backend: keys are stored as
+    /// `<namespace>:<key>`, and `clear` only removes the keys of the namespace. See
+    /// `namespace` to derive namespaced handles from an existing one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{adapter::inmemory::InMemoryStore, Keyv};
+    /// # async {
+    /// let sessions = Keyv::try_new_with_namespace(InMemoryStore::new(), "sessions")
+    ///     .await
+    ///     .unwrap();
+    /// sessions.set("abc", "alice").await.unwrap(); // Stored as "sessions:abc"
+    /// # };
+    /// ```
+    pub async fn try_new_with_namespace<S: Store + 'static>(
+        store: S,
+        namespace: &str,
+    ) -> Result<Self, KeyvError> {
+        Ok(Self::try_new(store).await?.namespace(namespace))
+    }
+
+    /// Returns a builder configuring the store, its layers and the handle's settings
+    /// in one place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::{adapter::inmemory::InMemoryStore, Keyv};
+    /// # async {
+    /// let keyv = Keyv::builder()
+    ///     .store(InMemoryStore::new())
+    ///     .namespace("app")
+    ///     .default_ttl(Duration::from_secs(300))
+    ///     .build()
+    ///     .await
+    ///     .unwrap();
+    /// # };
+    /// ```
+    pub fn builder() -> KeyvBuilder {
+        KeyvBuilder::new()
+    }
+
+    /// Creates a handle with default settings on a store that is already initialized.
+    pub(crate) fn from_store(store: Arc<dyn Store>) -> Self {
+        Self {
+            #[cfg(feature = "transform")]
+            values: Values {
+                store: store.clone(),
+                serializer: None,
+                transformers: Vec::new(),
+                stats: None,
+            },
+            store,
+            stats: None,
+            clear_policy: ClearPolicy::default(),
+            key_hasher: Arc::new(SipKeyHasher::default()),
+            generator: Arc::new(UuidV7Generator),
+            default_ttl: None,
+            expiry_policy: ExpiryPolicy::default(),
+            namespaced: false,
+            change_feed: None,
+        }
+    }
+
+    /// Wraps the handle's store with `layer`, below the value pipeline if there is one.
+    fn with_layer(mut self, layer: impl FnOnce(Arc<dyn Store>) -> Arc<dyn Store>) -> Self {
+        #[cfg(feature = "transform")]
+        {
+            self.values.store = layer(self.values.store.clone());
+            self.store = self.values.build();
+        }
+        #[cfg(not(feature = "transform"))]
+        {
+            self.store = layer(self.store);
+        }
+        self
+    }
+
+    /// Limits the number of store operations this instance runs concurrently.
+    ///
+    /// Operations beyond the limit either wait for a free slot or fail with
+    /// `StoreError::ConcurrencyLimitExceeded`, depending on `mode`. This keeps a traffic
+    /// spike from exhausting a connection pool shared with the rest of the application.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_in_flight` - Maximum number of concurrent store operations.
+    /// * `mode` - Whether excess operations are queued or rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, layer::concurrency::ConcurrencyMode};
+    /// # async {
+    /// let keyv = Keyv::default().with_concurrency_limit(32, ConcurrencyMode::FailFast);
+    /// keyv.set("key", "value").await.unwrap();
+    /// # };
+    /// ```
+    pub fn with_concurrency_limit(self, max_in_flight: usize, mode: ConcurrencyMode) -> Self {
+        self.with_layer(|store| Arc::new(ConcurrencyLimitStore::new(store, max_in_flight, mode)))
+    }
+
+    /// Maps every key through `codec` before it reaches the store.
+    ///
+    /// Keeps key namespacing, escaping and hashing in one place instead of relying on
+    /// each adapter's own conventions. Codecs compose as tuples and are applied left to
+    /// right.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, layer::key_codec::{CaseFoldCodec, PrefixCodec}};
+    /// # async {
+    /// let keyv = Keyv::default().with_key_codec((CaseFoldCodec, PrefixCodec::new("users")));
+    /// keyv.set("Alice", "profile").await.unwrap();
+    /// assert!(keyv.get("alice").await.unwrap().is_some());
+    /// # };
+    /// ```
+    pub fn with_key_codec<C: KeyCodec + 'static>(mut self, codec: C) -> Self {
+        let codec = Arc::new(codec);
+        if let Some(feed) = self.change_feed.take() {
+            self.change_feed = Some(Arc::new(KeyCodecStore::new(feed, codec.clone())));
+        }
+        self.with_layer(|store| Arc::new(KeyCodecStore::new(store, codec)))
+    }
+
+    /// Reads `changes` from the change feed of `feed`, usually the adapter wrapped in a
+    /// `layer::change_feed::ChangeFeedStore` by this handle.
+    ///
+    /// The feed goes through the handle's key codecs, including those added later by
+    /// `with_key_codec` or `namespace`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use keyv::{Keyv, adapter::inmemory::InMemoryStore, layer::change_feed::ChangeFeedStore};
+    /// # async {
+    /// let backend = Arc::new(InMemoryStore::new());
+    /// let keyv = Keyv::try_new(ChangeFeedStore::new(backend.clone()))
+    ///     .await
+    ///     .unwrap()
+    ///     .with_change_feed(backend);
+    /// # };
+    /// ```
+    pub fn with_change_feed<B: ChangeFeedBackend + 'static>(mut self, feed: B) -> Self {
+        self.change_feed = Some(Arc::new(feed));
+        self
+    }
+
+    /// Passes every value through a pipeline of `transformers`, such as compression or
+    /// encryption, applied in order before it reaches the store, see `TransformStore`.
+    ///
+    /// Replaces the transformers the handle had, including those a namespaced handle
+    /// inherits, so each namespace of a store can use its own pipeline. The pipeline
+    /// wraps every other layer of the handle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "compression")]
+    /// # async {
+    /// # use std::sync::Arc;
+    /// # use keyv::{Keyv, layer::compression::Gzip};
+    /// let keyv = Keyv::default().with_transformers([Arc::new(Gzip::new()) as _]);
+    /// keyv.set("report", "x".repeat(10_000)).await.unwrap();
+    /// # };
+    /// ```
+    #[cfg(feature = "transform")]
+    pub fn with_transformers(
+        mut self,
+        transformers: impl IntoIterator<Item = Arc<dyn Transformer>>,
+    ) -> Self {
+        self.values.transformers = transformers.into_iter().collect();
+        self.store = self.values.build();
+        self
+    }
+
+    /// Serializes values with `serializer` instead of JSON before they are transformed,
+    /// see `TransformStore::serializer`. Replaces any serializer the handle inherits.
+    ///
+    /// Values written this way are always enveloped, so `increment` cannot be used on
+    /// the handle.
+    #[cfg(feature = "transform")]
+    pub fn with_serializer<S: Serializer + 'static>(mut self, serializer: S) -> Self {
+        self.values.serializer = Some(Arc::new(serializer));
+        self.store = self.values.build();
+        self
+    }
+
+    /// Stores values as plain JSON, dropping the serializer and transformers the handle
+    /// inherited, e.g. for a namespace of counters under a compressed handle.
+    ///
+    /// Values transformed by another handle are still read, as long as this handle
+    /// knows their transformers; with none, reading them fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "compression")]
+    /// # async {
+    /// # use std::sync::Arc;
+    /// # use keyv::{Keyv, layer::compression::Gzip};
+    /// let keyv = Keyv::default().with_transformers([Arc::new(Gzip::new()) as _]);
+    /// let counters = keyv.namespace("counters").with_raw_values();
+    /// counters.increment("visits", 1, None).await.unwrap();
+    /// # };
+    /// ```
+    #[cfg(feature = "transform")]
+    pub fn with_raw_values(mut self) -> Self {
+        self.values.serializer = None;
+        self.values.transformers.clear();
+        self.store = self.values.build();
+        self
+    }
+
+    /// Enables hit/miss counters and per-operation latency histograms.
+    ///
+    /// The collected data is available through `stats()` and `latency_report()`. With
+    /// a value pipeline, the size of values before and after it is counted too, see
+    /// `Stats::compression_ratio`; call `with_stats` on a namespaced handle to count
+    /// its namespace apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default().with_stats();
+    /// keyv.get("key").await.unwrap();
+    ///
+    /// let report = keyv.latency_report().unwrap();
+    /// println!("get p99: {:?}", report.get.p99);
+    /// # };
+    /// ```
+    pub fn with_stats(mut self) -> Self {
+        let stats = Arc::new(Stats::new());
+        #[cfg(feature = "transform")]
+        {
+            self.values.stats = Some(stats.clone());
+        }
+        self.stats = Some(stats.clone());
+        self.with_layer(|store| Arc::new(StatsStore::new(store, stats)))
+    }
+
+    /// Guards `clear` against wiping the store by accident.
+    ///
+    /// With `ClearPolicy::RequireConfirmation`, the store can only be wiped by first
+    /// calling `clear_dry_run` and passing its token to `clear_confirmed`; with
+    /// `ClearPolicy::Deny`, not at all. Meant for handles on production or read-mostly
+    /// stores.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{ClearPolicy, Keyv};
+    /// # async {
+    /// let keyv = Keyv::default().with_clear_policy(ClearPolicy::RequireConfirmation);
+    /// assert!(keyv.clear().await.is_err());
+    ///
+    /// let preview = keyv.clear_dry_run().await.unwrap();
+    /// println!("Removing {:?} entries", preview.entries);
+    /// keyv.clear_confirmed(preview.token).await.unwrap();
+    /// # };
+    /// ```
+    pub fn with_clear_policy(self, policy: ClearPolicy) -> Self {
+        Self {
+            clear_policy: policy,
+            ..self
+        }
+    }
+
+    /// Replaces the `SipKeyHasher` that `key_for` derives keys with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, SipKeyHasher};
+    /// let keyv = Keyv::default().with_key_hasher(SipKeyHasher::with_keys(1, 2));
+    /// ```
+    pub fn with_key_hasher<H: KeyHasher + 'static>(self, hasher: H) -> Self {
+        Self {
+            key_hasher: Arc::new(hasher),
+            ..self
+        }
+    }
+
+    /// Replaces the `UuidV7Generator` that lease tokens are drawn from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, SequentialGenerator};
+    /// let keyv = Keyv::default().with_generator(SequentialGenerator::new());
+    /// ```
+    pub fn with_generator<G: Generator + 'static>(self, generator: G) -> Self {
+        Self {
+            generator: Arc::new(generator),
+            ..self
+        }
+    }
+
+    /// Expires values written without an explicit TTL after `ttl`.
+    ///
+    /// Applies to `set`, and to `set_with_priority` and `set_if_absent` when they are
+    /// given no TTL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::Keyv;
+    /// let keyv = Keyv::default().with_default_ttl(Duration::from_secs(600));
+    /// ```
+    pub fn with_default_ttl(self, ttl: Duration) -> Self {
+        Self {
+            default_ttl: Some(ttl),
+            ..self
+        }
+    }
+
+    /// Selects whether entries expire a fixed time after being written or after being
+    /// idle.
+    ///
+    /// With `ExpiryPolicy::TimeToIdle`, `get`, `get_entry` and `get_many` reset the
+    /// expiration of every entry they read, and writes without an explicit TTL expire
+    /// after the idle duration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::{ExpiryPolicy, Keyv};
+    /// # async {
+    /// let sessions =
+    ///     Keyv::default().with_expiry_policy(ExpiryPolicy::TimeToIdle(Duration::from_secs(1800)));
+    /// sessions.set("session:1", "alice").await.unwrap();
+    ///
+    /// // Each read keeps the session alive for another 30 minutes.
+    /// sessions.get("session:1").await.unwrap();
+    /// # };
+    /// ```
+    pub fn with_expiry_policy(self, policy: ExpiryPolicy) -> Self {
+        Self {
+            expiry_policy: policy,
+            ..self
+        }
+    }
+
+    /// Returns a handle whose keys live under `namespace`, sharing this handle's store.
+    ///
+    /// Keys are stored as `namespace:key`, so subsystems of one application can share a
+    /// store without colliding. The handle starts with this handle's settings and is
+    /// configured independently afterwards, e.g. with its own default TTL or value
+    /// pipeline. `clear` on the handle only removes the keys in its namespace, which
+    /// requires a store that can scan its keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The prefix placed before every key of the handle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let emails = keyv.namespace("emails").with_default_ttl(Duration::from_secs(600));
+    ///
+    /// emails.set("alice", "queued").await.unwrap();
+    /// assert!(keyv.get("emails:alice").await.unwrap().is_some());
+    /// # };
+    /// ```
+    pub fn namespace(&self, namespace: &str) -> Keyv {
+        Keyv {
+            store: self.store.clone(),
+            stats: self.stats.clone(),
+            clear_policy: self.clear_policy,
+            key_hasher: self.key_hasher.clone(),
+            generator: self.generator.clone(),
+            default_ttl: self.default_ttl,
+            expiry_policy: self.expiry_policy,
+            namespaced: true,
+            change_feed: self.change_feed.clone(),
+            #[cfg(feature = "transform")]
+            values: self.values.clone(),
+        }
+        .with_key_codec(PrefixCodec::new(namespace))
+    }
+
+    /// Returns the TTL of writes that do not pass one.
+    fn default_ttl(&self) -> Option<Duration> {
+        self.expiry_policy.idle_timeout().or(self.default_ttl)
+    }
+
+    /// Returns the statistics collected since `with_stats()` was called, if enabled.
+    pub fn stats(&self) -> Option<&Stats> {
+        self.stats.as_deref()
+    }
+
+    /// Returns p50/p95/p99 latencies per operation, if stats are enabled.
+    pub fn latency_report(&self) -> Option<LatencyReport> {
+        self.stats.as_ref().map(|stats| stats.latency_report())
     }
 
     /// Sets a value for a given key without a TTL.
@@ -129,7 +609,11 @@ impl Keyv {
     /// # };
     /// ```
     pub async fn set<T: Serialize>(&self, key: &str, value: T) -> Result<(), KeyvError> {
-        Ok(self.store.set(key, json!(value), None).await?)
+        validate_key(key)?;
+        Ok(self
+            .store
+            .set(key, json!(value), self.default_ttl())
+            .await?)
     }
 
     /// Sets a value for a given key with an expiry TTL (Time-To-Live).
@@ -159,109 +643,1451 @@ impl Keyv {
         value: T,
         ttl: u64,
     ) -> Result<(), KeyvError> {
-        Ok(self.store.set(key, json!(value), Some(ttl)).await?)
+        self.set_for(key, value, Duration::from_secs(ttl)).await
     }
 
-    /// Retrieves a value based on a key.
+    /// Sets a value for a given key that expires after `ttl`.
+    ///
+    /// Prefer this over `set_with_ttl` when the TTL is computed or shorter than a second;
+    /// stores keep expirations with millisecond precision.
     ///
     /// # Arguments
     ///
-    /// * `key` - A string slice that holds the key to retrieve the value for.
+    /// * `key` - A string slice that holds the key.
+    /// * `value` - The value to be stored, which must implement `Serialize`.
+    /// * `ttl` - How long the key-value pair lives.
     ///
     /// # Returns
     ///
-    /// Returns an `Ok` result with `Option<Value>` on success, where `None` indicates the
-    /// key does not exist, or a `KeyvError` on failure.
+    /// Returns an `Ok` result on successful insertion, or a `KeyvError` on failure.
     ///
     /// # Examples
     ///
     /// ```
+    /// # use std::time::Duration;
     /// # use keyv::Keyv;
     /// # async {
     /// let keyv = Keyv::default();
-    ///
-    /// keyv.set("array", vec!["hola", "test"]).await.unwrap();
-    ///
-    /// match keyv.get("array").await.unwrap() {
-    ///     Some(array) => {
-    ///         let array: Vec<String> = serde_json::from_value(array).unwrap();
-    ///         assert_eq!(array, vec!["hola".to_string(), "test".to_string()])
-    ///     }
-    ///     None => assert!(false),
-    /// }
-    ///
-    /// keyv.set("string", "life long").await.unwrap();
-    /// match keyv.get("string").await.unwrap() {
-    ///     Some(string) => {
-    ///         let string: String = serde_json::from_value(string).unwrap();
-    ///         assert_eq!(string, "life long");
-    ///     }
-    ///     None => assert!(false),
-    /// }
-    ///
+    /// keyv.set_for("otp", "123456", Duration::from_millis(1500)).await.unwrap();
     /// # };
     /// ```
-    pub async fn get(&self, key: &str) -> Result<Option<Value>, KeyvError> {
-        Ok(self.store.get(key).await?)
+    pub async fn set_for<T: Serialize>(
+        &self,
+        key: &str,
+        value: T,
+        ttl: Duration,
+    ) -> Result<(), KeyvError> {
+        validate_key(key)?;
+        Ok(self.store.set(key, json!(value), Some(ttl)).await?)
     }
 
-    /// Removes a specified key from the store.
+    /// Sets a value for a given key that expires at a fixed wall-clock time.
+    ///
+    /// Stores that can persist absolute expirations (Redis, SQL and MongoDB) do so, so
+    /// the expiry is not shifted by how long the write takes. A time in the past removes
+    /// the key.
     ///
     /// # Arguments
     ///
-    /// * `key` - A string slice that represents the key to be removed.
+    /// * `key` - A string slice that holds the key.
+    /// * `value` - The value to be stored, which must implement `Serialize`.
+    /// * `expires_at` - The time at which the key-value pair expires.
     ///
     /// # Returns
     ///
-    /// Returns an `Ok` result if the key has been successfully removed, or a `KeyvError`
-    /// on failure.
+    /// Returns an `Ok` result on successful insertion, or a `KeyvError` on failure.
     ///
     /// # Examples
     ///
     /// ```
+    /// # use std::time::{Duration, SystemTime};
     /// # use keyv::Keyv;
     /// # async {
     /// let keyv = Keyv::default();
-    /// keyv.remove("my_key").await.unwrap(); // Removes "my_key" from the store
+    /// let campaign_end = SystemTime::now() + Duration::from_secs(7 * 24 * 3600);
+    /// keyv.set_until("banner", "spring sale", campaign_end).await.unwrap();
     /// # };
     /// ```
-    pub async fn remove(&self, key: &str) -> Result<(), KeyvError> {
-        Ok(self.store.remove(key).await?)
+    pub async fn set_until<T: Serialize>(
+        &self,
+        key: &str,
+        value: T,
+        expires_at: SystemTime,
+    ) -> Result<(), KeyvError> {
+        validate_key(key)?;
+        Ok(self.store.set_until(key, json!(value), expires_at).await?)
     }
 
-    /// Removes multiple keys from the store in one operation.
+    /// Replaces the value of a key without touching its expiration.
+    ///
+    /// Useful to refresh cached data while keeping the TTL it was first stored with. A
+    /// key that does not exist, or has expired, is stored without an expiration. Redis
+    /// requires version 6.0 or later.
     ///
     /// # Arguments
     ///
-    /// * `keys` - A slice of strings or string-like objects that represent the keys to be removed.
+    /// * `key` - A string slice that holds the key.
+    /// * `value` - The value to be stored, which must implement `Serialize`.
     ///
     /// # Returns
     ///
-    /// Returns an `Ok` result if the keys have been successfully removed, or a `KeyvError`
-    /// on failure.
+    /// Returns an `Ok` result on successful insertion, or a `KeyvError` on failure.
     ///
     /// # Examples
     ///
     /// ```
+    /// # use std::time::Duration;
     /// # use keyv::Keyv;
     /// # async {
     /// let keyv = Keyv::default();
-    /// keyv.remove_many(&["key1", "key2"]).await.unwrap(); // Removes "key1" and "key2"
+    /// keyv.set_for("session", "v1", Duration::from_secs(900)).await.unwrap();
+    /// keyv.set_keep_ttl("session", "v2").await.unwrap();
     /// # };
     /// ```
-    pub async fn remove_many<T: AsRef<str> + Sync>(&self, keys: &[T]) -> Result<(), KeyvError> {
-        let keys: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
-        Ok(self.store.remove_many(&keys).await?)
+    pub async fn set_keep_ttl<T: Serialize>(&self, key: &str, value: T) -> Result<(), KeyvError> {
+        validate_key(key)?;
+        Ok(self.store.set_keep_ttl(key, json!(value)).await?)
     }
 
-    /// Clears the entire store, removing all key-value pairs.
-    ///
-    /// # Returns
+    /// Stores a value with an eviction priority.
     ///
-    /// Returns an `Ok` result if the store has been successfully cleared, or a `KeyvError`
-    /// on failure.
+    /// Capacity-bounded stores evict lower-priority entries first, so values that are
+    /// expensive to recompute can be kept over cheap ones. Unbounded stores ignore the
+    /// priority.
     ///
-    /// # Examples
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key.
+    /// * `value` - The value to be stored, which must implement `Serialize`.
+    /// * `ttl` - An optional duration after which the value expires.
+    /// * `priority` - How reluctantly the entry should be evicted.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result on successful insertion, or a `KeyvError` on failure.
+    pub async fn set_with_priority<T: Serialize>(
+        &self,
+        key: &str,
+        value: T,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), KeyvError> {
+        validate_key(key)?;
+        Ok(self
+            .store
+            .set_with_priority(key, json!(value), ttl.or(self.default_ttl()), priority)
+            .await?)
+    }
+
+    /// Sets several key-value pairs at once.
+    ///
+    /// The SQL stores write the entries in one transaction and Redis in one pipeline,
+    /// which is much faster than a `set` per key when seeding a cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The key-value pairs to store. Values must implement `Serialize`.
+    /// * `ttl` - An optional duration after which every entry expires, the default TTL
+    ///   if `None`.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result on successful insertion, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let users = [("user:1", "alice"), ("user:2", "bob")];
+    /// keyv.set_many(&users, Some(Duration::from_secs(3600))).await.unwrap();
+    /// # };
+    /// ```
+    pub async fn set_many<K: AsRef<str> + Sync, T: Serialize>(
+        &self,
+        entries: &[(K, T)],
+        ttl: Option<Duration>,
+    ) -> Result<(), KeyvError> {
+        let entries: Vec<(&str, Value)> = entries
+            .iter()
+            .map(|(key, value)| (key.as_ref(), json!(value)))
+            .collect();
+        for (key, _) in &entries {
+            validate_key(key)?;
+        }
+        Ok(self
+            .store
+            .set_many(&entries, ttl.or(self.default_ttl()))
+            .await?)
+    }
+
+    /// Retrieves a value based on a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve the value for.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with `Option<Value>` on success, where `None` indicates the
+    /// key does not exist, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    ///
+    /// keyv.set("array", vec!["hola", "test"]).await.unwrap();
+    ///
+    /// match keyv.get("array").await.unwrap() {
+    ///     Some(array) => {
+    ///         let array: Vec<String> = serde_json::from_value(array).unwrap();
+    ///         assert_eq!(array, vec!["hola".to_string(), "test".to_string()])
+    ///     }
+    ///     None => assert!(false),
+    /// }
+    ///
+    /// keyv.set("string", "life long").await.unwrap();
+    /// match keyv.get("string").await.unwrap() {
+    ///     Some(string) => {
+    ///         let string: String = serde_json::from_value(string).unwrap();
+    ///         assert_eq!(string, "life long");
+    ///     }
+    ///     None => assert!(false),
+    /// }
+    ///
+    /// # };
+    /// ```
+    pub async fn get(&self, key: &str) -> Result<Option<Value>, KeyvError> {
+        validate_key(key)?;
+        match self.expiry_policy.idle_timeout() {
+            Some(idle) => Ok(self.store.get_and_touch(key, idle).await?),
+            None => Ok(self.store.get(key).await?),
+        }
+    }
+
+    /// Retrieves a value as a shared reference.
+    ///
+    /// Prefer it over `get` for large values read often: the in-memory store hands out
+    /// the value it holds instead of copying it. Other stores, and handles with an idle
+    /// timeout, read the value as `get` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve the value for.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with `Option<Arc<Value>>` on success, where `None`
+    /// indicates the key does not exist, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("catalog", vec!["book"; 10_000]).await.unwrap();
+    ///
+    /// let catalog = keyv.get_ref("catalog").await.unwrap().unwrap();
+    /// assert_eq!(catalog.as_array().unwrap().len(), 10_000);
+    /// # };
+    /// ```
+    pub async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, KeyvError> {
+        validate_key(key)?;
+        match self.expiry_policy.idle_timeout() {
+            Some(idle) => Ok(self.store.get_and_touch(key, idle).await?.map(Arc::new)),
+            None => Ok(self.store.get_ref(key).await?),
+        }
+    }
+
+    /// Retrieves the values of several keys.
+    ///
+    /// Stores that support multi-key reads, such as Redis with `MGET`, fetch all keys in
+    /// one round trip.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to retrieve the values for.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with one `Option<Value>` per key, in the order of `keys`,
+    /// or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user:1", "alice").await.unwrap();
+    ///
+    /// let users = keyv.get_many(&["user:1", "user:2"]).await.unwrap();
+    /// assert_eq!(users, vec![Some(serde_json::json!("alice")), None]);
+    /// # };
+    /// ```
+    pub async fn get_many<T: AsRef<str> + Sync>(
+        &self,
+        keys: &[T],
+    ) -> Result<Vec<Option<Value>>, KeyvError> {
+        let keys: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
+        for key in &keys {
+            validate_key(key)?;
+        }
+        let Some(idle) = self.expiry_policy.idle_timeout() else {
+            return Ok(self.store.get_many(&keys).await?);
+        };
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(self.store.get_and_touch(key, idle).await?);
+        }
+        Ok(values)
+    }
+
+    /// Retrieves the values of several keys as a map from key to value.
+    ///
+    /// Like `get_many`, but only the keys that were found are present in the map.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to retrieve the values for.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the found entries, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user:1", "alice").await.unwrap();
+    ///
+    /// let users = keyv.get_map(&["user:1", "user:2"]).await.unwrap();
+    /// assert_eq!(users.len(), 1);
+    /// assert_eq!(users["user:1"], serde_json::json!("alice"));
+    /// # };
+    /// ```
+    pub async fn get_map<T: AsRef<str> + Sync>(
+        &self,
+        keys: &[T],
+    ) -> Result<HashMap<String, Value>, KeyvError> {
+        let values = self.get_many(keys).await?;
+        Ok(keys
+            .iter()
+            .zip(values)
+            .filter_map(|(key, value)| Some((key.as_ref().to_string(), value?)))
+            .collect())
+    }
+
+    /// Retrieves a value and resets its expiration to `ttl` from now.
+    ///
+    /// Gives keys a sliding expiration: a session read on every request stays alive
+    /// until it has been idle for `ttl`. Redis does this in a single `GETEX` command.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve the value for.
+    /// * `ttl` - The duration after which the value expires, counted from now.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with `Option<Value>` on success, where `None` indicates the
+    /// key does not exist, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_for("session", "data", Duration::from_secs(60)).await.unwrap();
+    ///
+    /// let session = keyv.get_and_touch("session", Duration::from_secs(60)).await.unwrap();
+    /// assert_eq!(session, Some(serde_json::json!("data")));
+    /// # };
+    /// ```
+    pub async fn get_and_touch(
+        &self,
+        key: &str,
+        ttl: Duration,
+    ) -> Result<Option<Value>, KeyvError> {
+        validate_key(key)?;
+        Ok(self.store.get_and_touch(key, ttl).await?)
+    }
+
+    /// Resets the expiration of several keys to `ttl` from now, without reading them.
+    ///
+    /// Lets a session sweeper extend every active session in one operation: Redis sends
+    /// one pipeline of `PEXPIRE` commands and the SQL stores run a single `UPDATE`.
+    /// Missing and expired keys are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - A slice of strings or string-like objects that represent the keys to extend.
+    /// * `ttl` - The duration after which the keys expire, counted from now.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the number of keys that were extended, or a `KeyvError`
+    /// on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_for("session:1", "alice", Duration::from_secs(60)).await.unwrap();
+    /// keyv.set_for("session:2", "bob", Duration::from_secs(60)).await.unwrap();
+    ///
+    /// let sessions = ["session:1", "session:2", "session:3"];
+    /// let touched = keyv.touch_many(&sessions, Duration::from_secs(600)).await.unwrap();
+    /// assert_eq!(touched, 2);
+    /// # };
+    /// ```
+    pub async fn touch_many<T: AsRef<str> + Sync>(
+        &self,
+        keys: &[T],
+        ttl: Duration,
+    ) -> Result<u64, KeyvError> {
+        let keys: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
+        for key in &keys {
+            validate_key(key)?;
+        }
+        Ok(self.store.touch_many(&keys, ttl).await?)
+    }
+
+    /// Retrieves a value, telling a stored `null` apart from a missing key.
+    ///
+    /// Use it to cache legitimate "no result" answers: store `None` and check for
+    /// `Entry::Null` instead of treating every `null` as a miss.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve the value for.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Entry::Missing`, `Entry::Null` or `Entry::Value`, or a `KeyvError` on
+    /// failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Entry, Keyv};
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user:42:avatar", None::<String>).await.unwrap();
+    ///
+    /// assert_eq!(keyv.get_entry("user:42:avatar").await.unwrap(), Entry::Null);
+    /// assert_eq!(keyv.get_entry("user:43:avatar").await.unwrap(), Entry::Missing);
+    /// # };
+    /// ```
+    pub async fn get_entry(&self, key: &str) -> Result<Entry, KeyvError> {
+        Ok(self.get(key).await?.into())
+    }
+
+    /// Retrieves a value together with when it was written and when it expires.
+    ///
+    /// Every built-in store reports the expiration, from which `Metadata::expires_in`
+    /// derives the remaining TTL. The SQL stores also record when the key was first
+    /// written and last updated; other stores report those times as `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve the value for.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the value and its `Metadata`, `None` if the key
+    /// does not exist, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("report", "ready").await.unwrap();
+    ///
+    /// if let Some((_, metadata)) = keyv.get_with_metadata("report").await.unwrap() {
+    ///     println!("cached at {:?}", metadata.created_at);
+    ///     println!("fresh for another {:?}", metadata.expires_in());
+    /// }
+    /// # };
+    /// ```
+    pub async fn get_with_metadata(
+        &self,
+        key: &str,
+    ) -> Result<Option<(Value, Metadata)>, KeyvError> {
+        validate_key(key)?;
+        Ok(self.store.get_with_metadata(key).await?)
+    }
+
+    /// Retrieves a value even if it has expired, telling how long ago it did.
+    ///
+    /// Instead of treating an expired value as missing, the store returns it as long as
+    /// it still holds it, without removing it, so callers can apply their own
+    /// staleness policy, e.g. serve a value that expired less than a minute ago while
+    /// refreshing it. Stores that drop expired entries on their own, such as Redis,
+    /// never return stale values.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to retrieve the value for.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with `StaleRead::Fresh`, `StaleRead::Stale` or
+    /// `StaleRead::Missing`, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::{Keyv, StaleRead};
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set_for("rates", "1.08", Duration::from_millis(10)).await.unwrap();
+    /// tokio::time::sleep(Duration::from_millis(20)).await;
+    ///
+    /// match keyv.get_allow_stale("rates").await.unwrap() {
+    ///     StaleRead::Fresh(rates) => println!("rates: {}", rates),
+    ///     StaleRead::Stale(rates, expired_by) => println!("rates {:?} old: {}", expired_by, rates),
+    ///     StaleRead::Missing => println!("no rates"),
+    /// }
+    /// # };
+    /// ```
+    pub async fn get_allow_stale(&self, key: &str) -> Result<StaleRead, KeyvError> {
+        validate_key(key)?;
+        let Some((value, metadata)) = self.store.get_stale(key).await? else {
+            return Ok(StaleRead::Missing);
+        };
+        let expired_by = metadata
+            .expires_at
+            .and_then(|expires_at| SystemTime::now().duration_since(expires_at).ok());
+        Ok(match expired_by {
+            Some(expired_by) => StaleRead::Stale(value, expired_by),
+            None => StaleRead::Fresh(value),
+        })
+    }
+
+    /// Retrieves the values previously written under a key, newest first.
+    ///
+    /// Requires a store that keeps a history, such as one wrapped in
+    /// `layer::history::HistoryStore`; other stores return `StoreError::Unsupported`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key whose history is retrieved.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the recorded `Version`s, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::{Keyv, adapter::inmemory::InMemoryStore, layer::history::HistoryStore};
+    /// # async {
+    /// let store = HistoryStore::new(InMemoryStore::new(), InMemoryStore::new(), 10);
+    /// let keyv = Keyv::try_new(store).await.unwrap();
+    /// keyv.set("feature_flags", ["search"]).await.unwrap();
+    ///
+    /// for version in keyv.get_versions("feature_flags").await.unwrap() {
+    ///     println!("{:?}: {}", version.recorded_at, version.value);
+    /// }
+    /// # };
+    /// ```
+    pub async fn get_versions(&self, key: &str) -> Result<Vec<Version>, KeyvError> {
+        validate_key(key)?;
+        Ok(self.store.get_versions(key).await?)
+    }
+
+    /// Reads the change feed after `since`, oldest first.
+    ///
+    /// Requires a feed set with `with_change_feed`, whose mutations are recorded by a
+    /// `layer::change_feed::ChangeFeedStore`. Up to `DEFAULT_CHANGES_LIMIT` changes are
+    /// returned per call; pass the cursor of the last one to read the next page. An
+    /// empty result means the consumer has caught up. For a namespaced handle, only the
+    /// changes to keys of its namespace are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - The cursor of the last change already processed, or `None` to read
+    ///   from the start of the feed.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result with the changes, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use keyv::{Keyv, adapter::inmemory::InMemoryStore, layer::change_feed::ChangeFeedStore};
+    /// # async {
+    /// let backend = Arc::new(InMemoryStore::new());
+    /// let keyv = Keyv::try_new(ChangeFeedStore::new(backend.clone()))
+    ///     .await
+    ///     .unwrap()
+    ///     .with_change_feed(backend);
+    /// keyv.set("config", "v1").await.unwrap();
+    ///
+    /// let mut cursor = None;
+    /// loop {
+    ///     let changes = keyv.changes(cursor.as_deref()).await.unwrap();
+    ///     let Some(last) = changes.last() else { break };
+    ///     cursor = Some(last.cursor.clone());
+    ///     for change in &changes {
+    ///         println!("{:?} {:?}", change.kind, change.key);
+    ///     }
+    /// }
+    /// # };
+    /// ```
+    pub async fn changes(&self, since: Option<&str>) -> Result<Vec<Change>, KeyvError> {
+        let Some(feed) = &self.change_feed else {
+            return Err(StoreError::Unsupported("read_changes").into());
+        };
+        Ok(feed.read_changes(since, DEFAULT_CHANGES_LIMIT).await?)
+    }
+
+    /// Streams the values of `keys`, reading them `batch_size` keys at a time.
+    ///
+    /// Unlike `get_many`, only one batch of values is held in memory at a time, so
+    /// tens of thousands of keys can be read without buffering every result. Each batch
+    /// is a single `get_many` call on the store. Results are yielded in the order of
+    /// `keys`.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to read.
+    /// * `batch_size` - The number of keys to read per round trip; `0` is treated as `1`.
+    ///
+    /// # Returns
+    ///
+    /// A stream of `(key, value)` pairs, ending after the last key or the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use futures::TryStreamExt;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user:1", "alice").await.unwrap();
+    ///
+    /// let mut users = keyv.get_many_stream(["user:1", "user:2"], 500);
+    /// while let Some((key, value)) = users.try_next().await.unwrap() {
+    ///     println!("{}: {:?}", key, value);
+    /// }
+    /// # };
+    /// ```
+    pub fn get_many_stream<I>(
+        &self,
+        keys: I,
+        batch_size: usize,
+    ) -> impl Stream<Item = Result<(String, Option<Value>), KeyvError>> + Unpin + '_
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let keys: Vec<String> = keys.into_iter().map(|k| k.as_ref().to_string()).collect();
+        let batches: Vec<Vec<String>> = keys
+            .chunks(batch_size.max(1))
+            .map(<[String]>::to_vec)
+            .collect();
+        Box::pin(stream::unfold(
+            batches.into_iter(),
+            move |mut batches| async move {
+                let batch = batches.next()?;
+                match self.get_many(&batch).await {
+                    Ok(values) => Some((Ok(batch.into_iter().zip(values).collect()), batches)),
+                    // Stop after the first failed batch.
+                    Err(e) => Some((Err(e), Vec::new().into_iter())),
+                }
+            },
+        ))
+        .flat_map(|batch: Result<Vec<_>, KeyvError>| {
+            stream::iter(match batch {
+                Ok(entries) => entries.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            })
+        })
+    }
+
+    /// Reads `keys` ahead of an expected burst of reads, at most `concurrency` at a time.
+    ///
+    /// Fetching the keys primes every layer between `Keyv` and the backend, such as
+    /// pooled connections and prepared statements, and lets the caller warm a local
+    /// cache from the results. Each key is yielded with its result as soon as it
+    /// completes, so results are not in the order of `keys`.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to read.
+    /// * `concurrency` - Maximum number of reads in flight; `0` is treated as `1`.
+    ///
+    /// # Returns
+    ///
+    /// A stream of `(key, result)` pairs, one per key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use futures::StreamExt;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user:1", "alice").await.unwrap();
+    ///
+    /// let mut results = keyv.prefetch(["user:1", "user:2"], 8);
+    /// while let Some((key, result)) = results.next().await {
+    ///     println!("{}: {:?}", key, result.unwrap());
+    /// }
+    /// # };
+    /// ```
+    pub fn prefetch<I>(
+        &self,
+        keys: I,
+        concurrency: usize,
+    ) -> impl Stream<Item = (String, Result<Option<Value>, KeyvError>)> + '_
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let keys: Vec<String> = keys.into_iter().map(|k| k.as_ref().to_string()).collect();
+        stream::iter(keys)
+            .map(move |key| async move {
+                let result = self.get(&key).await;
+                (key, result)
+            })
+            .buffer_unordered(concurrency.max(1))
+    }
+
+    /// Streams the live entries whose key starts with `prefix`, in batches.
+    ///
+    /// Only one batch is held in memory at a time, so exports, migrations and custom
+    /// cleanup jobs can walk large keyspaces. Stores that cannot enumerate their keys
+    /// yield a single `StoreError::Unsupported`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Only keys starting with this prefix are returned, or all keys if `None`.
+    /// * `batch_size` - The number of entries to fetch per round trip.
+    ///
+    /// # Returns
+    ///
+    /// A stream of entry batches, ending after the last batch or the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use futures::TryStreamExt;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user:1", "alice").await.unwrap();
+    ///
+    /// let mut batches = keyv.scan(Some("user:"), 500);
+    /// while let Some(batch) = batches.try_next().await.unwrap() {
+    ///     for entry in batch {
+    ///         println!("{} = {}", entry.key, entry.value);
+    ///     }
+    /// }
+    /// # };
+    /// ```
+    pub fn scan<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> impl Stream<Item = Result<Vec<ScanEntry>, KeyvError>> + 'a {
+        self.store
+            .scan_entries(prefix, batch_size)
+            .map(|batch| batch.map_err(KeyvError::from))
+    }
+
+    /// Streams the live entries whose key matches a glob pattern.
+    ///
+    /// `*` matches any sequence of characters, `?` a single character, and `\` makes
+    /// the next character match literally. Matching runs on the backend where possible:
+    /// Redis `MATCH`, SQL `LIKE` or `GLOB`, and MongoDB regular expressions. Stores that
+    /// cannot enumerate their keys yield a single `StoreError::Unsupported`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The glob pattern keys must match.
+    ///
+    /// # Returns
+    ///
+    /// A stream of `(key, value)` pairs, ending after the last match or the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use futures::TryStreamExt;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user:1:profile", "alice").await.unwrap();
+    /// keyv.set("user:1:settings", "dark").await.unwrap();
+    ///
+    /// let profiles: Vec<_> = keyv.find("user:*:profile").try_collect().await.unwrap();
+    /// assert_eq!(profiles.len(), 1);
+    /// # };
+    /// ```
+    pub fn find<'a>(
+        &'a self,
+        pattern: &'a str,
+    ) -> impl Stream<Item = Result<(String, Value), KeyvError>> + 'a {
+        self.store
+            .find_entries(pattern, DEFAULT_SCAN_BATCH_SIZE)
+            .flat_map(|batch| {
+                let entries: Vec<_> = match batch {
+                    Ok(batch) => batch
+                        .into_iter()
+                        .map(|entry| Ok((entry.key, entry.value)))
+                        .collect(),
+                    Err(e) => vec![Err(KeyvError::from(e))],
+                };
+                stream::iter(entries)
+            })
+    }
+
+    /// Derives a cache key from a request object.
+    ///
+    /// The request is serialized to JSON, with object keys sorted, and hashed with the
+    /// configured `KeyHasher`. Unlike keys built from `std::hash::Hash`, the result is
+    /// the same on every platform and across releases of Rust and of this crate, so
+    /// several versions of a service share their cache entries.
+    ///
+    /// # Returns
+    ///
+    /// Returns the key, or a `KeyvError` if the request cannot be serialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let request = serde_json::json!({ "query": "rust", "page": 2 });
+    ///
+    /// let key = format!("search:{}", keyv.key_for(&request).unwrap());
+    /// keyv.set(&key, vec!["keyv"]).await.unwrap();
+    /// # };
+    /// ```
+    pub fn key_for<T: Serialize + ?Sized>(&self, request: &T) -> Result<String, KeyvError> {
+        // Object keys are sorted explicitly, since `Value` keeps insertion order when
+        // another dependency enables serde_json's `preserve_order` feature.
+        let canonical = serde_json::to_value(request)
+            .and_then(|mut value| {
+                value.sort_all_objects();
+                serde_json::to_vec(&value)
+            })
+            .map_err(|source| StoreError::SerializationError { source })?;
+        Ok(self.key_hasher.hash(&canonical))
+    }
+
+    /// Sets a value for a given key only if the key is missing or has expired.
+    ///
+    /// The check and the write are atomic, so of several callers racing to claim the
+    /// same key exactly one succeeds.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key.
+    /// * `value` - The value to be stored, which must implement `Serialize`.
+    /// * `ttl` - An optional duration after which the value expires.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the value was set, `false` if the key already held a live value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// assert!(keyv.set_if_absent("job:42", "worker-1", None).await.unwrap());
+    /// assert!(!keyv.set_if_absent("job:42", "worker-2", None).await.unwrap());
+    /// # };
+    /// ```
+    pub async fn set_if_absent<T: Serialize>(
+        &self,
+        key: &str,
+        value: T,
+        ttl: Option<Duration>,
+    ) -> Result<bool, KeyvError> {
+        validate_key(key)?;
+        Ok(self
+            .store
+            .set_if_absent(key, json!(value), ttl.or(self.default_ttl()))
+            .await?)
+    }
+
+    /// Returns the value stored under `key`, or computes, stores and returns it if the
+    /// key is missing.
+    ///
+    /// `compute` only runs on a miss, and its value is stored with the default TTL of
+    /// the handle, if any; see `get_or_set_for` to choose the TTL. Concurrent callers
+    /// missing the same key each run `compute`, and the last value written wins.
+    ///
+    /// # Returns
+    ///
+    /// Returns the cached or computed value, or a `KeyvError` if the store fails, the
+    /// cached value does not deserialize to `T`, or `compute` fails, in which case
+    /// nothing is stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let name: String = keyv
+    ///     .get_or_set("user:1:name", || async { Ok::<_, std::io::Error>("alice".to_string()) })
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(name, "alice");
+    /// # };
+    /// ```
+    pub async fn get_or_set<T, F, Fut, E>(&self, key: &str, compute: F) -> Result<T, KeyvError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        self.get_or_set_with(key, self.default_ttl(), compute).await
+    }
+
+    /// Like `get_or_set`, storing the computed value for `ttl`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let rate: f64 = keyv
+    ///     .get_or_set_for("rate:eur:usd", Duration::from_secs(60), || async {
+    ///         Ok::<_, std::io::Error>(1.08)
+    ///     })
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(rate, 1.08);
+    /// # };
+    /// ```
+    pub async fn get_or_set_for<T, F, Fut, E>(
+        &self,
+        key: &str,
+        ttl: Duration,
+        compute: F,
+    ) -> Result<T, KeyvError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        self.get_or_set_with(key, Some(ttl), compute).await
+    }
+
+    async fn get_or_set_with<T, F, Fut, E>(
+        &self,
+        key: &str,
+        ttl: Option<Duration>,
+        compute: F,
+    ) -> Result<T, KeyvError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        if let Some(value) = self.get(key).await? {
+            return serde_json::from_value(value)
+                .map_err(|e| StoreError::SerializationError { source: e }.into());
+        }
+        let value = compute()
+            .await
+            .map_err(|e| KeyvError::LoaderError(e.into()))?;
+        self.store.set(key, json!(value), ttl).await?;
+        Ok(value)
+    }
+
+    /// Atomically adds `delta` to the integer stored under `key` and returns the result.
+    ///
+    /// A missing or expired key counts as zero and is created with `ttl`; incrementing
+    /// an existing key keeps its expiration. See `counter` for named, windowed counters.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key.
+    /// * `delta` - The amount to add, which may be negative.
+    /// * `ttl` - An optional duration after which a newly created key expires.
+    ///
+    /// # Returns
+    ///
+    /// Returns the value after the increment, or a `KeyvError` if the stored value is not
+    /// an integer or the store does not support increments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// assert_eq!(keyv.increment("visits", 1, None).await.unwrap(), 1);
+    /// assert_eq!(keyv.increment("visits", 2, None).await.unwrap(), 3);
+    /// # };
+    /// ```
+    pub async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, KeyvError> {
+        validate_key(key)?;
+        Ok(self.store.increment(key, delta, ttl).await?)
+    }
+
+    /// Sets a value for a given key and keeps renewing its TTL until the returned guard
+    /// is dropped, after which the entry expires.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key.
+    /// * `value` - The value to be stored, which must implement `Serialize`.
+    /// * `ttl` - How long the entry outlives the guard.
+    ///
+    /// # Returns
+    ///
+    /// Returns the guard once the value is first set, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let presence = keyv
+    ///     .lease("workers:7", "busy", Duration::from_secs(15))
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// assert!(keyv.get("workers:7").await.unwrap().is_some());
+    /// presence.release().await.unwrap();
+    /// assert!(keyv.get("workers:7").await.unwrap().is_none());
+    /// # };
+    /// ```
+    pub async fn lease<T: Serialize>(
+        &self,
+        key: &str,
+        value: T,
+        ttl: Duration,
+    ) -> Result<Lease, KeyvError> {
+        validate_key(key)?;
+        let token = self.generator.generate();
+        Lease::acquire(self.store.clone(), key, json!(value), ttl, token).await
+    }
+
+    /// Returns the counter called `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let api_calls = keyv.counter("api_calls").window(Duration::from_secs(60));
+    ///
+    /// api_calls.incr().await.unwrap();
+    /// assert_eq!(api_calls.get().await.unwrap(), 1);
+    /// # };
+    /// ```
+    pub fn counter(&self, name: &str) -> Counter<'_> {
+        Counter::new(self, name)
+    }
+
+    /// Reads the configuration stored under `key` as a `T` and returns a receiver that
+    /// sees every later change, checked every five seconds.
+    ///
+    /// A background task reads the key again until every receiver is dropped. If the
+    /// stored configuration goes missing or no longer deserializes into `T`, the change
+    /// is logged and the receiver keeps the last good value.
+    ///
+    /// # Returns
+    ///
+    /// Returns the receiver, or a `KeyvError` if the key is missing or its value does
+    /// not deserialize into `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("app:config", serde_json::json!({ "workers": 4 })).await.unwrap();
+    ///
+    /// let config = keyv.config::<serde_json::Value>("app:config").await.unwrap();
+    /// assert_eq!(config.borrow()["workers"], 4);
+    /// # };
+    /// ```
+    pub async fn config<T>(&self, key: &str) -> Result<watch::Receiver<T>, KeyvError>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        self.config_with_poll_interval(key, DEFAULT_CONFIG_POLL_INTERVAL)
+            .await
+    }
+
+    /// Like `config`, reading the key again every `interval`.
+    pub async fn config_with_poll_interval<T>(
+        &self,
+        key: &str,
+        interval: Duration,
+    ) -> Result<watch::Receiver<T>, KeyvError>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        validate_key(key)?;
+        watch_config(self.store.clone(), key, interval).await
+    }
+
+    /// Returns the feature flags stored under `namespace`.
+    pub fn flags(&self, namespace: &str) -> Flags {
+        Flags::new(self.store.clone(), namespace)
+    }
+
+    /// Returns the idempotency records kept for `ttl` after the request they belong to
+    /// completes.
+    pub fn idempotency(&self, ttl: Duration) -> Idempotency {
+        Idempotency::new(self.store.clone(), ttl)
+    }
+
+    /// Enters this replica in the leader election called `name`, with leases lasting
+    /// `lease`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let campaign = keyv.campaign("scheduler", Duration::from_secs(10));
+    ///
+    /// assert_eq!(campaign.campaign().await.unwrap(), Some(1));
+    /// assert!(campaign.is_leader());
+    /// # };
+    /// ```
+    pub fn campaign(&self, name: &str, lease: Duration) -> Campaign {
+        Campaign::new(self.store.clone(), name, lease)
+    }
+
+    /// Starts a batch of operations sent to the store together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("c", "session").await.unwrap();
+    ///
+    /// let results = keyv
+    ///     .pipeline()
+    ///     .set("a", 1)
+    ///     .remove("b")
+    ///     .touch("c", 60)
+    ///     .execute()
+    ///     .await
+    ///     .unwrap();
+    /// assert!(results.iter().all(Result::is_ok));
+    /// # };
+    /// ```
+    pub fn pipeline(&self) -> Pipeline {
+        Pipeline::new(self.store.clone())
+    }
+
+    /// Returns a read-through view filling misses with `loader`, storing what it returns
+    /// for `ttl`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let users = keyv
+    ///     .read_through(Duration::from_secs(60), |key| async move {
+    ///         Ok::<_, std::io::Error>(Some(serde_json::json!({ "id": key })))
+    ///     })
+    ///     .refresh_ahead(0.2);
+    ///
+    /// let user = users.get("user:1").await.unwrap();
+    /// assert_eq!(user, Some(serde_json::json!({ "id": "user:1" })));
+    /// # };
+    /// ```
+    pub fn read_through<F, Fut, E>(&self, ttl: Duration, loader: F) -> ReadThrough
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Option<Value>, E>> + Send + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        ReadThrough::new(self.store.clone(), ttl, loader)
+    }
+
+    /// Removes a specified key from the store.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that represents the key to be removed.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result if the key has been successfully removed, or a `KeyvError`
+    /// on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.remove("my_key").await.unwrap(); // Removes "my_key" from the store
+    /// # };
+    /// ```
+    pub async fn remove(&self, key: &str) -> Result<(), KeyvError> {
+        validate_key(key)?;
+        Ok(self.store.remove(key).await?)
+    }
+
+    /// Removes multiple keys from the store in one operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - A slice of strings or string-like objects that represent the keys to be removed.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result if the keys have been successfully removed, or a `KeyvError`
+    /// on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.remove_many(&["key1", "key2"]).await.unwrap(); // Removes "key1" and "key2"
+    /// # };
+    /// ```
+    pub async fn remove_many<T: AsRef<str> + Sync>(&self, keys: &[T]) -> Result<(), KeyvError> {
+        let keys: Vec<&str> = keys.iter().map(|k| k.as_ref()).collect();
+        for key in &keys {
+            validate_key(key)?;
+        }
+        Ok(self.store.remove_many(&keys).await?)
+    }
+
+    /// Removes a key only if it still holds `expected`.
+    ///
+    /// Use it to release a lock or clean up an idempotency record without deleting a
+    /// value another writer has stored since. The comparison and the removal are
+    /// atomic: a Lua script on Redis and a `DELETE ... WHERE value = ?` on the SQL
+    /// stores.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice that holds the key to remove.
+    /// * `expected` - The value the key must hold to be removed.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the key was removed, `Ok(false)` if it is missing or holds
+    /// another value, or a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("lock:report", "worker-1").await.unwrap();
+    ///
+    /// assert!(!keyv.remove_if("lock:report", "worker-2").await.unwrap());
+    /// assert!(keyv.remove_if("lock:report", "worker-1").await.unwrap());
+    /// # };
+    /// ```
+    pub async fn remove_if<T: Serialize>(&self, key: &str, expected: T) -> Result<bool, KeyvError> {
+        validate_key(key)?;
+        Ok(self.store.remove_if(key, &json!(expected)).await?)
+    }
+
+    /// Moves the value stored under `old_key` to `new_key`, keeping its expiration.
+    ///
+    /// Useful for migrating a key naming scheme in place. A value already stored under
+    /// `new_key` is overwritten. Redis moves the key with `RENAME` and the SQL stores
+    /// update the key column in a transaction, so readers never see both keys or
+    /// neither; other stores copy the value and then remove the old key.
+    ///
+    /// # Arguments
+    ///
+    /// * `old_key` - The key the value is currently stored under.
+    /// * `new_key` - The key to move the value to.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result if the value has been moved, or a `KeyvError` on failure.
+    /// If `old_key` does not exist the error is `StoreError::NotFound`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("user_42", "alice").await.unwrap();
+    ///
+    /// keyv.rename("user_42", "user:42").await.unwrap();
+    /// assert!(keyv.get("user_42").await.unwrap().is_none());
+    /// assert_eq!(keyv.get("user:42").await.unwrap(), Some(serde_json::json!("alice")));
+    /// # };
+    /// ```
+    pub async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), KeyvError> {
+        validate_key(old_key)?;
+        validate_key(new_key)?;
+        Ok(self.store.rename(old_key, new_key).await?)
+    }
+
+    /// Copies the value under `key` into the namespace of another handle, keeping its
+    /// expiration.
+    ///
+    /// Meant for populating a blue/green namespace from the live one before a cut-over.
+    /// The value is written under the same key in `target`, overwriting what is there.
+    /// The target's default TTL does not apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the value to copy.
+    /// * `target` - The handle of the namespace to copy into, usually from `namespace()`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the value was copied, `Ok(false)` if `key` does not exist, or
+    /// a `KeyvError` on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let blue = keyv.namespace("blue");
+    /// let green = keyv.namespace("green");
+    /// blue.set("homepage", "<html>").await.unwrap();
+    ///
+    /// assert!(blue.copy("homepage", &green).await.unwrap());
+    /// assert!(green.get("homepage").await.unwrap().is_some());
+    /// # };
+    /// ```
+    pub async fn copy(&self, key: &str, target: &Keyv) -> Result<bool, KeyvError> {
+        validate_key(key)?;
+        let Some((value, metadata)) = self.store.get_with_metadata(key).await? else {
+            return Ok(false);
+        };
+        match metadata.expires_at {
+            Some(expires_at) => target.store.set_until(key, value, expires_at).await?,
+            None => target.store.set(key, value, None).await?,
+        }
+        Ok(true)
+    }
+
+    /// Copies every entry whose key starts with `prefix` into the namespace of another
+    /// handle, keeping their expirations.
+    ///
+    /// Entries are read with `scan` and written to `target` in batches, one
+    /// `execute_batch` call per batch. The store must be able to scan its keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Only keys starting with this prefix are copied; `""` copies the whole
+    ///   namespace.
+    /// * `target` - The handle of the namespace to copy into.
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of entries copied, or a `KeyvError` on the first failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// let blue = keyv.namespace("blue");
+    /// let green = keyv.namespace("green");
+    /// blue.set("page:home", "<html>").await.unwrap();
+    /// blue.set("page:about", "<html>").await.unwrap();
+    ///
+    /// assert_eq!(blue.copy_prefix("page:", &green).await.unwrap(), 2);
+    /// # };
+    /// ```
+    pub async fn copy_prefix(&self, prefix: &str, target: &Keyv) -> Result<u64, KeyvError> {
+        let mut batches = self
+            .store
+            .scan_entries(Some(prefix), DEFAULT_SCAN_BATCH_SIZE);
+        let mut copied = 0;
+        while let Some(batch) = batches.try_next().await? {
+            // Entries expiring during the scan are skipped rather than written with a
+            // zero TTL, which some backends reject.
+            let now = SystemTime::now();
+            let ops = batch
+                .into_iter()
+                .filter(|entry| entry.expires_at.is_none_or(|expires_at| expires_at > now))
+                .map(|entry| BatchOp::Set {
+                    ttl: entry
+                        .expires_at
+                        .map(|expires_at| expires_at.duration_since(now).unwrap_or_default()),
+                    key: entry.key,
+                    value: entry.value,
+                })
+                .collect();
+            for result in target.store.execute_batch(ops).await? {
+                result?;
+                copied += 1;
+            }
+        }
+        Ok(copied)
+    }
+
+    /// Clears the entire store, removing all key-value pairs.
+    ///
+    /// On a namespaced handle, only the keys of the namespace are removed.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result if the store has been successfully cleared, or a `KeyvError`
+    /// on failure.
+    ///
+    /// # Examples
     ///
     /// ```
     /// # use keyv::Keyv;
@@ -271,14 +2097,137 @@ impl Keyv {
     /// # };
     /// ```
     pub async fn clear(&self) -> Result<(), KeyvError> {
-        Ok(self.store.clear().await?)
+        match self.clear_policy {
+            ClearPolicy::Allow => self.clear_store().await,
+            ClearPolicy::RequireConfirmation => Err(KeyvError::ClearNotConfirmed),
+            ClearPolicy::Deny => Err(KeyvError::ClearDisabled),
+        }
+    }
+
+    /// Reports how many entries `clear` would remove, without removing anything.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ClearPreview` holding the count, if the store can report it, and the
+    /// token `clear_confirmed` expects.
+    pub async fn clear_dry_run(&self) -> Result<ClearPreview, KeyvError> {
+        let usage = if self.namespaced {
+            self.namespace_keys().await.map(|keys| keys.len() as u64)
+        } else {
+            self.store.usage().await.map(|usage| usage.entries)
+        };
+        let entries = match usage {
+            Ok(entries) => Some(entries),
+            Err(StoreError::Unsupported(_)) => None,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(ClearPreview {
+            entries,
+            token: ConfirmToken {
+                handle: self.handle_id(),
+                issued_at: Instant::now(),
+            },
+        })
+    }
+
+    /// Clears the entire store, given a token from `clear_dry_run` on this handle that
+    /// is less than a minute old.
+    ///
+    /// # Returns
+    ///
+    /// Returns an `Ok` result if the store has been cleared, or a `KeyvError` if the
+    /// token is invalid, the clear policy is `ClearPolicy::Deny` or clearing fails.
+    pub async fn clear_confirmed(&self, token: ConfirmToken) -> Result<(), KeyvError> {
+        if self.clear_policy == ClearPolicy::Deny {
+            return Err(KeyvError::ClearDisabled);
+        }
+        if token.handle != self.handle_id() || token.issued_at.elapsed() > CONFIRM_TOKEN_TTL {
+            return Err(KeyvError::InvalidConfirmToken);
+        }
+        self.clear_store().await
+    }
+
+    /// Clears the store, or only the handle's namespace if it has one.
+    async fn clear_store(&self) -> Result<(), KeyvError> {
+        if !self.namespaced {
+            return Ok(self.store.clear().await?);
+        }
+        // The store is shared with other namespaces, so only this one's keys are removed.
+        let keys = self.namespace_keys().await?;
+        for batch in keys.chunks(DEFAULT_SCAN_BATCH_SIZE) {
+            let batch: Vec<&str> = batch.iter().map(String::as_str).collect();
+            self.store.remove_many(&batch).await?;
+        }
+        Ok(())
+    }
+
+    /// Lists the keys of the handle's namespace.
+    async fn namespace_keys(&self) -> Result<Vec<String>, StoreError> {
+        self.store
+            .scan_entries(Some(""), DEFAULT_SCAN_BATCH_SIZE)
+            .map_ok(|batch| stream::iter(batch.into_iter().map(|entry| Ok(entry.key))))
+            .try_flatten()
+            .try_collect()
+            .await
+    }
+
+    /// Identifies this handle's store, so tokens are not accepted by other handles.
+    fn handle_id(&self) -> usize {
+        Arc::as_ptr(&self.store) as *const () as usize
+    }
+
+    /// Reports the number of entries and approximate bytes used by the store.
+    ///
+    /// Intended for capacity dashboards; see `Usage` for how precise the figures are.
+    /// On a handle with a namespace, only the entries of the namespace are counted, the
+    /// same way `clear_dry_run` does, and `bytes` is `None`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the store's `Usage`, or a `KeyvError` if the store cannot report it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// # async {
+    /// let keyv = Keyv::default();
+    /// keyv.set("key", "value").await.unwrap();
+    ///
+    /// let usage = keyv.usage().await.unwrap();
+    /// assert_eq!(usage.entries, 1);
+    /// # };
+    /// ```
+    pub async fn usage(&self) -> Result<Usage, KeyvError> {
+        if self.namespaced {
+            let keys = self.namespace_keys().await?;
+            return Ok(Usage {
+                entries: keys.len() as u64,
+                bytes: None,
+            });
+        }
+        Ok(self.store.usage().await?)
+    }
+
+    /// Reports which optional features the store supports, once wrapped in the layers
+    /// of this handle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use keyv::Keyv;
+    /// let keyv = Keyv::default();
+    /// if keyv.capabilities().scan {
+    ///     // Safe to list keys with `scan` or `find`.
+    /// }
+    /// ```
+    pub fn capabilities(&self) -> Capabilities {
+        self.store.capabilities()
     }
 }
 
 impl Default for Keyv {
     fn default() -> Self {
-        Self {
-            store: Arc::new(InMemoryStore::new()),
-        }
+        Self::from_store(Arc::new(InMemoryStore::new()))
     }
 }
@@ -0,0 +1,79 @@
+use std::{sync::Arc, time::Duration};
+
+use serde_json::Value;
+use tokio::task::JoinHandle;
+
+use crate::store::Store;
+
+use super::KeyvError;
+
+/// An entry kept alive while the guard is held, returned by `Keyv::lease`.
+///
+/// A background task writes the entry again with its TTL every third of the TTL. Once
+/// the guard is dropped the task stops and the entry expires, so a crashed worker
+/// disappears from a presence registry on its own. `release` removes it right away.
+///
+/// Every lease gets a token from the handle's `Generator`, telling apart successive
+/// leases on the same key.
+pub struct Lease {
+    store: Arc<dyn Store>,
+    key: String,
+    token: String,
+    heartbeat: JoinHandle<()>,
+}
+
+impl Lease {
+    pub(crate) async fn acquire(
+        store: Arc<dyn Store>,
+        key: &str,
+        value: Value,
+        ttl: Duration,
+        token: String,
+    ) -> Result<Self, KeyvError> {
+        store.set(key, value.clone(), Some(ttl)).await?;
+
+        let heartbeat = tokio::spawn({
+            let store = store.clone();
+            let key = key.to_string();
+            async move {
+                let mut interval = tokio::time::interval(ttl / 3);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = store.set(&key, value.clone(), Some(ttl)).await {
+                        log::error!("Failed to renew the lease on '{}': {}", key, e);
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            store,
+            key: key.to_string(),
+            token,
+            heartbeat,
+        })
+    }
+
+    /// Returns the key the lease holds.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the token of this lease.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Stops renewing the entry and removes it without waiting for it to expire.
+    pub async fn release(self) -> Result<(), KeyvError> {
+        self.heartbeat.abort();
+        Ok(self.store.remove(&self.key).await?)
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        self.heartbeat.abort();
+    }
+}
@@ -1,5 +1,34 @@
 mod errors;
 pub use errors::*;
 
+mod entry;
+pub use entry::*;
+
+mod stale;
+pub use stale::*;
+
+mod counter;
+pub use counter::*;
+
+mod config;
+
+mod clear;
+pub use clear::*;
+
+mod expiry_policy;
+pub use expiry_policy::*;
+
+mod lease;
+pub use lease::*;
+
+mod pipeline;
+pub use pipeline::*;
+
+mod read_through;
+pub use read_through::*;
+
+mod builder;
+pub use builder::*;
+
 mod keyv;
 pub use keyv::*;
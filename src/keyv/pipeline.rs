@@ -0,0 +1,82 @@
+use std::{sync::Arc, time::Duration};
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::store::{validate_key, BatchOp, Store};
+
+use super::KeyvError;
+
+/// Operations queued to run together, returned by `Keyv::pipeline`.
+///
+/// `execute` sends them in one Redis pipeline or one SQL transaction; other stores run
+/// them one after the other.
+pub struct Pipeline {
+    store: Arc<dyn Store>,
+    ops: Vec<BatchOp>,
+}
+
+impl Pipeline {
+    pub(crate) fn new(store: Arc<dyn Store>) -> Self {
+        Self {
+            store,
+            ops: Vec::new(),
+        }
+    }
+
+    /// Queues setting `value` under `key` without a TTL.
+    pub fn set<T: Serialize>(self, key: &str, value: T) -> Self {
+        self.push(BatchOp::Set {
+            key: key.to_string(),
+            value: json!(value),
+            ttl: None,
+        })
+    }
+
+    /// Queues setting `value` under `key`, expiring after `ttl`.
+    pub fn set_for<T: Serialize>(self, key: &str, value: T, ttl: Duration) -> Self {
+        self.push(BatchOp::Set {
+            key: key.to_string(),
+            value: json!(value),
+            ttl: Some(ttl),
+        })
+    }
+
+    /// Queues removing `key`.
+    pub fn remove(self, key: &str) -> Self {
+        self.push(BatchOp::Remove {
+            key: key.to_string(),
+        })
+    }
+
+    /// Queues making `key` expire `ttl` seconds from now. The operation fails with
+    /// `StoreError::NotFound` if the key is missing.
+    pub fn touch(self, key: &str, ttl: u64) -> Self {
+        self.push(BatchOp::Touch {
+            key: key.to_string(),
+            ttl: Duration::from_secs(ttl),
+        })
+    }
+
+    /// Runs the queued operations in order.
+    ///
+    /// # Returns
+    ///
+    /// Returns the result of each operation, in the order they were queued, or a
+    /// `KeyvError` if a key is invalid or the batch could not be run at all.
+    pub async fn execute(self) -> Result<Vec<Result<(), KeyvError>>, KeyvError> {
+        for op in &self.ops {
+            validate_key(op.key())?;
+        }
+        let results = self.store.execute_batch(self.ops).await?;
+        Ok(results
+            .into_iter()
+            .map(|result| result.map_err(KeyvError::from))
+            .collect())
+    }
+
+    fn push(mut self, op: BatchOp) -> Self {
+        self.ops.push(op);
+        self
+    }
+}
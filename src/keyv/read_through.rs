@@ -0,0 +1,156 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+use crate::store::{validate_key, Store};
+
+use super::KeyvError;
+
+type LoaderError = Box<dyn Error + Send + Sync>;
+type Loader =
+    Arc<dyn Fn(String) -> BoxFuture<'static, Result<Option<Value>, LoaderError>> + Send + Sync>;
+
+/// Expiration of an entry this process loaded, and whether a refresh is running.
+struct Loaded {
+    expires_at: Instant,
+    refreshing: bool,
+}
+
+/// A read-through view of a `Keyv`, returned by `Keyv::read_through`.
+///
+/// Misses are filled by calling the loader and storing what it returns for the TTL.
+/// With `refresh_ahead` set, a hit whose remaining TTL has dropped below the given
+/// fraction of the TTL is returned as is while the loader refreshes it in the
+/// background, so hot keys never expire in the request path.
+///
+/// Expirations are tracked for the entries loaded through this value only: entries
+/// written by other handles or processes are refreshed once they have expired.
+#[derive(Clone)]
+pub struct ReadThrough {
+    store: Arc<dyn Store>,
+    loader: Loader,
+    ttl: Duration,
+    refresh_ahead: Option<f64>,
+    loaded: Arc<Mutex<HashMap<String, Loaded>>>,
+}
+
+impl ReadThrough {
+    pub(crate) fn new<F, Fut, E>(store: Arc<dyn Store>, ttl: Duration, loader: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Option<Value>, E>> + Send + 'static,
+        E: Into<LoaderError>,
+    {
+        Self {
+            store,
+            loader: Arc::new(move |key| {
+                let load = loader(key);
+                Box::pin(async move { load.await.map_err(Into::into) })
+            }),
+            ttl,
+            refresh_ahead: None,
+            loaded: Arc::default(),
+        }
+    }
+
+    /// Refreshes entries in the background once less than `fraction` of their TTL
+    /// remains, e.g. `0.2` to refresh during the last fifth. Clamped to `0.0..=1.0`.
+    pub fn refresh_ahead(self, fraction: f64) -> Self {
+        Self {
+            refresh_ahead: Some(fraction.clamp(0.0, 1.0)),
+            ..self
+        }
+    }
+
+    /// Returns the value stored under `key`, loading and storing it on a miss.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(None)` if the key is missing and the loader has nothing for it, or a
+    /// `KeyvError` if the store or the loader fails.
+    pub async fn get(&self, key: &str) -> Result<Option<Value>, KeyvError> {
+        validate_key(key)?;
+        if let Some(value) = self.store.get(key).await? {
+            if self.claim_refresh(key) {
+                self.spawn_refresh(key);
+            }
+            return Ok(Some(value));
+        }
+
+        let value = (self.loader)(key.to_string())
+            .await
+            .map_err(KeyvError::LoaderError)?;
+        if let Some(value) = &value {
+            self.store.set(key, value.clone(), Some(self.ttl)).await?;
+            self.track(key);
+        }
+        Ok(value)
+    }
+
+    /// Marks `key` as refreshing if it is due for a refresh and none is running.
+    fn claim_refresh(&self, key: &str) -> bool {
+        let Some(fraction) = self.refresh_ahead else {
+            return false;
+        };
+        let mut loaded = lock(&self.loaded);
+        let Some(entry) = loaded.get_mut(key) else {
+            return false;
+        };
+        let remaining = entry.expires_at.saturating_duration_since(Instant::now());
+        if entry.refreshing || remaining >= self.ttl.mul_f64(fraction) {
+            return false;
+        }
+        entry.refreshing = true;
+        true
+    }
+
+    fn spawn_refresh(&self, key: &str) {
+        let this = self.clone();
+        let key = key.to_string();
+        tokio::spawn(async move {
+            let refreshed = match (this.loader)(key.clone()).await {
+                Ok(Some(value)) => this.store.set(&key, value, Some(this.ttl)).await,
+                Ok(None) => this.store.remove(&key).await,
+                Err(e) => {
+                    log::error!("Failed to refresh '{}': {}", key, e);
+                    if let Some(entry) = lock(&this.loaded).get_mut(&key) {
+                        entry.refreshing = false;
+                    }
+                    return;
+                }
+            };
+            match refreshed {
+                Ok(()) => this.track(&key),
+                Err(e) => {
+                    log::error!("Failed to store the refreshed '{}': {}", key, e);
+                    lock(&this.loaded).remove(&key);
+                }
+            }
+        });
+    }
+
+    fn track(&self, key: &str) {
+        if self.refresh_ahead.is_some() {
+            lock(&self.loaded).insert(
+                key.to_string(),
+                Loaded {
+                    expires_at: Instant::now() + self.ttl,
+                    refreshing: false,
+                },
+            );
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::KeyvError;
+
+/// The result of `Keyv::get_allow_stale`, which also returns expired values the store
+/// still holds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StaleRead {
+    /// The key holds a live value.
+    Fresh(Value),
+    /// The key holds a value that expired this long ago.
+    Stale(Value, Duration),
+    /// The key does not exist, or its expired value was already purged.
+    Missing,
+}
+
+impl StaleRead {
+    /// Returns `true` if the key holds a live value.
+    pub fn is_fresh(&self) -> bool {
+        matches!(self, StaleRead::Fresh(_))
+    }
+
+    /// Returns the value, live or expired, or `None` if missing.
+    pub fn into_value(self) -> Option<Value> {
+        match self {
+            StaleRead::Fresh(value) | StaleRead::Stale(value, _) => Some(value),
+            StaleRead::Missing => None,
+        }
+    }
+
+    /// Returns the live value, `None` if missing, or `KeyvError::Expired` if the value
+    /// expired longer ago than `max_stale`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use keyv::StaleRead;
+    /// # use serde_json::json;
+    /// let read = StaleRead::Stale(json!("cached"), Duration::from_secs(5));
+    /// assert!(read.clone().within(Duration::from_secs(10)).is_ok());
+    /// assert!(read.within(Duration::from_secs(1)).is_err());
+    /// ```
+    pub fn within(self, max_stale: Duration) -> Result<Option<Value>, KeyvError> {
+        match self {
+            StaleRead::Stale(_, expired_by) if expired_by > max_stale => {
+                Err(KeyvError::Expired(expired_by))
+            }
+            read => Ok(read.into_value()),
+        }
+    }
+}
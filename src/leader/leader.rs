@@ -0,0 +1,196 @@
+use std::{
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use tokio::task::JoinHandle;
+
+use crate::{
+    store::{validate_key, Store},
+    KeyvError,
+};
+
+/// Prefix of the keys elections are held under.
+const LEADER_PREFIX: &str = "leader";
+
+/// A term of leadership won by this campaign.
+#[derive(Clone, Copy)]
+struct Term {
+    token: i64,
+    renewed_at: Instant,
+}
+
+struct Inner {
+    store: Arc<dyn Store>,
+    name: String,
+    lease: Duration,
+    term: Mutex<Option<Term>>,
+}
+
+/// A replica's candidacy in the election called `name`, returned by `Keyv::campaign`.
+///
+/// The leader holds a lease, the key `leader:<name>`, that expires after `lease` unless
+/// renewed. Candidates race to create it with an atomic increment, so at most one of
+/// them wins while it is held; once the leader stops renewing, the lease expires and
+/// the next round elects another replica. Every term gets a fencing token from the
+/// counter `leader:<name>:term`, strictly greater than the tokens of earlier terms, for
+/// downstream systems to reject writes from a deposed leader.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use keyv::Keyv;
+/// # async {
+/// let keyv = Keyv::default();
+/// let campaign = keyv.campaign("scheduler", Duration::from_secs(10));
+/// campaign.start_heartbeat();
+///
+/// if let Some(token) = campaign.token() {
+///     // Run the scheduled jobs, passing `token` along with their writes.
+/// }
+/// # };
+/// ```
+pub struct Campaign {
+    inner: Arc<Inner>,
+    heartbeat: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Campaign {
+    pub(crate) fn new(store: Arc<dyn Store>, name: &str, lease: Duration) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                store,
+                name: name.to_string(),
+                lease,
+                term: Mutex::new(None),
+            }),
+            heartbeat: Mutex::new(None),
+        }
+    }
+
+    /// Runs one round of the election: renews the lease if this replica leads, or
+    /// tries to take it otherwise.
+    ///
+    /// Returns the fencing token of the current term if this replica leads.
+    pub async fn campaign(&self) -> Result<Option<i64>, KeyvError> {
+        self.inner.campaign().await
+    }
+
+    /// Runs a round of the election every third of the lease in a background task, so
+    /// the leader renews its lease well before it expires.
+    ///
+    /// The task stops when the campaign is dropped or resigns.
+    pub fn start_heartbeat(&self) {
+        let inner: Weak<Inner> = Arc::downgrade(&self.inner);
+        let interval = self.inner.lease / 3;
+        let handle = tokio::spawn(async move {
+            loop {
+                let Some(inner) = inner.upgrade() else {
+                    break;
+                };
+                if let Err(e) = inner.campaign().await {
+                    log::error!("Failed to campaign for '{}': {}", inner.name, e);
+                }
+                drop(inner);
+                tokio::time::sleep(interval).await;
+            }
+        });
+
+        if let Some(previous) = self.heartbeat.lock().unwrap().replace(handle) {
+            previous.abort();
+        }
+    }
+
+    /// Returns whether this replica leads, i.e. its lease has not run out since it was
+    /// last renewed.
+    pub fn is_leader(&self) -> bool {
+        self.token().is_some()
+    }
+
+    /// Returns the fencing token of the current term if this replica leads.
+    pub fn token(&self) -> Option<i64> {
+        self.inner.current().map(|term| term.token)
+    }
+
+    /// Stops the heartbeat and, if this replica leads, releases the lease so another
+    /// replica can be elected without waiting for it to expire.
+    pub async fn resign(&self) -> Result<(), KeyvError> {
+        if let Some(heartbeat) = self.heartbeat.lock().unwrap().take() {
+            heartbeat.abort();
+        }
+        if self.inner.term.lock().unwrap().take().is_some() {
+            self.inner.store.remove(&self.inner.lease_key()).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Campaign {
+    fn drop(&mut self) {
+        if let Some(heartbeat) = self.heartbeat.lock().unwrap().take() {
+            heartbeat.abort();
+        }
+    }
+}
+
+impl Inner {
+    fn lease_key(&self) -> String {
+        format!("{}:{}", LEADER_PREFIX, self.name)
+    }
+
+    fn term_key(&self) -> String {
+        format!("{}:{}:term", LEADER_PREFIX, self.name)
+    }
+
+    /// Returns the current term, unless the lease ran out since it was last renewed.
+    ///
+    /// Renewals are timed from before the request is sent, so the lease never runs out
+    /// here later than it expires in the store.
+    fn current(&self) -> Option<Term> {
+        let mut term = self.term.lock().unwrap();
+        if term.is_some_and(|term| term.renewed_at.elapsed() >= self.lease) {
+            log::warn!("Lost the leadership of '{}'", self.name);
+            *term = None;
+        }
+        *term
+    }
+
+    async fn campaign(&self) -> Result<Option<i64>, KeyvError> {
+        validate_key(&self.lease_key())?;
+        let started_at = Instant::now();
+
+        if let Some(term) = self.current() {
+            // Only the leader renews the lease, so a live lease is still this term's.
+            let renewed = self
+                .store
+                .get_and_touch(&self.lease_key(), self.lease)
+                .await?;
+            let mut current = self.term.lock().unwrap();
+            if renewed.is_some() {
+                *current = Some(Term {
+                    token: term.token,
+                    renewed_at: started_at,
+                });
+                return Ok(Some(term.token));
+            }
+            log::warn!("Lost the leadership of '{}'", self.name);
+            *current = None;
+        }
+
+        // The candidate creating the lease wins; the others merely count up.
+        let contenders = self
+            .store
+            .increment(&self.lease_key(), 1, Some(self.lease))
+            .await?;
+        if contenders != 1 {
+            return Ok(None);
+        }
+        let token = self.store.increment(&self.term_key(), 1, None).await?;
+        *self.term.lock().unwrap() = Some(Term {
+            token,
+            renewed_at: started_at,
+        });
+        Ok(Some(token))
+    }
+}
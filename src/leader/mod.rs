@@ -0,0 +1,2 @@
+mod leader;
+pub use leader::*;
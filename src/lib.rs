@@ -1,9 +1,24 @@
-#![allow(dead_code)]
+#![allow(dead_code, clippy::module_inception)]
 
 pub const DEFAUTL_NAMESPACE_NAME: &str = "keyv";
 
+#[cfg(feature = "runtime")]
 mod keyv;
+#[cfg(feature = "runtime")]
 pub use keyv::*;
 
 mod store;
 pub use store::*;
+
+#[cfg(feature = "runtime")]
+pub mod flags;
+#[cfg(feature = "runtime")]
+pub mod idempotency;
+#[cfg(feature = "runtime")]
+pub mod import;
+#[cfg(feature = "runtime")]
+pub mod leader;
+#[cfg(feature = "runtime")]
+pub mod queue;
+#[cfg(feature = "testing")]
+pub mod testing;
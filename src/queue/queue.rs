@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    store::{validate_key, QueueBackend, QueueMessage},
+    KeyvError,
+};
+
+/// A named work queue shared by every process using the same backend.
+///
+/// Messages are delivered at least once: `pop` hides the message it returns for the
+/// visibility timeout, and a message not acknowledged with `ack` by then is handed to
+/// the next consumer. Queues are backed by Redis lists and by a `<table>_queue` table
+/// on SQL stores.
+///
+/// # Examples
+///
+/// ```
+/// # use std::{sync::Arc, time::Duration};
+/// # use keyv::{adapter::inmemory::InMemoryStore, queue::Queue, Keyv};
+/// # async {
+/// let store = Arc::new(InMemoryStore::new());
+/// let keyv = Keyv::try_new(store.clone()).await.unwrap();
+/// let jobs = Queue::new(store, "jobs");
+///
+/// jobs.push("resize:42").await.unwrap();
+/// let message = jobs.pop(Duration::from_secs(30)).await.unwrap().unwrap();
+/// assert_eq!(message.payload, "resize:42");
+/// jobs.ack(&message.id).await.unwrap();
+/// # };
+/// ```
+#[derive(Clone)]
+pub struct Queue<B: QueueBackend> {
+    backend: B,
+    name: String,
+}
+
+impl<B: QueueBackend> Queue<B> {
+    /// Returns the queue called `name` of `backend`.
+    pub fn new(backend: B, name: &str) -> Self {
+        Self {
+            backend,
+            name: name.to_string(),
+        }
+    }
+
+    /// Returns the name of the queue.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Appends `payload` to the queue and returns the id of the new message.
+    pub async fn push<T: Serialize>(&self, payload: T) -> Result<String, KeyvError> {
+        validate_key(&self.name)?;
+        Ok(self.backend.queue_push(&self.name, json!(payload)).await?)
+    }
+
+    /// Claims the oldest available message, hiding it from other consumers for
+    /// `visibility_timeout`. Returns `None` if no message is available.
+    pub async fn pop(
+        &self,
+        visibility_timeout: Duration,
+    ) -> Result<Option<QueueMessage>, KeyvError> {
+        validate_key(&self.name)?;
+        Ok(self
+            .backend
+            .queue_pop(&self.name, visibility_timeout)
+            .await?)
+    }
+
+    /// Removes the message `id` from the queue once it has been processed.
+    pub async fn ack(&self, id: &str) -> Result<(), KeyvError> {
+        validate_key(&self.name)?;
+        Ok(self.backend.queue_ack(&self.name, id).await?)
+    }
+}
@@ -0,0 +1,63 @@
+use crate::StoreError;
+
+/// Names of the columns a SQL store maps entries to.
+///
+/// Defaults to the columns the stores create themselves; builders let applications
+/// point a store at an existing table with different names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Columns {
+    pub(crate) key: String,
+    pub(crate) value: String,
+    pub(crate) expires_at: String,
+    /// `None` when the table has no creation time column.
+    pub(crate) created_at: Option<String>,
+    /// `None` when the table has no update time column.
+    pub(crate) updated_at: Option<String>,
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Self {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            expires_at: "expires_at".to_string(),
+            created_at: Some("created_at".to_string()),
+            updated_at: Some("updated_at".to_string()),
+        }
+    }
+}
+
+/// Checks that `name` is a plain identifier, as table and column names are inlined into SQL.
+pub(crate) fn validate_identifier(field: &str, name: &str) -> Result<(), StoreError> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(StoreError::invalid_configuration(
+            field,
+            "Names may only contain ASCII letters, digits and underscores",
+        ));
+    }
+    Ok(())
+}
+
+impl Columns {
+    /// Checks that every name is a plain identifier.
+    pub(crate) fn validate(&self) -> Result<(), StoreError> {
+        validate_identifier("key_column", &self.key)?;
+        validate_identifier("value_column", &self.value)?;
+        validate_identifier("ttl_column", &self.expires_at)?;
+        if let Some(created_at) = &self.created_at {
+            validate_identifier("created_at_column", created_at)?;
+        }
+        if let Some(updated_at) = &self.updated_at {
+            validate_identifier("updated_at_column", updated_at)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the timestamp columns the table has, `created_at` first.
+    pub(crate) fn timestamps(&self) -> impl Iterator<Item = &str> {
+        self.created_at
+            .iter()
+            .chain(&self.updated_at)
+            .map(String::as_str)
+    }
+}
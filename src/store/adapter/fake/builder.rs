@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use crate::{adapter::inmemory::InMemoryStore, layer::stats::Operation};
+
+use super::{FakeStore, Latency};
+
+/// Builder for creating a `FakeStore`.
+///
+/// Latencies are set per `Operation`, the groups `layer::stats::Operation` reports, so
+/// a load test configured here reads back the same groups from the stats layer.
+/// Operations without a latency complete right away.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use keyv::{adapter::fake::{FakeStoreBuilder, Latency}, layer::stats::Operation, Keyv};
+/// # async {
+/// let store = FakeStoreBuilder::new()
+///     .latency(Latency::Fixed(Duration::from_millis(1)))
+///     .operation_latency(
+///         Operation::Get,
+///         Latency::Exponential {
+///             min: Duration::from_millis(2),
+///             mean: Duration::from_millis(8),
+///         },
+///     )
+///     .seed(7)
+///     .build();
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// # };
+/// ```
+#[derive(Default)]
+pub struct FakeStoreBuilder {
+    store: Option<InMemoryStore>,
+    latency: Latency,
+    latencies: HashMap<Operation, Latency>,
+    seed: u64,
+}
+
+impl FakeStoreBuilder {
+    /// Creates a builder for a store without latency, seeded with `0`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the latency of every operation without one of its own.
+    pub fn latency(mut self, latency: Latency) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Sets the latency of `operation`, overriding `latency`.
+    pub fn operation_latency(mut self, operation: Operation, latency: Latency) -> Self {
+        self.latencies.insert(operation, latency);
+        self
+    }
+
+    /// Sets the seed latencies are sampled with. Stores built with the same seed and
+    /// latencies delay the n-th call of each operation by the same amount.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Keeps the entries in `store` instead of a default `InMemoryStore`, e.g. to bound
+    /// it or to give it a `ManualClock`.
+    pub fn store(mut self, store: InMemoryStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Builds the `FakeStore`.
+    pub fn build(self) -> FakeStore {
+        let delays = Operation::ALL
+            .into_iter()
+            .map(|operation| {
+                let latency = self
+                    .latencies
+                    .get(&operation)
+                    .copied()
+                    .unwrap_or(self.latency);
+                (operation, latency.samples(self.seed))
+            })
+            .collect();
+        FakeStore::new(self.store.unwrap_or_default(), delays)
+    }
+}
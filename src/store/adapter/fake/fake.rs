@@ -0,0 +1,246 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use serde_json::Value;
+
+use crate::{
+    adapter::inmemory::InMemoryStore, layer::stats::Operation, BatchOp, Capabilities,
+    EvictionPriority, Metadata, ScanEntry, Store, StoreError, Usage, Version,
+};
+
+use super::{FakeStoreBuilder, Samples};
+
+/// In-memory store that delays every operation by a configurable latency, to run load
+/// tests and tune timeouts without a real backend.
+///
+/// Entries are kept in an `InMemoryStore`, so the store behaves like one apart from
+/// taking its time. Delays are drawn from the `Latency` of each `Operation` with a
+/// seeded generator: the n-th call of an operation always waits as long as the n-th
+/// value of `Latency::samples`, however calls of different operations interleave.
+/// Usage, queue and scan calls are not delayed.
+///
+/// Delays are `tokio::time::sleep`s, so a paused Tokio clock skips them.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use keyv::{adapter::fake::{FakeStore, Latency}, Keyv};
+/// # async {
+/// let store = FakeStore::builder()
+///     .latency(Latency::Fixed(Duration::from_millis(3)))
+///     .build();
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// keyv.set("key", "value").await.unwrap(); // Takes 3ms
+/// # };
+/// ```
+pub struct FakeStore {
+    store: InMemoryStore,
+    delays: HashMap<Operation, Mutex<Samples>>,
+}
+
+impl FakeStore {
+    pub(super) fn new(store: InMemoryStore, delays: HashMap<Operation, Samples>) -> Self {
+        Self {
+            store,
+            delays: delays
+                .into_iter()
+                .map(|(operation, samples)| (operation, Mutex::new(samples)))
+                .collect(),
+        }
+    }
+
+    /// Returns a builder configuring the latencies.
+    pub fn builder() -> FakeStoreBuilder {
+        FakeStoreBuilder::new()
+    }
+
+    /// Returns a reference to the store holding the entries.
+    pub fn inner(&self) -> &InMemoryStore {
+        &self.store
+    }
+
+    /// Waits for the next delay of `operation`.
+    async fn delay(&self, operation: Operation) {
+        let delay = self.delays.get(&operation).and_then(|samples| {
+            samples
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .next()
+        });
+        if let Some(delay) = delay.filter(|delay| !delay.is_zero()) {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[async_trait]
+impl Store for FakeStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.store.initialize().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.store.capabilities()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.delay(Operation::Get).await;
+        self.store.get(key).await
+    }
+
+    async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, StoreError> {
+        self.delay(Operation::Get).await;
+        self.store.get_ref(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.delay(Operation::Get).await;
+        self.store.get_many(keys).await
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.delay(Operation::Get).await;
+        self.store.get_with_metadata(key).await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.delay(Operation::Get).await;
+        self.store.get_stale(key).await
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        self.delay(Operation::Get).await;
+        self.store.get_versions(key).await
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        self.delay(Operation::Get).await;
+        self.store.get_and_touch(key, ttl).await
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        self.delay(Operation::Set).await;
+        self.store.touch_many(keys, ttl).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.delay(Operation::Set).await;
+        self.store.set(key, value, ttl).await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        self.delay(Operation::Set).await;
+        self.store.set_many(entries, ttl).await
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        self.delay(Operation::Set).await;
+        self.store
+            .set_with_priority(key, value, ttl, priority)
+            .await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        self.delay(Operation::Set).await;
+        self.store.set_until(key, value, expires_at).await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        self.delay(Operation::Set).await;
+        self.store.set_keep_ttl(key, value).await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        self.delay(Operation::Set).await;
+        self.store.increment(key, delta, ttl).await
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        self.delay(Operation::Set).await;
+        self.store.set_if_absent(key, value, ttl).await
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        self.delay(Operation::Set).await;
+        self.store.execute_batch(ops).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.delay(Operation::Remove).await;
+        self.store.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.delay(Operation::Remove).await;
+        self.store.remove_many(keys).await
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        self.delay(Operation::Remove).await;
+        self.store.remove_if(key, expected).await
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        self.delay(Operation::Set).await;
+        self.store.rename(old_key, new_key).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.delay(Operation::Clear).await;
+        self.store.clear().await
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        self.store.usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store.scan_entries(prefix, batch_size)
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store.find_entries(pattern, batch_size)
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+/// How long an operation of a `FakeStore` takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Latency {
+    /// Every call takes exactly this long.
+    Fixed(Duration),
+    /// Calls take between `min` and `max`, uniformly distributed.
+    Uniform { min: Duration, max: Duration },
+    /// Calls take `min` plus an exponentially distributed delay averaging `mean`, the
+    /// long tail of a backend across the network.
+    Exponential { min: Duration, mean: Duration },
+}
+
+impl Default for Latency {
+    fn default() -> Self {
+        Latency::Fixed(Duration::ZERO)
+    }
+}
+
+impl Latency {
+    /// Returns the delays a `FakeStore` seeded with `seed` draws for an operation, in
+    /// order, e.g. to compute the percentiles a timeout is tuned against.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use keyv::adapter::fake::Latency;
+    /// let latency = Latency::Uniform {
+    ///     min: Duration::from_millis(5),
+    ///     max: Duration::from_millis(20),
+    /// };
+    /// let mut delays: Vec<Duration> = latency.samples(42).take(1_000).collect();
+    /// delays.sort();
+    /// let p99 = delays[989];
+    /// assert!(p99 <= Duration::from_millis(20));
+    /// ```
+    pub fn samples(self, seed: u64) -> Samples {
+        Samples {
+            latency: self,
+            state: seed,
+        }
+    }
+}
+
+/// Deterministic sequence of delays drawn from a `Latency`, see `Latency::samples`.
+#[derive(Debug, Clone)]
+pub struct Samples {
+    latency: Latency,
+    /// SplitMix64 state.
+    state: u64,
+}
+
+impl Samples {
+    /// Returns a number uniformly distributed in `[0, 1)`.
+    fn next_unit(&mut self) -> f64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Iterator for Samples {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        Some(match self.latency {
+            Latency::Fixed(duration) => duration,
+            Latency::Uniform { min, max } => {
+                let max = max.max(min);
+                min + (max - min).mul_f64(self.next_unit())
+            }
+            Latency::Exponential { min, mean } => {
+                min + mean.mul_f64(-(1.0 - self.next_unit()).ln())
+            }
+        })
+    }
+}
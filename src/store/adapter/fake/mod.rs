@@ -0,0 +1,8 @@
+mod fake;
+pub use fake::*;
+
+mod builder;
+pub use builder::*;
+
+mod latency;
+pub use latency::*;
@@ -1,57 +1,932 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
 
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
 use serde_json::Value;
 use tokio::sync::Mutex;
 
-use crate::{Store, StoreError};
+use super::journal::{Journal, Record};
+use crate::{
+    store::{
+        expiry::{now_millis, system_time_from_millis},
+        parse_sequence_cursor,
+    },
+    Capabilities, Change, ChangeFeedBackend, ChangeKind, Clock, EvictionPriority, Metadata,
+    QueueBackend, QueueMessage, ScanEntry, Store, StoreError, SystemClock, Usage,
+};
 
+/// Number of journal records after which the journal is compacted, provided it holds
+/// more than twice as many records as there are entries.
+pub const DEFAULT_JOURNAL_COMPACTION_THRESHOLD: u64 = 10_000;
+
+struct Entry {
+    value: Arc<Value>,
+    expires_at: Option<Instant>,
+    priority: EvictionPriority,
+    last_used: u64,
+    weight: u64,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Returns the value with its metadata; only the expiration is tracked.
+    fn with_metadata(&self, now: Instant) -> (Value, Metadata) {
+        let metadata = Metadata {
+            expires_at: self
+                .expires_at
+                .map(|expires_at| system_time_from_millis(instant_to_millis(expires_at, now))),
+            ..Metadata::default()
+        };
+        (Value::clone(&self.value), metadata)
+    }
+}
+
+/// Why an `InMemoryStore` dropped an entry, as reported to its eviction listener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionReason {
+    /// The entry was evicted to stay within `max_entries` or `max_bytes`.
+    Capacity,
+    /// The entry's TTL elapsed.
+    Expired,
+    /// The entry was removed by `remove`, `remove_many` or `clear`.
+    Removed,
+}
+
+/// Callback notified of every entry an `InMemoryStore` drops, see `on_evict`.
+pub type EvictionListener = Arc<dyn Fn(&str, &Value, EvictionReason) + Send + Sync>;
+
+/// The entries of an `InMemoryStore` and their recency, tracked per priority.
+struct Entries {
+    map: HashMap<String, Entry>,
+    /// Keys ordered from least to most recently used, one queue per priority.
+    recency: [BTreeMap<u64, String>; 3],
+    clock: u64,
+    /// Sum of the weights of all entries.
+    weight: u64,
+    on_evict: Option<EvictionListener>,
+    /// The clock expirations are measured against.
+    time: Arc<dyn Clock>,
+}
+
+impl Default for Entries {
+    fn default() -> Self {
+        Self {
+            map: HashMap::new(),
+            recency: Default::default(),
+            clock: 0,
+            weight: 0,
+            on_evict: None,
+            time: Arc::new(SystemClock),
+        }
+    }
+}
+
+impl Entries {
+    fn now(&self) -> Instant {
+        self.time.now()
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Returns the live entry for `key`, marking it as recently used.
+    fn get(&mut self, key: &str) -> Option<&Entry> {
+        if self.map.get(key)?.is_expired(self.now()) {
+            self.discard(key, EvictionReason::Expired);
+            return None;
+        }
+        let tick = self.tick();
+        let entry = self.map.get_mut(key)?;
+        let queue = &mut self.recency[entry.priority as usize];
+        queue.remove(&entry.last_used);
+        queue.insert(tick, key.to_string());
+        entry.last_used = tick;
+        Some(entry)
+    }
+
+    fn insert(
+        &mut self,
+        key: &str,
+        value: Arc<Value>,
+        expires_at: Option<Instant>,
+        priority: EvictionPriority,
+        weight: u64,
+    ) {
+        if self
+            .map
+            .get(key)
+            .is_some_and(|entry| entry.is_expired(self.now()))
+        {
+            self.discard(key, EvictionReason::Expired);
+        }
+        self.remove(key);
+        let tick = self.tick();
+        self.recency[priority as usize].insert(tick, key.to_string());
+        self.weight += weight;
+        self.map.insert(
+            key.to_string(),
+            Entry {
+                value,
+                expires_at,
+                priority,
+                last_used: tick,
+                weight,
+            },
+        );
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.map.remove(key)?;
+        self.recency[entry.priority as usize].remove(&entry.last_used);
+        self.weight -= entry.weight;
+        Some(entry)
+    }
+
+    /// Removes the entry for `key` and notifies the eviction listener, reporting
+    /// `reason` unless the entry had already expired.
+    fn discard(&mut self, key: &str, reason: EvictionReason) {
+        let Some(entry) = self.remove(key) else {
+            return;
+        };
+        if let Some(on_evict) = &self.on_evict {
+            let reason = if entry.is_expired(self.now()) {
+                EvictionReason::Expired
+            } else {
+                reason
+            };
+            on_evict(key, &entry.value, reason);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.recency.iter_mut().for_each(BTreeMap::clear);
+        self.weight = 0;
+    }
+
+    /// Returns a `Set` record for every live entry, least recently used first.
+    fn snapshot(&self) -> Vec<Record> {
+        let now = self.now();
+        self.recency
+            .iter()
+            .flat_map(|queue| queue.values())
+            .filter_map(|key| {
+                let entry = self.map.get(key).filter(|entry| !entry.is_expired(now))?;
+                Some(Record::Set {
+                    key: key.clone(),
+                    value: Value::clone(&entry.value),
+                    expires_at: entry
+                        .expires_at
+                        .map(|expires_at| instant_to_millis(expires_at, now)),
+                    priority: entry.priority,
+                })
+            })
+            .collect()
+    }
+
+    /// Evicts least recently used entries, lowest priority first, until at most
+    /// `max_entries` remain and their total weight is at most `max_bytes`.
+    fn trim(&mut self, max_entries: Option<usize>, max_bytes: Option<u64>) {
+        while max_entries.is_some_and(|max_entries| self.map.len() > max_entries)
+            || max_bytes.is_some_and(|max_bytes| self.weight > max_bytes)
+        {
+            let Some(key) = self
+                .recency
+                .iter_mut()
+                .find_map(|queue| queue.pop_first().map(|(_, key)| key))
+            else {
+                break;
+            };
+            if let Some(entry) = self.map.remove(&key) {
+                self.weight -= entry.weight;
+                if let Some(on_evict) = &self.on_evict {
+                    on_evict(&key, &entry.value, EvictionReason::Capacity);
+                }
+            }
+        }
+    }
+}
+
+/// A message of an in-memory queue.
+struct QueuedMessage {
+    payload: Value,
+    claimed_until: Option<Instant>,
+}
+
+/// The queues of an `InMemoryStore`, each holding its messages by id in push order.
+#[derive(Default)]
+struct Queues {
+    queues: HashMap<String, BTreeMap<u64, QueuedMessage>>,
+    next_id: u64,
+}
+
+/// Store keeping values in a process-local `HashMap`.
+///
+/// TTLs are honoured with millisecond precision or better: expired entries are never
+/// returned and are dropped the next time they are read or overwritten.
+///
+/// Values are kept behind `Arc`s, so `Store::get_ref` reads them without copying them,
+/// and touching or renaming an entry does not copy its value either.
+///
+/// The store is unbounded by default. With `max_entries` or `max_bytes` set, writes
+/// beyond the limit evict the least recently used entry of the lowest
+/// `EvictionPriority` present.
+///
+/// With an `on_evict` listener set, the store reports every entry it drops, whether
+/// evicted for capacity, expired or removed.
+///
+/// With a `journal` configured, every mutation is appended to a local file that
+/// `initialize()` replays, so the contents survive a restart of the process. Queue
+/// messages and the change feed are not journaled.
 pub struct InMemoryStore {
-    db: Mutex<HashMap<String, Value>>,
+    db: Mutex<Entries>,
+    queues: Mutex<Queues>,
+    /// The change feed, in append order: the change at index `i` has cursor `i + 1`.
+    changes: Mutex<Vec<Change>>,
+    max_entries: Option<usize>,
+    max_bytes: Option<u64>,
+    weigher: Weigher,
+    journal: Option<std::sync::Mutex<Journal>>,
+    compact_journal_after: u64,
+}
+
+/// Computes the weight of an entry, in bytes, counted against `max_bytes`.
+pub type Weigher = Arc<dyn Fn(&str, &Value) -> u64 + Send + Sync>;
+
+/// Weighs an entry by the length of its serialized value.
+fn serialized_len(_key: &str, value: &Value) -> u64 {
+    value.to_string().len() as u64
+}
+
+/// Converts an expiration instant, possibly in the past, to milliseconds since the
+/// Unix epoch.
+fn instant_to_millis(expires_at: Instant, now: Instant) -> i64 {
+    let millis = |duration: Duration| i64::try_from(duration.as_millis()).unwrap_or(i64::MAX);
+    match expires_at.checked_duration_since(now) {
+        Some(remaining) => now_millis().saturating_add(millis(remaining)),
+        None => now_millis().saturating_sub(millis(now - expires_at)),
+    }
+}
+
+/// Converts milliseconds since the Unix epoch to an expiration instant, or `None` if
+/// the time has already passed.
+fn millis_to_instant(expires_at: i64, now: Instant) -> Option<Instant> {
+    let remaining = u64::try_from(expires_at.checked_sub(now_millis())?).ok()?;
+    (remaining > 0).then(|| now + Duration::from_millis(remaining))
+}
+
+impl Default for InMemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl InMemoryStore {
     pub fn new() -> Self {
         InMemoryStore {
-            db: Mutex::new(HashMap::new()),
+            db: Mutex::new(Entries::default()),
+            queues: Mutex::new(Queues::default()),
+            changes: Mutex::new(Vec::new()),
+            max_entries: None,
+            max_bytes: None,
+            weigher: Arc::new(serialized_len),
+            journal: None,
+            compact_journal_after: DEFAULT_JOURNAL_COMPACTION_THRESHOLD,
+        }
+    }
+
+    /// Bounds the store to `max_entries` entries, evicting by priority and recency.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use keyv::{Keyv, EvictionPriority, adapter::inmemory::InMemoryStore};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let keyv = Keyv::try_new(InMemoryStore::new().max_entries(1)).await.unwrap();
+    /// keyv.set_with_priority("report", 42, None, EvictionPriority::High).await.unwrap();
+    /// keyv.set_with_priority("page", 1, None, EvictionPriority::Low).await.unwrap();
+    /// assert!(keyv.get("report").await.unwrap().is_some());
+    /// # }
+    /// ```
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Bounds the total weight of the entries to `max_bytes`, evicting by priority and
+    /// recency.
+    ///
+    /// Entries are weighed with the `weigher`, by default the length of the serialized
+    /// value. An entry heavier than `max_bytes` on its own is evicted right away.
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Sets the function weighing entries against `max_bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use keyv::adapter::inmemory::InMemoryStore;
+    /// let store = InMemoryStore::new()
+    ///     .max_bytes(64 * 1024 * 1024)
+    ///     .weigher(|key, value| (key.len() + value.to_string().len()) as u64);
+    /// ```
+    pub fn weigher<F>(mut self, weigher: F) -> Self
+    where
+        F: Fn(&str, &Value) -> u64 + Send + Sync + 'static,
+    {
+        self.weigher = Arc::new(weigher);
+        self
+    }
+
+    /// Calls `on_evict` with the key, value and `EvictionReason` of every entry the
+    /// store drops, e.g. to move evicted entries to a colder tier or to count churn.
+    ///
+    /// Expired entries are reported when the store drops them, the next time they are
+    /// read or overwritten, not the moment their TTL elapses. The listener runs while
+    /// the store is locked, so it must not block; hand slow work off to a task.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use keyv::adapter::inmemory::{EvictionReason, InMemoryStore};
+    /// let store = InMemoryStore::new()
+    ///     .max_entries(10_000)
+    ///     .on_evict(|key, _value, reason| {
+    ///         if reason == EvictionReason::Capacity {
+    ///             log::info!("Evicted '{}'", key);
+    ///         }
+    ///     });
+    /// ```
+    pub fn on_evict<F>(mut self, on_evict: F) -> Self
+    where
+        F: Fn(&str, &Value, EvictionReason) + Send + Sync + 'static,
+    {
+        self.db.get_mut().on_evict = Some(Arc::new(on_evict));
+        self
+    }
+
+    /// Sets the clock TTLs are measured against, `SystemClock` by default.
+    ///
+    /// Pass a `ManualClock` to expire entries in tests without waiting, see its
+    /// example. Queue visibility timeouts keep following the system clock.
+    pub fn clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.db.get_mut().time = Arc::new(clock);
+        self
+    }
+
+    /// Persists mutations to an append-only journal at `path`.
+    ///
+    /// `initialize()` replays the journal, so a restarted process starts with the
+    /// entries it had before, minus those that expired in the meantime. Records are
+    /// written before the mutation is applied, without waiting for them to reach the
+    /// disk: they survive a crash of the process but not of the machine. Recency is not
+    /// recorded, so eviction order after a restart follows write order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use keyv::{Keyv, adapter::inmemory::InMemoryStore};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let store = InMemoryStore::new().journal("/var/lib/app/cache.journal");
+    /// let keyv = Keyv::try_new(store).await.unwrap();
+    /// # }
+    /// ```
+    pub fn journal<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.journal = Some(std::sync::Mutex::new(Journal::new(path.into())));
+        self
+    }
+
+    /// Sets the number of journal records that triggers a compaction.
+    ///
+    /// The journal is rewritten with one record per entry once it holds at least
+    /// `records` records and more than twice as many records as entries. Defaults to
+    /// `DEFAULT_JOURNAL_COMPACTION_THRESHOLD`.
+    pub fn compact_journal_after(mut self, records: u64) -> Self {
+        self.compact_journal_after = records.max(1);
+        self
+    }
+
+    /// Returns a copy of every live entry, keyed by key.
+    ///
+    /// Meant for tests asserting the full contents of a cache. Entries whose TTL has
+    /// elapsed are left out, and reading the snapshot does not count as a use for
+    /// eviction.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::collections::HashMap;
+    /// # use keyv::{adapter::inmemory::InMemoryStore, Store};
+    /// # async {
+    /// let store = InMemoryStore::new();
+    /// store.set("user:1", "alice".into(), None).await.unwrap();
+    ///
+    /// let expected = HashMap::from([("user:1".to_string(), "alice".into())]);
+    /// assert_eq!(store.snapshot().await, expected);
+    /// # };
+    /// ```
+    pub async fn snapshot(&self) -> HashMap<String, Value> {
+        self.iter().await.collect()
+    }
+
+    /// Returns the live entries ordered by key, like `snapshot`, for tests comparing
+    /// the contents against a list.
+    pub async fn iter(&self) -> impl Iterator<Item = (String, Value)> {
+        let db_lock = self.db.lock().await;
+        let now = db_lock.now();
+        let mut entries: Vec<(String, Value)> = db_lock
+            .map
+            .iter()
+            .filter(|(_, entry)| !entry.is_expired(now))
+            .map(|(key, entry)| (key.clone(), Value::clone(&entry.value)))
+            .collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        entries.into_iter()
+    }
+
+    /// Rewrites the journal with one record per live entry.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the journal was rewritten, or if no journal is configured.
+    /// - `Err(StoreError)` if the journal file could not be written.
+    pub async fn compact_journal(&self) -> Result<(), StoreError> {
+        let db_lock = self.db.lock().await;
+        match &self.journal {
+            Some(journal) => lock_journal(journal).rewrite(db_lock.snapshot()),
+            None => Ok(()),
+        }
+    }
+
+    /// Appends the record built by `record` to the journal, if any, compacting it first
+    /// when it has grown past the threshold. Must be called while holding the `db` lock,
+    /// before the mutation is applied.
+    fn log(&self, db_lock: &Entries, record: impl FnOnce() -> Record) -> Result<(), StoreError> {
+        let Some(journal) = &self.journal else {
+            return Ok(());
+        };
+        let mut journal = lock_journal(journal);
+        let records = journal.records();
+        if records >= self.compact_journal_after && records > 2 * db_lock.map.len() as u64 {
+            journal.rewrite(db_lock.snapshot())?;
         }
+        journal.append(&record())
+    }
+
+    fn insert(
+        &self,
+        db_lock: &mut Entries,
+        key: &str,
+        value: Arc<Value>,
+        expires_at: Option<Instant>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        self.log(db_lock, || Record::Set {
+            key: key.to_string(),
+            value: Value::clone(&value),
+            expires_at: expires_at.map(|expires_at| instant_to_millis(expires_at, db_lock.now())),
+            priority,
+        })?;
+        self.apply(db_lock, key, value, expires_at, priority);
+        Ok(())
+    }
+
+    fn apply(
+        &self,
+        db_lock: &mut Entries,
+        key: &str,
+        value: Arc<Value>,
+        expires_at: Option<Instant>,
+        priority: EvictionPriority,
+    ) {
+        let weight = if self.max_bytes.is_some() {
+            (self.weigher)(key, &value)
+        } else {
+            0
+        };
+        db_lock.insert(key, value, expires_at, priority, weight);
+        db_lock.trim(self.max_entries, self.max_bytes);
     }
 }
 
+fn lock_journal(journal: &std::sync::Mutex<Journal>) -> std::sync::MutexGuard<'_, Journal> {
+    journal
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
 #[async_trait]
 impl Store for InMemoryStore {
     async fn initialize(&self) -> Result<(), StoreError> {
-        Ok(())
+        let Some(journal) = &self.journal else {
+            return Ok(());
+        };
+        let mut db_lock = self.db.lock().await;
+        let mut journal = lock_journal(journal);
+        for record in journal.read()? {
+            match record {
+                Record::Set {
+                    key,
+                    value,
+                    expires_at,
+                    priority,
+                } => {
+                    match expires_at.map(|expires_at| millis_to_instant(expires_at, db_lock.now()))
+                    {
+                        Some(None) => {
+                            db_lock.remove(&key);
+                        }
+                        Some(expires_at) => {
+                            self.apply(&mut db_lock, &key, Arc::new(value), expires_at, priority)
+                        }
+                        None => self.apply(&mut db_lock, &key, Arc::new(value), None, priority),
+                    }
+                }
+                Record::Remove { key } => {
+                    db_lock.remove(&key);
+                }
+                Record::Clear => db_lock.clear(),
+            }
+        }
+        journal.rewrite(db_lock.snapshot())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            native_ttl: true,
+            atomic_increment: true,
+            transactions: false,
+            scan: true,
+            watch: true,
+        }
     }
 
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let mut db_lock = self.db.lock().await;
+        Ok(db_lock.get(key).map(|entry| Value::clone(&entry.value)))
+    }
+
+    async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, StoreError> {
+        let mut db_lock = self.db.lock().await;
+        Ok(db_lock.get(key).map(|entry| entry.value.clone()))
+    }
+
+    /// Only the expiration is tracked; creation and update times are not.
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        let mut db_lock = self.db.lock().await;
+        let now = db_lock.now();
+        Ok(db_lock.get(key).map(|entry| entry.with_metadata(now)))
+    }
+
+    /// Expired entries stay readable until they are read with `get`, overwritten or
+    /// evicted.
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
         let db_lock = self.db.lock().await;
-        Ok(db_lock.get(key).cloned())
+        let now = db_lock.now();
+        Ok(db_lock.map.get(key).map(|entry| entry.with_metadata(now)))
     }
 
-    async fn set(&self, key: &str, value: Value, _ttl: Option<u64>) -> Result<(), StoreError> {
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
         let mut db_lock = self.db.lock().await;
-        db_lock.insert(key.to_string(), value.clone());
-        Ok(())
+        let Some((value, priority)) = db_lock
+            .get(key)
+            .map(|entry| (entry.value.clone(), entry.priority))
+        else {
+            return Ok(None);
+        };
+        let expires_at = Some(db_lock.now() + ttl);
+        self.insert(&mut db_lock, key, value.clone(), expires_at, priority)?;
+        Ok(Some(Value::clone(&value)))
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        let mut db_lock = self.db.lock().await;
+        let expires_at = Some(db_lock.now() + ttl);
+        let mut touched = 0;
+        for key in keys {
+            let Some((value, priority)) = db_lock
+                .get(key)
+                .map(|entry| (entry.value.clone(), entry.priority))
+            else {
+                continue;
+            };
+            self.insert(&mut db_lock, key, value, expires_at, priority)?;
+            touched += 1;
+        }
+        Ok(touched)
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let mut db_lock = self.db.lock().await;
+        Ok(keys
+            .iter()
+            .map(|key| db_lock.get(key).map(|entry| Value::clone(&entry.value)))
+            .collect())
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        let mut db_lock = self.db.lock().await;
+        let expires_at = ttl.map(|ttl| db_lock.now() + ttl);
+        self.insert(
+            &mut db_lock,
+            key,
+            Arc::new(value),
+            expires_at,
+            EvictionPriority::Normal,
+        )
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        let mut db_lock = self.db.lock().await;
+        let expires_at = ttl.map(|ttl| db_lock.now() + ttl);
+        self.insert(&mut db_lock, key, Arc::new(value), expires_at, priority)
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        let mut db_lock = self.db.lock().await;
+        let (expires_at, priority) = db_lock
+            .get(key)
+            .map_or((None, EvictionPriority::Normal), |entry| {
+                (entry.expires_at, entry.priority)
+            });
+        self.insert(&mut db_lock, key, Arc::new(value), expires_at, priority)
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        let mut db_lock = self.db.lock().await;
+        if db_lock.get(key).is_some() {
+            return Ok(false);
+        }
+        let expires_at = ttl.map(|ttl| db_lock.now() + ttl);
+        self.insert(
+            &mut db_lock,
+            key,
+            Arc::new(value),
+            expires_at,
+            EvictionPriority::Normal,
+        )?;
+        Ok(true)
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        let mut db_lock = self.db.lock().await;
+        let (current, expires_at, priority) = match db_lock.get(key) {
+            Some(entry) => (
+                entry.value.as_i64().ok_or_else(|| {
+                    StoreError::QueryError(format!("The value of '{}' is not an integer", key))
+                })?,
+                entry.expires_at,
+                entry.priority,
+            ),
+            None => (
+                0,
+                ttl.map(|ttl| db_lock.now() + ttl),
+                EvictionPriority::Normal,
+            ),
+        };
+        let value = current.checked_add(delta).ok_or_else(|| {
+            StoreError::QueryError(format!("Incrementing '{}' overflows an i64", key))
+        })?;
+        self.insert(
+            &mut db_lock,
+            key,
+            Arc::new(value.into()),
+            expires_at,
+            priority,
+        )?;
+        Ok(value)
     }
 
     async fn remove(&self, key: &str) -> Result<(), StoreError> {
         let mut db_lock = self.db.lock().await;
-        db_lock.remove(key);
+        self.log(&db_lock, || Record::Remove {
+            key: key.to_string(),
+        })?;
+        db_lock.discard(key, EvictionReason::Removed);
         Ok(())
     }
 
     async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
         let mut db_lock = self.db.lock().await;
         for key in keys {
-            db_lock.remove(&key.to_string());
+            self.log(&db_lock, || Record::Remove {
+                key: key.to_string(),
+            })?;
+            db_lock.discard(key, EvictionReason::Removed);
         }
         Ok(())
     }
 
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        let mut db_lock = self.db.lock().await;
+        if db_lock.get(key).map(|entry| entry.value.as_ref()) != Some(expected) {
+            return Ok(false);
+        }
+        self.log(&db_lock, || Record::Remove {
+            key: key.to_string(),
+        })?;
+        db_lock.discard(key, EvictionReason::Removed);
+        Ok(true)
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        let mut db_lock = self.db.lock().await;
+        let Some((value, expires_at, priority)) = db_lock
+            .get(old_key)
+            .map(|entry| (entry.value.clone(), entry.expires_at, entry.priority))
+        else {
+            return Err(StoreError::NotFound);
+        };
+        if old_key == new_key {
+            return Ok(());
+        }
+
+        // A move is not a removal, so the eviction listener is not notified.
+        self.log(&db_lock, || Record::Remove {
+            key: old_key.to_string(),
+        })?;
+        db_lock.remove(old_key);
+        self.insert(&mut db_lock, new_key, value, expires_at, priority)
+    }
+
     async fn clear(&self) -> Result<(), StoreError> {
         let mut db_lock = self.db.lock().await;
+        self.log(&db_lock, || Record::Clear)?;
+        if db_lock.on_evict.is_some() {
+            let keys: Vec<String> = db_lock.map.keys().cloned().collect();
+            for key in keys {
+                db_lock.discard(&key, EvictionReason::Removed);
+            }
+        }
         db_lock.clear();
         Ok(())
     }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        let db_lock = self.db.lock().await;
+        let now = db_lock.now();
+        let mut usage = Usage {
+            entries: 0,
+            bytes: Some(0),
+        };
+        for (key, entry) in db_lock
+            .map
+            .iter()
+            .filter(|(_, entry)| !entry.is_expired(now))
+        {
+            usage.entries += 1;
+            usage.bytes = usage
+                .bytes
+                .map(|bytes| bytes + (key.len() + entry.value.to_string().len()) as u64);
+        }
+        Ok(usage)
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        let prefix = prefix.unwrap_or_default();
+        let batch_size = batch_size.max(1);
+        Box::pin(
+            stream::once(async move {
+                // The entries already live in memory, so the matching ones are copied
+                // under a single lock and handed out in batches.
+                let db_lock = self.db.lock().await;
+                let now = db_lock.now();
+                let wall_now = SystemTime::now();
+                let mut entries: Vec<ScanEntry> = db_lock
+                    .map
+                    .iter()
+                    .filter(|(key, entry)| !entry.is_expired(now) && key.starts_with(prefix))
+                    .map(|(key, entry)| ScanEntry {
+                        key: key.clone(),
+                        value: Value::clone(&entry.value),
+                        expires_at: entry
+                            .expires_at
+                            .map(|expires_at| wall_now + expires_at.duration_since(now)),
+                    })
+                    .collect();
+                entries.sort_by(|a, b| a.key.cmp(&b.key));
+                let batches: Vec<Result<Vec<ScanEntry>, StoreError>> = entries
+                    .chunks(batch_size)
+                    .map(|batch| Ok(batch.to_vec()))
+                    .collect();
+                stream::iter(batches)
+            })
+            .flatten(),
+        )
+    }
+}
+
+#[async_trait]
+impl QueueBackend for InMemoryStore {
+    async fn queue_push(&self, queue: &str, payload: Value) -> Result<String, StoreError> {
+        let mut queues = self.queues.lock().await;
+        queues.next_id += 1;
+        let id = queues.next_id;
+        queues.queues.entry(queue.to_string()).or_default().insert(
+            id,
+            QueuedMessage {
+                payload,
+                claimed_until: None,
+            },
+        );
+        Ok(id.to_string())
+    }
+
+    async fn queue_pop(
+        &self,
+        queue: &str,
+        visibility_timeout: Duration,
+    ) -> Result<Option<QueueMessage>, StoreError> {
+        let mut queues = self.queues.lock().await;
+        let now = Instant::now();
+        let Some(messages) = queues.queues.get_mut(queue) else {
+            return Ok(None);
+        };
+        let available = messages.iter_mut().find(|(_, message)| {
+            message
+                .claimed_until
+                .is_none_or(|claimed_until| claimed_until <= now)
+        });
+        Ok(available.map(|(id, message)| {
+            message.claimed_until = Some(now + visibility_timeout);
+            QueueMessage {
+                id: id.to_string(),
+                payload: message.payload.clone(),
+            }
+        }))
+    }
+
+    async fn queue_ack(&self, queue: &str, id: &str) -> Result<(), StoreError> {
+        let mut queues = self.queues.lock().await;
+        if let (Some(messages), Ok(id)) = (queues.queues.get_mut(queue), id.parse::<u64>()) {
+            messages.remove(&id);
+            if messages.is_empty() {
+                queues.queues.remove(queue);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChangeFeedBackend for InMemoryStore {
+    async fn append_change(
+        &self,
+        key: Option<&str>,
+        kind: ChangeKind,
+    ) -> Result<String, StoreError> {
+        let mut changes = self.changes.lock().await;
+        let cursor = (changes.len() + 1).to_string();
+        changes.push(Change {
+            cursor: cursor.clone(),
+            key: key.map(str::to_string),
+            kind,
+            recorded_at: SystemTime::now(),
+        });
+        Ok(cursor)
+    }
+
+    async fn read_changes(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Change>, StoreError> {
+        let after = parse_sequence_cursor(after)? as usize;
+        let changes = self.changes.lock().await;
+        Ok(changes.iter().skip(after).take(limit).cloned().collect())
+    }
 }
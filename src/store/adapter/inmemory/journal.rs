@@ -0,0 +1,174 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, ErrorKind, Write},
+    path::PathBuf,
+};
+
+use serde_json::{json, Value};
+
+use crate::{EvictionPriority, StoreError};
+
+/// A mutation recorded in the journal of an `InMemoryStore`.
+pub(crate) enum Record {
+    Set {
+        key: String,
+        value: Value,
+        /// Expiration in milliseconds since the Unix epoch.
+        expires_at: Option<i64>,
+        priority: EvictionPriority,
+    },
+    Remove {
+        key: String,
+    },
+    Clear,
+}
+
+fn priority_name(priority: EvictionPriority) -> &'static str {
+    match priority {
+        EvictionPriority::Low => "low",
+        EvictionPriority::Normal => "normal",
+        EvictionPriority::High => "high",
+    }
+}
+
+fn parse_priority(name: &str) -> Option<EvictionPriority> {
+    match name {
+        "low" => Some(EvictionPriority::Low),
+        "normal" => Some(EvictionPriority::Normal),
+        "high" => Some(EvictionPriority::High),
+        _ => None,
+    }
+}
+
+impl Record {
+    fn to_json(&self) -> Value {
+        match self {
+            Record::Set {
+                key,
+                value,
+                expires_at,
+                priority,
+            } => json!({
+                "op": "set",
+                "key": key,
+                "value": value,
+                "expires_at": expires_at,
+                "priority": priority_name(*priority),
+            }),
+            Record::Remove { key } => json!({ "op": "remove", "key": key }),
+            Record::Clear => json!({ "op": "clear" }),
+        }
+    }
+
+    fn from_json(mut record: Value) -> Option<Record> {
+        let key = record
+            .get("key")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        match record.get("op")?.as_str()? {
+            "set" => Some(Record::Set {
+                key: key?,
+                expires_at: record.get("expires_at").and_then(Value::as_i64),
+                priority: parse_priority(record.get("priority")?.as_str()?)?,
+                value: record.get_mut("value")?.take(),
+            }),
+            "remove" => Some(Record::Remove { key: key? }),
+            "clear" => Some(Record::Clear),
+            _ => None,
+        }
+    }
+}
+
+fn io_error(source: std::io::Error) -> StoreError {
+    StoreError::DatabaseError {
+        source: Box::new(source),
+    }
+}
+
+/// Append-only log of the mutations of an `InMemoryStore`, one JSON record per line.
+pub(crate) struct Journal {
+    path: PathBuf,
+    file: Option<File>,
+    records: u64,
+}
+
+impl Journal {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            file: None,
+            records: 0,
+        }
+    }
+
+    /// Number of records appended since the journal was last rewritten.
+    pub(crate) fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Reads every record of the journal. A missing journal holds no records, and
+    /// lines that cannot be parsed, such as one torn by a crash, are skipped.
+    pub(crate) fn read(&self) -> Result<Vec<Record>, StoreError> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_error(e)),
+        };
+        let mut records = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(io_error)?;
+            match serde_json::from_str(&line).ok().and_then(Record::from_json) {
+                Some(record) => records.push(record),
+                None => log::warn!(
+                    "Skipping an unreadable record in journal '{}'",
+                    self.path.display()
+                ),
+            }
+        }
+        Ok(records)
+    }
+
+    pub(crate) fn append(&mut self, record: &Record) -> Result<(), StoreError> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => self.file.insert(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+                    .map_err(io_error)?,
+            ),
+        };
+        let mut line = record.to_json().to_string();
+        line.push('\n');
+        file.write_all(line.as_bytes()).map_err(io_error)?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Atomically replaces the journal with `records`.
+    pub(crate) fn rewrite<I: IntoIterator<Item = Record>>(
+        &mut self,
+        records: I,
+    ) -> Result<(), StoreError> {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+
+        let mut writer = BufWriter::new(File::create(&tmp_path).map_err(io_error)?);
+        let mut count = 0;
+        for record in records {
+            serde_json::to_writer(&mut writer, &record.to_json())?;
+            writer.write_all(b"\n").map_err(io_error)?;
+            count += 1;
+        }
+        let file = writer.into_inner().map_err(|e| io_error(e.into_error()))?;
+        file.sync_all().map_err(io_error)?;
+        drop(file);
+
+        self.file = None;
+        fs::rename(&tmp_path, &self.path).map_err(io_error)?;
+        self.records = count;
+        Ok(())
+    }
+}
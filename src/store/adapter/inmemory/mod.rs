@@ -1,2 +1,4 @@
 mod inmemory;
 pub use inmemory::*;
+
+mod journal;
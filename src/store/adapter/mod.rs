@@ -14,3 +14,32 @@ pub mod mongodb;
 pub mod sqlite;
 
 pub mod inmemory;
+
+pub mod fake;
+
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+mod columns;
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+pub(crate) use columns::*;
+
+#[cfg(any(
+    feature = "postgres",
+    feature = "mysql",
+    feature = "sqlite",
+    feature = "redis",
+    feature = "mongodb"
+))]
+mod uri;
+#[cfg(any(
+    feature = "postgres",
+    feature = "mysql",
+    feature = "sqlite",
+    feature = "redis",
+    feature = "mongodb"
+))]
+pub(crate) use uri::*;
+
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+mod value_format;
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub use value_format::*;
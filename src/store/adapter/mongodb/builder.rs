@@ -1,10 +1,13 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-pub use mongodb::{options::ClientOptions, Client};
+pub use mongodb::{
+    options::{ClientOptions, Credential, Tls, TlsOptions},
+    Client,
+};
 
-use crate::{StoreError, DEFAUTL_NAMESPACE_NAME};
+use crate::{adapter::validate_uri_scheme, RetryPolicy, StoreError, DEFAUTL_NAMESPACE_NAME};
 
-use super::MongoStore;
+use super::{CappedCollection, MongoStore};
 
 /// Builder for creating a `MongoStore`.
 ///
@@ -30,6 +33,27 @@ use super::MongoStore;
 /// }
 /// ```
 ///
+/// ## Configuring Credentials and TLS on the Builder
+///
+/// ```rust,no_run
+/// # use keyv::adapter::mongodb::{MongoStoreBuilder, TlsOptions};
+/// # use std::time::Duration;
+/// # #[tokio::main]
+/// # async fn main() {
+/// let tls = TlsOptions::builder()
+///     .ca_file_path(std::path::PathBuf::from("/etc/ssl/mongo-ca.pem"))
+///     .build();
+/// let store = MongoStoreBuilder::new()
+///     .uri("mongodb://mongo.internal:27017")
+///     .credentials("app", "secret")
+///     .auth_source("admin")
+///     .tls(tls)
+///     .server_selection_timeout(Duration::from_secs(5))
+///     .build()
+///     .await.unwrap();
+/// }
+/// ```
+///
 /// ## Using an Existing Client
 ///
 /// ```rust,no_run
@@ -53,6 +77,21 @@ pub struct MongoStoreBuilder {
     database_name: Option<String>,
     collection_name: Option<String>,
     client: Option<Arc<Client>>,
+    initialize_retry: Option<RetryPolicy>,
+    capped: Option<CappedCollection>,
+    max_documents: Option<u64>,
+    transactions: bool,
+    username: Option<String>,
+    password: Option<String>,
+    auth_source: Option<String>,
+    tls: Option<TlsOptions>,
+    server_selection_timeout: Option<Duration>,
+}
+
+impl Default for MongoStoreBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MongoStoreBuilder {
@@ -66,6 +105,15 @@ impl MongoStoreBuilder {
             database_name: None,
             collection_name: None,
             client: None,
+            initialize_retry: None,
+            capped: None,
+            max_documents: None,
+            transactions: false,
+            username: None,
+            password: None,
+            auth_source: None,
+            tls: None,
+            server_selection_timeout: None,
         }
     }
 
@@ -107,8 +155,8 @@ impl MongoStoreBuilder {
 
     /// Uses an existing client for the `MongoStore`.
     ///
-    /// This method allows for using an already configured MongoDB `Client`. If set,
-    /// the `uri` option is ignored.
+    /// This method allows for using an already configured MongoDB `Client`. It cannot be
+    /// combined with the `uri` option.
     ///
     /// # Arguments
     ///
@@ -118,30 +166,249 @@ impl MongoStoreBuilder {
         self
     }
 
+    /// Retries connecting and initializing the backend according to `policy`.
+    ///
+    /// Useful when the application may start before the database, e.g. under
+    /// docker-compose or Kubernetes. Without a policy a single attempt is made.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The retry attempts, backoff and deadline to apply.
+    pub fn initialize_retry(mut self, policy: RetryPolicy) -> Self {
+        self.initialize_retry = Some(policy);
+        self
+    }
+
+    /// Creates the collection as a capped collection.
+    ///
+    /// MongoDB evicts the oldest documents once the collection reaches `max_bytes` or,
+    /// if set, `max_documents`. The collection is only created as capped if it does not
+    /// exist when `initialize()` runs. Capped collections do not support TTL indexes,
+    /// so expired documents are hidden from reads but only removed by eviction.
+    ///
+    /// MongoDB rejects updates that change the size of a document in a capped
+    /// collection, so writes overwriting a key remove its document and insert a new
+    /// one, which requires MongoDB 5.0 and is not atomic. In-place updates, such as
+    /// `increment`, `get_and_touch`, `touch_many` and `rename`, fail when they change
+    /// the size of the document.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_bytes` - The maximum size of the collection in bytes.
+    /// * `max_documents` - An optional maximum number of documents.
+    pub fn capped(mut self, max_bytes: u64, max_documents: Option<u64>) -> Self {
+        self.capped = Some(CappedCollection {
+            max_bytes,
+            max_documents,
+        });
+        self
+    }
+
+    /// Bounds a regular collection to `max_documents`, evicting the least recently
+    /// written documents first.
+    ///
+    /// Unlike `capped`, the collection keeps supporting TTL expiry. Eviction runs after
+    /// writes that push the estimated document count over the limit, so the collection
+    /// can briefly exceed it under concurrent writes.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_documents` - The maximum number of documents to keep.
+    pub fn max_documents(mut self, max_documents: u64) -> Self {
+        self.max_documents = Some(max_documents);
+        self
+    }
+
+    /// Runs `set_many` and `remove_many` in a multi-document transaction.
+    ///
+    /// Each batch executes in a causally consistent session and is applied entirely or
+    /// not at all. Transactions require a replica set or sharded cluster. Transient
+    /// failures, such as write conflicts, are reported as
+    /// `StoreError::TransactionAborted` and can be retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether batches run in transactions.
+    pub fn transactions(mut self, enabled: bool) -> Self {
+        self.transactions = enabled;
+        self
+    }
+
+    /// Sets the username and password to authenticate with.
+    ///
+    /// Takes precedence over credentials embedded in the URI, so secrets can be kept
+    /// out of connection strings. Not applicable when an existing client is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The user to authenticate as.
+    /// * `password` - The user's password.
+    pub fn credentials<U: Into<String>, P: Into<String>>(
+        mut self,
+        username: U,
+        password: P,
+    ) -> Self {
+        self.username = Some(username.into());
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Sets the database the credentials are defined in.
+    ///
+    /// Takes precedence over the `authSource` URI option. MongoDB defaults to `admin`.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth_source` - The name of the authentication database.
+    pub fn auth_source<S: Into<String>>(mut self, auth_source: S) -> Self {
+        self.auth_source = Some(auth_source.into());
+        self
+    }
+
+    /// Connects over TLS with the given options.
+    ///
+    /// Takes precedence over the `tls*` URI options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The CA file, client certificate and verification settings to use.
+    pub fn tls(mut self, options: TlsOptions) -> Self {
+        self.tls = Some(options);
+        self
+    }
+
+    /// Sets how long an operation waits for a suitable server before failing.
+    ///
+    /// Takes precedence over the `serverSelectionTimeoutMS` URI option. The driver
+    /// defaults to 30 seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The maximum time to wait for a server.
+    pub fn server_selection_timeout(mut self, timeout: Duration) -> Self {
+        self.server_selection_timeout = Some(timeout);
+        self
+    }
+
+    /// Checks the configuration without connecting to the backend.
+    ///
+    /// `build()` runs the same checks, so calling this is only needed to report
+    /// misconfiguration early, e.g. at application startup.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the builder can be built, or `StoreError::InvalidConfiguration`
+    /// describing the offending option.
+    pub fn validate(&self) -> Result<(), StoreError> {
+        if self.client.is_none() && self.uri.is_none() {
+            return Err(StoreError::invalid_configuration(
+                "uri",
+                "MongoDB requires a URI or an existing client to be set",
+            ));
+        }
+        if let Some(uri) = &self.uri {
+            if self.client.is_some() {
+                return Err(StoreError::invalid_configuration(
+                    "client",
+                    "An existing client cannot be combined with a URI; set only one of them",
+                ));
+            }
+            validate_uri_scheme(uri, &["mongodb", "mongodb+srv"])?;
+        }
+        for (field, name) in [
+            ("database_name", &self.database_name),
+            ("collection_name", &self.collection_name),
+        ] {
+            if name.as_ref().is_some_and(|name| name.is_empty()) {
+                return Err(StoreError::invalid_configuration(
+                    field,
+                    "The name cannot be empty; leave it unset to use the default",
+                ));
+            }
+        }
+        if self.client.is_some()
+            && (self.username.is_some()
+                || self.auth_source.is_some()
+                || self.tls.is_some()
+                || self.server_selection_timeout.is_some())
+        {
+            return Err(StoreError::invalid_configuration(
+                "client",
+                "Connection options cannot be applied to an existing client; configure the client instead",
+            ));
+        }
+        if self.server_selection_timeout == Some(Duration::ZERO) {
+            return Err(StoreError::invalid_configuration(
+                "server_selection_timeout",
+                "The server selection timeout must be greater than zero",
+            ));
+        }
+        if self.capped.is_some() && self.max_documents.is_some() {
+            return Err(StoreError::invalid_configuration(
+                "max_documents",
+                "Capped collections are bounded by MongoDB; use the capped document limit instead",
+            ));
+        }
+        if self.capped.is_some_and(|capped| capped.max_bytes == 0) {
+            return Err(StoreError::invalid_configuration(
+                "capped",
+                "The capped collection size must be greater than zero",
+            ));
+        }
+        if self.max_documents == Some(0) {
+            return Err(StoreError::invalid_configuration(
+                "max_documents",
+                "The document limit must be greater than zero",
+            ));
+        }
+        Ok(())
+    }
+
     /// Builds the `MongoStore` based on the provided configurations.
     ///
     /// Finalizes the builder and creates a `MongoStore` instance.
     /// It requires either a MongoDB URI or an existing client to be set.
     ///
+    /// The MongoDB driver connects lazily and monitors the deployment in the
+    /// background, so the store can be built before the server is reachable and
+    /// recovers on its own after a server restart.
+    ///
     /// # Returns
     ///
     /// This method returns a `Result` which, on success, contains the initialized `MongoStore`.
     /// On failure, it returns a `StoreError` indicating what went wrong during the initialization.
     pub async fn build(self) -> Result<MongoStore, StoreError> {
+        self.validate()?;
         let client = match self.client {
             Some(client) => client,
             None => {
-                let uri = self
-                    .uri
-                    .expect("MongoDB requires a URI or an existing client to be set");
+                let uri = self.uri.ok_or_else(|| {
+                    StoreError::invalid_configuration(
+                        "uri",
+                        "MongoDB requires a URI or an existing client to be set",
+                    )
+                })?;
 
-                let options = ClientOptions::parse(&uri)
+                let mut options = ClientOptions::parse(&uri)
                     .await
-                    .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
-                Arc::new(
-                    Client::with_options(options)
-                        .map_err(|e| StoreError::ConnectionError(e.to_string()))?,
-                )
+                    .map_err(StoreError::connection)?;
+                if self.username.is_some() || self.auth_source.is_some() {
+                    let credential = options.credential.get_or_insert_with(Credential::default);
+                    if let Some(username) = self.username {
+                        credential.username = Some(username);
+                        credential.password = self.password;
+                    }
+                    if let Some(auth_source) = self.auth_source {
+                        credential.source = Some(auth_source);
+                    }
+                }
+                if let Some(tls) = self.tls {
+                    options.tls = Some(Tls::Enabled(tls));
+                }
+                if let Some(timeout) = self.server_selection_timeout {
+                    options.server_selection_timeout = Some(timeout);
+                }
+                Arc::new(Client::with_options(options).map_err(StoreError::connection)?)
             }
         };
 
@@ -165,6 +432,10 @@ impl MongoStoreBuilder {
             client,
             database_name,
             collection_name,
+            initialize_retry: self.initialize_retry,
+            capped: self.capped,
+            max_documents: self.max_documents,
+            transactions: self.transactions,
         })
     }
 }
@@ -1,38 +1,611 @@
 use async_trait::async_trait;
+use futures::{
+    stream::{self, BoxStream},
+    TryStreamExt,
+};
 use mongodb::{
-    bson::{doc, Bson, Document},
-    Client, Collection,
+    bson::{doc, Bson, DateTime, Document},
+    error::{TRANSIENT_TRANSACTION_ERROR, UNKNOWN_TRANSACTION_COMMIT_RESULT},
+    options::{
+        CreateCollectionOptions, FindOneAndUpdateOptions, FindOptions, IndexOptions,
+        ReplaceOptions, ReturnDocument, SessionOptions, UpdateOptions,
+    },
+    Client, ClientSession, Collection, IndexModel,
 };
 use serde_json::Value;
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    store::{expiry::expires_at_millis, glob},
+    Capabilities, Metadata, RetryPolicy, ScanEntry, Store, StoreError, Usage,
+};
+
+/// Filter clauses matching documents that have no expiration or have not expired yet.
+fn not_expired() -> Vec<Document> {
+    vec![
+        doc! { "expires_at": Bson::Null },
+        doc! { "expires_at": { "$gt": DateTime::now() } },
+    ]
+}
 
-use crate::{Store, StoreError};
+/// Returns a regular expression matching keys that start with `prefix`.
+fn prefix_regex(prefix: &str) -> String {
+    let mut regex = String::with_capacity(prefix.len() + 1);
+    regex.push('^');
+    for c in prefix.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            regex.push('\\');
+        }
+        regex.push(c);
+    }
+    regex
+}
 
 pub struct MongoStore {
     pub(crate) client: Arc<Client>,
     pub(crate) database_name: String,
     pub(crate) collection_name: String,
+    pub(crate) initialize_retry: Option<RetryPolicy>,
+    pub(crate) capped: Option<CappedCollection>,
+    pub(crate) max_documents: Option<u64>,
+    pub(crate) transactions: bool,
+}
+
+/// Prints the database and collection, never the client and its credentials.
+impl fmt::Debug for MongoStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MongoStore")
+            .field("database_name", &self.database_name)
+            .field("collection_name", &self.collection_name)
+            .field("capped", &self.capped)
+            .field("max_documents", &self.max_documents)
+            .field("transactions", &self.transactions)
+            .finish_non_exhaustive()
+    }
+}
+
+fn replace_options() -> ReplaceOptions {
+    ReplaceOptions::builder().upsert(true).build()
+}
+
+/// Maps an error raised inside a transaction, flagging the ones MongoDB labels as
+/// transient as retryable.
+fn transaction_error(error: mongodb::error::Error, context: &str) -> StoreError {
+    if error.contains_label(TRANSIENT_TRANSACTION_ERROR)
+        || error.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT)
+    {
+        StoreError::TransactionAborted(error.to_string())
+    } else {
+        StoreError::QueryError(format!("{}: {}", context, error))
+    }
+}
+
+/// Capped collection limits configured with `MongoStoreBuilder::capped`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CappedCollection {
+    pub(crate) max_bytes: u64,
+    pub(crate) max_documents: Option<u64>,
 }
 
 impl MongoStore {
-    fn get_collection(&self) -> Collection<Document> {
+    /// Returns the MongoDB client backing the store.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Returns the collection holding the store's documents.
+    ///
+    /// Gives access to backend-specific operations, such as aggregations, without
+    /// configuring a second client.
+    pub fn collection(&self) -> Collection<Document> {
         self.client
             .database(&self.database_name)
             .collection(&self.collection_name)
     }
+
+    async fn create_capped_collection(&self, capped: CappedCollection) -> Result<(), StoreError> {
+        let database = self.client.database(&self.database_name);
+        let existing = database
+            .list_collection_names(doc! { "name": &self.collection_name })
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to list the collections: {}", e))
+            })?;
+        if !existing.is_empty() {
+            return Ok(());
+        }
+
+        let options = CreateCollectionOptions::builder()
+            .capped(true)
+            .size(capped.max_bytes)
+            .max(capped.max_documents)
+            .build();
+        database
+            .create_collection(&self.collection_name, options)
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to create the capped collection: {}", e))
+            })
+    }
+
+    /// Deletes the least recently written documents above the `max_documents` limit.
+    async fn evict(&self, max_documents: u64) -> Result<(), StoreError> {
+        let coll = self.collection();
+        let count = coll
+            .estimated_document_count(None)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to count the documents: {}", e)))?;
+        if count <= max_documents {
+            return Ok(());
+        }
+
+        let options = FindOptions::builder()
+            .sort(doc! { "written_at": 1 })
+            .limit((count - max_documents) as i64)
+            .projection(doc! { "_id": 1 })
+            .build();
+        let ids: Vec<Bson> = coll
+            .find(doc! {}, options)
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to find documents to evict: {}", e))
+            })?
+            .try_collect::<Vec<Document>>()
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to find documents to evict: {}", e))
+            })?
+            .into_iter()
+            .filter_map(|doc| doc.get("_id").cloned())
+            .collect();
+
+        coll.delete_many(doc! { "_id": { "$in": ids } }, None)
+            .await
+            .map(|_| ())
+            .map_err(|e| StoreError::QueryError(format!("Failed to evict documents: {}", e)))
+    }
+
+    fn document(
+        key: &str,
+        value: &Value,
+        expires_at: Option<DateTime>,
+    ) -> Result<Document, StoreError> {
+        let value_str = serde_json::to_string(value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+
+        Ok(doc! {
+            "key": key,
+            "value": value_str,
+            "expires_at": expires_at.map_or(Bson::Null, Bson::DateTime),
+            "written_at": DateTime::now()
+        })
+    }
+
+    /// Reads `key` with its expiration, skipping it if it has expired unless
+    /// `include_expired` is set.
+    async fn read_with_metadata(
+        &self,
+        key: &str,
+        include_expired: bool,
+    ) -> Result<Option<(Value, Metadata)>, StoreError> {
+        let coll = self.collection();
+        let mut filter = doc! { "key": key };
+        if !include_expired {
+            filter.insert("$or", not_expired());
+        }
+        let Some(doc) = coll
+            .find_one(filter, None)
+            .await
+            .map_err(|e| StoreError::QueryError(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        let Some(value) = doc.get("value").and_then(Bson::as_str) else {
+            return Ok(None);
+        };
+        let value = serde_json::from_str(value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+        let metadata = Metadata {
+            expires_at: doc
+                .get_datetime("expires_at")
+                .ok()
+                .map(|expires_at| expires_at.to_system_time()),
+            ..Metadata::default()
+        };
+        Ok(Some((value, metadata)))
+    }
+
+    async fn upsert(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: Option<DateTime>,
+    ) -> Result<(), StoreError> {
+        let doc = Self::document(key, &value, expires_at)?;
+
+        if self.capped.is_some() {
+            self.replace_capped(key, doc).await?;
+        } else {
+            self.collection()
+                .replace_one(doc! { "key": key }, doc, replace_options())
+                .await
+                .map(|replace_result| {
+                    if replace_result.upserted_id.is_some() {
+                        log::info!("A new document was upserted");
+                    }
+                })
+                .map_err(|e| StoreError::QueryError(format!("Failed to set the value: {}", e)))?;
+        }
+
+        if let Some(max_documents) = self.max_documents {
+            self.evict(max_documents).await?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the document of `key` in a capped collection, which rejects updates
+    /// changing the size of a document, by removing it and inserting `doc`. The two
+    /// steps are not atomic.
+    async fn replace_capped(&self, key: &str, doc: Document) -> Result<(), StoreError> {
+        let coll = self.collection();
+        coll.delete_many(doc! { "key": key }, None)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to set the value: {}", e)))?;
+        coll.insert_one(doc, None)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to set the value: {}", e)))?;
+        Ok(())
+    }
+
+    /// Upserts `key` with an update pipeline that keeps `expires_at` unless it has
+    /// already passed.
+    async fn upsert_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        if self.capped.is_some() {
+            // Not atomic: a concurrent write between the read and the write loses its TTL.
+            let expires_at = self
+                .read_with_metadata(key, false)
+                .await?
+                .and_then(|(_, metadata)| metadata.expires_at)
+                .map(DateTime::from_system_time);
+            return self.upsert(key, value, expires_at).await;
+        }
+
+        let value_str = serde_json::to_string(&value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+        let now = DateTime::now();
+
+        let update = vec![doc! {
+            "$set": {
+                "value": value_str,
+                "written_at": now,
+                "expires_at": {
+                    "$cond": [{ "$gt": ["$expires_at", now] }, "$expires_at", Bson::Null]
+                }
+            }
+        }];
+        self.collection()
+            .update_one(
+                doc! { "key": key },
+                update,
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to set the value: {}", e)))?;
+
+        if let Some(max_documents) = self.max_documents {
+            self.evict(max_documents).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes `value` under `key` unless the key holds a live value, returning whether
+    /// it was written.
+    async fn insert_document_if_absent(
+        &self,
+        key: &str,
+        value: &Value,
+        expires_at: Option<DateTime>,
+    ) -> Result<bool, StoreError> {
+        let value_str = serde_json::to_string(value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+        let now = DateTime::now();
+        let live = doc! {
+            "$and": [
+                { "$ne": [{ "$type": "$value" }, "missing"] },
+                { "$or": [
+                    { "$eq": [{ "$ifNull": ["$expires_at", Bson::Null] }, Bson::Null] },
+                    { "$gt": ["$expires_at", now] }
+                ] }
+            ]
+        };
+        let update = vec![doc! {
+            "$set": {
+                "value": { "$cond": [live.clone(), "$value", value_str] },
+                "expires_at": {
+                    "$cond": [live.clone(), "$expires_at", expires_at.map_or(Bson::Null, Bson::DateTime)]
+                },
+                "written_at": { "$cond": [live, "$written_at", now] }
+            }
+        }];
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::Before)
+            .build();
+        let previous = self
+            .collection()
+            .find_one_and_update(doc! { "key": key }, update, options)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to set the value: {}", e)))?;
+
+        let written = match previous {
+            None => true,
+            Some(previous) => previous
+                .get_datetime("expires_at")
+                .is_ok_and(|expires_at| *expires_at <= now),
+        };
+        if written {
+            if let Some(max_documents) = self.max_documents {
+                self.evict(max_documents).await?;
+            }
+        }
+        Ok(written)
+    }
+
+    /// Adds `delta` to the counter under `key` with an update pipeline, restarting it
+    /// from `delta` if it is missing or has expired.
+    async fn increment_document(
+        &self,
+        key: &str,
+        delta: i64,
+        expires_at: Option<DateTime>,
+    ) -> Result<i64, StoreError> {
+        let now = DateTime::now();
+        let live = doc! {
+            "$and": [
+                { "$ne": [{ "$type": "$value" }, "missing"] },
+                { "$or": [
+                    { "$eq": [{ "$ifNull": ["$expires_at", Bson::Null] }, Bson::Null] },
+                    { "$gt": ["$expires_at", now] }
+                ] }
+            ]
+        };
+        let update = vec![doc! {
+            "$set": {
+                "value": {
+                    "$toString": {
+                        "$add": [{ "$cond": [live.clone(), { "$toLong": "$value" }, 0_i64] }, delta]
+                    }
+                },
+                "expires_at": {
+                    "$cond": [live, "$expires_at", expires_at.map_or(Bson::Null, Bson::DateTime)]
+                },
+                "written_at": now
+            }
+        }];
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .build();
+        let doc = self
+            .collection()
+            .find_one_and_update(doc! { "key": key }, update, options)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to increment: {}", e)))?;
+
+        if let Some(max_documents) = self.max_documents {
+            self.evict(max_documents).await?;
+        }
+        doc.as_ref()
+            .and_then(|doc| doc.get_str("value").ok())
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| StoreError::QueryError("Failed to read the counter".to_string()))
+    }
+
+    async fn start_transaction(&self) -> Result<ClientSession, StoreError> {
+        let options = SessionOptions::builder().causal_consistency(true).build();
+        let mut session = self
+            .client
+            .start_session(options)
+            .await
+            .map_err(StoreError::connection)?;
+        session
+            .start_transaction(None)
+            .await
+            .map_err(|e| transaction_error(e, "Failed to start the transaction"))?;
+        Ok(session)
+    }
+
+    /// Upserts every entry in one transaction. Dropping the session on error aborts
+    /// the transaction.
+    async fn set_many_in_transaction(
+        &self,
+        entries: &[(&str, Value)],
+        expires_at: Option<DateTime>,
+    ) -> Result<(), StoreError> {
+        let coll = self.collection();
+        let mut session = self.start_transaction().await?;
+        for (key, value) in entries {
+            let doc = Self::document(key, value, expires_at)?;
+            coll.replace_one_with_session(
+                doc! { "key": key },
+                doc,
+                replace_options(),
+                &mut session,
+            )
+            .await
+            .map_err(|e| transaction_error(e, "Failed to set the value"))?;
+        }
+        session
+            .commit_transaction()
+            .await
+            .map_err(|e| transaction_error(e, "Failed to commit the transaction"))?;
+
+        if let Some(max_documents) = self.max_documents {
+            self.evict(max_documents).await?;
+        }
+        Ok(())
+    }
+
+    /// Moves a document to `new_key` in one transaction, replacing any document already
+    /// stored there. Dropping the session on error aborts the transaction.
+    async fn rename_in_transaction(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        let coll = self.collection();
+        let mut session = self.start_transaction().await?;
+        if old_key != new_key {
+            coll.delete_one_with_session(doc! { "key": new_key }, None, &mut session)
+                .await
+                .map_err(|e| transaction_error(e, "Failed to rename the key"))?;
+        }
+        let renamed = coll
+            .update_one_with_session(
+                doc! { "key": old_key, "$or": not_expired() },
+                doc! { "$set": { "key": new_key } },
+                None,
+                &mut session,
+            )
+            .await
+            .map_err(|e| transaction_error(e, "Failed to rename the key"))?;
+        if renamed.matched_count == 0 {
+            return Err(StoreError::NotFound);
+        }
+        session
+            .commit_transaction()
+            .await
+            .map_err(|e| transaction_error(e, "Failed to commit the transaction"))
+    }
+
+    /// Streams the live documents whose key matches `regex`.
+    fn scan_matching(
+        &self,
+        regex: String,
+        batch_size: usize,
+    ) -> BoxStream<'_, Result<Vec<ScanEntry>, StoreError>> {
+        let batch_size = batch_size.max(1);
+        // Keyset pagination: each batch starts after the last key of the previous one.
+        Box::pin(stream::try_unfold(Some(None::<String>), move |cursor| {
+            let regex = regex.clone();
+            async move {
+                let Some(cursor) = cursor else {
+                    return Ok(None);
+                };
+                let mut key_filter = doc! { "$regex": regex };
+                if let Some(cursor) = cursor {
+                    key_filter.insert("$gt", cursor);
+                }
+                let options = FindOptions::builder()
+                    .sort(doc! { "key": 1 })
+                    .limit(batch_size as i64)
+                    .build();
+                let docs: Vec<Document> = self
+                    .collection()
+                    .find(doc! { "key": key_filter, "$or": not_expired() }, options)
+                    .await
+                    .map_err(|e| {
+                        StoreError::QueryError(format!("Failed to scan the collection: {}", e))
+                    })?
+                    .try_collect()
+                    .await
+                    .map_err(|e| {
+                        StoreError::QueryError(format!("Failed to scan the collection: {}", e))
+                    })?;
+                if docs.is_empty() {
+                    return Ok(None);
+                }
+
+                let next = (docs.len() == batch_size).then(|| {
+                    docs.last()
+                        .and_then(|doc| doc.get_str("key").ok())
+                        .map(str::to_string)
+                });
+                let batch = docs
+                    .into_iter()
+                    .filter_map(|doc| {
+                        Some(ScanEntry {
+                            key: doc.get_str("key").ok()?.to_string(),
+                            value: serde_json::from_str(doc.get_str("value").ok()?).ok()?,
+                            expires_at: doc
+                                .get_datetime("expires_at")
+                                .ok()
+                                .map(|expires_at| expires_at.to_system_time()),
+                        })
+                    })
+                    .collect();
+                Ok(Some((batch, next)))
+            }
+        }))
+    }
 }
 
 #[async_trait]
 impl Store for MongoStore {
     async fn initialize(&self) -> Result<(), StoreError> {
         // MongoDB creates databases and collections automatically when you insert data,
-        // so explicit creation is not needed.
+        // so explicit creation is not needed. When a retry policy is configured we wait
+        // for the deployment to answer a ping instead.
+        if let Some(retry) = &self.initialize_retry {
+            retry
+                .run(|| async {
+                    self.client
+                        .database("admin")
+                        .run_command(doc! { "ping": 1 }, None)
+                        .await
+                        .map(|_| ())
+                        .map_err(StoreError::connection)
+                })
+                .await?;
+        }
+
+        if let Some(capped) = self.capped {
+            self.create_capped_collection(capped).await?;
+            // Capped collections reject TTL indexes.
+            return Ok(());
+        }
+
+        // Lets the server purge expired documents in the background; reads filter
+        // them out until then.
+        let index = IndexModel::builder()
+            .keys(doc! { "expires_at": 1 })
+            .options(IndexOptions::builder().expire_after(Duration::ZERO).build())
+            .build();
+        self.collection()
+            .create_index(index, None)
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to create the TTL index: {}", e))
+            })?;
+
+        if self.max_documents.is_some() {
+            let index = IndexModel::builder().keys(doc! { "written_at": 1 }).build();
+            self.collection()
+                .create_index(index, None)
+                .await
+                .map_err(|e| {
+                    StoreError::QueryError(format!("Failed to create the eviction index: {}", e))
+                })?;
+        }
+
         Ok(())
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            native_ttl: true,
+            atomic_increment: true,
+            transactions: false,
+            scan: true,
+            watch: false,
+        }
+    }
+
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
-        let coll = self.get_collection();
-        let filter = doc! { "key": key };
+        let coll = self.collection();
+        let filter = doc! { "key": key, "$or": not_expired() };
         let result = coll
             .find_one(filter, None)
             .await
@@ -42,41 +615,127 @@ impl Store for MongoStore {
             .map_or(Ok(None), |doc| {
                 doc.get("value")
                     .and_then(Bson::as_str)
-                    .map(|s| serde_json::from_str::<Value>(s))
+                    .map(serde_json::from_str::<Value>)
                     .transpose()
             })
-            .map_err(|e| StoreError::SerializationError { source: e.into() })
+            .map_err(|e| StoreError::SerializationError { source: e })
     }
 
-    async fn set(&self, key: &str, value: Value, _: Option<u64>) -> Result<(), StoreError> {
-        let coll = self.get_collection();
-        let value_str = serde_json::to_string(&value)
-            .map_err(|e| StoreError::SerializationError { source: e })?;
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let docs: Vec<Document> = self
+            .collection()
+            .find(doc! { "key": { "$in": keys }, "$or": not_expired() }, None)
+            .await
+            .map_err(|e| StoreError::QueryError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| StoreError::QueryError(e.to_string()))?;
+        let values: HashMap<&str, &str> = docs
+            .iter()
+            .filter_map(|doc| Some((doc.get_str("key").ok()?, doc.get_str("value").ok()?)))
+            .collect();
 
-        let doc = doc! {
-            "key": key,
-            "value": value_str
-        };
+        keys.iter()
+            .map(|key| {
+                values
+                    .get(key)
+                    .map(|value| serde_json::from_str::<Value>(value))
+                    .transpose()
+                    .map_err(|e| StoreError::SerializationError { source: e })
+            })
+            .collect()
+    }
 
-        let replace_options = mongodb::options::ReplaceOptions::builder()
-            .upsert(true)
-            .build();
+    /// MongoDB documents carry no creation or update times, only the expiration.
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.read_with_metadata(key, false).await
+    }
 
-        coll.replace_one(doc! { "key": key }, doc, replace_options)
+    /// Expired documents are readable until the TTL monitor of MongoDB removes them,
+    /// which runs every minute.
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.read_with_metadata(key, true).await
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        let expires_at = expires_at_millis(Some(ttl)).map(DateTime::from_millis);
+        let result = self
+            .collection()
+            .find_one_and_update(
+                doc! { "key": key, "$or": not_expired() },
+                doc! { "$set": { "expires_at": expires_at } },
+                None,
+            )
             .await
-            .map(|replace_result| {
-                if replace_result.upserted_id.is_some() {
-                    log::info!("A new document was upserted");
-                }
-                ()
-            })
-            .map_err(|e| {
-                StoreError::QueryError(format!("Failed to set the value: {}", e.to_string()))
+            .map_err(|e| StoreError::QueryError(format!("Failed to touch the value: {}", e)))?;
+
+        result
+            .map_or(Ok(None), |doc| {
+                doc.get("value")
+                    .and_then(Bson::as_str)
+                    .map(serde_json::from_str::<Value>)
+                    .transpose()
             })
+            .map_err(|e| StoreError::SerializationError { source: e })
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        let expires_at = expires_at_millis(ttl).map(DateTime::from_millis);
+        self.upsert(key, value, expires_at).await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        self.upsert(key, value, Some(DateTime::from_system_time(expires_at)))
+            .await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        self.upsert_keep_ttl(key, value).await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        let expires_at = expires_at_millis(ttl).map(DateTime::from_millis);
+        if self.transactions {
+            return self.set_many_in_transaction(entries, expires_at).await;
+        }
+        for (key, value) in entries {
+            self.upsert(key, value.clone(), expires_at).await?;
+        }
+        Ok(())
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        let expires_at = expires_at_millis(ttl).map(DateTime::from_millis);
+        self.insert_document_if_absent(key, &value, expires_at)
+            .await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        let expires_at = expires_at_millis(ttl).map(DateTime::from_millis);
+        self.increment_document(key, delta, expires_at).await
     }
 
     async fn remove(&self, key: &str) -> Result<(), StoreError> {
-        let coll = self.get_collection();
+        let coll = self.collection();
         coll.delete_one(doc! { "key": key }, None)
             .await
             .map(|_| ())
@@ -84,18 +743,131 @@ impl Store for MongoStore {
     }
 
     async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
-        let coll = self.get_collection();
+        let coll = self.collection();
+        if self.transactions {
+            let mut session = self.start_transaction().await?;
+            coll.delete_many_with_session(doc! { "key": { "$in": keys } }, None, &mut session)
+                .await
+                .map_err(|e| transaction_error(e, "Failed to remove the keys"))?;
+            return session
+                .commit_transaction()
+                .await
+                .map_err(|e| transaction_error(e, "Failed to commit the transaction"));
+        }
         coll.delete_many(doc! { "key": { "$in": keys } }, None)
             .await
             .map(|_| ())
             .map_err(|_| StoreError::QueryError("Failed to remove the keys".to_string()))
     }
 
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        let expected = serde_json::to_string(expected)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+        self.collection()
+            .delete_one(
+                doc! { "key": key, "value": expected, "$or": not_expired() },
+                None,
+            )
+            .await
+            .map(|result| result.deleted_count > 0)
+            .map_err(|_| StoreError::QueryError("Failed to remove the key".to_string()))
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        if self.transactions {
+            return self.rename_in_transaction(old_key, new_key).await;
+        }
+
+        // Without a transaction the old key is checked first, so a missing key does not
+        // cost the value under `new_key`.
+        let coll = self.collection();
+        let query_error = |_| StoreError::QueryError("Failed to rename the key".to_string());
+        let live = coll
+            .count_documents(doc! { "key": old_key, "$or": not_expired() }, None)
+            .await
+            .map_err(query_error)?;
+        if live == 0 {
+            return Err(StoreError::NotFound);
+        }
+        if old_key == new_key {
+            return Ok(());
+        }
+        coll.delete_one(doc! { "key": new_key }, None)
+            .await
+            .map_err(query_error)?;
+        let renamed = coll
+            .update_one(
+                doc! { "key": old_key, "$or": not_expired() },
+                doc! { "$set": { "key": new_key } },
+                None,
+            )
+            .await
+            .map_err(query_error)?;
+        if renamed.matched_count == 0 {
+            return Err(StoreError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        let expires_at = expires_at_millis(Some(ttl)).map(DateTime::from_millis);
+        self.collection()
+            .update_many(
+                doc! { "key": { "$in": keys }, "$or": not_expired() },
+                doc! { "$set": { "expires_at": expires_at } },
+                None,
+            )
+            .await
+            .map(|result| result.matched_count)
+            .map_err(|_| StoreError::QueryError("Failed to touch the keys".to_string()))
+    }
+
     async fn clear(&self) -> Result<(), StoreError> {
-        let coll = self.get_collection();
+        let coll = self.collection();
         coll.delete_many(doc! {}, None)
             .await
             .map(|_| ())
             .map_err(|_| StoreError::QueryError("Failed to clear the collection".to_string()))
     }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        let entries = self
+            .collection()
+            .count_documents(doc! { "$or": not_expired() }, None)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to count the documents: {}", e)))?;
+
+        let stats = self
+            .client
+            .database(&self.database_name)
+            .run_command(doc! { "collStats": &self.collection_name }, None)
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to read the collection stats: {}", e))
+            })?;
+        let bytes = match stats.get("size") {
+            Some(Bson::Int32(size)) => Some(*size as u64),
+            Some(Bson::Int64(size)) => Some(*size as u64),
+            Some(Bson::Double(size)) => Some(*size as u64),
+            _ => None,
+        };
+
+        Ok(Usage { entries, bytes })
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.scan_matching(prefix_regex(prefix.unwrap_or_default()), batch_size)
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.scan_matching(glob::to_regex(pattern), batch_size)
+    }
 }
@@ -1,5 +1,7 @@
 mod builder;
 pub use builder::*;
 
+mod queries;
+
 pub mod mysql;
 pub use mysql::*;
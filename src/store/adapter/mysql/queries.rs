@@ -0,0 +1,201 @@
+use crate::adapter::{Columns, ValueFormat};
+
+/// SQL statements for one table, built once when the store is created.
+///
+/// Reusing the same strings lets sqlx keep a single prepared statement per query and
+/// connection instead of formatting and hashing a new statement on every call.
+pub(crate) struct Queries {
+    pub(crate) verify: String,
+    pub(crate) get: String,
+    pub(crate) get_with_metadata: String,
+    pub(crate) get_stale: String,
+    pub(crate) upsert: String,
+    pub(crate) upsert_keep_ttl: String,
+    pub(crate) live_row: String,
+    pub(crate) live_key: String,
+    pub(crate) insert: String,
+    pub(crate) remove_expired: String,
+    pub(crate) increment: String,
+    pub(crate) live_counter: String,
+    pub(crate) touch: String,
+    pub(crate) rename: String,
+    pub(crate) remove: String,
+    pub(crate) remove_if: String,
+    pub(crate) clear: String,
+    pub(crate) count: String,
+    pub(crate) scan: String,
+    pub(crate) quarantine: String,
+    pub(crate) queue_push: String,
+    pub(crate) queue_claimable: String,
+    pub(crate) queue_claim: String,
+    pub(crate) queue_ack: String,
+    pub(crate) changes_lock: String,
+    pub(crate) changes_append: String,
+    pub(crate) changes_read: String,
+    get_many_prefix: String,
+    remove_many_prefix: String,
+    touch_many_prefix: String,
+}
+
+impl Queries {
+    pub(crate) fn new(table: &str, columns: &Columns, value_format: ValueFormat) -> Self {
+        let Columns {
+            key,
+            value,
+            expires_at,
+            created_at,
+            updated_at,
+        } = columns;
+        // JSON columns are read back as text; MySQL parses bound strings itself. Values
+        // are always read as `value`.
+        let read_value = match value_format {
+            ValueFormat::Text => format!("`{value}` AS `value`"),
+            ValueFormat::Json => format!("CAST(`{value}` AS CHAR) AS `value`"),
+        };
+        // Comparing a JSON column with a string needs the string parsed first.
+        let compared_value = match value_format {
+            ValueFormat::Text => "?",
+            ValueFormat::Json => "CAST(? AS JSON)",
+        };
+        // Timestamp columns the table lacks are left out of writes and read as NULL.
+        let read_timestamp = |column: &Option<String>, alias: &str| match column {
+            Some(c) => format!("`{c}` AS `{alias}`"),
+            None => format!("CAST(NULL AS SIGNED) AS `{alias}`"),
+        };
+        let read_created = read_timestamp(created_at, "created_at");
+        let read_updated = read_timestamp(updated_at, "updated_at");
+        let timestamps: String = columns.timestamps().map(|c| format!(", `{c}`")).collect();
+        let timestamp_values: String = columns.timestamps().map(|_| ", ?").collect();
+        // MySQL applies assignments left to right, so `created_at` is computed first,
+        // while `expires_at` still holds the previous row's expiration. It is kept on
+        // overwrite unless that row had expired.
+        let keep_created = created_at.as_ref().map_or(String::new(), |c| {
+            format!("`{c}` = IF(`{expires_at}` <= VALUES(`{c}`), VALUES(`{c}`), `{c}`), ")
+        });
+        let set_updated = updated_at
+            .as_ref()
+            .map_or(String::new(), |c| format!(", `{c}` = VALUES(`{c}`)"));
+        Self {
+            // Reads no rows, but fails unless the table has every column the store uses.
+            verify: format!("SELECT `{key}`, `{value}`, `{expires_at}`{timestamps} FROM {table} WHERE 1 = 0"),
+            get: format!(
+                "SELECT {read_value} FROM {table} WHERE `{key}` = ? AND (`{expires_at}` IS NULL OR `{expires_at}` > ?)"
+            ),
+            get_with_metadata: format!(
+                "SELECT {read_value}, {read_created}, {read_updated}, `{expires_at}` AS `expires_at` FROM {table} WHERE `{key}` = ? AND (`{expires_at}` IS NULL OR `{expires_at}` > ?)"
+            ),
+            // Expired rows included, for callers that decide themselves what is too old.
+            get_stale: format!(
+                "SELECT {read_value}, {read_created}, {read_updated}, `{expires_at}` AS `expires_at` FROM {table} WHERE `{key}` = ?"
+            ),
+            upsert: format!(
+                "INSERT INTO {table} (`{key}`, `{value}`, `{expires_at}`{timestamps}) VALUES (?, ?, ?{timestamp_values}) ON DUPLICATE KEY UPDATE {keep_created}`{value}` = VALUES(`{value}`), `{expires_at}` = VALUES(`{expires_at}`){set_updated}"
+            ),
+            // The last parameter is the current time.
+            upsert_keep_ttl: format!(
+                "INSERT INTO {table} (`{key}`, `{value}`{timestamps}) VALUES (?, ?{timestamp_values}) ON DUPLICATE KEY UPDATE {keep_created}`{expires_at}` = IF(`{expires_at}` > ?, `{expires_at}`, NULL), `{value}` = VALUES(`{value}`){set_updated}"
+            ),
+            live_row: format!(
+                "SELECT {read_created}, `{expires_at}` FROM {table} WHERE `{key}` = ? AND `{expires_at}` > ? FOR UPDATE"
+            ),
+            live_key: format!(
+                "SELECT 1 FROM {table} WHERE `{key}` = ? AND (`{expires_at}` IS NULL OR `{expires_at}` > ?) FOR UPDATE"
+            ),
+            insert: format!(
+                "INSERT INTO {table} (`{key}`, `{value}`, `{expires_at}`{timestamps}) VALUES (?, ?, ?{timestamp_values})"
+            ),
+            remove_expired: format!(
+                "DELETE FROM {table} WHERE `{key}` = ? AND `{expires_at}` <= ?"
+            ),
+            // As in `upsert`, assignments read the previous row's `expires_at` until it
+            // is replaced last. An expired row restarts from `delta`; a live value other
+            // than an integer is left unchanged. The parameters after the inserted row
+            // are the current time, `delta` and the current time again.
+            increment: format!(
+                "INSERT INTO {table} (`{key}`, `{value}`, `{expires_at}`{timestamps}) VALUES (?, ?, ?{timestamp_values}) ON DUPLICATE KEY UPDATE {keep_created}`{value}` = IF(`{expires_at}` <= ?, VALUES(`{value}`), IF(JSON_TYPE(`{value}`) = 'INTEGER', CAST(CAST(CAST(`{value}` AS CHAR) AS SIGNED) + ? AS CHAR), `{value}`)), `{expires_at}` = IF(`{expires_at}` <= ?, VALUES(`{expires_at}`), `{expires_at}`){set_updated}"
+            ),
+            // Locks the live row and returns the JSON type of its value along with the
+            // counter, so that `increment` only applies to integers.
+            live_counter: format!(
+                "SELECT JSON_TYPE(`{value}`), CAST(CAST(`{value}` AS CHAR) AS SIGNED), {read_created}, `{expires_at}` FROM {table} WHERE `{key}` = ? AND (`{expires_at}` IS NULL OR `{expires_at}` > ?) FOR UPDATE"
+            ),
+            touch: format!(
+                "UPDATE {table} SET `{expires_at}` = ? WHERE `{key}` = ? AND (`{expires_at}` IS NULL OR `{expires_at}` > ?)"
+            ),
+            rename: format!(
+                "UPDATE {table} SET `{key}` = ? WHERE `{key}` = ? AND (`{expires_at}` IS NULL OR `{expires_at}` > ?)"
+            ),
+            remove: format!("DELETE FROM {table} WHERE `{key}` = ?"),
+            remove_if: format!(
+                "DELETE FROM {table} WHERE `{key}` = ? AND `{value}` = {compared_value} AND (`{expires_at}` IS NULL OR `{expires_at}` > ?)"
+            ),
+            clear: format!("DELETE FROM {table}"),
+            count: format!(
+                "SELECT COUNT(*) FROM {table} WHERE `{expires_at}` IS NULL OR `{expires_at}` > ?"
+            ),
+            scan: format!(
+                "SELECT `{key}`, {read_value}, `{expires_at}` FROM {table} WHERE `{key}` >= ? AND LEFT(`{key}`, CHAR_LENGTH(?)) = ? AND (? IS NULL OR `{key}` > ?) AND (`{expires_at}` IS NULL OR `{expires_at}` > ?) AND (? IS NULL OR `{key}` LIKE ?) ORDER BY `{key}` LIMIT ?"
+            ),
+            quarantine: format!(
+                "INSERT INTO {table}_quarantine (`key`, `value`, `error`, `quarantined_at`) VALUES (?, ?, ?, ?) ON DUPLICATE KEY UPDATE `value` = VALUES(`value`), `error` = VALUES(`error`), `quarantined_at` = VALUES(`quarantined_at`)"
+            ),
+            queue_push: format!(
+                "INSERT INTO {table}_queue (`queue`, `payload`) VALUES (?, ?)"
+            ),
+            // Locks the oldest message nobody holds or whose claim has lapsed, skipping
+            // the rows other consumers are claiming concurrently.
+            queue_claimable: format!(
+                "SELECT `id`, `payload` FROM {table}_queue WHERE `queue` = ? AND (`claimed_until` IS NULL OR `claimed_until` <= ?) ORDER BY `id` LIMIT 1 FOR UPDATE SKIP LOCKED"
+            ),
+            queue_claim: format!(
+                "UPDATE {table}_queue SET `claimed_until` = ? WHERE `id` = ?"
+            ),
+            queue_ack: format!(
+                "DELETE FROM {table}_queue WHERE `queue` = ? AND `id` = ?"
+            ),
+            // Locked by every append until it commits, so that ids are committed in increasing
+            // order and a reader never moves past a change still being appended. Reads are
+            // not blocked.
+            changes_lock: format!("SELECT `id` FROM {table}_changes_lock WHERE `id` = 1 FOR UPDATE"),
+            changes_append: format!(
+                "INSERT INTO {table}_changes (`key`, `kind`, `recorded_at`) VALUES (?, ?, ?)"
+            ),
+            changes_read: format!(
+                "SELECT `id`, `key`, `kind`, `recorded_at` FROM {table}_changes WHERE `id` > ? ORDER BY `id` LIMIT ?"
+            ),
+            get_many_prefix: format!(
+                "SELECT `{key}` AS `key`, {read_value} FROM {table} WHERE (`{expires_at}` IS NULL OR `{expires_at}` > ?) AND `{key}` IN ("
+            ),
+            remove_many_prefix: format!("DELETE FROM {table} WHERE `{key}` IN ("),
+            touch_many_prefix: format!(
+                "UPDATE {table} SET `{expires_at}` = ? WHERE (`{expires_at}` IS NULL OR `{expires_at}` > ?) AND `{key}` IN ("
+            ),
+        }
+    }
+
+    /// Returns the statement reading the live rows of `count` keys.
+    pub(crate) fn get_many(&self, count: usize) -> String {
+        with_placeholders(&self.get_many_prefix, count)
+    }
+
+    /// Returns the statement deleting `count` keys. The placeholder list depends on the
+    /// number of keys, so only the prefix is precomputed.
+    pub(crate) fn remove_many(&self, count: usize) -> String {
+        with_placeholders(&self.remove_many_prefix, count)
+    }
+
+    /// Returns the statement extending the expiration of `count` keys.
+    pub(crate) fn touch_many(&self, count: usize) -> String {
+        with_placeholders(&self.touch_many_prefix, count)
+    }
+}
+
+/// Appends `count` placeholders and the closing parenthesis of an `IN` list to `prefix`.
+fn with_placeholders(prefix: &str, count: usize) -> String {
+    let mut sql = prefix.to_string();
+    for i in 0..count {
+        sql.push_str(if i == 0 { "?" } else { ", ?" });
+    }
+    sql.push(')');
+    sql
+}
@@ -2,9 +2,13 @@ use std::sync::Arc;
 
 pub use sqlx::{postgres::PgPoolOptions, PgPool};
 
-use crate::{StoreError, DEFAUTL_NAMESPACE_NAME};
+use crate::{
+    adapter::{validate_identifier, validate_uri_scheme, Columns, ValueFormat},
+    QuarantineListener, QuarantinedEntry, RetryPolicy, SerializationFailurePolicy, StoreError,
+    DEFAUTL_NAMESPACE_NAME,
+};
 
-use super::PostgresStore;
+use super::{postgres::qualified_table_name, queries::Queries, PostgresStore};
 
 /// Builder for creating a `PostgresStore`.
 ///
@@ -51,7 +55,23 @@ pub struct PostgresStoreBuilder {
     uri: Option<String>,
     pool: Option<Arc<PgPool>>,
     table_name: Option<String>,
+    lazy_connect: bool,
+    initialize_retry: Option<RetryPolicy>,
     schema: Option<String>,
+    partitions: Option<u32>,
+    cleanup_schedule: Option<String>,
+    value_format: ValueFormat,
+    serialization_failure: SerializationFailurePolicy,
+    quarantine_listener: Option<QuarantineListener>,
+    columns: Columns,
+    create_tables: bool,
+    create_schema: bool,
+}
+
+impl Default for PostgresStoreBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Creates a new builder instance with default configuration.
@@ -64,7 +84,17 @@ impl PostgresStoreBuilder {
             uri: None,
             pool: None,
             table_name: None,
+            lazy_connect: false,
+            initialize_retry: None,
             schema: None,
+            partitions: None,
+            cleanup_schedule: None,
+            value_format: ValueFormat::Text,
+            serialization_failure: SerializationFailurePolicy::default(),
+            quarantine_listener: None,
+            columns: Columns::default(),
+            create_tables: true,
+            create_schema: true,
         }
     }
 
@@ -81,6 +111,61 @@ impl PostgresStoreBuilder {
         self
     }
 
+    /// Sets whether `initialize()` creates the `schema` if it does not exist.
+    ///
+    /// Defaults to `true`. Creating a schema requires the `CREATE` privilege on the
+    /// database, which application users often lack even when they may create tables
+    /// in an existing schema.
+    pub fn create_schema(mut self, create: bool) -> Self {
+        self.create_schema = create;
+        self
+    }
+
+    /// Creates the table hash-partitioned on the key into `count` partitions.
+    ///
+    /// Partitioning keeps vacuum and bulk deletes manageable for very large keyspaces.
+    /// Queries go through the parent table and PostgreSQL routes them to the right
+    /// partition, named `<table>_p<n>`. The option only applies when `initialize()`
+    /// creates the table; an existing unpartitioned table is left as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of hash partitions, at least 1.
+    pub fn partitions(mut self, count: u32) -> Self {
+        self.partitions = Some(count);
+        self
+    }
+
+    /// Purges expired rows server-side on a `pg_cron` schedule.
+    ///
+    /// During `initialize()` the store creates a `<table>_purge_expired()` SQL function
+    /// and, if the `pg_cron` extension is installed, schedules it as the job
+    /// `keyv_purge_<table>`, so expired rows are deleted even while no application is
+    /// running. Without `pg_cron` a warning explains how to schedule the function with
+    /// another scheduler.
+    ///
+    /// # Arguments
+    ///
+    /// * `schedule` - A cron expression, e.g. `"*/15 * * * *"`.
+    pub fn cleanup_schedule<S: Into<String>>(mut self, schedule: S) -> Self {
+        self.cleanup_schedule = Some(schedule.into());
+        self
+    }
+
+    /// Sets the column type values are stored in.
+    ///
+    /// `ValueFormat::Json` creates the `value` column as `JSONB`, so other consumers of
+    /// the table can query values natively, e.g. `WHERE value->>'status' = 'active'`.
+    /// The option only applies when `initialize()` creates the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The column type for values, `ValueFormat::Text` by default.
+    pub fn value_format(mut self, format: ValueFormat) -> Self {
+        self.value_format = format;
+        self
+    }
+
     /// Sets the table name for the `PostgresStore`.
     ///
     /// This method configures the table name to be used by the store. If not set,
@@ -93,6 +178,79 @@ impl PostgresStoreBuilder {
         self.table_name = Some(table.into());
         self
     }
+
+    /// Sets the column holding the keys. Defaults to `key`.
+    ///
+    /// Together with `value_column`, `ttl_column` and `create_tables`, lets the store
+    /// sit on top of an existing cache table.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use keyv::adapter::postgres::PostgresStoreBuilder;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let store = PostgresStoreBuilder::new()
+    ///     .uri("postgresql://localhost")
+    ///     .table_name("http_cache")
+    ///     .key_column("cache_key")
+    ///     .value_column("payload")
+    ///     .ttl_column("expires")
+    ///     .create_tables(false)
+    ///     .build()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn key_column<S: Into<String>>(mut self, column: S) -> Self {
+        self.columns.key = column.into();
+        self
+    }
+
+    /// Sets the column holding the values, in the `value_format`. Defaults to `value`.
+    pub fn value_column<S: Into<String>>(mut self, column: S) -> Self {
+        self.columns.value = column.into();
+        self
+    }
+
+    /// Sets the `BIGINT` column holding the expiration time, in milliseconds since the
+    /// Unix epoch, or `NULL` for entries that never expire. Defaults to `expires_at`.
+    pub fn ttl_column<S: Into<String>>(mut self, column: S) -> Self {
+        self.columns.expires_at = column.into();
+        self
+    }
+
+    /// Sets the `BIGINT` column holding when each key was first written, or `None` if
+    /// the table has no such column. Defaults to `created_at`.
+    ///
+    /// Without the column, `get_with_metadata` returns no creation time.
+    pub fn created_at_column(mut self, column: Option<&str>) -> Self {
+        self.columns.created_at = column.map(str::to_string);
+        self
+    }
+
+    /// Sets the `BIGINT` column holding when each value was last written, or `None`
+    /// if the table has no such column. Defaults to `updated_at`.
+    ///
+    /// Without the column, `get_with_metadata` returns no update time.
+    pub fn updated_at_column(mut self, column: Option<&str>) -> Self {
+        self.columns.updated_at = column.map(str::to_string);
+        self
+    }
+
+    /// Sets whether `initialize()` creates the schema, tables and partitions, adds
+    /// missing columns and registers the cleanup schedule.
+    ///
+    /// Defaults to `true`, which requires the `CREATE` privilege. With `false`,
+    /// `initialize()` runs no DDL and only checks that the table exists with the key,
+    /// value and expiration columns, plus the nullable `BIGINT` timestamp columns set
+    /// with `created_at_column` and `updated_at_column`. The `<table>_queue` and
+    /// `<table>_quarantine` tables must exist too if the queue or quarantine is used.
+    pub fn create_tables(mut self, create: bool) -> Self {
+        self.create_tables = create;
+        self
+    }
+
     /// Sets the database URI for connecting to the PostgreSQL database.
     ///
     /// This method configures the database URI. It's required if no existing connection pool is provided.
@@ -107,8 +265,8 @@ impl PostgresStoreBuilder {
 
     /// Uses an existing connection pool for the `PostgresStore`.
     ///
-    /// This method allows for using an already configured `PgPool`. If set,
-    /// the `uri` option is ignored.
+    /// This method allows for using an already configured `PgPool`. It cannot be
+    /// combined with the `uri` option.
     ///
     /// # Arguments
     ///
@@ -118,6 +276,115 @@ impl PostgresStoreBuilder {
         self
     }
 
+    /// Defers opening database connections until the first query.
+    ///
+    /// By default `build()` connects eagerly and fails if the database is not
+    /// reachable. With lazy connection enabled the pool is created without
+    /// connecting, so the store can be built before the database is up. The pool
+    /// replaces broken connections automatically in both modes.
+    ///
+    /// # Arguments
+    ///
+    /// * `lazy` - Whether to postpone connecting to the first query.
+    pub fn lazy_connect(mut self, lazy: bool) -> Self {
+        self.lazy_connect = lazy;
+        self
+    }
+
+    /// Retries connecting and initializing the backend according to `policy`.
+    ///
+    /// Useful when the application may start before the database, e.g. under
+    /// docker-compose or Kubernetes. Without a policy a single attempt is made.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The retry attempts, backoff and deadline to apply.
+    pub fn initialize_retry(mut self, policy: RetryPolicy) -> Self {
+        self.initialize_retry = Some(policy);
+        self
+    }
+
+    /// Sets what happens when a stored value cannot be deserialized.
+    ///
+    /// Defaults to `SerializationFailurePolicy::LogAndMiss`. With
+    /// `SerializationFailurePolicy::Quarantine`, `initialize()` also creates the
+    /// `<table>_quarantine` table the entries are moved to.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Whether to fail the read, or log and report a miss, optionally
+    ///   removing the entry.
+    pub fn on_serialization_failure(mut self, policy: SerializationFailurePolicy) -> Self {
+        self.serialization_failure = policy;
+        self
+    }
+
+    /// Calls `listener` for every entry moved to quarantine.
+    ///
+    /// Only used with `SerializationFailurePolicy::Quarantine`. The listener runs on the
+    /// read that found the entry, so it should return quickly.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The callback receiving each quarantined entry.
+    pub fn on_quarantine<F>(mut self, listener: F) -> Self
+    where
+        F: Fn(&QuarantinedEntry) + Send + Sync + 'static,
+    {
+        self.quarantine_listener = Some(Arc::new(listener));
+        self
+    }
+
+    /// Checks the configuration without connecting to the backend.
+    ///
+    /// `build()` runs the same checks, so calling this is only needed to report
+    /// misconfiguration early, e.g. at application startup.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the builder can be built, or `StoreError::InvalidConfiguration`
+    /// describing the offending option.
+    pub fn validate(&self) -> Result<(), StoreError> {
+        if self.pool.is_none() && self.uri.is_none() {
+            return Err(StoreError::invalid_configuration(
+                "uri",
+                "PostgresStore requires either a URI or an existing pool to be set",
+            ));
+        }
+        if self.pool.is_some() && self.uri.is_some() {
+            return Err(StoreError::invalid_configuration(
+                "pool",
+                "An existing pool cannot be combined with a URI; set only one of them",
+            ));
+        }
+        if let Some(uri) = &self.uri {
+            validate_uri_scheme(uri, &["postgres", "postgresql"])?;
+        }
+        if let Some(schema) = &self.schema {
+            validate_identifier("schema", schema)?;
+        }
+        if let Some(table_name) = &self.table_name {
+            validate_identifier("table_name", table_name)?;
+        }
+        if self.partitions == Some(0) {
+            return Err(StoreError::invalid_configuration(
+                "partitions",
+                "The partition count must be at least 1",
+            ));
+        }
+        if self
+            .cleanup_schedule
+            .as_ref()
+            .is_some_and(|schedule| schedule.trim().is_empty())
+        {
+            return Err(StoreError::invalid_configuration(
+                "cleanup_schedule",
+                "The cleanup schedule must be a cron expression",
+            ));
+        }
+        self.columns.validate()
+    }
+
     /// Builds the `PostgresStore` based on the provided configurations.
     ///
     /// Finalizes the builder and creates a `PostgresStore` instance.
@@ -128,15 +395,33 @@ impl PostgresStoreBuilder {
     /// This method returns a `Result` which, on success, contains the initialized `PostgresStore`.
     /// On failure, it returns a `KeyvError` indicating what went wrong during the initialization.
     pub async fn build(self) -> Result<PostgresStore, StoreError> {
+        self.validate()?;
+        let retry = self.initialize_retry.unwrap_or_default();
         let pool = match self.pool {
             Some(pool) => pool,
             None => {
-                let uri = self
-                    .uri
-                    .expect("PostgresStore requires either a URI or an existing pool to be set");
-                Arc::new(PgPoolOptions::new().connect(&uri).await.map_err(|_| {
-                    StoreError::ConnectionError("Failed to connect to the database".to_string())
-                })?)
+                let uri = self.uri.ok_or_else(|| {
+                    StoreError::invalid_configuration(
+                        "uri",
+                        "PostgresStore requires either a URI or an existing pool to be set",
+                    )
+                })?;
+                let pool = if self.lazy_connect {
+                    PgPoolOptions::new().connect_lazy(&uri).map_err(|_| {
+                        StoreError::ConnectionError("Failed to connect to the database".to_string())
+                    })?
+                } else {
+                    retry
+                        .run(|| async {
+                            PgPoolOptions::new().connect(&uri).await.map_err(|_| {
+                                StoreError::ConnectionError(
+                                    "Failed to connect to the database".to_string(),
+                                )
+                            })
+                        })
+                        .await?
+                };
+                Arc::new(pool)
             }
         };
 
@@ -148,10 +433,26 @@ impl PostgresStoreBuilder {
             }
         };
 
+        let queries = Queries::new(
+            &qualified_table_name(self.schema.as_deref(), &table_name),
+            &self.columns,
+            self.value_format,
+        );
+
         Ok(PostgresStore {
             pool,
             table_name,
             schema: self.schema,
+            partitions: self.partitions,
+            cleanup_schedule: self.cleanup_schedule,
+            value_format: self.value_format,
+            retry,
+            queries,
+            serialization_failure: self.serialization_failure,
+            quarantine_listener: self.quarantine_listener,
+            columns: self.columns,
+            create_tables: self.create_tables,
+            create_schema: self.create_schema,
         })
     }
 }
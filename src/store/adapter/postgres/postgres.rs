@@ -1,30 +1,185 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::TryStreamExt;
 use serde_json::Value;
 use sqlx::{PgPool, Row};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 
-use crate::{Store, StoreError};
+use super::queries::Queries;
+use crate::{
+    adapter::{Columns, ValueFormat},
+    store::{
+        change_from_row,
+        expiry::{expires_at_millis, millis_since_epoch, now_millis, system_time_from_millis},
+        glob, parse_sequence_cursor, set_many_in_batch, Quarantine,
+    },
+    BatchOp, Capabilities, Change, ChangeFeedBackend, ChangeKind, Metadata, QuarantineListener,
+    QuarantinedEntry, QueueBackend, QueueMessage, RetryPolicy, ScanEntry,
+    SerializationFailurePolicy, Store, StoreError, Usage,
+};
+
+/// Returns `table_name` qualified with `schema`, if any.
+pub(crate) fn qualified_table_name(schema: Option<&str>, table_name: &str) -> String {
+    match schema {
+        Some(schema) => format!("{}.{}", schema, table_name),
+        None => table_name.to_string(),
+    }
+}
 
 pub struct PostgresStore {
     pub(crate) pool: Arc<PgPool>,
     pub(crate) table_name: String,
     pub(crate) schema: Option<String>,
+    pub(crate) partitions: Option<u32>,
+    pub(crate) cleanup_schedule: Option<String>,
+    pub(crate) value_format: ValueFormat,
+    pub(crate) retry: RetryPolicy,
+    pub(crate) queries: Queries,
+    pub(crate) serialization_failure: SerializationFailurePolicy,
+    pub(crate) quarantine_listener: Option<QuarantineListener>,
+    pub(crate) columns: Columns,
+    pub(crate) create_tables: bool,
+    pub(crate) create_schema: bool,
+}
+
+/// Prints the table layout, never the pool and its connection options.
+impl fmt::Debug for PostgresStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PostgresStore")
+            .field("schema", &self.schema)
+            .field("table_name", &self.table_name)
+            .field("columns", &self.columns)
+            .field("value_format", &self.value_format)
+            .finish_non_exhaustive()
+    }
 }
 
 impl PostgresStore {
-    fn get_table_name(&self) -> String {
-        match &self.schema {
-            Some(schema) => format!("{}.{}", schema, self.table_name),
-            None => self.table_name.clone(),
+    /// Returns the connection pool backing the store.
+    ///
+    /// Gives access to backend-specific queries without opening a second pool.
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Writes the live entries to `writer` as CSV, using `COPY ... TO STDOUT`.
+    ///
+    /// Each line holds the key, value, expiration, creation and update time of an
+    /// entry, the times in milliseconds since the Unix epoch. Streaming the table
+    /// through `COPY` is far faster than paging through it with `scan_entries`, which
+    /// makes it the way to back up large cache tables. Read the dump back with `import`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use keyv::adapter::postgres::PostgresStoreBuilder;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let store = PostgresStoreBuilder::new().uri("postgresql://localhost").build().await.unwrap();
+    /// let mut file = tokio::fs::File::create("cache.csv").await.unwrap();
+    /// store.export(&mut file).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn export<W: AsyncWrite + Unpin>(&self, mut writer: W) -> Result<(), StoreError> {
+        let query_error =
+            |e: sqlx::Error| StoreError::QueryError(format!("Failed to export: {}", e));
+        let Columns {
+            key,
+            value,
+            expires_at,
+            created_at,
+            updated_at,
+        } = &self.columns;
+        // Dumps always have both timestamp fields, empty when the table lacks the column.
+        let timestamps = [created_at, updated_at]
+            .map(|column| column.as_deref().unwrap_or("NULL"))
+            .join(", ");
+        // COPY takes no bind parameters, so the current time is inlined.
+        let sql = format!(
+            "COPY (SELECT {key}, {value}::text, {expires_at}, {timestamps} FROM {table} WHERE {expires_at} IS NULL OR {expires_at} > {now}) TO STDOUT WITH (FORMAT csv)",
+            table = self.get_table_name(),
+            now = now_millis()
+        );
+        let mut conn = self.pool.acquire().await.map_err(query_error)?;
+        let mut chunks = conn.copy_out_raw(&sql).await.map_err(query_error)?;
+        while let Some(chunk) = chunks.try_next().await.map_err(query_error)? {
+            writer.write_all(&chunk).await.map_err(|e| {
+                StoreError::QueryError(format!("Failed to write the export: {}", e))
+            })?;
         }
+        writer
+            .flush()
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to write the export: {}", e)))
     }
-}
 
-#[async_trait]
-impl Store for PostgresStore {
-    async fn initialize(&self) -> Result<(), StoreError> {
-        if let Some(ref schema) = self.schema {
+    /// Loads a dump written by `export` from `reader`, using `COPY ... FROM STDIN`.
+    ///
+    /// Entries overwrite those already stored under the same key, and entries that
+    /// expired since the export are skipped. The import runs in a single transaction:
+    /// a malformed dump leaves the table untouched.
+    ///
+    /// # Returns
+    /// - `Ok(u64)` with the number of entries written.
+    /// - `Err(StoreError)` if the dump is malformed or the import fails.
+    pub async fn import<R: AsyncRead + Unpin>(&self, reader: R) -> Result<u64, StoreError> {
+        let query_error =
+            |e: sqlx::Error| StoreError::QueryError(format!("Failed to import: {}", e));
+        let mut tx = self.pool.begin().await.map_err(query_error)?;
+        sqlx::query(&self.queries.import_stage)
+            .execute(&mut *tx)
+            .await
+            .map_err(query_error)?;
+        let mut copy = tx
+            .copy_in_raw(&self.queries.import_copy)
+            .await
+            .map_err(query_error)?;
+        if let Err(e) = copy.read_from(reader).await {
+            let _ = copy.abort("Failed to read the dump").await;
+            return Err(query_error(e));
+        }
+        copy.finish().await.map_err(query_error)?;
+        let imported = sqlx::query(&self.queries.import_merge)
+            .bind(now_millis())
+            .execute(&mut *tx)
+            .await
+            .map_err(query_error)?;
+        tx.commit().await.map_err(query_error)?;
+        Ok(imported.rows_affected())
+    }
+
+    fn get_table_name(&self) -> String {
+        qualified_table_name(self.schema.as_deref(), &self.table_name)
+    }
+
+    /// Checks that the table exists with the expected columns, for stores that do not
+    /// create it themselves.
+    async fn verify_table(&self) -> Result<(), StoreError> {
+        sqlx::query(&self.queries.verify)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| {
+                StoreError::invalid_configuration(
+                    "table_name",
+                    format!(
+                        "The table '{}' is missing or lacks the expected columns: {}",
+                        self.get_table_name(),
+                        e
+                    ),
+                )
+            })?;
+        Ok(())
+    }
+
+    async fn create_table(&self) -> Result<(), StoreError> {
+        if let Some(schema) = self.schema.as_ref().filter(|_| self.create_schema) {
             let create_schema_sql = format!("CREATE SCHEMA IF NOT EXISTS {}", schema);
             sqlx::query(&create_schema_sql)
                 .execute(&*self.pool)
@@ -32,56 +187,484 @@ impl Store for PostgresStore {
                 .map_err(|e| {
                     StoreError::QueryError(format!(
                         "Failed to create the schema '{}': {}",
-                        schema,
-                        e.to_string()
+                        schema, e
                     ))
                 })?;
         }
 
+        let Columns {
+            key,
+            value,
+            expires_at,
+            ..
+        } = &self.columns;
+        let timestamps: String = self
+            .columns
+            .timestamps()
+            .map(|column| format!(",\n            {} BIGINT", column))
+            .collect();
+        let partition_clause = if self.partitions.is_some() {
+            format!(" PARTITION BY HASH ({})", key)
+        } else {
+            String::new()
+        };
+        let value_type = match self.value_format {
+            ValueFormat::Text => "TEXT",
+            ValueFormat::Json => "JSONB",
+        };
         let sql = format!(
             "CREATE TABLE IF NOT EXISTS {} (
+            {} VARCHAR PRIMARY KEY,
+            {} {} NOT NULL,
+            {} BIGINT{}
+        ){}",
+            self.get_table_name(),
+            key,
+            value,
+            value_type,
+            expires_at,
+            timestamps,
+            partition_clause
+        );
+
+        sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
+            StoreError::QueryError(format!("Failed to initialize the database table: {}", e))
+        })?;
+
+        if let Some(partitions) = self.partitions {
+            self.create_partitions(partitions).await?;
+        }
+
+        // Tables created by earlier versions lack the expiration and timestamp columns.
+        let missing_timestamps: String = self
+            .columns
+            .timestamps()
+            .map(|column| format!(",\n            ADD COLUMN IF NOT EXISTS {} BIGINT", column))
+            .collect();
+        let migrate_sql = format!(
+            "ALTER TABLE {} ADD COLUMN IF NOT EXISTS {} BIGINT{}",
+            self.get_table_name(),
+            expires_at,
+            missing_timestamps
+        );
+        sqlx::query(&migrate_sql)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to add missing columns: {}", e)))?;
+
+        if let Some(ref schedule) = self.cleanup_schedule {
+            self.register_cleanup(schedule).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates the table undeserializable entries are moved to, if the serialization
+    /// failure policy quarantines them.
+    async fn create_quarantine_table(&self) -> Result<(), StoreError> {
+        if self.serialization_failure != SerializationFailurePolicy::Quarantine {
+            return Ok(());
+        }
+
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {}_quarantine (
             key VARCHAR PRIMARY KEY,
-            value TEXT NOT NULL
+            value TEXT NOT NULL,
+            error TEXT NOT NULL,
+            quarantined_at BIGINT NOT NULL
         )",
             self.get_table_name()
         );
+        sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
+            StoreError::QueryError(format!("Failed to create the quarantine table: {}", e))
+        })?;
 
+        Ok(())
+    }
+
+    /// Creates the table holding the messages of `queue_push`.
+    async fn create_queue_table(&self) -> Result<(), StoreError> {
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {}_queue (
+            id BIGSERIAL PRIMARY KEY,
+            queue VARCHAR NOT NULL,
+            payload TEXT NOT NULL,
+            claimed_until BIGINT
+        )",
+            self.get_table_name()
+        );
         sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
-            StoreError::QueryError(format!(
-                "Failed to initialize the database table: {}",
-                e.to_string()
-            ))
+            StoreError::QueryError(format!("Failed to create the queue table: {}", e))
         })?;
 
         Ok(())
     }
 
+    /// Creates the outbox table holding the change feed.
+    async fn create_changes_table(&self) -> Result<(), StoreError> {
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {}_changes (
+            id BIGSERIAL PRIMARY KEY,
+            key VARCHAR,
+            kind VARCHAR NOT NULL,
+            recorded_at BIGINT NOT NULL
+        )",
+            self.get_table_name()
+        );
+        sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
+            StoreError::QueryError(format!("Failed to create the changes table: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// Creates the function purging expired rows and schedules it with `pg_cron` when
+    /// the extension is available.
+    async fn register_cleanup(&self, schedule: &str) -> Result<(), StoreError> {
+        let table_name = self.get_table_name();
+        let function_name = format!("{}_purge_expired", table_name);
+
+        let function_sql = format!(
+            "CREATE OR REPLACE FUNCTION {}() RETURNS void LANGUAGE sql AS $$
+            DELETE FROM {} WHERE {} <= (EXTRACT(EPOCH FROM clock_timestamp()) * 1000)::BIGINT
+        $$",
+            function_name, table_name, self.columns.expires_at
+        );
+        sqlx::query(&function_sql)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to create the cleanup function: {}", e))
+            })?;
+
+        let has_pg_cron: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'pg_cron')",
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| {
+            StoreError::QueryError(format!("Failed to look up the pg_cron extension: {}", e))
+        })?;
+
+        if !has_pg_cron {
+            log::warn!(
+                "pg_cron is not installed; schedule `SELECT {}()` with an external scheduler to purge expired rows",
+                function_name
+            );
+            return Ok(());
+        }
+
+        // Scheduling a job under an existing name replaces its schedule and command.
+        sqlx::query("SELECT cron.schedule($1, $2, $3)")
+            .bind(format!("keyv_purge_{}", table_name))
+            .bind(schedule)
+            .bind(format!("SELECT {}()", function_name))
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to schedule the cleanup job: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    async fn create_partitions(&self, partitions: u32) -> Result<(), StoreError> {
+        let table_name = self.get_table_name();
+        let is_partitioned: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM pg_partitioned_table WHERE partrelid = $1::regclass)",
+        )
+        .bind(&table_name)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| StoreError::QueryError(format!("Failed to inspect the table: {}", e)))?;
+
+        if !is_partitioned {
+            log::warn!(
+                "Table '{}' already exists without partitions, ignoring the partitions option",
+                table_name
+            );
+            return Ok(());
+        }
+
+        for remainder in 0..partitions {
+            let sql = format!(
+                "CREATE TABLE IF NOT EXISTS {}_p{} PARTITION OF {} FOR VALUES WITH (MODULUS {}, REMAINDER {})",
+                table_name, remainder, table_name, partitions, remainder
+            );
+            sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
+                StoreError::QueryError(format!(
+                    "Failed to create partition {} of '{}': {}",
+                    remainder, table_name, e
+                ))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads `key` with its metadata, skipping it if it has expired unless
+    /// `include_expired` is set.
+    async fn read_with_metadata(
+        &self,
+        key: &str,
+        include_expired: bool,
+    ) -> Result<Option<(Value, Metadata)>, StoreError> {
+        let query = if include_expired {
+            sqlx::query(&self.queries.get_stale).bind(key)
+        } else {
+            sqlx::query(&self.queries.get_with_metadata)
+                .bind(key)
+                .bind(now_millis())
+        };
+        let result = query
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to fetch the value".to_string()))?;
+
+        let Some(row) = result else {
+            return Ok(None);
+        };
+        let value = self
+            .serialization_failure
+            .decode(self, key, row.get("value"))
+            .await?;
+        Ok(value.map(|value| {
+            let metadata = Metadata {
+                created_at: row
+                    .get::<Option<i64>, _>("created_at")
+                    .map(system_time_from_millis),
+                updated_at: row
+                    .get::<Option<i64>, _>("updated_at")
+                    .map(system_time_from_millis),
+                expires_at: row
+                    .get::<Option<i64>, _>("expires_at")
+                    .map(system_time_from_millis),
+            };
+            (value, metadata)
+        }))
+    }
+
+    /// Inserts or replaces `key`, storing `expires_at` in milliseconds since the epoch.
+    async fn upsert(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: Option<i64>,
+    ) -> Result<(), StoreError> {
+        let value_str = serde_json::to_string(&value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+
+        sqlx::query(&self.queries.upsert)
+            .bind(key)
+            .bind(value_str)
+            .bind(expires_at)
+            .bind(now_millis())
+            .execute(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to set the value".to_string()))?;
+
+        Ok(())
+    }
+
+    /// Streams the live rows whose key starts with `prefix` and, if set, matches the
+    /// `LIKE` pattern `like`.
+    fn scan_rows<'a>(
+        &'a self,
+        prefix: &'a str,
+        like: Option<String>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        let batch_size = batch_size.max(1);
+        // Keyset pagination: each batch starts after the last key of the previous one.
+        Box::pin(stream::try_unfold(Some(None::<String>), move |cursor| {
+            let like = like.clone();
+            async move {
+                let Some(cursor) = cursor else {
+                    return Ok(None);
+                };
+                let rows = sqlx::query_as::<_, (String, String, Option<i64>)>(&self.queries.scan)
+                    .bind(cursor)
+                    .bind(prefix)
+                    .bind(now_millis())
+                    .bind(batch_size as i64)
+                    .bind(like)
+                    .fetch_all(&*self.pool)
+                    .await
+                    .map_err(|e| {
+                        StoreError::QueryError(format!("Failed to scan the table: {}", e))
+                    })?;
+                if rows.is_empty() {
+                    return Ok(None);
+                }
+
+                let next =
+                    (rows.len() == batch_size).then(|| rows.last().map(|(key, _, _)| key.clone()));
+                let rows = rows
+                    .into_iter()
+                    .map(|(key, value, expires_at)| {
+                        (key, value, expires_at.map(system_time_from_millis))
+                    })
+                    .collect();
+                let batch = self
+                    .serialization_failure
+                    .decode_entries(self, rows)
+                    .await?;
+                Ok(Some((batch, next)))
+            }
+        }))
+    }
+}
+
+#[async_trait]
+impl Quarantine for PostgresStore {
+    async fn quarantine(&self, entries: &[QuarantinedEntry]) -> Result<(), StoreError> {
+        let query_error =
+            |e: sqlx::Error| StoreError::QueryError(format!("Failed to quarantine: {}", e));
+        let mut tx = self.pool.begin().await.map_err(query_error)?;
+        for entry in entries {
+            sqlx::query(&self.queries.quarantine)
+                .bind(&entry.key)
+                .bind(&entry.raw)
+                .bind(&entry.error)
+                .bind(now_millis())
+                .execute(&mut *tx)
+                .await
+                .map_err(query_error)?;
+            sqlx::query(&self.queries.remove)
+                .bind(&entry.key)
+                .execute(&mut *tx)
+                .await
+                .map_err(query_error)?;
+        }
+        tx.commit().await.map_err(query_error)
+    }
+
+    fn quarantine_listener(&self) -> Option<&QuarantineListener> {
+        self.quarantine_listener.as_ref()
+    }
+}
+
+#[async_trait]
+impl Store for PostgresStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        if !self.create_tables {
+            return self.retry.run(|| self.verify_table()).await;
+        }
+        self.retry.run(|| self.create_table()).await?;
+        self.create_quarantine_table().await?;
+        self.create_queue_table().await?;
+        self.create_changes_table().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            native_ttl: true,
+            atomic_increment: true,
+            transactions: true,
+            scan: true,
+            watch: true,
+        }
+    }
+
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
-        let query = format!("SELECT value FROM {} WHERE key = $1", self.get_table_name());
-        let result = sqlx::query(&query)
+        let result = sqlx::query(&self.queries.get)
             .bind(key)
+            .bind(now_millis())
             .fetch_optional(&*self.pool)
             .await
             .map_err(|_| StoreError::QueryError("Failed to fetch the value".to_string()))?;
 
-        Ok(result.and_then(|row| serde_json::from_str(row.get("value")).ok()))
+        match result {
+            Some(row) => {
+                self.serialization_failure
+                    .decode(self, key, row.get("value"))
+                    .await
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let rows: HashMap<String, String> = sqlx::query(&self.queries.get_many)
+            .bind(keys)
+            .bind(now_millis())
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to fetch the values".to_string()))?
+            .into_iter()
+            .map(|row| (row.get("key"), row.get("value")))
+            .collect();
+
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(match rows.get(*key) {
+                Some(value) => self.serialization_failure.decode(self, key, value).await?,
+                None => None,
+            });
+        }
+        Ok(values)
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.read_with_metadata(key, false).await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.read_with_metadata(key, true).await
     }
 
-    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
-        if ttl.is_some() {
-            log::warn!("Postgres store does not support TTL");
+    /// Extends the expiration with the same `UPDATE` that returns the value, so a
+    /// concurrent write is never overwritten with the value read.
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        let result = sqlx::query(&self.queries.get_and_touch)
+            .bind(expires_at_millis(Some(ttl)))
+            .bind(key)
+            .bind(now_millis())
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to touch the value".to_string()))?;
+
+        match result {
+            Some(row) => {
+                self.serialization_failure
+                    .decode(self, key, row.get("value"))
+                    .await
+            }
+            None => Ok(None),
         }
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.upsert(key, value, expires_at_millis(ttl)).await
+    }
 
+    /// Entries are written in one transaction.
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        set_many_in_batch(self, entries, ttl).await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        self.upsert(key, value, Some(millis_since_epoch(expires_at)))
+            .await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
         let value_str = serde_json::to_string(&value)
             .map_err(|e| StoreError::SerializationError { source: e })?;
 
-        let sql = format!(
-            "INSERT INTO {} (key, value) VALUES ($1, $2) ON CONFLICT(key) DO UPDATE SET value = EXCLUDED.value",
-            self.get_table_name()
-        );
-        sqlx::query(&sql)
+        sqlx::query(&self.queries.upsert_keep_ttl)
             .bind(key)
             .bind(value_str)
+            .bind(now_millis())
             .execute(&*self.pool)
             .await
             .map_err(|_| StoreError::QueryError("Failed to set the value".to_string()))?;
@@ -89,9 +672,97 @@ impl Store for PostgresStore {
         Ok(())
     }
 
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        let value_str = serde_json::to_string(&value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+
+        let result = sqlx::query(&self.queries.insert_if_absent)
+            .bind(key)
+            .bind(value_str)
+            .bind(expires_at_millis(ttl))
+            .bind(now_millis())
+            .execute(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to set the value".to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        sqlx::query_scalar::<_, i64>(&self.queries.increment)
+            .bind(key)
+            .bind(delta)
+            .bind(expires_at_millis(ttl))
+            .bind(now_millis())
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to increment: {}", e)))
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        let query_error =
+            |e: sqlx::Error| StoreError::QueryError(format!("Failed to run the batch: {}", e));
+        let mut tx = self.pool.begin().await.map_err(query_error)?;
+        let now = now_millis();
+        let mut results = Vec::with_capacity(ops.len());
+        for op in ops {
+            match op {
+                BatchOp::Set { key, value, ttl } => {
+                    let value_str = serde_json::to_string(&value)
+                        .map_err(|e| StoreError::SerializationError { source: e })?;
+                    sqlx::query(&self.queries.upsert)
+                        .bind(key)
+                        .bind(value_str)
+                        .bind(expires_at_millis(ttl))
+                        .bind(now)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(query_error)?;
+                    results.push(Ok(()));
+                }
+                BatchOp::Remove { key } => {
+                    sqlx::query(&self.queries.remove)
+                        .bind(key)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(query_error)?;
+                    results.push(Ok(()));
+                }
+                BatchOp::Touch { key, ttl } => {
+                    let touched = sqlx::query(&self.queries.touch)
+                        .bind(expires_at_millis(Some(ttl)))
+                        .bind(key)
+                        .bind(now)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(query_error)?;
+                    results.push(if touched.rows_affected() > 0 {
+                        Ok(())
+                    } else {
+                        Err(StoreError::NotFound)
+                    });
+                }
+            }
+        }
+        tx.commit().await.map_err(query_error)?;
+        Ok(results)
+    }
+
     async fn remove(&self, key: &str) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {} WHERE key = $1", self.get_table_name());
-        sqlx::query(&query)
+        sqlx::query(&self.queries.remove)
             .bind(key)
             .execute(&*self.pool)
             .await
@@ -101,9 +772,7 @@ impl Store for PostgresStore {
     }
 
     async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {} WHERE key = ANY($1)", self.get_table_name());
-
-        sqlx::query(&query)
+        sqlx::query(&self.queries.remove_many)
             .bind(keys)
             .execute(&*self.pool)
             .await
@@ -112,14 +781,206 @@ impl Store for PostgresStore {
         Ok(())
     }
 
-    async fn clear(&self) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {}", self.get_table_name());
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        let expected = serde_json::to_string(expected)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+        let removed = sqlx::query(&self.queries.remove_if)
+            .bind(key)
+            .bind(expected)
+            .bind(now_millis())
+            .execute(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to remove the key".to_string()))?;
+
+        Ok(removed.rows_affected() > 0)
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        let query_error =
+            |e: sqlx::Error| StoreError::QueryError(format!("Failed to rename the key: {}", e));
+        let mut tx = self.pool.begin().await.map_err(query_error)?;
+        if old_key != new_key {
+            sqlx::query(&self.queries.remove)
+                .bind(new_key)
+                .execute(&mut *tx)
+                .await
+                .map_err(query_error)?;
+        }
+        let renamed = sqlx::query(&self.queries.rename)
+            .bind(new_key)
+            .bind(old_key)
+            .bind(now_millis())
+            .execute(&mut *tx)
+            .await
+            .map_err(query_error)?;
+        if renamed.rows_affected() == 0 {
+            // Dropping the transaction rolls back the removal of `new_key`.
+            return Err(StoreError::NotFound);
+        }
+        tx.commit().await.map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        let touched = sqlx::query(&self.queries.touch_many)
+            .bind(expires_at_millis(Some(ttl)))
+            .bind(keys)
+            .bind(now_millis())
+            .execute(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to touch the keys".to_string()))?;
+
+        Ok(touched.rows_affected())
+    }
 
-        sqlx::query(&query)
+    async fn clear(&self) -> Result<(), StoreError> {
+        sqlx::query(&self.queries.clear)
             .execute(&*self.pool)
             .await
             .map_err(|_| StoreError::QueryError("Failed to clear the table".to_string()))?;
 
         Ok(())
     }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        let (entries, bytes): (i64, i64) = sqlx::query_as(&self.queries.usage)
+            .bind(self.get_table_name())
+            .bind(now_millis())
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to read the table usage: {}", e))
+            })?;
+
+        Ok(Usage {
+            entries: entries as u64,
+            bytes: Some(bytes as u64),
+        })
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.scan_rows(prefix.unwrap_or_default(), None, batch_size)
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.scan_rows(
+            glob::literal_prefix(pattern),
+            Some(glob::to_like(pattern)),
+            batch_size,
+        )
+    }
+}
+
+#[async_trait]
+impl QueueBackend for PostgresStore {
+    async fn queue_push(&self, queue: &str, payload: Value) -> Result<String, StoreError> {
+        let payload = serde_json::to_string(&payload)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+        let id = sqlx::query_scalar::<_, i64>(&self.queries.queue_push)
+            .bind(queue)
+            .bind(payload)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to push the message: {}", e)))?;
+
+        Ok(id.to_string())
+    }
+
+    async fn queue_pop(
+        &self,
+        queue: &str,
+        visibility_timeout: Duration,
+    ) -> Result<Option<QueueMessage>, StoreError> {
+        let now = now_millis();
+        let claimed = sqlx::query_as::<_, (i64, String)>(&self.queries.queue_pop)
+            .bind(queue)
+            .bind(now)
+            .bind(now.saturating_add(visibility_timeout.as_millis() as i64))
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to pop a message: {}", e)))?;
+
+        claimed
+            .map(|(id, payload)| {
+                let payload = serde_json::from_str(&payload)
+                    .map_err(|e| StoreError::SerializationError { source: e })?;
+                Ok(QueueMessage {
+                    id: id.to_string(),
+                    payload,
+                })
+            })
+            .transpose()
+    }
+
+    async fn queue_ack(&self, queue: &str, id: &str) -> Result<(), StoreError> {
+        // Ids this store never handed out cannot name a message.
+        let Ok(id) = id.parse::<i64>() else {
+            return Ok(());
+        };
+        sqlx::query(&self.queries.queue_ack)
+            .bind(queue)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to acknowledge the message: {}", e))
+            })?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChangeFeedBackend for PostgresStore {
+    async fn append_change(
+        &self,
+        key: Option<&str>,
+        kind: ChangeKind,
+    ) -> Result<String, StoreError> {
+        let query_error =
+            |e: sqlx::Error| StoreError::QueryError(format!("Failed to append the change: {}", e));
+        let mut tx = self.pool.begin().await.map_err(query_error)?;
+        sqlx::query(&self.queries.changes_lock)
+            .execute(&mut *tx)
+            .await
+            .map_err(query_error)?;
+        let id = sqlx::query_scalar::<_, i64>(&self.queries.changes_append)
+            .bind(key)
+            .bind(kind.as_str())
+            .bind(now_millis())
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(query_error)?;
+        tx.commit().await.map_err(query_error)?;
+
+        Ok(id.to_string())
+    }
+
+    async fn read_changes(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Change>, StoreError> {
+        let rows =
+            sqlx::query_as::<_, (i64, Option<String>, String, i64)>(&self.queries.changes_read)
+                .bind(parse_sequence_cursor(after)?)
+                .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+                .fetch_all(&*self.pool)
+                .await
+                .map_err(|e| {
+                    StoreError::QueryError(format!("Failed to read the changes: {}", e))
+                })?;
+
+        rows.into_iter()
+            .map(|(id, key, kind, recorded_at)| change_from_row(id, key, &kind, recorded_at))
+            .collect()
+    }
 }
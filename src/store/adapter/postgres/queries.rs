@@ -0,0 +1,197 @@
+use crate::adapter::{Columns, ValueFormat};
+
+/// SQL statements for one table, built once when the store is created.
+///
+/// Reusing the same strings lets sqlx keep a single prepared statement per query and
+/// connection instead of formatting and hashing a new statement on every call.
+pub(crate) struct Queries {
+    pub(crate) verify: String,
+    pub(crate) get: String,
+    pub(crate) get_many: String,
+    pub(crate) get_with_metadata: String,
+    pub(crate) get_stale: String,
+    pub(crate) get_and_touch: String,
+    pub(crate) upsert: String,
+    pub(crate) upsert_keep_ttl: String,
+    pub(crate) insert_if_absent: String,
+    pub(crate) increment: String,
+    pub(crate) touch: String,
+    pub(crate) rename: String,
+    pub(crate) remove: String,
+    pub(crate) remove_if: String,
+    pub(crate) remove_many: String,
+    pub(crate) touch_many: String,
+    pub(crate) clear: String,
+    pub(crate) usage: String,
+    pub(crate) scan: String,
+    pub(crate) quarantine: String,
+    pub(crate) queue_push: String,
+    pub(crate) queue_pop: String,
+    pub(crate) queue_ack: String,
+    pub(crate) changes_lock: String,
+    pub(crate) changes_append: String,
+    pub(crate) changes_read: String,
+    pub(crate) import_stage: String,
+    pub(crate) import_copy: String,
+    pub(crate) import_merge: String,
+}
+
+impl Queries {
+    pub(crate) fn new(table: &str, columns: &Columns, value_format: ValueFormat) -> Self {
+        let Columns {
+            key,
+            value,
+            expires_at,
+            created_at,
+            updated_at,
+        } = columns;
+        // JSONB values are read back as text and bound as text cast to JSONB, so the
+        // store code is the same for both formats. Values are always read as `value`.
+        let (read_value, param, imported) = match value_format {
+            ValueFormat::Text => (format!("{value} AS value"), "$2", "value"),
+            ValueFormat::Json => (
+                format!("{value}::text AS value"),
+                "$2::jsonb",
+                "value::jsonb",
+            ),
+        };
+        // Counters are computed as BIGINT and written back in the column's format.
+        let (counter_insert, counter_update, counter) = match value_format {
+            ValueFormat::Text => (
+                "$2::bigint::text".to_string(),
+                format!("({table}.{value}::bigint + $2::bigint)::text"),
+                format!("{value}::bigint"),
+            ),
+            ValueFormat::Json => (
+                "to_jsonb($2::bigint)".to_string(),
+                format!("to_jsonb(({table}.{value} #>> '{{}}')::bigint + $2::bigint)"),
+                format!("({value} #>> '{{}}')::bigint"),
+            ),
+        };
+        // Timestamp columns the table lacks are left out of writes and read as NULL.
+        let read_timestamps = [(created_at, "created_at"), (updated_at, "updated_at")]
+            .map(|(column, name)| match column {
+                Some(column) => format!("{column} AS {name}"),
+                None => format!("NULL::bigint AS {name}"),
+            })
+            .join(", ");
+        let timestamps: String = columns.timestamps().map(|c| format!(", {c}")).collect();
+        let timestamp_values = |param: &str| -> String {
+            columns.timestamps().map(|_| format!(", {param}")).collect()
+        };
+        // `created_at` is kept unless the previous row had expired by `now`.
+        let keep_created = |now: &str| {
+            match created_at {
+            Some(c) => format!(
+                ", {c} = CASE WHEN {table}.{expires_at} <= {now} THEN EXCLUDED.{c} ELSE {table}.{c} END"
+            ),
+            None => String::new(),
+        }
+        };
+        let reset_created = created_at
+            .as_ref()
+            .map_or(String::new(), |c| format!(", {c} = EXCLUDED.{c}"));
+        let set_updated = updated_at
+            .as_ref()
+            .map_or(String::new(), |c| format!(", {c} = EXCLUDED.{c}"));
+        // Dumps always carry both timestamps; only those the table has are imported.
+        let imported_timestamps: String = [(created_at, "created_at"), (updated_at, "updated_at")]
+            .iter()
+            .filter(|(column, _)| column.is_some())
+            .map(|(_, name)| format!(", {name}"))
+            .collect();
+        Self {
+            // Reads no rows, but fails unless the table has every column the store uses.
+            verify: format!("SELECT {key}, {value}, {expires_at}{timestamps} FROM {table} WHERE 1 = 0"),
+            get: format!(
+                "SELECT {read_value} FROM {table} WHERE {key} = $1 AND ({expires_at} IS NULL OR {expires_at} > $2)"
+            ),
+            get_many: format!(
+                "SELECT {key} AS key, {read_value} FROM {table} WHERE {key} = ANY($1) AND ({expires_at} IS NULL OR {expires_at} > $2)"
+            ),
+            get_with_metadata: format!(
+                "SELECT {read_value}, {read_timestamps}, {expires_at} AS expires_at FROM {table} WHERE {key} = $1 AND ({expires_at} IS NULL OR {expires_at} > $2)"
+            ),
+            // Expired rows included, for callers that decide themselves what is too old.
+            get_stale: format!(
+                "SELECT {read_value}, {read_timestamps}, {expires_at} AS expires_at FROM {table} WHERE {key} = $1"
+            ),
+            get_and_touch: format!(
+                "UPDATE {table} SET {expires_at} = $1 WHERE {key} = $2 AND ({expires_at} IS NULL OR {expires_at} > $3) RETURNING {read_value}"
+            ),
+            // `created_at` is kept on overwrite unless the previous row had expired.
+            upsert: format!(
+                "INSERT INTO {table} ({key}, {value}, {expires_at}{timestamps}) VALUES ($1, {param}, $3{values}) ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {expires_at} = EXCLUDED.{expires_at}{keep_created}{set_updated}",
+                values = timestamp_values("$4"),
+                keep_created = keep_created("$4"),
+            ),
+            upsert_keep_ttl: format!(
+                "INSERT INTO {table} ({key}, {value}{timestamps}) VALUES ($1, {param}{values}) ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {expires_at} = CASE WHEN {table}.{expires_at} > $3 THEN {table}.{expires_at} END{keep_created}{set_updated}",
+                values = timestamp_values("$3"),
+                keep_created = keep_created("$3"),
+            ),
+            // Only an expired row is overwritten; a live one is left alone and counts as
+            // no row affected.
+            insert_if_absent: format!(
+                "INSERT INTO {table} ({key}, {value}, {expires_at}{timestamps}) VALUES ($1, {param}, $3{values}) ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {expires_at} = EXCLUDED.{expires_at}{reset_created}{set_updated} WHERE {table}.{expires_at} <= $4",
+                values = timestamp_values("$4"),
+            ),
+            // An expired row restarts from `delta`.
+            increment: format!(
+                "INSERT INTO {table} ({key}, {value}, {expires_at}{timestamps}) VALUES ($1, {counter_insert}, $3{values}) ON CONFLICT({key}) DO UPDATE SET {value} = CASE WHEN {table}.{expires_at} <= $4 THEN EXCLUDED.{value} ELSE {counter_update} END, {expires_at} = CASE WHEN {table}.{expires_at} <= $4 THEN EXCLUDED.{expires_at} ELSE {table}.{expires_at} END{keep_created}{set_updated} RETURNING {counter}",
+                values = timestamp_values("$4"),
+                keep_created = keep_created("$4"),
+            ),
+            touch: format!(
+                "UPDATE {table} SET {expires_at} = $1 WHERE {key} = $2 AND ({expires_at} IS NULL OR {expires_at} > $3)"
+            ),
+            rename: format!(
+                "UPDATE {table} SET {key} = $1 WHERE {key} = $2 AND ({expires_at} IS NULL OR {expires_at} > $3)"
+            ),
+            remove: format!("DELETE FROM {table} WHERE {key} = $1"),
+            remove_if: format!(
+                "DELETE FROM {table} WHERE {key} = $1 AND {value} = {param} AND ({expires_at} IS NULL OR {expires_at} > $3)"
+            ),
+            remove_many: format!("DELETE FROM {table} WHERE {key} = ANY($1)"),
+            touch_many: format!(
+                "UPDATE {table} SET {expires_at} = $1 WHERE {key} = ANY($2) AND ({expires_at} IS NULL OR {expires_at} > $3)"
+            ),
+            clear: format!("DELETE FROM {table}"),
+            scan: format!(
+                "SELECT {key}, {read_value}, {expires_at} FROM {table} WHERE {key} >= $2 AND left({key}, length($2)) = $2 AND ($1::varchar IS NULL OR {key} > $1) AND ({expires_at} IS NULL OR {expires_at} > $3) AND ($5::varchar IS NULL OR {key} LIKE $5) ORDER BY {key} LIMIT $4"
+            ),
+            quarantine: format!(
+                "INSERT INTO {table}_quarantine (key, value, error, quarantined_at) VALUES ($1, $2, $3, $4) ON CONFLICT(key) DO UPDATE SET value = EXCLUDED.value, error = EXCLUDED.error, quarantined_at = EXCLUDED.quarantined_at"
+            ),
+            queue_push: format!(
+                "INSERT INTO {table}_queue (queue, payload) VALUES ($1, $2) RETURNING id"
+            ),
+            // Claims the oldest message nobody holds or whose claim has lapsed, skipping
+            // the rows other consumers are claiming concurrently.
+            queue_pop: format!(
+                "UPDATE {table}_queue SET claimed_until = $3 WHERE id = (SELECT id FROM {table}_queue WHERE queue = $1 AND (claimed_until IS NULL OR claimed_until <= $2) ORDER BY id LIMIT 1 FOR UPDATE SKIP LOCKED) RETURNING id, payload"
+            ),
+            queue_ack: format!("DELETE FROM {table}_queue WHERE queue = $1 AND id = $2"),
+            // Taken by every append so that ids are committed in increasing order and a
+            // reader never moves past a change still being appended. Reads are not blocked.
+            changes_lock: format!("LOCK TABLE {table}_changes IN SHARE ROW EXCLUSIVE MODE"),
+            changes_append: format!(
+                "INSERT INTO {table}_changes (key, kind, recorded_at) VALUES ($1, $2, $3) RETURNING id"
+            ),
+            changes_read: format!(
+                "SELECT id, key, kind, recorded_at FROM {table}_changes WHERE id > $1 ORDER BY id LIMIT $2"
+            ),
+            // Dumps are loaded into a staging table first so that existing keys are
+            // overwritten instead of failing the COPY, and rows that expired since the
+            // export are skipped.
+            import_stage: "CREATE TEMPORARY TABLE keyv_import (key VARCHAR NOT NULL, value TEXT NOT NULL, expires_at BIGINT, created_at BIGINT, updated_at BIGINT) ON COMMIT DROP".to_string(),
+            import_copy: "COPY keyv_import (key, value, expires_at, created_at, updated_at) FROM STDIN WITH (FORMAT csv)".to_string(),
+            import_merge: format!(
+                "INSERT INTO {table} ({key}, {value}, {expires_at}{timestamps}) SELECT key, {imported}, expires_at{imported_timestamps} FROM keyv_import WHERE expires_at IS NULL OR expires_at > $1 ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {expires_at} = EXCLUDED.{expires_at}{reset_created}{set_updated}"
+            ),
+            usage: format!(
+                "SELECT COUNT(*), (SELECT COALESCE(SUM(pg_total_relation_size(relid)), 0)::BIGINT FROM pg_partition_tree($1::regclass)) FROM {table} WHERE {expires_at} IS NULL OR {expires_at} > $2"
+            ),
+        }
+    }
+}
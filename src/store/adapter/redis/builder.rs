@@ -1,16 +1,41 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 pub use redis::Client;
+use redis::IntoConnectionInfo;
+use tokio::sync::OnceCell;
 
-use crate::StoreError;
+use crate::{
+    adapter::validate_uri_scheme,
+    layer::key_codec::{KeyCodecStore, PrefixCodec},
+    RetryPolicy, StoreError,
+};
 
 use super::RedisStore;
 
+/// The store returned by `RedisStoreBuilder::build`: a `RedisStore` behind a
+/// `KeyCodecStore` prefixing keys and queue names with the namespace, if any.
+///
+/// Entries, queues and the change feed are all reachable through this type; use
+/// `inner()` only for the methods specific to the adapter, such as `client()`, since
+/// it bypasses the namespace.
+pub type NamespacedRedisStore = KeyCodecStore<RedisStore, Option<PrefixCodec>>;
+
 pub struct RedisStoreBuilder {
     connection_string: Option<String>,
     client: Option<Arc<Client>>,
-    default_ttl: Option<u64>,
+    default_ttl: Option<Duration>,
     namespace: Option<String>, // Adding namespace option
+    lazy_connect: bool,
+    initialize_retry: Option<RetryPolicy>,
+    database: Option<i64>,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl Default for RedisStoreBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Builder for creating a `RedisStore`.
@@ -26,7 +51,7 @@ pub struct RedisStoreBuilder {
 ///
 /// This example demonstrates how to create a `RedisStore` using a connection string.
 ///
-/// ```rust
+/// ```rust,no_run
 /// # use keyv::adapter::redis::{RedisStoreBuilder};
 /// # use std::sync::Arc;
 /// # #[tokio::main]
@@ -40,11 +65,32 @@ pub struct RedisStoreBuilder {
 /// # }
 /// ```
 ///
+/// ## Passing Credentials Separately
+///
+/// Credentials set on the builder are never part of the connection string, so they
+/// can come from a secrets manager without ending up in logs.
+///
+/// ```rust,no_run
+/// # use keyv::adapter::redis::{RedisStoreBuilder};
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let password = String::from("secret");
+/// let store = RedisStoreBuilder::new()
+///     .uri("redis://localhost:6379")
+///     .database(2)
+///     .username("app")
+///     .password(password)
+///     .build()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+///
 /// ## Using an Existing Client
 ///
 /// This example shows how to initialize a `RedisStore` with an existing Redis client instance.
 ///
-/// ```rust
+/// ```rust,no_run
 /// # use keyv::adapter::redis::{RedisStoreBuilder};
 /// # use redis::Client;
 /// # use std::sync::Arc;
@@ -74,12 +120,20 @@ impl RedisStoreBuilder {
             client: None,
             default_ttl: None,
             namespace: None,
+            lazy_connect: false,
+            initialize_retry: None,
+            database: None,
+            username: None,
+            password: None,
         }
     }
 
     /// Sets the namespace for the keys in the `RedisStore`.
     ///
-    /// This method configures a namespace prefix that will be prepended to all key names.
+    /// This method configures a namespace prefix that will be prepended to all key names,
+    /// e.g. `users:alice`. Keys and queue names are prefixed with a `PrefixCodec` by the
+    /// `NamespacedRedisStore` that `build()` returns; the change feed is namespaced as
+    /// well, and `clear` only removes the keys of the namespace.
     ///
     /// # Arguments
     ///
@@ -103,8 +157,8 @@ impl RedisStoreBuilder {
 
     /// Uses an existing client for the `RedisStore`.
     ///
-    /// This method allows for using an already configured `Client`. If set,
-    /// the `connection_string` option is ignored.
+    /// This method allows for using an already configured `Client`. It cannot be
+    /// combined with the `uri` option.
     ///
     /// # Arguments
     ///
@@ -122,11 +176,142 @@ impl RedisStoreBuilder {
     /// # Arguments
     ///
     /// * `ttl` - The time to live in seconds.
-    pub fn default_ttl(mut self, ttl: u64) -> Self {
+    pub fn default_ttl(self, ttl: u64) -> Self {
+        self.default_ttl_duration(Duration::from_secs(ttl))
+    }
+
+    /// Sets the default TTL (time to live) for the keys as a `Duration`.
+    ///
+    /// Unlike `default_ttl`, sub-second TTLs are preserved; keys are written with
+    /// `PSETEX`, so Redis expires them with millisecond precision.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - The time to live.
+    pub fn default_ttl_duration(mut self, ttl: Duration) -> Self {
         self.default_ttl = Some(ttl);
         self
     }
 
+    /// Defers connecting to Redis until the first operation.
+    ///
+    /// By default `build()` opens the connection eagerly and fails if Redis is not
+    /// reachable. With lazy connection enabled the store can be built while Redis is
+    /// still starting up. In both cases a dropped connection is re-established
+    /// automatically.
+    ///
+    /// # Arguments
+    ///
+    /// * `lazy` - Whether to postpone the connection to the first operation.
+    pub fn lazy_connect(mut self, lazy: bool) -> Self {
+        self.lazy_connect = lazy;
+        self
+    }
+
+    /// Retries connecting and initializing the backend according to `policy`.
+    ///
+    /// Useful when the application may start before the database, e.g. under
+    /// docker-compose or Kubernetes. Without a policy a single attempt is made.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The retry attempts, backoff and deadline to apply.
+    pub fn initialize_retry(mut self, policy: RetryPolicy) -> Self {
+        self.initialize_retry = Some(policy);
+        self
+    }
+
+    /// Selects the logical database to use.
+    ///
+    /// Takes precedence over the database given in the connection string.
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - The database index, `0` by default.
+    pub fn database(mut self, database: i64) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    /// Sets the ACL username to authenticate as.
+    ///
+    /// Takes precedence over the username given in the connection string.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username.
+    pub fn username<S: Into<String>>(mut self, username: S) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    /// Sets the password to authenticate with.
+    ///
+    /// Takes precedence over the password given in the connection string.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password.
+    pub fn password<S: Into<String>>(mut self, password: S) -> Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Checks the configuration without connecting to the backend.
+    ///
+    /// `build()` runs the same checks, so calling this is only needed to report
+    /// misconfiguration early, e.g. at application startup.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the builder can be built, or `StoreError::InvalidConfiguration`
+    /// describing the offending option.
+    pub fn validate(&self) -> Result<(), StoreError> {
+        if self.client.is_none() && self.connection_string.is_none() {
+            return Err(StoreError::invalid_configuration(
+                "connection_string",
+                "A connection string or an existing client must be set",
+            ));
+        }
+        if let Some(connection_string) = &self.connection_string {
+            if self.client.is_some() {
+                return Err(StoreError::invalid_configuration(
+                    "client",
+                    "An existing client cannot be combined with a connection string; set only one of them",
+                ));
+            }
+            validate_uri_scheme(
+                connection_string,
+                &["redis", "rediss", "redis+unix", "unix"],
+            )?;
+        }
+        if self
+            .namespace
+            .as_ref()
+            .is_some_and(|namespace| namespace.is_empty())
+        {
+            return Err(StoreError::invalid_configuration(
+                "namespace",
+                "The namespace cannot be empty; leave it unset to store keys unprefixed",
+            ));
+        }
+        if self.client.is_some()
+            && (self.database.is_some() || self.username.is_some() || self.password.is_some())
+        {
+            return Err(StoreError::invalid_configuration(
+                "client",
+                "Connection options cannot be applied to an existing client; configure the client instead",
+            ));
+        }
+        if self.database.is_some_and(|database| database < 0) {
+            return Err(StoreError::invalid_configuration(
+                "database",
+                "The database index cannot be negative",
+            ));
+        }
+        Ok(())
+    }
+
     /// Builds the `RedisStore` based on the provided configurations.
     ///
     /// Finalizes the builder process and creates a `RedisStore` instance.
@@ -134,26 +319,51 @@ impl RedisStoreBuilder {
     ///
     /// # Returns
     ///
-    /// This method returns a `Result` which, on success, contains the initialized `RedisStore`.
+    /// This method returns a `Result` which, on success, contains the initialized `RedisStore`
+    /// wrapped in a `NamespacedRedisStore` applying the namespace, if any.
     /// On failure, it returns a `StoreError` indicating what went wrong during the initialization.
-    pub async fn build(self) -> Result<RedisStore, StoreError> {
+    pub async fn build(self) -> Result<NamespacedRedisStore, StoreError> {
+        self.validate()?;
         let client = match self.client {
             Some(client) => client,
             None => {
-                let connection_string = self
-                    .connection_string
-                    .expect("A connection string or an existing client must be set");
-                Arc::new(
-                    Client::open(connection_string)
-                        .map_err(|e| StoreError::ConnectionError(e.to_string()))?,
-                )
+                let connection_string = self.connection_string.ok_or_else(|| {
+                    StoreError::invalid_configuration(
+                        "connection_string",
+                        "A connection string or an existing client must be set",
+                    )
+                })?;
+                let mut info = connection_string
+                    .as_str()
+                    .into_connection_info()
+                    .map_err(StoreError::connection)?;
+                if let Some(database) = self.database {
+                    info.redis.db = database;
+                }
+                if let Some(username) = self.username {
+                    info.redis.username = Some(username);
+                }
+                if let Some(password) = self.password {
+                    info.redis.password = Some(password);
+                }
+                Arc::new(Client::open(info).map_err(StoreError::connection)?)
             }
         };
 
-        Ok(RedisStore {
+        let namespace = self.namespace.map(PrefixCodec::new);
+        let store = RedisStore {
             client,
+            connection: OnceCell::new(),
             default_ttl: self.default_ttl,
-            namespace: self.namespace,
-        })
+            namespace: namespace.clone(),
+            initialize_retry: self.initialize_retry,
+        };
+
+        if !self.lazy_connect {
+            let retry = store.initialize_retry.clone().unwrap_or_default();
+            retry.run(|| store.connection()).await?;
+        }
+
+        Ok(KeyCodecStore::new(store, namespace))
     }
 }
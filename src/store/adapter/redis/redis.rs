@@ -1,40 +1,378 @@
-use std::sync::Arc;
+use std::{
+    fmt,
+    future::Future,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use async_trait::async_trait;
-use redis::{Client, Commands};
+use futures::stream::{self, BoxStream};
+use redis::{aio::ConnectionManager, AsyncCommands, Client, RedisResult};
 use serde_json::Value;
+use tokio::sync::OnceCell;
 
-use crate::{Store, StoreError};
+use crate::{
+    layer::key_codec::{KeyCodec, PrefixCodec},
+    redact_credentials,
+    store::{
+        expiry::{millis_since_epoch, now_millis, system_time_from_millis, ttl_millis},
+        glob, set_many_in_batch,
+    },
+    BatchOp, Capabilities, Change, ChangeFeedBackend, ChangeKind, Metadata, QueueBackend,
+    QueueMessage, RetryPolicy, ScanEntry, Store, StoreError, Usage,
+};
+
+/// Prefix of the keys the store manages itself, the queues and the change feed.
+///
+/// Store keys never contain NUL (see `validate_key`) and, with a namespace, all start
+/// with it, so internal keys neither collide with them nor fall under `clear`.
+const INTERNAL_PREFIX: &str = "\0keyv:";
+
+/// Returns whether `key` is one of the keys the store manages itself.
+fn is_internal(key: &str) -> bool {
+    key.starts_with(INTERNAL_PREFIX)
+}
+
+/// Number of keys whose `MEMORY USAGE` is sampled to estimate the keyspace size.
+const USAGE_SAMPLE_SIZE: usize = 64;
+
+/// Claims the next message of a queue.
+///
+/// Messages whose visibility timeout has passed go back to the front of the pending
+/// list first. Ids left in the list after their message was acknowledged are skipped.
+const QUEUE_POP_SCRIPT: &str = r"
+local expired = redis.call('ZRANGEBYSCORE', KEYS[2], '-inf', ARGV[1])
+for i = #expired, 1, -1 do
+    redis.call('ZREM', KEYS[2], expired[i])
+    redis.call('LPUSH', KEYS[1], expired[i])
+end
+while true do
+    local id = redis.call('LPOP', KEYS[1])
+    if not id then
+        return false
+    end
+    local payload = redis.call('HGET', KEYS[3], id)
+    if payload then
+        redis.call('ZADD', KEYS[2], ARGV[2], id)
+        return {id, payload}
+    end
+end
+";
+
+/// Deletes a key if it holds the given value, returning the number of keys deleted.
+const REMOVE_IF_SCRIPT: &str = r"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+";
+
+/// Moves a key with `RENAME`, returning 0 instead of an error if it does not exist.
+const RENAME_SCRIPT: &str = r"
+if redis.call('EXISTS', KEYS[1]) == 0 then
+    return 0
+end
+redis.call('RENAME', KEYS[1], KEYS[2])
+return 1
+";
+
+/// Escapes the glob characters of `s` so it matches literally in a `SCAN` pattern.
+pub(crate) fn escape_glob(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Returns the smallest stream id after `cursor`, so that `XRANGE` resumes past it
+/// without the exclusive ranges of Redis 6.2.
+fn next_stream_id(cursor: &str) -> Result<String, StoreError> {
+    let invalid = || StoreError::QueryError(format!("Invalid change cursor `{}`", cursor));
+    let (millis, sequence) = cursor.split_once('-').ok_or_else(invalid)?;
+    let millis: u64 = millis.parse().map_err(|_| invalid())?;
+    let sequence: u64 = sequence.parse().map_err(|_| invalid())?;
+    Ok(match sequence.checked_add(1) {
+        Some(sequence) => format!("{}-{}", millis, sequence),
+        None => format!("{}-0", millis.checked_add(1).ok_or_else(invalid)?),
+    })
+}
+
+/// Builds a change from an entry of the change stream, whose fields alternate names
+/// and values.
+fn change_from_stream_entry(id: String, fields: Vec<String>) -> Result<Change, StoreError> {
+    let (mut key, mut kind, mut recorded_at) = (None, None, None);
+    for field in fields.chunks_exact(2) {
+        match field[0].as_str() {
+            "key" => key = Some(field[1].clone()),
+            "kind" => kind = ChangeKind::parse(&field[1]),
+            "recorded_at" => recorded_at = field[1].parse::<i64>().ok(),
+            _ => {}
+        }
+    }
+    let malformed = || StoreError::QueryError(format!("Malformed change `{}`", id));
+    Ok(Change {
+        kind: kind.ok_or_else(malformed)?,
+        recorded_at: system_time_from_millis(recorded_at.ok_or_else(malformed)?),
+        cursor: id,
+        key,
+    })
+}
 
 pub struct RedisStore {
     pub(crate) client: Arc<Client>,
-    pub(crate) default_ttl: Option<u64>,
-    pub(crate) namespace: Option<String>,
+    pub(crate) connection: OnceCell<ConnectionManager>,
+    pub(crate) default_ttl: Option<Duration>,
+    /// Prefixes the change feed key, after `INTERNAL_PREFIX`, and bounds what `clear`
+    /// removes. Entry keys and queue names arrive prefixed by the `KeyCodecStore` that
+    /// `RedisStoreBuilder` wraps the store in.
+    pub(crate) namespace: Option<PrefixCodec>,
+    pub(crate) initialize_retry: Option<RetryPolicy>,
 }
+
+/// Prints the server address and options, never the credentials.
+impl fmt::Debug for RedisStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let info = self.client.get_connection_info();
+        f.debug_struct("RedisStore")
+            .field("addr", &info.addr.to_string())
+            .field("database", &info.redis.db)
+            .field("namespace", &self.namespace)
+            .field("default_ttl", &self.default_ttl)
+            .finish_non_exhaustive()
+    }
+}
+
 impl RedisStore {
-    fn get_key(&self, key: &str) -> String {
-        if let Some(ref ns) = self.namespace {
-            format!("{}:{}", ns, key)
-        } else {
-            key.to_string()
+    /// Returns the Redis client backing the store.
+    ///
+    /// Gives access to commands that the `Store` trait does not cover, such as pub/sub,
+    /// without configuring a second client.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Returns the key holding the `part` of the queue named `queue`: its id sequence,
+    /// its `messages` hash, its `pending` list or its `claimed` sorted set. Queue names
+    /// arrive namespaced by `KeyCodecStore`, like entry keys.
+    fn queue_key(&self, queue: &str, part: &str) -> String {
+        format!("{}queue:{}:{}", INTERNAL_PREFIX, queue, part)
+    }
+
+    /// Returns the key of the stream holding the change feed, outside the keyspace of
+    /// the entries.
+    fn changes_key(&self) -> String {
+        format!("{}{}", INTERNAL_PREFIX, self.namespace.encode("changes"))
+    }
+
+    /// Returns the `SCAN` pattern matching every key of the namespace, or `None` when
+    /// the store owns the whole database.
+    fn key_pattern(&self) -> Option<String> {
+        self.namespace.as_ref().map(|namespace| {
+            let mut pattern = escape_glob(&namespace.encode(""));
+            pattern.push('*');
+            pattern
+        })
+    }
+
+    /// Counts the keys the store manages itself, across all namespaces.
+    async fn count_internal_keys(&self, conn: &mut ConnectionManager) -> Result<u64, StoreError> {
+        let mut pattern = escape_glob(INTERNAL_PREFIX);
+        pattern.push('*');
+        let mut count: u64 = 0;
+        let mut cursor: u64 = 0;
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(1000)
+                .query_async(conn)
+                .await
+                .map_err(|e| StoreError::QueryError(e.to_string()))?;
+            count += keys.len() as u64;
+            cursor = next;
+            if cursor == 0 {
+                return Ok(count);
+            }
+        }
+    }
+
+    /// Returns the `SCAN` pattern matching the keys of the store starting with `prefix`.
+    fn prefix_pattern(&self, prefix: &str) -> String {
+        let mut pattern = escape_glob(prefix);
+        pattern.push('*');
+        pattern
+    }
+
+    /// Reads the values and remaining TTLs of `keys`, skipping keys removed since they
+    /// were scanned.
+    async fn read_batch(&self, keys: Vec<String>) -> Result<Vec<ScanEntry>, StoreError> {
+        let mut conn = self.connection().await?;
+        let query_error = |e: redis::RedisError| StoreError::QueryError(e.to_string());
+
+        let values: Vec<Option<String>> = conn.mget(&keys).await.map_err(query_error)?;
+        let mut pipeline = redis::pipe();
+        for key in &keys {
+            pipeline.pttl(key);
         }
+        let ttls: Vec<i64> = pipeline.query_async(&mut conn).await.map_err(query_error)?;
+
+        let now = SystemTime::now();
+        Ok(keys
+            .into_iter()
+            .zip(values)
+            .zip(ttls)
+            .filter_map(|((key, value), ttl)| {
+                let value = serde_json::from_str(&value?).ok()?;
+                // PTTL returns -1 for keys without an expiration.
+                let expires_at = u64::try_from(ttl)
+                    .ok()
+                    .map(|ttl| now + Duration::from_millis(ttl));
+                Some(ScanEntry {
+                    key,
+                    value,
+                    expires_at,
+                })
+            })
+            .collect())
+    }
+
+    /// Returns the shared connection, establishing it on first use.
+    ///
+    /// The connection manager transparently reconnects after the server drops the
+    /// connection, so a Redis restart does not require recreating the store. Commands
+    /// sent on it share the store's multiplexed connection; keys are not namespaced.
+    pub async fn connection(&self) -> Result<ConnectionManager, StoreError> {
+        self.connection
+            .get_or_try_init(|| async {
+                ConnectionManager::new((*self.client).clone())
+                    .await
+                    .map_err(StoreError::connection)
+            })
+            .await
+            .cloned()
+    }
+
+    /// Runs a read-only `command` on the shared connection, retrying once if the
+    /// connection was dropped while the command was in flight.
+    async fn read<T, F, Fut>(&self, command: F) -> Result<T, StoreError>
+    where
+        F: Fn(ConnectionManager) -> Fut,
+        Fut: Future<Output = RedisResult<T>>,
+    {
+        let conn = self.connection().await?;
+        match command(conn.clone()).await {
+            Err(e) if e.is_connection_dropped() || e.is_io_error() => {
+                log::warn!(
+                    "Redis connection lost, retrying after reconnect: {}",
+                    redact_credentials(&e.to_string())
+                );
+                command(conn).await.map_err(StoreError::connection)
+            }
+            result => result.map_err(|e| StoreError::QueryError(e.to_string())),
+        }
+    }
+
+    /// Runs `command` on the shared connection once.
+    ///
+    /// Writes are never retried: when the connection drops mid-command the server may
+    /// already have applied it, and running `INCRBY` or `XADD` again would apply it
+    /// twice. The failure is returned as a connection error instead.
+    async fn execute<T, F, Fut>(&self, command: F) -> Result<T, StoreError>
+    where
+        F: FnOnce(ConnectionManager) -> Fut,
+        Fut: Future<Output = RedisResult<T>>,
+    {
+        let conn = self.connection().await?;
+        command(conn).await.map_err(|e| {
+            if e.is_connection_dropped() || e.is_io_error() {
+                StoreError::connection(e)
+            } else {
+                StoreError::QueryError(e.to_string())
+            }
+        })
+    }
+
+    /// Streams the entries whose key matches the `SCAN` pattern `pattern` and, if set,
+    /// the glob pattern `glob`.
+    fn scan_matching<'a>(
+        &'a self,
+        pattern: String,
+        glob: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        let batch_size = batch_size.max(1);
+        Box::pin(stream::try_unfold(Some(0u64), move |cursor| {
+            let pattern = pattern.clone();
+            async move {
+                let Some(mut cursor) = cursor else {
+                    return Ok(None);
+                };
+                let mut conn = self.connection().await?;
+                loop {
+                    let (next, mut keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                        .arg(cursor)
+                        .arg("MATCH")
+                        .arg(&pattern)
+                        .arg("COUNT")
+                        .arg(batch_size)
+                        .query_async(&mut conn)
+                        .await
+                        .map_err(|e| StoreError::QueryError(e.to_string()))?;
+                    let next = (next != 0).then_some(next);
+                    // Without a namespace the pattern also matches the internal keys.
+                    keys.retain(|key| !is_internal(key));
+                    if let Some(glob) = glob {
+                        keys.retain(|key| glob::matches(glob, key));
+                    }
+
+                    if !keys.is_empty() {
+                        return Ok(Some((self.read_batch(keys).await?, next)));
+                    }
+                    match next {
+                        Some(next) => cursor = next,
+                        None => return Ok(None),
+                    }
+                }
+            }
+        }))
     }
 }
 
 #[async_trait]
 impl Store for RedisStore {
     async fn initialize(&self) -> Result<(), StoreError> {
-        Ok(()) // Redis doesn't require initialization like a DB schema.
+        // Redis doesn't require initialization like a DB schema, but when a retry
+        // policy is configured we wait for the server to accept connections.
+        if let Some(retry) = &self.initialize_retry {
+            retry.run(|| self.connection()).await?;
+        }
+        Ok(())
+    }
+
+    /// Batches are pipelined but not wrapped in `MULTI`, so they are not transactional.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            native_ttl: true,
+            atomic_increment: true,
+            transactions: false,
+            scan: true,
+            watch: true,
+        }
     }
 
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
-        let mut conn = self
-            .client
-            .get_connection()
-            .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
-        let value: Option<String> = conn
-            .get(self.get_key(key))
-            .map_err(|e| StoreError::QueryError(e.to_string()))?;
+        let value: Option<String> = self
+            .read(|mut conn| {
+                let key = key.to_string();
+                async move { conn.get(key).await }
+            })
+            .await?;
         match value {
             Some(val) => Ok(serde_json::from_str(&val)
                 .map(Some)
@@ -43,51 +381,576 @@ impl Store for RedisStore {
         }
     }
 
-    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError> {
+    /// Redis tracks no creation or update times, only the expiration.
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        let (value, ttl): (Option<String>, i64) = self
+            .read(|mut conn| {
+                let pipeline = redis::pipe().atomic().get(key).pttl(key).clone();
+                async move { pipeline.query_async(&mut conn).await }
+            })
+            .await?;
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        let value = serde_json::from_str(&value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+        // PTTL returns -1 for keys without an expiration.
+        let metadata = Metadata {
+            expires_at: u64::try_from(ttl)
+                .ok()
+                .map(|ttl| SystemTime::now() + Duration::from_millis(ttl)),
+            ..Metadata::default()
+        };
+        Ok(Some((value, metadata)))
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let values: Vec<Option<String>> = self
+            .read(|mut conn| {
+                let command = redis::cmd("MGET").arg(keys).clone();
+                async move { command.query_async(&mut conn).await }
+            })
+            .await?;
+        values
+            .into_iter()
+            .map(|value| {
+                value
+                    .map(|val| serde_json::from_str(&val))
+                    .transpose()
+                    .map_err(|e| StoreError::SerializationError { source: e })
+            })
+            .collect()
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        // GETEX requires Redis 6.2 or later.
+        let value: Option<String> = self
+            .execute(|mut conn| {
+                let command = redis::cmd("GETEX")
+                    .arg(key)
+                    .arg("PX")
+                    .arg(ttl_millis(ttl))
+                    .clone();
+                async move { command.query_async(&mut conn).await }
+            })
+            .await?;
+        match value {
+            Some(val) => Ok(serde_json::from_str(&val)
+                .map(Some)
+                .map_err(|e| StoreError::SerializationError { source: e })?),
+            None => Ok(None),
+        }
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        if keys.is_empty() {
+            return Ok(0);
+        }
+
+        // One PEXPIRE per key in a single pipeline; each replies 1 if the key existed.
+        let touched: Vec<u64> = self
+            .execute(|mut conn| {
+                let mut pipeline = redis::pipe();
+                for key in keys {
+                    pipeline.cmd("PEXPIRE").arg(key).arg(ttl_millis(ttl));
+                }
+                async move { pipeline.query_async(&mut conn).await }
+            })
+            .await?;
+        Ok(touched.into_iter().sum())
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
         let ttl = ttl.or(self.default_ttl);
-        let namespaced_key = self.get_key(key);
-        let mut conn = self
-            .client
-            .get_connection()
-            .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
         let value_str = serde_json::to_string(&value)
             .map_err(|e| StoreError::SerializationError { source: e })?;
 
-        if let Some(expire) = ttl {
-            conn.set_ex(&namespaced_key, value_str, expire)
-                .map_err(|e| StoreError::QueryError(e.to_string()))?;
-        } else {
-            conn.set(&namespaced_key, value_str)
-                .map_err(|e| StoreError::QueryError(e.to_string()))?;
+        self.execute(|mut conn| {
+            let key = key.to_string();
+            let value_str = value_str.clone();
+            async move {
+                match ttl {
+                    Some(expire) => {
+                        conn.pset_ex::<_, _, ()>(key, value_str, ttl_millis(expire))
+                            .await
+                    }
+                    None => conn.set::<_, _, ()>(key, value_str).await,
+                }
+            }
+        })
+        .await
+    }
+
+    /// Entries are written in one pipeline.
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        set_many_in_batch(self, entries, ttl).await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        let value_str = serde_json::to_string(&value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+        let expires_at = millis_since_epoch(expires_at);
+
+        // SET followed by PEXPIREAT in a MULTI block, so the key never exists without
+        // its expiration. A timestamp in the past deletes the key right away.
+        self.execute(|mut conn| {
+            let pipeline = redis::pipe()
+                .atomic()
+                .set(key, &value_str)
+                .ignore()
+                .pexpire_at(key, expires_at)
+                .ignore()
+                .clone();
+            async move { pipeline.query_async::<_, ()>(&mut conn).await }
+        })
+        .await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        let value_str = serde_json::to_string(&value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+
+        // KEEPTTL requires Redis 6.0 or later.
+        self.execute(|mut conn| {
+            let command = redis::cmd("SET")
+                .arg(key)
+                .arg(&value_str)
+                .arg("KEEPTTL")
+                .clone();
+            async move { command.query_async::<_, ()>(&mut conn).await }
+        })
+        .await
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        let ttl = ttl.or(self.default_ttl);
+        let value_str = serde_json::to_string(&value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+
+        // SET NX replies OK when it wrote the value and nil when the key exists.
+        let written: Option<String> = self
+            .execute(|mut conn| {
+                let mut command = redis::cmd("SET");
+                command.arg(key).arg(&value_str).arg("NX");
+                if let Some(ttl) = ttl {
+                    command.arg("PX").arg(ttl_millis(ttl));
+                }
+                async move { command.query_async(&mut conn).await }
+            })
+            .await?;
+        Ok(written.is_some())
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        let ttl = ttl.or(self.default_ttl);
+
+        // SET NX creates a missing counter with its expiration, then INCRBY adds to it,
+        // in a MULTI block so the key never exists without its expiration.
+        self.execute(|mut conn| {
+            let mut pipeline = redis::pipe();
+            pipeline.atomic();
+            if let Some(ttl) = ttl {
+                pipeline
+                    .cmd("SET")
+                    .arg(key)
+                    .arg(0)
+                    .arg("PX")
+                    .arg(ttl_millis(ttl))
+                    .arg("NX")
+                    .ignore();
+            }
+            pipeline.incr(key, delta);
+            async move {
+                let (value,): (i64,) = pipeline.query_async(&mut conn).await?;
+                Ok(value)
+            }
+        })
+        .await
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        let mut pipeline = redis::pipe();
+        for op in &ops {
+            match op {
+                BatchOp::Set { key, value, ttl } => {
+                    let value_str = serde_json::to_string(value)
+                        .map_err(|e| StoreError::SerializationError { source: e })?;
+                    match ttl.or(self.default_ttl) {
+                        Some(ttl) => pipeline.pset_ex(key, value_str, ttl_millis(ttl)),
+                        None => pipeline.set(key, value_str),
+                    };
+                }
+                BatchOp::Remove { key } => {
+                    pipeline.unlink(key);
+                }
+                BatchOp::Touch { key, ttl } => {
+                    pipeline.pexpire(key, ttl_millis(*ttl) as i64);
+                }
+            }
         }
-        Ok(())
+
+        let replies: Vec<redis::Value> = self
+            .execute(|mut conn| {
+                let pipeline = pipeline.clone();
+                async move { pipeline.query_async(&mut conn).await }
+            })
+            .await?;
+        Ok(ops
+            .iter()
+            .zip(replies)
+            .map(|(op, reply)| match (op, reply) {
+                // PEXPIRE replies 0 when the key does not exist.
+                (BatchOp::Touch { .. }, redis::Value::Int(0)) => Err(StoreError::NotFound),
+                _ => Ok(()),
+            })
+            .collect())
     }
 
     async fn remove(&self, key: &str) -> Result<(), StoreError> {
-        let mut conn = self
-            .client
-            .get_connection()
-            .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
-        conn.del(self.get_key(key))
-            .map_err(|e| StoreError::QueryError(e.to_string()))?;
-        Ok(())
+        self.execute(|mut conn| {
+            let key = key.to_string();
+            async move { conn.unlink::<_, ()>(key).await }
+        })
+        .await
     }
 
     async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
-        let mut conn = self
-            .client
-            .get_connection()
-            .map_err(|e| StoreError::ConnectionError(e.to_string()))?;
+        if keys.is_empty() {
+            return Ok(());
+        }
 
-        let namespaced_keys: Vec<String> = keys.iter().map(|key| self.get_key(key)).collect();
+        self.execute(|mut conn| {
+            let keys = keys.to_vec();
+            async move { conn.unlink::<_, ()>(keys).await }
+        })
+        .await
+    }
 
-        conn.del(namespaced_keys)
-            .map_err(|e| StoreError::QueryError(e.to_string()))?;
-        Ok(())
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        let expected = serde_json::to_string(expected)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+
+        self.execute(|mut conn| {
+            let script = redis::Script::new(REMOVE_IF_SCRIPT);
+            let (key, expected) = (key.to_string(), expected.clone());
+            async move { script.key(key).arg(expected).invoke_async(&mut conn).await }
+        })
+        .await
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        // RENAME keeps the expiration of the key.
+        let renamed: bool = self
+            .execute(|mut conn| {
+                let script = redis::Script::new(RENAME_SCRIPT);
+                let (old_key, new_key) = (old_key.to_string(), new_key.to_string());
+                async move {
+                    script
+                        .key(old_key)
+                        .key(new_key)
+                        .invoke_async(&mut conn)
+                        .await
+                }
+            })
+            .await?;
+        if renamed {
+            Ok(())
+        } else {
+            Err(StoreError::NotFound)
+        }
     }
 
     async fn clear(&self) -> Result<(), StoreError> {
-        log::warn!("Clearing the Redis store is not supported.");
-        Ok(())
+        // Without a namespace the store shares the database with other data, which
+        // must not be flushed.
+        let Some(pattern) = self.key_pattern() else {
+            log::warn!("Clearing a Redis store without a namespace is not supported.");
+            return Ok(());
+        };
+
+        // Queues and the change feed live outside the namespace pattern, so pending
+        // messages survive and feed consumers see the clear.
+        let mut conn = self.connection().await?;
+        let mut cursor: u64 = 0;
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(1000)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| StoreError::QueryError(e.to_string()))?;
+
+            if !keys.is_empty() {
+                conn.unlink::<_, ()>(keys)
+                    .await
+                    .map_err(|e| StoreError::QueryError(e.to_string()))?;
+            }
+
+            cursor = next;
+            if cursor == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        let mut conn = self.connection().await?;
+        let query_error = |e: redis::RedisError| StoreError::QueryError(e.to_string());
+
+        // Without a namespace every key but the internal ones belongs to the store, so
+        // DBSIZE minus the internal keys is exact; otherwise the namespace has to be
+        // scanned.
+        let pattern = self.key_pattern();
+        let mut entries: u64 = match pattern {
+            Some(_) => 0,
+            None => {
+                let keys: u64 = redis::cmd("DBSIZE")
+                    .query_async(&mut conn)
+                    .await
+                    .map_err(query_error)?;
+                keys.saturating_sub(self.count_internal_keys(&mut conn).await?)
+            }
+        };
+
+        let mut sample = Vec::with_capacity(USAGE_SAMPLE_SIZE);
+        let mut cursor: u64 = 0;
+        loop {
+            let mut scan = redis::cmd("SCAN");
+            scan.arg(cursor).arg("COUNT").arg(1000);
+            if let Some(pattern) = &pattern {
+                scan.arg("MATCH").arg(pattern);
+            }
+            let (next, mut keys): (u64, Vec<String>) =
+                scan.query_async(&mut conn).await.map_err(query_error)?;
+
+            keys.retain(|key| !is_internal(key));
+            if pattern.is_some() {
+                entries += keys.len() as u64;
+            }
+            let missing = USAGE_SAMPLE_SIZE - sample.len();
+            sample.extend(keys.into_iter().take(missing));
+
+            cursor = next;
+            if cursor == 0 || (pattern.is_none() && sample.len() == USAGE_SAMPLE_SIZE) {
+                break;
+            }
+        }
+
+        let mut sampled_bytes: u64 = 0;
+        let mut sampled_keys: u64 = 0;
+        for key in &sample {
+            let bytes: Option<u64> = redis::cmd("MEMORY")
+                .arg("USAGE")
+                .arg(key)
+                .query_async(&mut conn)
+                .await
+                .map_err(query_error)?;
+            if let Some(bytes) = bytes {
+                sampled_bytes += bytes;
+                sampled_keys += 1;
+            }
+        }
+
+        let bytes = match sampled_keys {
+            0 => 0,
+            n => sampled_bytes * entries / n,
+        };
+        Ok(Usage {
+            entries,
+            bytes: Some(bytes),
+        })
+    }
+
+    /// Keys are enumerated with `SCAN`, using `batch_size` as its `COUNT` hint, so
+    /// batch sizes vary.
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.scan_matching(
+            self.prefix_pattern(prefix.unwrap_or_default()),
+            None,
+            batch_size,
+        )
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.scan_matching(glob::to_redis_superset(pattern), Some(pattern), batch_size)
+    }
+}
+
+#[async_trait]
+impl QueueBackend for RedisStore {
+    async fn queue_push(&self, queue: &str, payload: Value) -> Result<String, StoreError> {
+        let payload = serde_json::to_string(&payload)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+        let sequence_key = self.queue_key(queue, "seq");
+        let messages_key = self.queue_key(queue, "messages");
+        let pending_key = self.queue_key(queue, "pending");
+
+        let id: u64 = self
+            .execute(|mut conn| {
+                let sequence_key = sequence_key.clone();
+                async move { conn.incr(sequence_key, 1).await }
+            })
+            .await?;
+        let id = id.to_string();
+        self.execute(|mut conn| {
+            let mut pipeline = redis::pipe();
+            pipeline
+                .atomic()
+                .hset(&messages_key, &id, &payload)
+                .ignore()
+                .rpush(&pending_key, &id)
+                .ignore();
+            async move { pipeline.query_async::<_, ()>(&mut conn).await }
+        })
+        .await?;
+        Ok(id)
+    }
+
+    async fn queue_pop(
+        &self,
+        queue: &str,
+        visibility_timeout: Duration,
+    ) -> Result<Option<QueueMessage>, StoreError> {
+        let pending_key = self.queue_key(queue, "pending");
+        let claimed_key = self.queue_key(queue, "claimed");
+        let messages_key = self.queue_key(queue, "messages");
+        let now = now_millis();
+        let claimed_until = now.saturating_add(ttl_millis(visibility_timeout) as i64);
+
+        let claimed: Option<(String, String)> = self
+            .execute(|mut conn| {
+                let script = redis::Script::new(QUEUE_POP_SCRIPT);
+                let (pending_key, claimed_key, messages_key) = (
+                    pending_key.clone(),
+                    claimed_key.clone(),
+                    messages_key.clone(),
+                );
+                async move {
+                    script
+                        .key(pending_key)
+                        .key(claimed_key)
+                        .key(messages_key)
+                        .arg(now)
+                        .arg(claimed_until)
+                        .invoke_async(&mut conn)
+                        .await
+                }
+            })
+            .await?;
+        claimed
+            .map(|(id, payload)| {
+                let payload = serde_json::from_str(&payload)
+                    .map_err(|e| StoreError::SerializationError { source: e })?;
+                Ok(QueueMessage { id, payload })
+            })
+            .transpose()
+    }
+
+    async fn queue_ack(&self, queue: &str, id: &str) -> Result<(), StoreError> {
+        let claimed_key = self.queue_key(queue, "claimed");
+        let messages_key = self.queue_key(queue, "messages");
+        self.execute(|mut conn| {
+            let mut pipeline = redis::pipe();
+            pipeline
+                .atomic()
+                .zrem(&claimed_key, id)
+                .ignore()
+                .hdel(&messages_key, id)
+                .ignore();
+            async move { pipeline.query_async::<_, ()>(&mut conn).await }
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl ChangeFeedBackend for RedisStore {
+    async fn append_change(
+        &self,
+        key: Option<&str>,
+        kind: ChangeKind,
+    ) -> Result<String, StoreError> {
+        let changes_key = self.changes_key();
+        let recorded_at = now_millis();
+        self.execute(|mut conn| {
+            let mut command = redis::cmd("XADD");
+            command
+                .arg(&changes_key)
+                .arg("*")
+                .arg("kind")
+                .arg(kind.as_str())
+                .arg("recorded_at")
+                .arg(recorded_at);
+            if let Some(key) = key {
+                command.arg("key").arg(key);
+            }
+            async move { command.query_async::<_, String>(&mut conn).await }
+        })
+        .await
+    }
+
+    async fn read_changes(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Change>, StoreError> {
+        let changes_key = self.changes_key();
+        let start = match after {
+            Some(cursor) => next_stream_id(cursor)?,
+            None => "-".to_string(),
+        };
+        let entries: Vec<(String, Vec<String>)> = self
+            .read(|mut conn| {
+                let mut command = redis::cmd("XRANGE");
+                command
+                    .arg(&changes_key)
+                    .arg(&start)
+                    .arg("+")
+                    .arg("COUNT")
+                    .arg(limit);
+                async move { command.query_async(&mut conn).await }
+            })
+            .await?;
+
+        entries
+            .into_iter()
+            .map(|(id, fields)| change_from_stream_entry(id, fields))
+            .collect()
     }
 }
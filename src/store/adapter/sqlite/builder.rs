@@ -1,10 +1,36 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc, time::Duration};
 
+use sqlx::sqlite::SqliteConnectOptions;
 pub use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+use tokio::sync::Mutex;
 
-use crate::{StoreError, DEFAUTL_NAMESPACE_NAME};
+use crate::{
+    adapter::{validate_identifier, validate_uri_scheme, Columns},
+    QuarantineListener, QuarantinedEntry, RetryPolicy, SerializationFailurePolicy, StoreError,
+    DEFAUTL_NAMESPACE_NAME,
+};
 
-use super::SqliteStore;
+use super::{queries::Queries, SqliteStore};
+
+/// How long a connection waits for a lock held by another connection before failing
+/// with `SQLITE_BUSY`.
+pub const DEFAULT_SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How the connections of a pool share an in-memory database.
+///
+/// An in-memory SQLite database lives only as long as a connection to it is open, so
+/// a pool that closes idle connections loses every entry. In-memory URIs such as
+/// `sqlite::memory:` are therefore served by connections that are never recycled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SqliteMemoryMode {
+    /// A single connection serves every query. Writes never contend, at the cost of
+    /// serializing reads too.
+    #[default]
+    SingleConnection,
+    /// The pool opens several connections to one shared-cache database, keeping at
+    /// least one of them open. Concurrent writers may get `SQLITE_LOCKED` errors.
+    SharedCache,
+}
 
 /// Builder for creating a `SqliteStore`.
 ///
@@ -51,6 +77,21 @@ pub struct SqliteStoreBuilder {
     uri: Option<String>,
     pool: Option<Arc<SqlitePool>>,
     table_name: Option<String>,
+    lazy_connect: bool,
+    memory_mode: SqliteMemoryMode,
+    busy_timeout: Duration,
+    serialize_writes: bool,
+    columns: Columns,
+    create_tables: bool,
+    initialize_retry: Option<RetryPolicy>,
+    serialization_failure: SerializationFailurePolicy,
+    quarantine_listener: Option<QuarantineListener>,
+}
+
+impl Default for SqliteStoreBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SqliteStoreBuilder {
@@ -59,6 +100,15 @@ impl SqliteStoreBuilder {
             uri: None,
             pool: None,
             table_name: None,
+            lazy_connect: false,
+            memory_mode: SqliteMemoryMode::default(),
+            busy_timeout: DEFAULT_SQLITE_BUSY_TIMEOUT,
+            serialize_writes: true,
+            columns: Columns::default(),
+            create_tables: true,
+            initialize_retry: None,
+            serialization_failure: SerializationFailurePolicy::default(),
+            quarantine_listener: None,
         }
     }
 
@@ -71,6 +121,77 @@ impl SqliteStoreBuilder {
         self
     }
 
+    /// Sets the column holding the keys. Defaults to `key`.
+    ///
+    /// Together with `value_column`, `ttl_column` and `create_tables`, lets the store
+    /// sit on top of an existing cache table.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use keyv::adapter::sqlite::SqliteStoreBuilder;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let store = SqliteStoreBuilder::new()
+    ///     .uri("sqlite://legacy.db")
+    ///     .table_name("http_cache")
+    ///     .key_column("cache_key")
+    ///     .value_column("payload")
+    ///     .ttl_column("expires")
+    ///     .create_tables(false)
+    ///     .build()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn key_column<S: Into<String>>(mut self, column: S) -> Self {
+        self.columns.key = column.into();
+        self
+    }
+
+    /// Sets the column holding the JSON-serialized values. Defaults to `value`.
+    pub fn value_column<S: Into<String>>(mut self, column: S) -> Self {
+        self.columns.value = column.into();
+        self
+    }
+
+    /// Sets the column holding the expiration time, in milliseconds since the Unix
+    /// epoch, or `NULL` for entries that never expire. Defaults to `expires_at`.
+    pub fn ttl_column<S: Into<String>>(mut self, column: S) -> Self {
+        self.columns.expires_at = column.into();
+        self
+    }
+
+    /// Sets the integer column holding when each key was first written, or `None` if
+    /// the table has no such column. Defaults to `created_at`.
+    ///
+    /// Without the column, `get_with_metadata` returns no creation time.
+    pub fn created_at_column(mut self, column: Option<&str>) -> Self {
+        self.columns.created_at = column.map(str::to_string);
+        self
+    }
+
+    /// Sets the integer column holding when each value was last written, or `None`
+    /// if the table has no such column. Defaults to `updated_at`.
+    ///
+    /// Without the column, `get_with_metadata` returns no update time.
+    pub fn updated_at_column(mut self, column: Option<&str>) -> Self {
+        self.columns.updated_at = column.map(str::to_string);
+        self
+    }
+
+    /// Sets whether `initialize()` creates the tables and adds missing columns.
+    ///
+    /// Defaults to `true`, which requires the `CREATE` privilege. With `false`,
+    /// `initialize()` runs no DDL and only checks that the table exists with the key,
+    /// value and expiration columns, plus the nullable integer timestamp columns set
+    /// with `created_at_column` and `updated_at_column`. The `<table>_queue` and
+    /// `<table>_quarantine` tables must exist too if the queue or quarantine is used.
+    pub fn create_tables(mut self, create: bool) -> Self {
+        self.create_tables = create;
+        self
+    }
+
     /// Sets the database URI for connecting to the SQLite database.
     ///
     /// This method configures the database URI. It's required if no existing connection pool is provided.
@@ -81,13 +202,147 @@ impl SqliteStoreBuilder {
 
     /// Uses an existing connection pool for the `SqliteStore`.
     ///
-    /// This method allows for using an already configured `SqlitePool`. If set,
-    /// the `uri` option is ignored.
+    /// This method allows for using an already configured `SqlitePool`. It cannot be
+    /// combined with the `uri` option.
     pub fn pool(mut self, pool: Arc<SqlitePool>) -> Self {
         self.pool = Some(pool);
         self
     }
 
+    /// Defers opening database connections until the first query.
+    ///
+    /// By default `build()` connects eagerly and fails if the database is not
+    /// reachable. With lazy connection enabled the pool is created without
+    /// connecting, so the store can be built before the database is up. The pool
+    /// replaces broken connections automatically in both modes.
+    ///
+    /// # Arguments
+    ///
+    /// * `lazy` - Whether to postpone connecting to the first query.
+    pub fn lazy_connect(mut self, lazy: bool) -> Self {
+        self.lazy_connect = lazy;
+        self
+    }
+
+    /// Sets how the pool shares the database when `uri` is in-memory.
+    ///
+    /// Defaults to `SqliteMemoryMode::SingleConnection`. Ignored for file databases and
+    /// for an existing pool.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use keyv::adapter::sqlite::{SqliteMemoryMode, SqliteStoreBuilder};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let store = SqliteStoreBuilder::new()
+    ///     .uri("sqlite::memory:")
+    ///     .memory_mode(SqliteMemoryMode::SharedCache)
+    ///     .build()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn memory_mode(mut self, mode: SqliteMemoryMode) -> Self {
+        self.memory_mode = mode;
+        self
+    }
+
+    /// Sets how long a connection waits for a lock held by another connection,
+    /// including one from another process, before failing with `SQLITE_BUSY`.
+    ///
+    /// Defaults to `DEFAULT_SQLITE_BUSY_TIMEOUT`. Ignored for an existing pool.
+    pub fn busy_timeout(mut self, timeout: Duration) -> Self {
+        self.busy_timeout = timeout;
+        self
+    }
+
+    /// Sets whether the store runs one write at a time.
+    ///
+    /// SQLite allows a single writer per database, and concurrent writers that outlast
+    /// the busy timeout fail with `SQLITE_BUSY`. By default the writes of the store
+    /// queue on an internal lock instead, so only writes from other stores or processes
+    /// contend for the database. Reads are never serialized.
+    pub fn serialize_writes(mut self, serialize: bool) -> Self {
+        self.serialize_writes = serialize;
+        self
+    }
+
+    /// Retries connecting and initializing the backend according to `policy`.
+    ///
+    /// Useful when the application may start before the database, e.g. under
+    /// docker-compose or Kubernetes. Without a policy a single attempt is made.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The retry attempts, backoff and deadline to apply.
+    pub fn initialize_retry(mut self, policy: RetryPolicy) -> Self {
+        self.initialize_retry = Some(policy);
+        self
+    }
+
+    /// Sets what happens when a stored value cannot be deserialized.
+    ///
+    /// Defaults to `SerializationFailurePolicy::LogAndMiss`. With
+    /// `SerializationFailurePolicy::Quarantine`, `initialize()` also creates the
+    /// `<table>_quarantine` table the entries are moved to.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Whether to fail the read, or log and report a miss, optionally
+    ///   removing the entry.
+    pub fn on_serialization_failure(mut self, policy: SerializationFailurePolicy) -> Self {
+        self.serialization_failure = policy;
+        self
+    }
+
+    /// Calls `listener` for every entry moved to quarantine.
+    ///
+    /// Only used with `SerializationFailurePolicy::Quarantine`. The listener runs on the
+    /// read that found the entry, so it should return quickly.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The callback receiving each quarantined entry.
+    pub fn on_quarantine<F>(mut self, listener: F) -> Self
+    where
+        F: Fn(&QuarantinedEntry) + Send + Sync + 'static,
+    {
+        self.quarantine_listener = Some(Arc::new(listener));
+        self
+    }
+
+    /// Checks the configuration without connecting to the backend.
+    ///
+    /// `build()` runs the same checks, so calling this is only needed to report
+    /// misconfiguration early, e.g. at application startup.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the builder can be built, or `StoreError::InvalidConfiguration`
+    /// describing the offending option.
+    pub fn validate(&self) -> Result<(), StoreError> {
+        if self.pool.is_none() && self.uri.is_none() {
+            return Err(StoreError::invalid_configuration(
+                "uri",
+                "SqliteStore requires either a URI or an existing pool to be set",
+            ));
+        }
+        if self.pool.is_some() && self.uri.is_some() {
+            return Err(StoreError::invalid_configuration(
+                "pool",
+                "An existing pool cannot be combined with a URI; set only one of them",
+            ));
+        }
+        if let Some(uri) = &self.uri {
+            validate_uri_scheme(uri, &["sqlite"])?;
+        }
+        if let Some(table_name) = &self.table_name {
+            validate_identifier("table_name", table_name)?;
+        }
+        self.columns.validate()
+    }
+
     /// Builds the `SqliteStore` based on the provided configurations.
     ///
     /// Finalizes the builder and creates an `SqliteStore` instance.
@@ -97,15 +352,39 @@ impl SqliteStoreBuilder {
     /// This method returns a `Result` which, on success, contains the initialized `SqliteStore`.
     /// On failure, it returns a `StoreError` indicating what went wrong during the initialization.
     pub async fn build(self) -> Result<SqliteStore, StoreError> {
+        self.validate()?;
+        let retry = self.initialize_retry.unwrap_or_default();
         let pool = match self.pool {
             Some(pool) => pool,
             None => {
-                let uri = self
-                    .uri
-                    .expect("SqliteStore requires either a URI or an existing pool to be set");
-                Arc::new(SqlitePoolOptions::new().connect(&uri).await.map_err(|_| {
-                    StoreError::ConnectionError("Failed to connect to the database".to_string())
-                })?)
+                let uri = self.uri.ok_or_else(|| {
+                    StoreError::invalid_configuration(
+                        "uri",
+                        "SqliteStore requires either a URI or an existing pool to be set",
+                    )
+                })?;
+                let connect_options = SqliteConnectOptions::from_str(&uri)
+                    .map_err(|e| StoreError::invalid_configuration("uri", e.to_string()))?
+                    .busy_timeout(self.busy_timeout);
+                let options = pool_options(&uri, self.memory_mode);
+                let pool = if self.lazy_connect {
+                    options.connect_lazy_with(connect_options)
+                } else {
+                    retry
+                        .run(|| async {
+                            options
+                                .clone()
+                                .connect_with(connect_options.clone())
+                                .await
+                                .map_err(|_| {
+                                    StoreError::ConnectionError(
+                                        "Failed to connect to the database".to_string(),
+                                    )
+                                })
+                        })
+                        .await?
+                };
+                Arc::new(pool)
             }
         };
 
@@ -114,6 +393,34 @@ impl SqliteStoreBuilder {
             DEFAUTL_NAMESPACE_NAME.to_string()
         });
 
-        Ok(SqliteStore { pool, table_name })
+        let queries = Queries::new(&table_name, &self.columns);
+
+        Ok(SqliteStore {
+            pool,
+            table_name,
+            retry,
+            queries,
+            serialization_failure: self.serialization_failure,
+            quarantine_listener: self.quarantine_listener,
+            write_lock: self.serialize_writes.then(|| Mutex::new(())),
+            columns: self.columns,
+            create_tables: self.create_tables,
+        })
     }
 }
+
+/// Returns the pool options for `uri`, keeping in-memory databases alive.
+fn pool_options(uri: &str, memory_mode: SqliteMemoryMode) -> SqlitePoolOptions {
+    if !is_in_memory(uri) {
+        return SqlitePoolOptions::new();
+    }
+    let options = match memory_mode {
+        SqliteMemoryMode::SingleConnection => SqlitePoolOptions::new().max_connections(1),
+        SqliteMemoryMode::SharedCache => SqlitePoolOptions::new().min_connections(1),
+    };
+    options.idle_timeout(None).max_lifetime(None)
+}
+
+fn is_in_memory(uri: &str) -> bool {
+    uri.contains(":memory:") || uri.contains("mode=memory")
+}
@@ -1,5 +1,7 @@
 mod sqlite;
 pub use sqlite::*;
 
+mod queries;
+
 mod builder;
 pub use builder::*;
@@ -0,0 +1,175 @@
+use crate::adapter::Columns;
+
+/// SQL statements for one table, built once when the store is created.
+///
+/// Reusing the same strings lets sqlx keep a single prepared statement per query and
+/// connection instead of formatting and hashing a new statement on every call.
+pub(crate) struct Queries {
+    pub(crate) verify: String,
+    pub(crate) get: String,
+    pub(crate) get_with_metadata: String,
+    pub(crate) get_stale: String,
+    pub(crate) get_and_touch: String,
+    pub(crate) upsert: String,
+    pub(crate) upsert_keep_ttl: String,
+    pub(crate) insert_if_absent: String,
+    pub(crate) increment: String,
+    pub(crate) touch: String,
+    pub(crate) rename: String,
+    pub(crate) remove: String,
+    pub(crate) remove_if: String,
+    pub(crate) clear: String,
+    pub(crate) usage: String,
+    pub(crate) scan: String,
+    pub(crate) quarantine: String,
+    pub(crate) queue_push: String,
+    pub(crate) queue_pop: String,
+    pub(crate) queue_ack: String,
+    pub(crate) changes_append: String,
+    pub(crate) changes_read: String,
+    get_many_prefix: String,
+    remove_many_prefix: String,
+    touch_many_prefix: String,
+}
+
+impl Queries {
+    pub(crate) fn new(table: &str, columns: &Columns) -> Self {
+        let Columns {
+            key,
+            value,
+            expires_at,
+            created_at,
+            updated_at,
+        } = columns;
+        // Timestamp columns the table lacks are left out of writes and read as NULL.
+        let read_timestamps = [created_at, updated_at]
+            .map(|column| column.as_deref().unwrap_or("NULL"))
+            .join(", ");
+        let timestamps: String = columns.timestamps().map(|c| format!(", {c}")).collect();
+        let timestamp_values = |param: &str| -> String {
+            columns.timestamps().map(|_| format!(", {param}")).collect()
+        };
+        // `created_at` is kept unless the previous row had expired by `now`.
+        let keep_created = |now: &str| match created_at {
+            Some(c) => {
+                format!(", {c} = CASE WHEN {expires_at} <= {now} THEN EXCLUDED.{c} ELSE {c} END")
+            }
+            None => String::new(),
+        };
+        let reset_created = created_at
+            .as_ref()
+            .map_or(String::new(), |c| format!(", {c} = EXCLUDED.{c}"));
+        let set_updated = updated_at
+            .as_ref()
+            .map_or(String::new(), |c| format!(", {c} = EXCLUDED.{c}"));
+        Self {
+            // Reads no rows, but fails unless the table has every column the store uses.
+            verify: format!("SELECT {key}, {value}, {expires_at}{timestamps} FROM {table} WHERE 1 = 0"),
+            get: format!(
+                "SELECT {value} FROM {table} WHERE {key} = ? AND ({expires_at} IS NULL OR {expires_at} > ?)"
+            ),
+            get_with_metadata: format!(
+                "SELECT {value}, {read_timestamps}, {expires_at} FROM {table} WHERE {key} = ? AND ({expires_at} IS NULL OR {expires_at} > ?)"
+            ),
+            // Expired rows included, for callers that decide themselves what is too old.
+            get_stale: format!(
+                "SELECT {value}, {read_timestamps}, {expires_at} FROM {table} WHERE {key} = ?"
+            ),
+            get_and_touch: format!(
+                "UPDATE {table} SET {expires_at} = ?1 WHERE {key} = ?2 AND ({expires_at} IS NULL OR {expires_at} > ?3) RETURNING {value}"
+            ),
+            // `created_at` is kept on overwrite unless the previous row had expired.
+            upsert: format!(
+                "INSERT INTO {table} ({key}, {value}, {expires_at}{timestamps}) VALUES (?1, ?2, ?3{values}) ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {expires_at} = EXCLUDED.{expires_at}{keep_created}{set_updated}",
+                values = timestamp_values("?4"),
+                keep_created = keep_created("?4"),
+            ),
+            upsert_keep_ttl: format!(
+                "INSERT INTO {table} ({key}, {value}{timestamps}) VALUES (?1, ?2{values}) ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {expires_at} = CASE WHEN {expires_at} > ?3 THEN {expires_at} END{keep_created}{set_updated}",
+                values = timestamp_values("?3"),
+                keep_created = keep_created("?3"),
+            ),
+            // Only an expired row is overwritten; a live one is left alone and counts as
+            // no row affected.
+            insert_if_absent: format!(
+                "INSERT INTO {table} ({key}, {value}, {expires_at}{timestamps}) VALUES (?1, ?2, ?3{values}) ON CONFLICT({key}) DO UPDATE SET {value} = EXCLUDED.{value}, {expires_at} = EXCLUDED.{expires_at}{reset_created}{set_updated} WHERE {expires_at} <= ?4",
+                values = timestamp_values("?4"),
+            ),
+            // An expired row restarts from `delta`; rows holding anything but a JSON
+            // integer are left alone and return nothing.
+            increment: format!(
+                "INSERT INTO {table} ({key}, {value}, {expires_at}{timestamps}) VALUES (?1, ?2, ?3{values}) ON CONFLICT({key}) DO UPDATE SET {value} = CASE WHEN {expires_at} <= ?4 THEN EXCLUDED.{value} ELSE CAST({value} AS INTEGER) + ?2 END, {expires_at} = CASE WHEN {expires_at} <= ?4 THEN EXCLUDED.{expires_at} ELSE {expires_at} END{keep_created}{set_updated} WHERE {expires_at} <= ?4 OR json_type({value}) = 'integer' RETURNING CAST({value} AS INTEGER)",
+                values = timestamp_values("?4"),
+                keep_created = keep_created("?4"),
+            ),
+            touch: format!(
+                "UPDATE {table} SET {expires_at} = ?1 WHERE {key} = ?2 AND ({expires_at} IS NULL OR {expires_at} > ?3)"
+            ),
+            // Rows keep their value, expiration and timestamps.
+            rename: format!(
+                "UPDATE {table} SET {key} = ?1 WHERE {key} = ?2 AND ({expires_at} IS NULL OR {expires_at} > ?3)"
+            ),
+            remove: format!("DELETE FROM {table} WHERE {key} = ?"),
+            remove_if: format!(
+                "DELETE FROM {table} WHERE {key} = ?1 AND {value} = ?2 AND ({expires_at} IS NULL OR {expires_at} > ?3)"
+            ),
+            clear: format!("DELETE FROM {table}"),
+            usage: format!(
+                "SELECT COUNT(*), COALESCE(SUM(LENGTH({key}) + LENGTH({value})), 0) FROM {table} WHERE {expires_at} IS NULL OR {expires_at} > ?"
+            ),
+            scan: format!(
+                "SELECT {key}, {value}, {expires_at} FROM {table} WHERE {key} >= ?2 AND substr({key}, 1, length(?2)) = ?2 AND (?1 IS NULL OR {key} > ?1) AND ({expires_at} IS NULL OR {expires_at} > ?3) AND (?5 IS NULL OR {key} GLOB ?5) ORDER BY {key} LIMIT ?4"
+            ),
+            quarantine: format!(
+                "INSERT INTO {table}_quarantine (key, value, error, quarantined_at) VALUES (?1, ?2, ?3, ?4) ON CONFLICT(key) DO UPDATE SET value = EXCLUDED.value, error = EXCLUDED.error, quarantined_at = EXCLUDED.quarantined_at"
+            ),
+            queue_push: format!(
+                "INSERT INTO {table}_queue (queue, payload) VALUES (?, ?) RETURNING id"
+            ),
+            // Claims the oldest message nobody holds or whose claim has lapsed.
+            queue_pop: format!(
+                "UPDATE {table}_queue SET claimed_until = ?3 WHERE id = (SELECT id FROM {table}_queue WHERE queue = ?1 AND (claimed_until IS NULL OR claimed_until <= ?2) ORDER BY id LIMIT 1) RETURNING id, payload"
+            ),
+            queue_ack: format!("DELETE FROM {table}_queue WHERE queue = ? AND id = ?"),
+            changes_append: format!(
+                "INSERT INTO {table}_changes (key, kind, recorded_at) VALUES (?, ?, ?) RETURNING id"
+            ),
+            changes_read: format!(
+                "SELECT id, key, kind, recorded_at FROM {table}_changes WHERE id > ? ORDER BY id LIMIT ?"
+            ),
+            get_many_prefix: format!(
+                "SELECT {key}, {value} FROM {table} WHERE ({expires_at} IS NULL OR {expires_at} > ?) AND {key} IN ("
+            ),
+            remove_many_prefix: format!("DELETE FROM {table} WHERE {key} IN ("),
+            touch_many_prefix: format!(
+                "UPDATE {table} SET {expires_at} = ? WHERE ({expires_at} IS NULL OR {expires_at} > ?) AND {key} IN ("
+            ),
+        }
+    }
+
+    /// Returns the statement reading the live rows of `count` keys.
+    pub(crate) fn get_many(&self, count: usize) -> String {
+        with_placeholders(&self.get_many_prefix, count)
+    }
+
+    /// Returns the statement deleting `count` keys. The placeholder list depends on the
+    /// number of keys, so only the prefix is precomputed.
+    pub(crate) fn remove_many(&self, count: usize) -> String {
+        with_placeholders(&self.remove_many_prefix, count)
+    }
+
+    /// Returns the statement extending the expiration of `count` keys.
+    pub(crate) fn touch_many(&self, count: usize) -> String {
+        with_placeholders(&self.touch_many_prefix, count)
+    }
+}
+
+/// Appends `count` placeholders and the closing parenthesis of an `IN` list to `prefix`.
+fn with_placeholders(prefix: &str, count: usize) -> String {
+    let mut sql = prefix.to_string();
+    for i in 0..count {
+        sql.push_str(if i == 0 { "?" } else { ", ?" });
+    }
+    sql.push(')');
+    sql
+}
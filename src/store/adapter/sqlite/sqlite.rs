@@ -1,67 +1,473 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
 use serde_json::Value;
 use sqlx::SqlitePool;
+use tokio::sync::{Mutex, MutexGuard};
 
-use crate::{Store, StoreError};
+use super::queries::Queries;
+use crate::{
+    adapter::Columns,
+    store::{
+        change_from_row,
+        expiry::{expires_at_millis, millis_since_epoch, now_millis, system_time_from_millis},
+        glob, parse_sequence_cursor, set_many_in_batch, Quarantine,
+    },
+    BatchOp, Capabilities, Change, ChangeFeedBackend, ChangeKind, Metadata, QuarantineListener,
+    QuarantinedEntry, QueueBackend, QueueMessage, RetryPolicy, ScanEntry,
+    SerializationFailurePolicy, Store, StoreError, Usage,
+};
 
 pub struct SqliteStore {
     pub(crate) pool: Arc<SqlitePool>,
     pub(crate) table_name: String,
+    pub(crate) retry: RetryPolicy,
+    pub(crate) queries: Queries,
+    pub(crate) serialization_failure: SerializationFailurePolicy,
+    pub(crate) quarantine_listener: Option<QuarantineListener>,
+    pub(crate) write_lock: Option<Mutex<()>>,
+    pub(crate) columns: Columns,
+    pub(crate) create_tables: bool,
+}
+
+/// Prints the table layout, never the pool and its connection options.
+impl fmt::Debug for SqliteStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteStore")
+            .field("table_name", &self.table_name)
+            .field("columns", &self.columns)
+            .field("serialize_writes", &self.write_lock.is_some())
+            .finish_non_exhaustive()
+    }
 }
 
 impl SqliteStore {
+    /// Returns the connection pool backing the store.
+    ///
+    /// Gives access to backend-specific queries without opening a second pool.
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    /// Waits for the other writes of this store to finish, unless writes run
+    /// concurrently.
+    async fn lock_writes(&self) -> Option<MutexGuard<'_, ()>> {
+        match &self.write_lock {
+            Some(write_lock) => Some(write_lock.lock().await),
+            None => None,
+        }
+    }
+
     fn get_table_name(&self) -> String {
         self.table_name.clone()
     }
-}
 
-#[async_trait]
-impl Store for SqliteStore {
-    async fn initialize(&self) -> Result<(), StoreError> {
+    /// Checks that the table exists with the expected columns, for stores that do not
+    /// create it themselves.
+    async fn verify_table(&self) -> Result<(), StoreError> {
+        sqlx::query(&self.queries.verify)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| {
+                StoreError::invalid_configuration(
+                    "table_name",
+                    format!(
+                        "The table '{}' is missing or lacks the expected columns: {}",
+                        self.get_table_name(),
+                        e
+                    ),
+                )
+            })?;
+        Ok(())
+    }
+
+    async fn create_table(&self) -> Result<(), StoreError> {
+        let Columns {
+            key,
+            value,
+            expires_at,
+            ..
+        } = &self.columns;
+        let timestamps: String = self
+            .columns
+            .timestamps()
+            .map(|column| format!(",\n                {} INTEGER", column))
+            .collect();
         let sql = format!(
             "CREATE TABLE IF NOT EXISTS {} (
+                {} TEXT PRIMARY KEY,
+                {} TEXT NOT NULL,
+                {} INTEGER{}
+            )",
+            self.get_table_name(),
+            key,
+            value,
+            expires_at,
+            timestamps
+        );
+
+        sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
+            StoreError::QueryError(format!("Failed to initialize the database table: {}", e))
+        })?;
+
+        // Tables created by earlier versions lack the expiration and timestamp columns.
+        for column in std::iter::once(expires_at.as_str()).chain(self.columns.timestamps()) {
+            let (has_column,) = sqlx::query_as::<_, (bool,)>(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info(?) WHERE name = ?",
+            )
+            .bind(self.get_table_name())
+            .bind(column)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to inspect the table: {}", e)))?;
+
+            if !has_column {
+                let migrate_sql = format!(
+                    "ALTER TABLE {} ADD COLUMN {} INTEGER",
+                    self.get_table_name(),
+                    column
+                );
+                sqlx::query(&migrate_sql)
+                    .execute(&*self.pool)
+                    .await
+                    .map_err(|e| {
+                        StoreError::QueryError(format!(
+                            "Failed to add the column '{}': {}",
+                            column, e
+                        ))
+                    })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates the table undeserializable entries are moved to, if the serialization
+    /// failure policy quarantines them.
+    async fn create_quarantine_table(&self) -> Result<(), StoreError> {
+        if self.serialization_failure != SerializationFailurePolicy::Quarantine {
+            return Ok(());
+        }
+
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {}_quarantine (
                 key TEXT PRIMARY KEY,
-                value TEXT NOT NULL
+                value TEXT NOT NULL,
+                error TEXT NOT NULL,
+                quarantined_at INTEGER NOT NULL
             )",
             self.get_table_name()
         );
+        sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
+            StoreError::QueryError(format!("Failed to create the quarantine table: {}", e))
+        })?;
 
+        Ok(())
+    }
+
+    /// Creates the table holding the messages of `queue_push`.
+    async fn create_queue_table(&self) -> Result<(), StoreError> {
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {}_queue (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                queue TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                claimed_until INTEGER
+            )",
+            self.get_table_name()
+        );
         sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
-            StoreError::QueryError(format!(
-                "Failed to initialize the database table: {}",
-                e.to_string()
-            ))
+            StoreError::QueryError(format!("Failed to create the queue table: {}", e))
         })?;
 
         Ok(())
     }
 
+    /// Creates the outbox table holding the change feed.
+    async fn create_changes_table(&self) -> Result<(), StoreError> {
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {}_changes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                key TEXT,
+                kind TEXT NOT NULL,
+                recorded_at INTEGER NOT NULL
+            )",
+            self.get_table_name()
+        );
+        sqlx::query(&sql).execute(&*self.pool).await.map_err(|e| {
+            StoreError::QueryError(format!("Failed to create the changes table: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// Reads `key` with its metadata, skipping it if it has expired unless
+    /// `include_expired` is set.
+    async fn read_with_metadata(
+        &self,
+        key: &str,
+        include_expired: bool,
+    ) -> Result<Option<(Value, Metadata)>, StoreError> {
+        let query = if include_expired {
+            sqlx::query_as::<_, (String, Option<i64>, Option<i64>, Option<i64>)>(
+                &self.queries.get_stale,
+            )
+            .bind(key)
+        } else {
+            sqlx::query_as(&self.queries.get_with_metadata)
+                .bind(key)
+                .bind(now_millis())
+        };
+        let result = query
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to fetch the value".to_string()))?;
+
+        let Some((value, created_at, updated_at, expires_at)) = result else {
+            return Ok(None);
+        };
+        let value = self.serialization_failure.decode(self, key, &value).await?;
+        Ok(value.map(|value| {
+            let metadata = Metadata {
+                created_at: created_at.map(system_time_from_millis),
+                updated_at: updated_at.map(system_time_from_millis),
+                expires_at: expires_at.map(system_time_from_millis),
+            };
+            (value, metadata)
+        }))
+    }
+
+    /// Inserts or replaces `key`, storing `expires_at` in milliseconds since the epoch.
+    async fn upsert(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: Option<i64>,
+    ) -> Result<(), StoreError> {
+        let _writer = self.lock_writes().await;
+        let value_str = serde_json::to_string(&value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+
+        sqlx::query(&self.queries.upsert)
+            .bind(key)
+            .bind(value_str)
+            .bind(expires_at)
+            .bind(now_millis())
+            .execute(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to set the value".to_string()))?;
+
+        Ok(())
+    }
+
+    /// Streams the live rows whose key starts with `prefix` and, if set, matches the
+    /// `GLOB` pattern `glob`.
+    fn scan_rows<'a>(
+        &'a self,
+        prefix: &'a str,
+        glob: Option<String>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        let batch_size = batch_size.max(1);
+        // Keyset pagination: each batch starts after the last key of the previous one.
+        Box::pin(stream::try_unfold(Some(None::<String>), move |cursor| {
+            let glob = glob.clone();
+            async move {
+                let Some(cursor) = cursor else {
+                    return Ok(None);
+                };
+                let rows = sqlx::query_as::<_, (String, String, Option<i64>)>(&self.queries.scan)
+                    .bind(cursor)
+                    .bind(prefix)
+                    .bind(now_millis())
+                    .bind(batch_size as i64)
+                    .bind(glob)
+                    .fetch_all(&*self.pool)
+                    .await
+                    .map_err(|e| {
+                        StoreError::QueryError(format!("Failed to scan the table: {}", e))
+                    })?;
+                if rows.is_empty() {
+                    return Ok(None);
+                }
+
+                let next =
+                    (rows.len() == batch_size).then(|| rows.last().map(|(key, _, _)| key.clone()));
+                let rows = rows
+                    .into_iter()
+                    .map(|(key, value, expires_at)| {
+                        (key, value, expires_at.map(system_time_from_millis))
+                    })
+                    .collect();
+                let batch = self
+                    .serialization_failure
+                    .decode_entries(self, rows)
+                    .await?;
+                Ok(Some((batch, next)))
+            }
+        }))
+    }
+}
+
+#[async_trait]
+impl Quarantine for SqliteStore {
+    async fn quarantine(&self, entries: &[QuarantinedEntry]) -> Result<(), StoreError> {
+        let _writer = self.lock_writes().await;
+        let query_error =
+            |e: sqlx::Error| StoreError::QueryError(format!("Failed to quarantine: {}", e));
+        let mut tx = self.pool.begin().await.map_err(query_error)?;
+        for entry in entries {
+            sqlx::query(&self.queries.quarantine)
+                .bind(&entry.key)
+                .bind(&entry.raw)
+                .bind(&entry.error)
+                .bind(now_millis())
+                .execute(&mut *tx)
+                .await
+                .map_err(query_error)?;
+            sqlx::query(&self.queries.remove)
+                .bind(&entry.key)
+                .execute(&mut *tx)
+                .await
+                .map_err(query_error)?;
+        }
+        tx.commit().await.map_err(query_error)
+    }
+
+    fn quarantine_listener(&self) -> Option<&QuarantineListener> {
+        self.quarantine_listener.as_ref()
+    }
+}
+
+#[async_trait]
+impl Store for SqliteStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        if !self.create_tables {
+            return self.retry.run(|| self.verify_table()).await;
+        }
+        self.retry.run(|| self.create_table()).await?;
+        self.create_quarantine_table().await?;
+        self.create_queue_table().await?;
+        self.create_changes_table().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            native_ttl: true,
+            atomic_increment: true,
+            transactions: true,
+            scan: true,
+            watch: true,
+        }
+    }
+
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
-        let query = format!("SELECT value FROM {} WHERE key = ?", self.get_table_name());
-        let result = sqlx::query_as::<_, (String,)>(query.as_str())
+        let result = sqlx::query_as::<_, (String,)>(&self.queries.get)
             .bind(key)
+            .bind(now_millis())
             .fetch_optional(&*self.pool)
             .await
             .map_err(|_| StoreError::QueryError("Failed to fetch the value".to_string()))?;
 
-        Ok(result
-            .map(|(value,)| serde_json::from_str(&value).ok())
-            .flatten())
+        match result {
+            Some((value,)) => self.serialization_failure.decode(self, key, &value).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = self.queries.get_many(keys.len());
+        let mut query = sqlx::query_as::<_, (String, String)>(&query).bind(now_millis());
+        for key in keys {
+            query = query.bind(key);
+        }
+        let rows: HashMap<String, String> = query
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to fetch the values".to_string()))?
+            .into_iter()
+            .collect();
+
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(match rows.get(*key) {
+                Some(value) => self.serialization_failure.decode(self, key, value).await?,
+                None => None,
+            });
+        }
+        Ok(values)
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.read_with_metadata(key, false).await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.read_with_metadata(key, true).await
+    }
+
+    /// Extends the expiration with the same `UPDATE` that returns the value, so a
+    /// concurrent write is never overwritten with the value read.
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        let result = {
+            let _writer = self.lock_writes().await;
+            sqlx::query_as::<_, (String,)>(&self.queries.get_and_touch)
+                .bind(expires_at_millis(Some(ttl)))
+                .bind(key)
+                .bind(now_millis())
+                .fetch_optional(&*self.pool)
+                .await
+                .map_err(|_| StoreError::QueryError("Failed to touch the value".to_string()))?
+        };
+
+        match result {
+            Some((value,)) => self.serialization_failure.decode(self, key, &value).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.upsert(key, value, expires_at_millis(ttl)).await
     }
 
-    async fn set(&self, key: &str, value: Value, _ttl: Option<u64>) -> Result<(), StoreError> {
+    /// Entries are written in one transaction.
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        set_many_in_batch(self, entries, ttl).await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        self.upsert(key, value, Some(millis_since_epoch(expires_at)))
+            .await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        let _writer = self.lock_writes().await;
         let value_str = serde_json::to_string(&value)
             .map_err(|e| StoreError::SerializationError { source: e })?;
 
-        let sql = format!(
-            "INSERT INTO {} (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value = EXCLUDED.value",
-            self.get_table_name()
-        );
-        sqlx::query(&sql)
+        sqlx::query(&self.queries.upsert_keep_ttl)
             .bind(key)
             .bind(value_str)
+            .bind(now_millis())
             .execute(&*self.pool)
             .await
             .map_err(|_| StoreError::QueryError("Failed to set the value".to_string()))?;
@@ -69,9 +475,105 @@ impl Store for SqliteStore {
         Ok(())
     }
 
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        let _writer = self.lock_writes().await;
+        let value_str = serde_json::to_string(&value)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+
+        let result = sqlx::query(&self.queries.insert_if_absent)
+            .bind(key)
+            .bind(value_str)
+            .bind(expires_at_millis(ttl))
+            .bind(now_millis())
+            .execute(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to set the value".to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        let _writer = self.lock_writes().await;
+        let value = sqlx::query_scalar::<_, i64>(&self.queries.increment)
+            .bind(key)
+            .bind(delta)
+            .bind(expires_at_millis(ttl))
+            .bind(now_millis())
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to increment: {}", e)))?;
+
+        value.ok_or_else(|| {
+            StoreError::QueryError(format!("The value of '{}' is not an integer", key))
+        })
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        let _writer = self.lock_writes().await;
+        let query_error =
+            |e: sqlx::Error| StoreError::QueryError(format!("Failed to run the batch: {}", e));
+        let mut tx = self.pool.begin().await.map_err(query_error)?;
+        let now = now_millis();
+        let mut results = Vec::with_capacity(ops.len());
+        for op in ops {
+            match op {
+                BatchOp::Set { key, value, ttl } => {
+                    let value_str = serde_json::to_string(&value)
+                        .map_err(|e| StoreError::SerializationError { source: e })?;
+                    sqlx::query(&self.queries.upsert)
+                        .bind(key)
+                        .bind(value_str)
+                        .bind(expires_at_millis(ttl))
+                        .bind(now)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(query_error)?;
+                    results.push(Ok(()));
+                }
+                BatchOp::Remove { key } => {
+                    sqlx::query(&self.queries.remove)
+                        .bind(key)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(query_error)?;
+                    results.push(Ok(()));
+                }
+                BatchOp::Touch { key, ttl } => {
+                    let touched = sqlx::query(&self.queries.touch)
+                        .bind(expires_at_millis(Some(ttl)))
+                        .bind(key)
+                        .bind(now)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(query_error)?;
+                    results.push(if touched.rows_affected() > 0 {
+                        Ok(())
+                    } else {
+                        Err(StoreError::NotFound)
+                    });
+                }
+            }
+        }
+        tx.commit().await.map_err(query_error)?;
+        Ok(results)
+    }
+
     async fn remove(&self, key: &str) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {} WHERE key = ?", self.get_table_name());
-        sqlx::query(&query)
+        let _writer = self.lock_writes().await;
+        sqlx::query(&self.queries.remove)
             .bind(key)
             .execute(&*self.pool)
             .await
@@ -81,32 +583,244 @@ impl Store for SqliteStore {
     }
 
     async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
-        let query = format!(
-            "DELETE FROM {} WHERE key IN ({})",
-            self.get_table_name(),
-            keys.iter().map(|_| "?").collect::<Vec<&str>>().join(",")
-        );
-
+        let _writer = self.lock_writes().await;
+        let query = self.queries.remove_many(keys.len());
         let mut query = sqlx::query(&query);
         for key in keys {
             query = query.bind(key);
         }
 
-        query.execute(&*self.pool).await.map_err(|e| {
-            StoreError::QueryError(format!("Failed to remove the keys: {}", e.to_string()))
-        })?;
+        query
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to remove the keys: {}", e)))?;
 
         Ok(())
     }
 
-    async fn clear(&self) -> Result<(), StoreError> {
-        let query = format!("DELETE FROM {}", self.get_table_name());
+    /// Values are compared once parsed, so objects match whatever their field order
+    /// in the stored text; the delete is guarded by that text.
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        let _writer = self.lock_writes().await;
+        let stored = sqlx::query_as::<_, (String,)>(&self.queries.get)
+            .bind(key)
+            .bind(now_millis())
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to fetch the value".to_string()))?;
+        let Some((stored,)) = stored else {
+            return Ok(false);
+        };
+        if serde_json::from_str::<Value>(&stored).ok().as_ref() != Some(expected) {
+            return Ok(false);
+        }
+        let removed = sqlx::query(&self.queries.remove_if)
+            .bind(key)
+            .bind(stored)
+            .bind(now_millis())
+            .execute(&*self.pool)
+            .await
+            .map_err(|_| StoreError::QueryError("Failed to remove the key".to_string()))?;
 
-        sqlx::query(&query)
+        Ok(removed.rows_affected() > 0)
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        let _writer = self.lock_writes().await;
+        let query_error =
+            |e: sqlx::Error| StoreError::QueryError(format!("Failed to rename the key: {}", e));
+        let mut tx = self.pool.begin().await.map_err(query_error)?;
+        if old_key != new_key {
+            sqlx::query(&self.queries.remove)
+                .bind(new_key)
+                .execute(&mut *tx)
+                .await
+                .map_err(query_error)?;
+        }
+        let renamed = sqlx::query(&self.queries.rename)
+            .bind(new_key)
+            .bind(old_key)
+            .bind(now_millis())
+            .execute(&mut *tx)
+            .await
+            .map_err(query_error)?;
+        if renamed.rows_affected() == 0 {
+            // Dropping the transaction rolls back the removal of `new_key`.
+            return Err(StoreError::NotFound);
+        }
+        tx.commit().await.map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        if keys.is_empty() {
+            return Ok(0);
+        }
+
+        let _writer = self.lock_writes().await;
+        let query = self.queries.touch_many(keys.len());
+        let mut query = sqlx::query(&query)
+            .bind(expires_at_millis(Some(ttl)))
+            .bind(now_millis());
+        for key in keys {
+            query = query.bind(key);
+        }
+
+        let touched = query
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to touch the keys: {}", e)))?;
+
+        Ok(touched.rows_affected())
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        let _writer = self.lock_writes().await;
+        sqlx::query(&self.queries.clear)
             .execute(&*self.pool)
             .await
             .map_err(|_| StoreError::QueryError("Failed to clear the table".to_string()))?;
 
         Ok(())
     }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        // SQLite has no per-table size statistics without the optional dbstat table, so
+        // the size is estimated from the stored keys and values.
+        let (entries, bytes): (i64, i64) = sqlx::query_as(&self.queries.usage)
+            .bind(now_millis())
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to read the table usage: {}", e))
+            })?;
+
+        Ok(Usage {
+            entries: entries as u64,
+            bytes: Some(bytes as u64),
+        })
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.scan_rows(prefix.unwrap_or_default(), None, batch_size)
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.scan_rows(
+            glob::literal_prefix(pattern),
+            Some(glob::to_sqlite_glob(pattern)),
+            batch_size,
+        )
+    }
+}
+
+#[async_trait]
+impl QueueBackend for SqliteStore {
+    async fn queue_push(&self, queue: &str, payload: Value) -> Result<String, StoreError> {
+        let _writer = self.lock_writes().await;
+        let payload = serde_json::to_string(&payload)
+            .map_err(|e| StoreError::SerializationError { source: e })?;
+        let id = sqlx::query_scalar::<_, i64>(&self.queries.queue_push)
+            .bind(queue)
+            .bind(payload)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to push the message: {}", e)))?;
+
+        Ok(id.to_string())
+    }
+
+    async fn queue_pop(
+        &self,
+        queue: &str,
+        visibility_timeout: Duration,
+    ) -> Result<Option<QueueMessage>, StoreError> {
+        let _writer = self.lock_writes().await;
+        let now = now_millis();
+        let claimed = sqlx::query_as::<_, (i64, String)>(&self.queries.queue_pop)
+            .bind(queue)
+            .bind(now)
+            .bind(now.saturating_add(visibility_timeout.as_millis() as i64))
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to pop a message: {}", e)))?;
+
+        claimed
+            .map(|(id, payload)| {
+                let payload = serde_json::from_str(&payload)
+                    .map_err(|e| StoreError::SerializationError { source: e })?;
+                Ok(QueueMessage {
+                    id: id.to_string(),
+                    payload,
+                })
+            })
+            .transpose()
+    }
+
+    async fn queue_ack(&self, queue: &str, id: &str) -> Result<(), StoreError> {
+        let _writer = self.lock_writes().await;
+        // Ids this store never handed out cannot name a message.
+        let Ok(id) = id.parse::<i64>() else {
+            return Ok(());
+        };
+        sqlx::query(&self.queries.queue_ack)
+            .bind(queue)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| {
+                StoreError::QueryError(format!("Failed to acknowledge the message: {}", e))
+            })?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChangeFeedBackend for SqliteStore {
+    async fn append_change(
+        &self,
+        key: Option<&str>,
+        kind: ChangeKind,
+    ) -> Result<String, StoreError> {
+        // Writes are serialized by SQLite, so ids are committed in increasing order.
+        let _writer = self.lock_writes().await;
+        let id = sqlx::query_scalar::<_, i64>(&self.queries.changes_append)
+            .bind(key)
+            .bind(kind.as_str())
+            .bind(now_millis())
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| StoreError::QueryError(format!("Failed to append the change: {}", e)))?;
+
+        Ok(id.to_string())
+    }
+
+    async fn read_changes(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Change>, StoreError> {
+        let rows =
+            sqlx::query_as::<_, (i64, Option<String>, String, i64)>(&self.queries.changes_read)
+                .bind(parse_sequence_cursor(after)?)
+                .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+                .fetch_all(&*self.pool)
+                .await
+                .map_err(|e| {
+                    StoreError::QueryError(format!("Failed to read the changes: {}", e))
+                })?;
+
+        rows.into_iter()
+            .map(|(id, key, kind, recorded_at)| change_from_row(id, key, &kind, recorded_at))
+            .collect()
+    }
 }
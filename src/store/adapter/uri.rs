@@ -0,0 +1,16 @@
+use crate::StoreError;
+
+/// Checks that `uri` starts with one of the `schemes` the backend's driver accepts.
+///
+/// Catches URIs meant for another backend, e.g. a Postgres URI handed to the Redis
+/// builder, before the driver reports a less obvious parse error.
+pub(crate) fn validate_uri_scheme(uri: &str, schemes: &[&str]) -> Result<(), StoreError> {
+    let scheme = uri.split_once(':').map(|(scheme, _)| scheme);
+    if scheme.is_some_and(|scheme| schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme))) {
+        return Ok(());
+    }
+    Err(StoreError::invalid_configuration(
+        "uri",
+        format!("The URI scheme must be one of: {}", schemes.join(", ")),
+    ))
+}
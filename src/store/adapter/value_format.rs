@@ -0,0 +1,14 @@
+/// Column type used by the SQL adapters to store values.
+///
+/// Values are serialized to JSON either way. With `Json` the column uses the
+/// database's native JSON type (`JSONB` on PostgreSQL, `JSON` on MySQL), so numbers,
+/// strings and documents keep their type and can be queried and indexed by other
+/// consumers of the table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValueFormat {
+    /// JSON text in a `TEXT` column.
+    #[default]
+    Text,
+    /// A native JSON column.
+    Json,
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::{Store, StoreError};
+
+/// One operation of a batch run with `Store::execute_batch`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchOp {
+    /// Sets `value` under `key`, expiring after `ttl` if given.
+    Set {
+        key: String,
+        value: Value,
+        ttl: Option<Duration>,
+    },
+    /// Removes `key`.
+    Remove { key: String },
+    /// Makes the live entry under `key` expire after `ttl`, failing with
+    /// `StoreError::NotFound` if there is none.
+    Touch { key: String, ttl: Duration },
+}
+
+impl BatchOp {
+    /// Returns the key the operation applies to.
+    pub fn key(&self) -> &str {
+        match self {
+            BatchOp::Set { key, .. } | BatchOp::Remove { key } | BatchOp::Touch { key, .. } => key,
+        }
+    }
+
+    /// Returns the operation applied to the key `f` maps its key to.
+    pub(crate) fn map_key(self, f: impl FnOnce(&str) -> String) -> Self {
+        match self {
+            BatchOp::Set { key, value, ttl } => BatchOp::Set {
+                key: f(&key),
+                value,
+                ttl,
+            },
+            BatchOp::Remove { key } => BatchOp::Remove { key: f(&key) },
+            BatchOp::Touch { key, ttl } => BatchOp::Touch { key: f(&key), ttl },
+        }
+    }
+}
+
+/// Runs `ops` one after the other through the single-key methods of `store`.
+pub(crate) async fn execute_sequentially<S: Store + ?Sized>(
+    store: &S,
+    ops: Vec<BatchOp>,
+) -> Vec<Result<(), StoreError>> {
+    let mut results = Vec::with_capacity(ops.len());
+    for op in ops {
+        results.push(match op {
+            BatchOp::Set { key, value, ttl } => store.set(&key, value, ttl).await,
+            BatchOp::Remove { key } => store.remove(&key).await,
+            BatchOp::Touch { key, ttl } => match store.get_and_touch(&key, ttl).await {
+                Ok(Some(_)) => Ok(()),
+                Ok(None) => Err(StoreError::NotFound),
+                Err(e) => Err(e),
+            },
+        });
+    }
+    results
+}
+
+/// Writes `entries` as one `Store::execute_batch` call, for adapters whose batches run
+/// in a single transaction or pipeline.
+pub(crate) async fn set_many_in_batch<S: Store + ?Sized>(
+    store: &S,
+    entries: &[(&str, Value)],
+    ttl: Option<Duration>,
+) -> Result<(), StoreError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let ops = entries
+        .iter()
+        .map(|(key, value)| BatchOp::Set {
+            key: key.to_string(),
+            value: value.clone(),
+            ttl,
+        })
+        .collect();
+    store.execute_batch(ops).await?.into_iter().collect()
+}
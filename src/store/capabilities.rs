@@ -0,0 +1,18 @@
+/// Optional features of a store, reported by `Store::capabilities`.
+///
+/// Lets generic code adapt to the backend up front, e.g. wrap it in
+/// `layer::ttl::TtlStore` when it cannot expire entries, rather than finding out from
+/// a `StoreError::Unsupported` at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities {
+    /// The store expires entries itself, honoring the TTLs it is given.
+    pub native_ttl: bool,
+    /// `Store::increment` is supported and atomic.
+    pub atomic_increment: bool,
+    /// `Store::execute_batch` applies the whole batch in one transaction.
+    pub transactions: bool,
+    /// `Store::scan_entries` and `Store::find_entries` are supported.
+    pub scan: bool,
+    /// The store is a `ChangeFeedBackend`, so changes can be watched.
+    pub watch: bool,
+}
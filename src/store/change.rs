@@ -0,0 +1,169 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+
+use super::{expiry::system_time_from_millis, StoreError};
+
+/// What a mutation recorded in the change feed did to its key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// A value was written under the key.
+    Set,
+    /// The key was removed.
+    Remove,
+    /// Every key of the store was removed.
+    Clear,
+}
+
+impl ChangeKind {
+    /// Returns the name the kind is persisted under.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Set => "set",
+            ChangeKind::Remove => "remove",
+            ChangeKind::Clear => "clear",
+        }
+    }
+
+    /// Parses a kind persisted with `as_str`.
+    pub(crate) fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "set" => Some(ChangeKind::Set),
+            "remove" => Some(ChangeKind::Remove),
+            "clear" => Some(ChangeKind::Clear),
+            _ => None,
+        }
+    }
+}
+
+/// A mutation read from the change feed with `ChangeFeedBackend::read_changes`.
+///
+/// The feed records which keys changed, not their values: consumers read the current
+/// value of the key when they process the change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// Position of the change in the feed, passed back to `read_changes` to resume
+    /// after it.
+    pub cursor: String,
+    /// The key that changed, or `None` for `ChangeKind::Clear`.
+    pub key: Option<String>,
+    /// What happened to the key.
+    pub kind: ChangeKind,
+    /// When the change was recorded.
+    pub recorded_at: SystemTime,
+}
+
+/// Number of changes read per call when no limit is given.
+pub const DEFAULT_CHANGES_LIMIT: usize = 1000;
+
+/// A backend keeping a durable change feed.
+///
+/// The SQL stores keep the feed in a `{table}_changes` outbox table, Redis in a stream
+/// and `InMemoryStore` in memory. Changes are usually appended by
+/// `layer::change_feed::ChangeFeedStore` and read with `Keyv::changes`. Apart from
+/// `KeyCodecStore`, layers do not implement the trait, so both are given the adapter.
+#[async_trait]
+pub trait ChangeFeedBackend: Send + Sync {
+    /// Appends a change to the feed.
+    ///
+    /// The feed is durable and ordered: `read_changes` returns changes in the order
+    /// they were appended, each with a cursor to resume after it.
+    ///
+    /// # Arguments
+    /// - `key`: The key that changed, or `None` for `ChangeKind::Clear`.
+    /// - `kind`: What happened to the key.
+    ///
+    /// # Returns
+    /// - `Ok(String)` with the cursor of the new change.
+    /// - `Err(StoreError)` if the change cannot be stored.
+    async fn append_change(
+        &self,
+        key: Option<&str>,
+        kind: ChangeKind,
+    ) -> Result<String, StoreError>;
+
+    /// Reads up to `limit` changes of the feed, oldest first.
+    ///
+    /// # Arguments
+    /// - `after`: The cursor of the last change already processed, or `None` to read
+    ///   from the start of the feed.
+    /// - `limit`: The maximum number of changes to return.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<Change>)` with the changes appended after `after`, empty once the
+    ///   consumer has caught up.
+    /// - `Err(StoreError)` if the cursor is invalid or the feed cannot be read.
+    async fn read_changes(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Change>, StoreError>;
+}
+
+#[async_trait]
+impl<B: ChangeFeedBackend + ?Sized> ChangeFeedBackend for Arc<B> {
+    async fn append_change(
+        &self,
+        key: Option<&str>,
+        kind: ChangeKind,
+    ) -> Result<String, StoreError> {
+        (**self).append_change(key, kind).await
+    }
+
+    async fn read_changes(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Change>, StoreError> {
+        (**self).read_changes(after, limit).await
+    }
+}
+
+#[async_trait]
+impl<B: ChangeFeedBackend + ?Sized> ChangeFeedBackend for &B {
+    async fn append_change(
+        &self,
+        key: Option<&str>,
+        kind: ChangeKind,
+    ) -> Result<String, StoreError> {
+        (**self).append_change(key, kind).await
+    }
+
+    async fn read_changes(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Change>, StoreError> {
+        (**self).read_changes(after, limit).await
+    }
+}
+
+/// Parses the cursor of a feed numbering its changes from 1, returning 0 to read from
+/// the start when there is none.
+pub(crate) fn parse_sequence_cursor(after: Option<&str>) -> Result<i64, StoreError> {
+    match after {
+        None => Ok(0),
+        Some(cursor) => cursor
+            .parse::<i64>()
+            .ok()
+            .filter(|sequence| *sequence >= 0)
+            .ok_or_else(|| StoreError::QueryError(format!("Invalid change cursor `{}`", cursor))),
+    }
+}
+
+/// Builds a change from a row of a `{table}_changes` outbox table.
+pub(crate) fn change_from_row(
+    id: i64,
+    key: Option<String>,
+    kind: &str,
+    recorded_at: i64,
+) -> Result<Change, StoreError> {
+    let kind = ChangeKind::parse(kind)
+        .ok_or_else(|| StoreError::QueryError(format!("Unknown change kind `{}`", kind)))?;
+    Ok(Change {
+        cursor: id.to_string(),
+        key,
+        kind,
+        recorded_at: system_time_from_millis(recorded_at),
+    })
+}
@@ -0,0 +1,92 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Source of the current time for stores that track TTLs themselves, such as
+/// `adapter::inmemory::InMemoryStore`.
+///
+/// Replace the default `SystemClock` with a `ManualClock` to test expirations without
+/// sleeping.
+pub trait Clock: Send + Sync {
+    /// Returns the current instant, never earlier than a previously returned one.
+    fn now(&self) -> Instant;
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+/// The monotonic clock of the operating system. The default `Clock`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clock that only moves when `advance` is called, for tests.
+///
+/// Clones share the same time, so a test keeps one clone to advance the clock of the
+/// store it handed the other to.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use keyv::{adapter::inmemory::InMemoryStore, ManualClock, Store};
+/// # async {
+/// let clock = ManualClock::new();
+/// let store = InMemoryStore::new().clock(clock.clone());
+/// store.set("session", "alice".into(), Some(Duration::from_secs(60))).await.unwrap();
+///
+/// clock.advance(Duration::from_secs(60));
+/// assert_eq!(store.get("session").await.unwrap(), None);
+/// # };
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    origin: Instant,
+    /// Nanoseconds the clock was advanced by since `origin`.
+    elapsed: Arc<AtomicU64>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self {
+            origin: Instant::now(),
+            elapsed: Arc::default(),
+        }
+    }
+}
+
+impl ManualClock {
+    /// Creates a clock stopped at the current instant.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        self.elapsed.fetch_add(nanos, Ordering::SeqCst);
+    }
+
+    /// Returns how far the clock was advanced since it was created.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed.load(Ordering::SeqCst))
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.origin + self.elapsed()
+    }
+}
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use super::redact_credentials;
+
 #[derive(Error, Debug)]
 pub enum StoreError {
     #[error("Failed to connect to the database backend: {0}")]
@@ -17,12 +19,50 @@ pub enum StoreError {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
 
+    #[error("Invalid configuration for `{field}`: {reason}")]
+    InvalidConfiguration { field: String, reason: String },
+
+    #[error("Invalid key `{key}`: {reason}")]
+    InvalidKey { key: String, reason: String },
+
     #[error("Database query error: {0}")]
     QueryError(String),
 
+    #[error("Transaction aborted, it can be retried: {0}")]
+    TransactionAborted(String),
+
+    #[error("Too many store operations in flight (limit: {0})")]
+    ConcurrencyLimitExceeded(usize),
+
+    #[error("Operation not supported by this store: {0}")]
+    Unsupported(&'static str),
+
     #[error("The requested key was not found")]
     NotFound,
 
     #[error("An unknown error has occurred")]
     Unknown,
 }
+
+impl StoreError {
+    /// Creates a `StoreError::ConnectionError`, masking any credentials the message quotes.
+    pub(crate) fn connection<E: std::fmt::Display>(error: E) -> Self {
+        StoreError::ConnectionError(redact_credentials(&error.to_string()))
+    }
+
+    /// Creates a `StoreError::InvalidConfiguration` for the given builder field.
+    pub fn invalid_configuration<F: Into<String>, R: Into<String>>(field: F, reason: R) -> Self {
+        StoreError::InvalidConfiguration {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Creates a `StoreError::InvalidKey` for the given key.
+    pub fn invalid_key<K: Into<String>, R: Into<String>>(key: K, reason: R) -> Self {
+        StoreError::InvalidKey {
+            key: key.into(),
+            reason: reason.into(),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Returns the current time in milliseconds since the Unix epoch, the unit in which
+/// stores persist expirations.
+pub(crate) fn now_millis() -> i64 {
+    millis_since_epoch(SystemTime::now())
+}
+
+/// Converts `time` to milliseconds since the Unix epoch, clamping times before the
+/// epoch to zero.
+pub(crate) fn millis_since_epoch(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| i64::try_from(elapsed.as_millis()).unwrap_or(i64::MAX))
+        .unwrap_or(0)
+}
+
+/// Converts milliseconds since the Unix epoch back to a `SystemTime`.
+pub(crate) fn system_time_from_millis(millis: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(u64::try_from(millis).unwrap_or(0))
+}
+
+/// Returns `ttl` in whole milliseconds. Non-zero TTLs shorter than a millisecond are
+/// rounded up so they still expire instead of being rejected by the backend.
+pub(crate) fn ttl_millis(ttl: Duration) -> u64 {
+    let millis = u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX);
+    if millis == 0 && !ttl.is_zero() {
+        1
+    } else {
+        millis
+    }
+}
+
+/// Returns the expiration, in milliseconds since the Unix epoch, of a value written
+/// now with `ttl`.
+pub(crate) fn expires_at_millis(ttl: Option<Duration>) -> Option<i64> {
+    ttl.map(|ttl| {
+        let ttl = i64::try_from(ttl_millis(ttl)).unwrap_or(i64::MAX);
+        now_millis().saturating_add(ttl)
+    })
+}
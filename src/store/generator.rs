@@ -0,0 +1,52 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Produces the unique ids `Keyv` attaches to what it records, such as version ids of
+/// `layer::history::HistoryStore` and lease tokens.
+///
+/// Replace the default `UuidV7Generator` with a deterministic generator in tests, or
+/// with one issuing snowflake ids in production.
+pub trait Generator: Send + Sync {
+    /// Returns a new id, distinct from every id returned before.
+    fn generate(&self) -> String;
+}
+
+impl<G: Generator + ?Sized> Generator for Arc<G> {
+    fn generate(&self) -> String {
+        (**self).generate()
+    }
+}
+
+/// Random, time-ordered UUIDs of version 7. The default `Generator`, available with
+/// the `runtime` feature.
+#[cfg(feature = "runtime")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UuidV7Generator;
+
+#[cfg(feature = "runtime")]
+impl Generator for UuidV7Generator {
+    fn generate(&self) -> String {
+        uuid::Uuid::now_v7().to_string()
+    }
+}
+
+/// Consecutive numbers from 1, for tests that assert on ids.
+#[derive(Debug, Default)]
+pub struct SequentialGenerator {
+    last: AtomicU64,
+}
+
+impl SequentialGenerator {
+    /// Creates a generator whose first id is `1`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Generator for SequentialGenerator {
+    fn generate(&self) -> String {
+        (self.last.fetch_add(1, Ordering::Relaxed) + 1).to_string()
+    }
+}
@@ -0,0 +1,148 @@
+//! Glob patterns shared by `Store::find_entries` implementations.
+//!
+//! Patterns support `*` (any sequence of characters), `?` (any single character) and
+//! `\` to match the next character literally.
+
+enum Token {
+    Literal(char),
+    AnyOne,
+    AnyMany,
+}
+
+fn tokens(pattern: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '*' => Token::AnyMany,
+            '?' => Token::AnyOne,
+            // A trailing backslash matches itself.
+            '\\' => Token::Literal(chars.next().unwrap_or('\\')),
+            c => Token::Literal(c),
+        });
+    }
+    tokens
+}
+
+/// Returns the part of `pattern` before its first special character. Every key matching
+/// the pattern starts with it.
+pub(crate) fn literal_prefix(pattern: &str) -> &str {
+    let end = pattern.find(['*', '?', '\\']).unwrap_or(pattern.len());
+    &pattern[..end]
+}
+
+/// Returns whether `key` matches `pattern`.
+pub(crate) fn matches(pattern: &str, key: &str) -> bool {
+    let tokens = tokens(pattern);
+    let key: Vec<char> = key.chars().collect();
+    let (mut t, mut k) = (0, 0);
+    // Position of the last `*` and the key position it is currently matched up to.
+    let mut backtrack = None;
+    while k < key.len() {
+        match tokens.get(t) {
+            Some(Token::AnyMany) => {
+                backtrack = Some((t, k));
+                t += 1;
+            }
+            Some(Token::AnyOne) => {
+                t += 1;
+                k += 1;
+            }
+            Some(Token::Literal(c)) if *c == key[k] => {
+                t += 1;
+                k += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    t = star + 1;
+                    k = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    tokens[t..]
+        .iter()
+        .all(|token| matches!(token, Token::AnyMany))
+}
+
+/// Translates `pattern` to a SQL `LIKE` pattern using `\` as the escape character.
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) fn to_like(pattern: &str) -> String {
+    let mut like = String::with_capacity(pattern.len());
+    for token in tokens(pattern) {
+        match token {
+            Token::AnyMany => like.push('%'),
+            Token::AnyOne => like.push('_'),
+            Token::Literal(c) => {
+                if matches!(c, '%' | '_' | '\\') {
+                    like.push('\\');
+                }
+                like.push(c);
+            }
+        }
+    }
+    like
+}
+
+/// Translates `pattern` to an SQLite `GLOB` pattern.
+#[cfg(feature = "sqlite")]
+pub(crate) fn to_sqlite_glob(pattern: &str) -> String {
+    let mut glob = String::with_capacity(pattern.len());
+    for token in tokens(pattern) {
+        match token {
+            Token::AnyMany => glob.push('*'),
+            Token::AnyOne => glob.push('?'),
+            Token::Literal(c) if matches!(c, '*' | '?' | '[') => {
+                glob.push('[');
+                glob.push(c);
+                glob.push(']');
+            }
+            Token::Literal(c) => glob.push(c),
+        }
+    }
+    glob
+}
+
+/// Translates `pattern` to an anchored regular expression in which `.` matches
+/// newlines.
+#[cfg(feature = "mongodb")]
+pub(crate) fn to_regex(pattern: &str) -> String {
+    let mut regex = String::from("(?s)^");
+    for token in tokens(pattern) {
+        match token {
+            Token::AnyMany => regex.push_str(".*"),
+            Token::AnyOne => regex.push('.'),
+            Token::Literal(c) => {
+                if "\\^$.|?*+()[]{}".contains(c) {
+                    regex.push('\\');
+                }
+                regex.push(c);
+            }
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// Translates `pattern` to a Redis `MATCH` pattern matching a superset of its keys.
+///
+/// Redis matches `?` against a single byte rather than a character, so it is widened
+/// to `*`; results must be filtered with `matches`.
+#[cfg(feature = "redis")]
+pub(crate) fn to_redis_superset(pattern: &str) -> String {
+    let mut redis = String::with_capacity(pattern.len());
+    for token in tokens(pattern) {
+        match token {
+            Token::AnyMany | Token::AnyOne => redis.push('*'),
+            Token::Literal(c) => {
+                if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+                    redis.push('\\');
+                }
+                redis.push(c);
+            }
+        }
+    }
+    redis
+}
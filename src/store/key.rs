@@ -0,0 +1,45 @@
+use super::StoreError;
+
+/// Maximum key length, in characters, accepted by every adapter.
+///
+/// This is the length of the MySQL key column, the smallest limit among the backends.
+pub const MAX_KEY_LENGTH: usize = 255;
+
+/// Checks `key` against the key contract shared by all adapters.
+///
+/// Any non-empty string of at most `MAX_KEY_LENGTH` characters without NUL characters
+/// is a valid key. Valid keys, including those containing `:`, whitespace, newlines or
+/// non-ASCII characters, are stored verbatim: they round-trip unchanged and keys that
+/// differ only in case, accents or trailing spaces never collide. `Keyv` checks every
+/// key before it reaches the store; custom front ends can call this directly.
+///
+/// # Returns
+/// - `Ok(())` if the key is valid.
+/// - `Err(StoreError::InvalidKey)` describing why the key is rejected.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::validate_key;
+/// assert!(validate_key("user:42 café\n").is_ok());
+/// assert!(validate_key("").is_err());
+/// assert!(validate_key("nul\0").is_err());
+/// ```
+pub fn validate_key(key: &str) -> Result<(), StoreError> {
+    if key.is_empty() {
+        return Err(StoreError::invalid_key(key, "keys must not be empty"));
+    }
+    if key.contains('\0') {
+        return Err(StoreError::invalid_key(
+            key,
+            "keys must not contain NUL characters",
+        ));
+    }
+    if key.chars().count() > MAX_KEY_LENGTH {
+        return Err(StoreError::invalid_key(
+            key,
+            format!("keys must not exceed {} characters", MAX_KEY_LENGTH),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+/// Turns the canonical bytes of a request into a cache key, see `Keyv::key_for`.
+///
+/// Implementations must give the same output for the same bytes on every platform and
+/// release, or caches shared by several versions of a service stop hitting.
+pub trait KeyHasher: Send + Sync {
+    /// Returns the key for `bytes`.
+    fn hash(&self, bytes: &[u8]) -> String;
+}
+
+/// SipHash-2-4 with fixed keys, hex encoded. The default `KeyHasher`.
+///
+/// Unlike `std::collections::hash_map::DefaultHasher`, whose algorithm may change
+/// between Rust releases, the output is pinned by the SipHash specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SipKeyHasher {
+    k0: u64,
+    k1: u64,
+}
+
+impl<H: KeyHasher + ?Sized> KeyHasher for Arc<H> {
+    fn hash(&self, bytes: &[u8]) -> String {
+        (**self).hash(bytes)
+    }
+}
+
+impl SipKeyHasher {
+    /// Creates a hasher keyed with `k0` and `k1`, e.g. to keep keys of several services
+    /// apart.
+    pub fn with_keys(k0: u64, k1: u64) -> Self {
+        Self { k0, k1 }
+    }
+
+    /// Returns the SipHash-2-4 of `bytes`.
+    pub fn hash_u64(&self, bytes: &[u8]) -> u64 {
+        let mut v = [
+            self.k0 ^ 0x736f_6d65_7073_6575,
+            self.k1 ^ 0x646f_7261_6e64_6f6d,
+            self.k0 ^ 0x6c79_6765_6e65_7261,
+            self.k1 ^ 0x7465_6462_7974_6573,
+        ];
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let m = u64::from_le_bytes(chunk.try_into().expect("chunks are 8 bytes long"));
+            v[3] ^= m;
+            sip_rounds(&mut v, 2);
+            v[0] ^= m;
+        }
+        let mut last = [0u8; 8];
+        last[..chunks.remainder().len()].copy_from_slice(chunks.remainder());
+        last[7] = bytes.len() as u8;
+        let m = u64::from_le_bytes(last);
+        v[3] ^= m;
+        sip_rounds(&mut v, 2);
+        v[0] ^= m;
+
+        v[2] ^= 0xff;
+        sip_rounds(&mut v, 4);
+        v[0] ^ v[1] ^ v[2] ^ v[3]
+    }
+}
+
+impl Default for SipKeyHasher {
+    fn default() -> Self {
+        Self::with_keys(0x6b65_7976_2d72_7573, 0x742d_6b65_7973_2131)
+    }
+}
+
+impl KeyHasher for SipKeyHasher {
+    fn hash(&self, bytes: &[u8]) -> String {
+        format!("{:016x}", self.hash_u64(bytes))
+    }
+}
+
+fn sip_rounds(v: &mut [u64; 4], rounds: usize) {
+    for _ in 0..rounds {
+        v[0] = v[0].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(13);
+        v[1] ^= v[0];
+        v[0] = v[0].rotate_left(32);
+        v[2] = v[2].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(16);
+        v[3] ^= v[2];
+        v[0] = v[0].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(21);
+        v[3] ^= v[0];
+        v[2] = v[2].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(17);
+        v[1] ^= v[2];
+        v[2] = v[2].rotate_left(32);
+    }
+}
+
+/// SHA-256, hex encoded, for when 64-bit SipHash keys collide too easily.
+#[cfg(feature = "hashed-keys")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sha256KeyHasher;
+
+#[cfg(feature = "hashed-keys")]
+impl KeyHasher for Sha256KeyHasher {
+    fn hash(&self, bytes: &[u8]) -> String {
+        use sha2::{Digest, Sha256};
+
+        Sha256::digest(bytes)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
@@ -0,0 +1,381 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Weak},
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde_json::Value;
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use crate::{
+    BatchOp, Capabilities, EvictionPriority, Metadata, ScanEntry, Store, StoreError, Usage, Version,
+};
+
+/// Default number of pending keys that triggers an immediate flush.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 1000;
+
+#[derive(Clone)]
+struct PendingWrite {
+    value: Value,
+    ttl: Option<Duration>,
+}
+
+#[derive(Default)]
+struct Buffer {
+    pending: HashMap<String, PendingWrite>,
+    flushing: HashMap<String, PendingWrite>,
+}
+
+/// Store wrapper that coalesces writes before they reach the backend.
+///
+/// `set` calls are buffered in memory for a short window. Repeated writes to the
+/// same key within the window are merged (last write wins) and distinct keys are
+/// written together through `Store::set_many`, grouped by TTL. Reads see buffered
+/// writes immediately, while removals and clears are applied to the buffer and the
+/// backend alike.
+///
+/// The background flusher is started by `initialize()`. Buffered writes that have
+/// not been flushed yet are lost if the process exits, so call `flush()` during a
+/// graceful shutdown.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore, layer::batching::BatchingStore};
+/// # async {
+/// let store = BatchingStore::new(InMemoryStore::new(), Duration::from_millis(100))
+///     .max_batch_size(500);
+/// let keyv = Keyv::try_new(store).await.unwrap();
+///
+/// keyv.set("requests", 1).await.unwrap();
+/// keyv.set("requests", 2).await.unwrap(); // Only the latest value is written
+/// # };
+/// ```
+pub struct BatchingStore<S: Store + 'static> {
+    store: Arc<S>,
+    buffer: Arc<Mutex<Buffer>>,
+    flush_lock: Arc<Mutex<()>>,
+    window: Duration,
+    max_batch_size: usize,
+    flusher: std::sync::Mutex<Option<JoinHandle<()>>>,
+}
+
+impl<S: Store + 'static> BatchingStore<S> {
+    /// Wraps `store`, flushing buffered writes every `window`.
+    pub fn new(store: S, window: Duration) -> Self {
+        Self {
+            store: Arc::new(store),
+            buffer: Arc::new(Mutex::new(Buffer::default())),
+            flush_lock: Arc::new(Mutex::new(())),
+            window,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            flusher: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Sets the number of distinct pending keys that triggers an immediate flush.
+    pub fn max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size.max(1);
+        self
+    }
+
+    /// Returns a reference to the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.store
+    }
+
+    /// Writes every buffered entry to the wrapped store.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the buffer was flushed.
+    /// - `Err(StoreError)` if the backend rejected a batch. Entries that were not
+    ///   overwritten in the meantime are kept in the buffer for the next flush.
+    pub async fn flush(&self) -> Result<(), StoreError> {
+        flush(&*self.store, &self.buffer, &self.flush_lock).await
+    }
+}
+
+async fn flush<S: Store>(
+    store: &S,
+    buffer: &Mutex<Buffer>,
+    flush_lock: &Mutex<()>,
+) -> Result<(), StoreError> {
+    let _guard = flush_lock.lock().await;
+
+    let writes = {
+        let mut buffer = buffer.lock().await;
+        if buffer.pending.is_empty() {
+            return Ok(());
+        }
+        let writes = std::mem::take(&mut buffer.pending);
+        buffer.flushing = writes.clone();
+        writes
+    };
+
+    let mut batches: HashMap<Option<Duration>, Vec<(&str, Value)>> = HashMap::new();
+    for (key, write) in &writes {
+        batches
+            .entry(write.ttl)
+            .or_default()
+            .push((key.as_str(), write.value.clone()));
+    }
+
+    let mut result = Ok(());
+    for (ttl, entries) in batches {
+        if let Err(e) = store.set_many(&entries, ttl).await {
+            result = Err(e);
+            break;
+        }
+    }
+
+    let mut buffer = buffer.lock().await;
+    let flushed = std::mem::take(&mut buffer.flushing);
+    if result.is_err() {
+        for (key, write) in flushed {
+            buffer.pending.entry(key).or_insert(write);
+        }
+    }
+
+    result
+}
+
+#[async_trait]
+impl<S: Store + 'static> Store for BatchingStore<S> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.store.initialize().await?;
+
+        let store: Weak<S> = Arc::downgrade(&self.store);
+        let buffer = Arc::downgrade(&self.buffer);
+        let flush_lock = Arc::downgrade(&self.flush_lock);
+        let window = self.window;
+
+        let handle = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(window).await;
+                let (Some(store), Some(buffer), Some(flush_lock)) =
+                    (store.upgrade(), buffer.upgrade(), flush_lock.upgrade())
+                else {
+                    break;
+                };
+                if let Err(e) = flush(&*store, &buffer, &flush_lock).await {
+                    log::error!("Failed to flush batched writes: {}", e);
+                }
+            }
+        });
+
+        if let Some(previous) = self.flusher.lock().unwrap().replace(handle) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.store.capabilities()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        {
+            let buffer = self.buffer.lock().await;
+            if let Some(write) = buffer.pending.get(key).or(buffer.flushing.get(key)) {
+                return Ok(Some(write.value.clone()));
+            }
+        }
+        self.store.get(key).await
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        // Timestamps are assigned by the backend, so buffered writes are flushed first.
+        self.flush().await?;
+        self.store.get_with_metadata(key).await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        // Expirations are assigned by the backend, so buffered writes are flushed first.
+        self.flush().await?;
+        self.store.get_stale(key).await
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        // Versions are recorded by the wrapped store, so buffered writes are flushed first.
+        self.flush().await?;
+        self.store.get_versions(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        let should_flush = {
+            let mut buffer = self.buffer.lock().await;
+            buffer
+                .pending
+                .insert(key.to_string(), PendingWrite { value, ttl });
+            buffer.pending.len() >= self.max_batch_size
+        };
+
+        if should_flush {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        let should_flush = {
+            let mut buffer = self.buffer.lock().await;
+            for (key, value) in entries {
+                buffer.pending.insert(
+                    key.to_string(),
+                    PendingWrite {
+                        value: value.clone(),
+                        ttl,
+                    },
+                );
+            }
+            buffer.pending.len() >= self.max_batch_size
+        };
+
+        if should_flush {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        // Prioritized writes are written through, replacing any buffered write for the
+        // key, since the buffer does not track priorities.
+        let _guard = self.flush_lock.lock().await;
+        self.buffer.lock().await.pending.remove(key);
+        self.store
+            .set_with_priority(key, value, ttl, priority)
+            .await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        // Absolute expirations are written through, replacing any buffered write for
+        // the key, so that a delayed flush cannot overwrite them.
+        let _guard = self.flush_lock.lock().await;
+        self.buffer.lock().await.pending.remove(key);
+        self.store.set_until(key, value, expires_at).await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        // The expiration to keep may still be sitting in the buffer, so it is flushed
+        // before writing through.
+        self.flush().await?;
+        self.store.set_keep_ttl(key, value).await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        // A buffered write to the counter must reach the backend before it is added to.
+        self.flush().await?;
+        self.store.increment(key, delta, ttl).await
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        // A buffered write to the key must reach the backend before it is checked.
+        self.flush().await?;
+        self.store.set_if_absent(key, value, ttl).await
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        // Buffered writes must reach the backend before the batch runs after them.
+        self.flush().await?;
+        self.store.execute_batch(ops).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        let _guard = self.flush_lock.lock().await;
+        self.buffer.lock().await.pending.remove(key);
+        self.store.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        let _guard = self.flush_lock.lock().await;
+        {
+            let mut buffer = self.buffer.lock().await;
+            for key in keys {
+                buffer.pending.remove(*key);
+            }
+        }
+        self.store.remove_many(keys).await
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        // A buffered write to the key must reach the backend before it is compared.
+        self.flush().await?;
+        self.store.remove_if(key, expected).await
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        // Buffered writes to either key must land before the move.
+        self.flush().await?;
+        self.store.rename(old_key, new_key).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        let _guard = self.flush_lock.lock().await;
+        self.buffer.lock().await.pending.clear();
+        self.store.clear().await
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        // Count buffered writes, which are otherwise invisible to the inner store.
+        self.flush().await?;
+        self.store.usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        // Buffered writes are flushed first so the scan sees them.
+        let flush = stream::once(self.flush()).filter_map(|result| async { result.err().map(Err) });
+        Box::pin(flush.chain(self.store.scan_entries(prefix, batch_size)))
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        let flush = stream::once(self.flush()).filter_map(|result| async { result.err().map(Err) });
+        Box::pin(flush.chain(self.store.find_entries(pattern, batch_size)))
+    }
+}
+
+impl<S: Store + 'static> Drop for BatchingStore<S> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.flusher.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
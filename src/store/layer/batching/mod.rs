@@ -0,0 +1,2 @@
+mod batching;
+pub use batching::*;
@@ -0,0 +1,258 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use serde_json::Value;
+
+use crate::{
+    BatchOp, Capabilities, ChangeFeedBackend, ChangeKind, EvictionPriority, Metadata, ScanEntry,
+    Store, StoreError, Usage, Version,
+};
+
+/// Store wrapper that appends every mutation to the change feed of the wrapped store,
+/// so an external consumer can follow them with `ChangeFeedBackend::read_changes`.
+///
+/// Unlike pub/sub notifications, the feed is durable: a consumer that was down resumes
+/// from the cursor of the last change it processed. The wrapped store must be a
+/// `ChangeFeedBackend`; the SQL adapters keep the feed in a `{table}_changes` outbox
+/// table, Redis in a stream and `InMemoryStore` in memory. `MongoStore` has no change
+/// feed. Wrap a shared adapter, such as an `Arc<InMemoryStore>`, to read the feed
+/// through `Keyv::with_change_feed`.
+///
+/// Each change is appended once the mutation it describes has been applied, so a
+/// consumer reading the key after seeing its change always finds the new value, and a
+/// failed mutation records nothing. The two writes are not atomic: if appending fails
+/// after the mutation succeeded, the error is returned and the mutation is missing from
+/// the feed. Changes carry keys, not values: consumers read the current value of the
+/// key when processing a change. Expirations and TTL updates are not recorded.
+///
+/// `clear` records a `ChangeKind::Clear` change and leaves the feed in place. Nothing
+/// trims the feed: delete consumed changes from the backend once every consumer has
+/// read them.
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore, layer::change_feed::ChangeFeedStore};
+/// # async {
+/// let backend = Arc::new(InMemoryStore::new());
+/// let keyv = Keyv::try_new(ChangeFeedStore::new(backend.clone()))
+///     .await
+///     .unwrap()
+///     .with_change_feed(backend);
+/// keyv.set("config", "v1").await.unwrap();
+///
+/// let changes = keyv.changes(None).await.unwrap();
+/// assert_eq!(changes[0].key.as_deref(), Some("config"));
+/// # };
+/// ```
+pub struct ChangeFeedStore<S: Store + ChangeFeedBackend> {
+    store: S,
+}
+
+impl<S: Store + ChangeFeedBackend> ChangeFeedStore<S> {
+    /// Wraps `store`, recording its mutations in its change feed.
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    /// Returns a reference to the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.store
+    }
+
+    async fn record(&self, key: &str, kind: ChangeKind) -> Result<(), StoreError> {
+        self.store.append_change(Some(key), kind).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<S: Store + ChangeFeedBackend> Store for ChangeFeedStore<S> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.store.initialize().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.store.capabilities()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.store.get(key).await
+    }
+
+    async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, StoreError> {
+        self.store.get_ref(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.store.get_many(keys).await
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.store.get_with_metadata(key).await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.store.get_stale(key).await
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        self.store.get_versions(key).await
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        self.store.get_and_touch(key, ttl).await
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        self.store.touch_many(keys, ttl).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.store.set(key, value, ttl).await?;
+        self.record(key, ChangeKind::Set).await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        self.store.set_many(entries, ttl).await?;
+        for (key, _) in entries {
+            self.record(key, ChangeKind::Set).await?;
+        }
+        Ok(())
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        self.store
+            .set_with_priority(key, value, ttl, priority)
+            .await?;
+        self.record(key, ChangeKind::Set).await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        self.store.set_until(key, value, expires_at).await?;
+        self.record(key, ChangeKind::Set).await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        self.store.set_keep_ttl(key, value).await?;
+        self.record(key, ChangeKind::Set).await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        let value = self.store.increment(key, delta, ttl).await?;
+        self.record(key, ChangeKind::Set).await?;
+        Ok(value)
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        let written = self.store.set_if_absent(key, value, ttl).await?;
+        if written {
+            self.record(key, ChangeKind::Set).await?;
+        }
+        Ok(written)
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        let changes: Vec<Option<(String, ChangeKind)>> = ops
+            .iter()
+            .map(|op| match op {
+                BatchOp::Set { key, .. } => Some((key.clone(), ChangeKind::Set)),
+                BatchOp::Remove { key } => Some((key.clone(), ChangeKind::Remove)),
+                BatchOp::Touch { .. } => None,
+            })
+            .collect();
+        let results = self.store.execute_batch(ops).await?;
+        for (change, result) in changes.iter().zip(&results) {
+            if let (Some((key, kind)), Ok(())) = (change, result) {
+                self.record(key, *kind).await?;
+            }
+        }
+        Ok(results)
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.store.remove(key).await?;
+        self.record(key, ChangeKind::Remove).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.store.remove_many(keys).await?;
+        for key in keys {
+            self.record(key, ChangeKind::Remove).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        let removed = self.store.remove_if(key, expected).await?;
+        if removed {
+            self.record(key, ChangeKind::Remove).await?;
+        }
+        Ok(removed)
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        self.store.rename(old_key, new_key).await?;
+        self.record(old_key, ChangeKind::Remove).await?;
+        self.record(new_key, ChangeKind::Set).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.store.clear().await?;
+        self.store.append_change(None, ChangeKind::Clear).await?;
+        Ok(())
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        self.store.usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store.scan_entries(prefix, batch_size)
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store.find_entries(pattern, batch_size)
+    }
+}
@@ -0,0 +1,2 @@
+mod change_feed;
+pub use change_feed::*;
@@ -0,0 +1,84 @@
+use std::io::{Read, Write};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use crate::{layer::transform::Transformer, StoreError};
+
+/// Default size, in bytes of serialized JSON, from which values are compressed.
+pub const DEFAULT_MIN_SIZE: usize = 1024;
+
+/// `Transformer` compressing large values with gzip, identified as `gzip`.
+///
+/// Values shorter than `min_size`, or that compression would not make smaller, are
+/// left unchanged, so small values and counters are stored as plain JSON. The output
+/// is standard gzip, as produced by Node's `zlib.gzipSync`.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore, layer::{compression::Gzip, transform::TransformStore}};
+/// # async {
+/// let store = TransformStore::new(InMemoryStore::new()).transformer(Gzip::new().min_size(256));
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// keyv.set("report", "x".repeat(10_000)).await.unwrap(); // Stored gzipped
+/// # };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Gzip {
+    min_size: usize,
+    level: Compression,
+}
+
+impl Default for Gzip {
+    fn default() -> Self {
+        Self {
+            min_size: DEFAULT_MIN_SIZE,
+            level: Compression::default(),
+        }
+    }
+}
+
+impl Gzip {
+    /// Creates a transformer compressing values of at least `DEFAULT_MIN_SIZE` bytes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the size, in bytes of serialized JSON, from which values are compressed.
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Sets the gzip compression level, from 0 to 9.
+    pub fn level(mut self, level: u32) -> Self {
+        self.level = Compression::new(level.min(9));
+        self
+    }
+}
+
+impl Transformer for Gzip {
+    fn id(&self) -> &str {
+        "gzip"
+    }
+
+    fn apply(&self, data: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        if data.len() < self.min_size {
+            return Ok(None);
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), self.level);
+        let compressed = encoder
+            .write_all(data)
+            .and_then(|()| encoder.finish())
+            .map_err(|e| StoreError::QueryError(format!("Failed to compress value: {}", e)))?;
+        Ok((compressed.len() < data.len()).then_some(compressed))
+    }
+
+    fn revert(&self, data: &[u8]) -> Result<Vec<u8>, StoreError> {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(data)
+            .read_to_end(&mut decompressed)
+            .map_err(|e| StoreError::QueryError(format!("Invalid gzip compressed value: {}", e)))?;
+        Ok(decompressed)
+    }
+}
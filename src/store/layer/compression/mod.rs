@@ -0,0 +1,2 @@
+mod compression;
+pub use compression::*;
@@ -0,0 +1,269 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use serde_json::Value;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::{
+    BatchOp, Capabilities, EvictionPriority, Metadata, ScanEntry, Store, StoreError, Usage, Version,
+};
+
+/// What to do with an operation when the concurrency limit has been reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConcurrencyMode {
+    /// Wait until one of the in-flight operations completes.
+    Queue,
+    /// Fail immediately with `StoreError::ConcurrencyLimitExceeded`.
+    FailFast,
+}
+
+/// Store wrapper that caps the number of in-flight operations on the backend.
+///
+/// Useful when the backend connection pool is shared with other parts of the
+/// application and a traffic spike must not exhaust it.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore};
+/// # use keyv::layer::concurrency::{ConcurrencyLimitStore, ConcurrencyMode};
+/// # async {
+/// let store = ConcurrencyLimitStore::new(InMemoryStore::new(), 16, ConcurrencyMode::Queue);
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// # };
+/// ```
+pub struct ConcurrencyLimitStore<S: Store> {
+    store: S,
+    semaphore: Semaphore,
+    max_in_flight: usize,
+    mode: ConcurrencyMode,
+}
+
+impl<S: Store> ConcurrencyLimitStore<S> {
+    /// Wraps `store`, allowing at most `max_in_flight` concurrent operations.
+    pub fn new(store: S, max_in_flight: usize, mode: ConcurrencyMode) -> Self {
+        let max_in_flight = max_in_flight.max(1);
+        Self {
+            store,
+            semaphore: Semaphore::new(max_in_flight),
+            max_in_flight,
+            mode,
+        }
+    }
+
+    /// Returns a reference to the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the number of operations that can currently start without waiting.
+    pub fn available_permits(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+
+    async fn acquire(&self) -> Result<SemaphorePermit<'_>, StoreError> {
+        match self.mode {
+            ConcurrencyMode::Queue => self
+                .semaphore
+                .acquire()
+                .await
+                .map_err(|_| StoreError::Unknown),
+            ConcurrencyMode::FailFast => self
+                .semaphore
+                .try_acquire()
+                .map_err(|_| StoreError::ConcurrencyLimitExceeded(self.max_in_flight)),
+        }
+    }
+
+    /// Takes a permit for each batch polled from `batches`, released before the batch
+    /// is handed over, so a long-running scan does not hold one while it is consumed.
+    fn limit_batches<'a>(
+        &'a self,
+        batches: BoxStream<'a, Result<Vec<ScanEntry>, StoreError>>,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        Box::pin(stream::unfold(Some(batches), move |batches| async move {
+            let mut batches = batches?;
+            let permit = match self.acquire().await {
+                Ok(permit) => permit,
+                Err(e) => return Some((Err(e), None)),
+            };
+            let batch = batches.next().await;
+            drop(permit);
+            batch.map(|batch| (batch, Some(batches)))
+        }))
+    }
+}
+
+#[async_trait]
+impl<S: Store> Store for ConcurrencyLimitStore<S> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.store.initialize().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.store.capabilities()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.get(key).await
+    }
+
+    async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.get_ref(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.get_many(keys).await
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.get_with_metadata(key).await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.get_stale(key).await
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.get_versions(key).await
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.get_and_touch(key, ttl).await
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.touch_many(keys, ttl).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.set(key, value, ttl).await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.set_many(entries, ttl).await
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        let _permit = self.acquire().await?;
+        self.store
+            .set_with_priority(key, value, ttl, priority)
+            .await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.set_until(key, value, expires_at).await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.set_keep_ttl(key, value).await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.increment(key, delta, ttl).await
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.set_if_absent(key, value, ttl).await
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.execute_batch(ops).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.remove_many(keys).await
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.remove_if(key, expected).await
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.rename(old_key, new_key).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.clear().await
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        let _permit = self.acquire().await?;
+        self.store.usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.limit_batches(self.store.scan_entries(prefix, batch_size))
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.limit_batches(self.store.find_entries(pattern, batch_size))
+    }
+}
@@ -0,0 +1,2 @@
+mod concurrency;
+pub use concurrency::*;
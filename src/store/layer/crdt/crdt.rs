@@ -0,0 +1,406 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use futures::{stream::BoxStream, TryStreamExt};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    store::expiry::now_millis, Capabilities, EvictionPriority, Metadata, ScanEntry, Store,
+    StoreError, Usage, DEFAULT_SCAN_BATCH_SIZE,
+};
+
+const VALUE_FIELD: &str = "value";
+const TIMESTAMP_FIELD: &str = "timestamp";
+const DELETED_FIELD: &str = "deleted";
+
+/// A hybrid logical clock reading: the physical time in milliseconds, a counter
+/// ordering events within the same millisecond, and the node that produced it to break
+/// ties between replicas.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Timestamp {
+    millis: u64,
+    counter: u32,
+    node: String,
+}
+
+impl Timestamp {
+    fn to_json(&self) -> Value {
+        json!([self.millis, self.counter, self.node])
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        match value.as_array()?.as_slice() {
+            [millis, counter, node] => Some(Self {
+                millis: millis.as_u64()?,
+                counter: u32::try_from(counter.as_u64()?).ok()?,
+                node: node.as_str()?.to_string(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// A value or a deletion, stamped with the time it was written.
+struct Register {
+    value: Option<Value>,
+    timestamp: Timestamp,
+}
+
+impl Register {
+    fn to_json(&self) -> Value {
+        json!({
+            VALUE_FIELD: self.value.clone().unwrap_or(Value::Null),
+            TIMESTAMP_FIELD: self.timestamp.to_json(),
+            DELETED_FIELD: self.value.is_none(),
+        })
+    }
+
+    /// Parses a register written by `LwwStore`, or returns `None` for any other value.
+    fn from_json(value: &Value) -> Option<Self> {
+        let envelope = value.as_object().filter(|envelope| is_envelope(envelope))?;
+        let timestamp = Timestamp::from_json(&envelope[TIMESTAMP_FIELD])?;
+        let deleted = envelope[DELETED_FIELD].as_bool()?;
+        Some(Self {
+            value: (!deleted).then(|| envelope[VALUE_FIELD].clone()),
+            timestamp,
+        })
+    }
+}
+
+fn is_envelope(envelope: &Map<String, Value>) -> bool {
+    envelope.len() == 3
+        && envelope.contains_key(VALUE_FIELD)
+        && envelope.contains_key(TIMESTAMP_FIELD)
+        && envelope.contains_key(DELETED_FIELD)
+}
+
+/// Unwraps a stored value, returning `None` for a tombstone and values written without
+/// the wrapper as is.
+fn unwrap(value: Value) -> Option<Value> {
+    match Register::from_json(&value) {
+        Some(register) => register.value,
+        None => Some(value),
+    }
+}
+
+/// The hybrid logical clock of one replica.
+struct Clock {
+    node: String,
+    last: Mutex<(u64, u32)>,
+}
+
+impl Clock {
+    /// Returns a timestamp greater than every one this clock issued or observed.
+    fn now(&self) -> Timestamp {
+        let physical = u64::try_from(now_millis()).unwrap_or(0);
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        *last = if physical > last.0 {
+            (physical, 0)
+        } else {
+            match last.1.checked_add(1) {
+                Some(counter) => (last.0, counter),
+                None => (last.0 + 1, 0),
+            }
+        };
+        Timestamp {
+            millis: last.0,
+            counter: last.1,
+            node: self.node.clone(),
+        }
+    }
+
+    /// Advances the clock past a timestamp received from another replica, so that
+    /// later local writes win over it.
+    fn observe(&self, timestamp: &Timestamp) {
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        *last = (*last).max((timestamp.millis, timestamp.counter));
+    }
+}
+
+/// The result of `LwwStore::sync`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Number of entries copied from the remote store.
+    pub pulled: u64,
+    /// Number of entries copied to the remote store.
+    pub pushed: u64,
+}
+
+/// Store wrapper making every key a last-writer-wins register, so that replicas
+/// written independently converge once synced.
+///
+/// Meant for offline-first applications: a desktop or mobile app writes to a local
+/// store, typically `SqliteStore`, and reconciles with a server store with `sync` when
+/// it is online. Every write is stamped with a hybrid logical clock timestamp, which
+/// follows the wall clock but never goes backwards and is advanced by every timestamp
+/// received, and the write with the greatest timestamp wins on both sides. Ties are
+/// broken by the node id, which must be unique per replica.
+///
+/// Values are written inside an envelope `{"value": ..., "timestamp": ..., "deleted":
+/// ...}`. Removing a key writes a tombstone so that the removal wins over older writes
+/// on other replicas; tombstones are kept, and `clear` writes one for every key.
+/// Operations that read and write in one step, such as `increment` or
+/// `set_if_absent`, are not supported.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, Store, adapter::inmemory::InMemoryStore, layer::crdt::LwwStore};
+/// # async {
+/// let laptop = LwwStore::new(InMemoryStore::new(), "laptop");
+/// let server = LwwStore::new(InMemoryStore::new(), "server");
+///
+/// laptop.set("draft", "offline edit".into(), None).await.unwrap();
+/// let report = laptop.sync(&server).await.unwrap();
+/// assert_eq!(report.pushed, 1);
+/// assert_eq!(server.get("draft").await.unwrap(), Some("offline edit".into()));
+/// # };
+/// ```
+pub struct LwwStore<S: Store> {
+    store: S,
+    clock: Clock,
+}
+
+impl<S: Store> LwwStore<S> {
+    /// Wraps `store` as the replica named `node`.
+    pub fn new(store: S, node: impl Into<String>) -> Self {
+        Self {
+            store,
+            clock: Clock {
+                node: node.into(),
+                last: Mutex::new((0, 0)),
+            },
+        }
+    }
+
+    /// Returns a reference to the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the id of the replica.
+    pub fn node(&self) -> &str {
+        &self.clock.node
+    }
+
+    fn wrap(&self, value: Option<Value>) -> Value {
+        Register {
+            value,
+            timestamp: self.clock.now(),
+        }
+        .to_json()
+    }
+
+    /// Reconciles this replica with `remote`, in both directions.
+    ///
+    /// Both stores are scanned and, for every key, the register with the greater
+    /// timestamp is copied to the replica holding an older one or none. Only the
+    /// differing entries are written. Values written without the wrapper have no
+    /// timestamp and are left alone. Each register is compared again just before it is
+    /// overwritten, which keeps most writes made during the sync, but the comparison
+    /// and the write are not atomic.
+    ///
+    /// Both wrapped stores must be able to scan their keys.
+    ///
+    /// # Returns
+    /// - `Ok(SyncReport)` with the number of entries copied each way.
+    /// - `Err(StoreError)` if either store fails. Entries copied before the failure
+    ///   are kept; syncing again completes the reconciliation.
+    pub async fn sync<R: Store>(&self, remote: &LwwStore<R>) -> Result<SyncReport, StoreError> {
+        let mut local = self.registers().await?;
+        let remote_registers = remote.registers().await?;
+        let mut report = SyncReport::default();
+
+        for (key, (theirs, ttl)) in remote_registers {
+            self.clock.observe(&theirs.timestamp);
+            match local.remove(&key) {
+                Some((ours, _)) if ours.timestamp == theirs.timestamp => {}
+                Some((ours, ours_ttl)) if ours.timestamp > theirs.timestamp => {
+                    remote.clock.observe(&ours.timestamp);
+                    if remote.merge(&key, ours, ours_ttl).await? {
+                        report.pushed += 1;
+                    }
+                }
+                _ => {
+                    if self.merge(&key, theirs, ttl).await? {
+                        report.pulled += 1;
+                    }
+                }
+            }
+        }
+        for (key, (ours, ttl)) in local {
+            remote.clock.observe(&ours.timestamp);
+            if remote.merge(&key, ours, ttl).await? {
+                report.pushed += 1;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Reads every register of the wrapped store, with its remaining TTL.
+    async fn registers(&self) -> Result<HashMap<String, (Register, Option<Duration>)>, StoreError> {
+        let mut registers = HashMap::new();
+        let mut batches = self.store.scan_entries(None, DEFAULT_SCAN_BATCH_SIZE);
+        while let Some(batch) = batches.try_next().await? {
+            for entry in batch {
+                if let Some(register) = Register::from_json(&entry.value) {
+                    registers.insert(entry.key.clone(), (register, entry.expires_in()));
+                }
+            }
+        }
+        Ok(registers)
+    }
+
+    /// Writes `register` under `key` unless the stored register is as recent.
+    async fn merge(
+        &self,
+        key: &str,
+        register: Register,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        let current = self.store.get(key).await?;
+        if let Some(current) = current.as_ref().and_then(Register::from_json) {
+            if current.timestamp >= register.timestamp {
+                return Ok(false);
+            }
+        }
+        self.store.set(key, register.to_json(), ttl).await?;
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl<S: Store> Store for LwwStore<S> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.store.initialize().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            atomic_increment: false,
+            transactions: false,
+            ..self.store.capabilities()
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        Ok(self.store.get(key).await?.and_then(unwrap))
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        Ok(self
+            .store
+            .get_many(keys)
+            .await?
+            .into_iter()
+            .map(|value| value.and_then(unwrap))
+            .collect())
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        Ok(self
+            .store
+            .get_with_metadata(key)
+            .await?
+            .and_then(|(value, metadata)| Some((unwrap(value)?, metadata))))
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        Ok(self
+            .store
+            .get_stale(key)
+            .await?
+            .and_then(|(value, metadata)| Some((unwrap(value)?, metadata))))
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.store.set(key, self.wrap(Some(value)), ttl).await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        let entries: Vec<(&str, Value)> = entries
+            .iter()
+            .map(|(key, value)| (*key, self.wrap(Some(value.clone()))))
+            .collect();
+        self.store.set_many(&entries, ttl).await
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        self.store
+            .set_with_priority(key, self.wrap(Some(value)), ttl, priority)
+            .await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        self.store
+            .set_until(key, self.wrap(Some(value)), expires_at)
+            .await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        self.store.set_keep_ttl(key, self.wrap(Some(value))).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.store.set(key, self.wrap(None), None).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        let tombstones: Vec<(&str, Value)> =
+            keys.iter().map(|key| (*key, self.wrap(None))).collect();
+        self.store.set_many(&tombstones, None).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        // Removing the rows would let other replicas resurrect them on the next sync.
+        let keys: Vec<String> = self
+            .scan_entries(None, DEFAULT_SCAN_BATCH_SIZE)
+            .map_ok(|batch| batch.into_iter().map(|entry| entry.key).collect::<Vec<_>>())
+            .try_concat()
+            .await?;
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        self.remove_many(&keys).await
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        self.store.usage().await
+    }
+
+    /// Tombstones are skipped.
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        Box::pin(self.store.scan_entries(prefix, batch_size).map_ok(|batch| {
+            batch
+                .into_iter()
+                .filter_map(|entry| {
+                    Some(ScanEntry {
+                        value: unwrap(entry.value)?,
+                        ..entry
+                    })
+                })
+                .collect()
+        }))
+    }
+}
@@ -0,0 +1,2 @@
+mod crdt;
+pub use crdt::*;
@@ -0,0 +1,293 @@
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use futures::{stream::BoxStream, StreamExt, TryStreamExt};
+use serde_json::Value;
+
+use crate::{
+    BatchOp, Capabilities, EvictionPriority, Metadata, ScanEntry, Store, StoreError, Usage, Version,
+};
+
+/// Store wrapper that migrates from an old backend to a new one lazily, as keys are
+/// read.
+///
+/// Reads are served by the new store. A key missing from it is looked up in the old
+/// store and, if found, copied to the new store with its expiration before being
+/// returned, so only the keys that are actually used are migrated. Writes go to the new
+/// store only; operations that depend on the current entry, such as `increment` or
+/// `set_if_absent`, copy it from the old store first.
+///
+/// Removals are applied to both stores, otherwise a removed key would be read back
+/// from the old store. For the same reason `clear` clears both stores. Scans list the
+/// entries of the new store, then those of the old store that were not migrated yet.
+/// Once the old store is no longer hit, replace this layer with the new store.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, Store, adapter::inmemory::InMemoryStore, layer::dual_read::DualReadStore};
+/// # async {
+/// let old = InMemoryStore::new();
+/// old.set("user:1", "alice".into(), None).await.unwrap();
+///
+/// let keyv = Keyv::try_new(DualReadStore::new(InMemoryStore::new(), old)).await.unwrap();
+/// // Read from the old store and copied to the new one.
+/// assert_eq!(keyv.get("user:1").await.unwrap(), Some("alice".into()));
+/// # };
+/// ```
+pub struct DualReadStore<N: Store, O: Store> {
+    new: N,
+    old: O,
+}
+
+impl<N: Store, O: Store> DualReadStore<N, O> {
+    /// Reads from `new`, falling back to and migrating from `old`.
+    pub fn new(new: N, old: O) -> Self {
+        Self { new, old }
+    }
+
+    /// Returns a reference to the store being migrated to.
+    pub fn new_store(&self) -> &N {
+        &self.new
+    }
+
+    /// Returns a reference to the store being migrated from.
+    pub fn old_store(&self) -> &O {
+        &self.old
+    }
+
+    /// Copies `key` from the old store to the new one if the new store does not hold
+    /// it, returning the entry or `None` if neither store holds it.
+    async fn backfill(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        if let Some(entry) = self.new.get_with_metadata(key).await? {
+            return Ok(Some(entry));
+        }
+        let Some((value, metadata)) = self.old.get_with_metadata(key).await? else {
+            return Ok(None);
+        };
+        match metadata.expires_at {
+            Some(expires_at) => self.new.set_until(key, value.clone(), expires_at).await?,
+            None => self.new.set(key, value.clone(), None).await?,
+        }
+        Ok(Some((value, metadata)))
+    }
+
+    async fn backfill_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        for key in keys {
+            self.backfill(key).await?;
+        }
+        Ok(())
+    }
+
+    /// Drops the entries of an old store batch that the new store also holds, as they
+    /// were already listed from the new store.
+    async fn not_migrated(&self, batch: Vec<ScanEntry>) -> Result<Vec<ScanEntry>, StoreError> {
+        let keys: Vec<&str> = batch.iter().map(|entry| entry.key.as_str()).collect();
+        let migrated = self.new.get_many(&keys).await?;
+        Ok(batch
+            .into_iter()
+            .zip(migrated)
+            .filter_map(|(entry, value)| value.is_none().then_some(entry))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl<N: Store, O: Store> Store for DualReadStore<N, O> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.new.initialize().await?;
+        self.old.initialize().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let old = self.old.capabilities();
+        let new = self.new.capabilities();
+        Capabilities {
+            native_ttl: new.native_ttl && old.native_ttl,
+            scan: new.scan && old.scan,
+            ..new
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        if let Some(value) = self.new.get(key).await? {
+            return Ok(Some(value));
+        }
+        Ok(self.backfill(key).await?.map(|(value, _)| value))
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let mut values = self.new.get_many(keys).await?;
+        for (key, value) in keys.iter().zip(values.iter_mut()) {
+            if value.is_none() {
+                *value = self.backfill(key).await?.map(|(value, _)| value);
+            }
+        }
+        Ok(values)
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.backfill(key).await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        // Expired entries are not worth migrating, so they are read in place.
+        match self.new.get_stale(key).await? {
+            Some(entry) => Ok(Some(entry)),
+            None => self.old.get_stale(key).await,
+        }
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        self.new.get_versions(key).await
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        self.backfill(key).await?;
+        self.new.get_and_touch(key, ttl).await
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        self.backfill_many(keys).await?;
+        self.new.touch_many(keys, ttl).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.new.set(key, value, ttl).await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        self.new.set_many(entries, ttl).await
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        self.new.set_with_priority(key, value, ttl, priority).await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        self.new.set_until(key, value, expires_at).await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        self.backfill(key).await?;
+        self.new.set_keep_ttl(key, value).await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        self.backfill(key).await?;
+        self.new.increment(key, delta, ttl).await
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        self.backfill(key).await?;
+        self.new.set_if_absent(key, value, ttl).await
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        let mut removed = Vec::new();
+        for op in &ops {
+            match op {
+                BatchOp::Touch { key, .. } => {
+                    self.backfill(key).await?;
+                }
+                BatchOp::Remove { key } => removed.push(key.clone()),
+                BatchOp::Set { .. } => {}
+            }
+        }
+        let results = self.new.execute_batch(ops).await?;
+        let removed: Vec<&str> = removed.iter().map(String::as_str).collect();
+        if !removed.is_empty() {
+            self.old.remove_many(&removed).await?;
+        }
+        Ok(results)
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.new.remove(key).await?;
+        self.old.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.new.remove_many(keys).await?;
+        self.old.remove_many(keys).await
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        self.backfill(key).await?;
+        let removed = self.new.remove_if(key, expected).await?;
+        if removed {
+            self.old.remove(key).await?;
+        }
+        Ok(removed)
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        self.backfill(old_key).await?;
+        self.new.rename(old_key, new_key).await?;
+        self.old.remove_many(&[old_key, new_key]).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.new.clear().await?;
+        self.old.clear().await
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        self.new.usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        let old = self
+            .old
+            .scan_entries(prefix, batch_size)
+            .and_then(move |batch| self.not_migrated(batch));
+        self.new.scan_entries(prefix, batch_size).chain(old).boxed()
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        let old = self
+            .old
+            .find_entries(pattern, batch_size)
+            .and_then(move |batch| self.not_migrated(batch));
+        self.new
+            .find_entries(pattern, batch_size)
+            .chain(old)
+            .boxed()
+    }
+}
@@ -0,0 +1,2 @@
+mod dual_read;
+pub use dual_read::*;
@@ -0,0 +1,71 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::layer::key_codec::{KeyCodec, KeyCodecStore};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Key codec replacing every key with its hex encoded HMAC-SHA256 under a secret.
+///
+/// Hashing is one-way, so keys cannot be decoded and scans through the codec are
+/// unsupported.
+#[derive(Clone)]
+pub struct HmacCodec {
+    secret: Vec<u8>,
+}
+
+impl HmacCodec {
+    /// Creates a codec hashing keys with the given secret.
+    pub fn new<K: AsRef<[u8]>>(secret: K) -> Self {
+        Self {
+            secret: secret.as_ref().to_vec(),
+        }
+    }
+}
+
+impl KeyCodec for HmacCodec {
+    fn encode(&self, key: &str) -> String {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(key.as_bytes());
+
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    fn decode(&self, _key: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Store wrapper that never writes logical keys to the backend in plaintext.
+///
+/// Every key is replaced by the hex encoded HMAC-SHA256 of the key under a
+/// secret before it reaches the wrapped store. The mapping is deterministic,
+/// so lookups keep working, but the backing store only ever sees digests and
+/// the original identifiers cannot be recovered from it. The same secret must
+/// be used to read the data back.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore};
+/// # use keyv::layer::hashed_keys::{HashedKeyStore, HmacCodec};
+/// # async {
+/// let store = HashedKeyStore::new(InMemoryStore::new(), HmacCodec::new(b"my-secret"));
+/// let keyv = Keyv::try_new(store).await.unwrap();
+///
+/// keyv.set("user:alice@example.com", "profile").await.unwrap();
+/// # };
+/// ```
+pub type HashedKeyStore<S> = KeyCodecStore<S, HmacCodec>;
+
+impl<S> KeyCodecStore<S, HmacCodec> {
+    /// Returns the physical key the wrapped store uses for `key`.
+    pub fn hash_key(&self, key: &str) -> String {
+        self.codec().encode(key)
+    }
+}
@@ -0,0 +1,2 @@
+mod hashed_keys;
+pub use hashed_keys::*;
@@ -0,0 +1,298 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use crate::{
+    store::expiry::{millis_since_epoch, system_time_from_millis},
+    BatchOp, Capabilities, EvictionPriority, Generator, Metadata, ScanEntry, Store, StoreError,
+    Usage, UuidV7Generator, Version,
+};
+
+/// Store wrapper that records every value written under a key in a sibling store, so
+/// prior values can be listed with `Store::get_versions`.
+///
+/// Meant for auditing values that change rarely, such as configuration. Each write
+/// through the layer appends a version to the history of the key, which keeps the
+/// `max_versions` most recent ones. The history is stored as one JSON array per key in
+/// `history`, typically the same backend on a sibling table or collection.
+///
+/// Writes through the layer are serialized so that the history records them in the
+/// order they were applied. Writes made by another process or directly on the wrapped
+/// store are not recorded. Removing, renaming or clearing keys leaves their history in
+/// place; clear `history` separately to drop it.
+///
+/// Every version gets an id from a `Generator`, `UuidV7Generator` unless set with
+/// `generator`.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore, layer::history::HistoryStore};
+/// # async {
+/// let store = HistoryStore::new(InMemoryStore::new(), InMemoryStore::new(), 5);
+/// let keyv = Keyv::try_new(store).await.unwrap();
+///
+/// keyv.set("config", "v1").await.unwrap();
+/// keyv.set("config", "v2").await.unwrap();
+/// let versions = keyv.get_versions("config").await.unwrap();
+/// assert_eq!(versions[0].value, "v2");
+/// # };
+/// ```
+pub struct HistoryStore<S: Store, H: Store> {
+    store: S,
+    history: H,
+    max_versions: usize,
+    generator: Arc<dyn Generator>,
+    write_lock: Mutex<()>,
+}
+
+impl<S: Store, H: Store> HistoryStore<S, H> {
+    /// Wraps `store`, recording up to `max_versions` versions per key in `history`.
+    pub fn new(store: S, history: H, max_versions: usize) -> Self {
+        Self {
+            store,
+            history,
+            max_versions: max_versions.max(1),
+            generator: Arc::new(UuidV7Generator),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Sets the generator of version ids.
+    pub fn generator<G: Generator + 'static>(mut self, generator: G) -> Self {
+        self.generator = Arc::new(generator);
+        self
+    }
+
+    /// Returns a reference to the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns a reference to the store holding the history.
+    pub fn history(&self) -> &H {
+        &self.history
+    }
+
+    /// Appends `value` to the history of `key`, dropping the oldest versions beyond
+    /// `max_versions`. Callers must hold `write_lock`.
+    async fn record(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        let mut versions = match self.history.get(key).await? {
+            Some(Value::Array(versions)) => versions,
+            _ => Vec::new(),
+        };
+        versions.push(json!({
+            "id": self.generator.generate(),
+            "value": value,
+            "recorded_at": millis_since_epoch(SystemTime::now()),
+        }));
+        let excess = versions.len().saturating_sub(self.max_versions);
+        versions.drain(..excess);
+        self.history.set(key, Value::Array(versions), None).await
+    }
+}
+
+#[async_trait]
+impl<S: Store, H: Store> Store for HistoryStore<S, H> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.store.initialize().await?;
+        self.history.initialize().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.store.capabilities()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.store.get(key).await
+    }
+
+    async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, StoreError> {
+        self.store.get_ref(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.store.get_many(keys).await
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.store.get_with_metadata(key).await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.store.get_stale(key).await
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        let versions = match self.history.get(key).await? {
+            Some(Value::Array(versions)) => versions,
+            _ => return Ok(Vec::new()),
+        };
+        Ok(versions
+            .into_iter()
+            .rev()
+            .filter_map(|mut version| {
+                let recorded_at = version.get("recorded_at")?.as_i64()?;
+                Some(Version {
+                    id: version
+                        .get("id")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                    value: version.get_mut("value")?.take(),
+                    recorded_at: system_time_from_millis(recorded_at),
+                })
+            })
+            .collect())
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        self.store.get_and_touch(key, ttl).await
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        self.store.touch_many(keys, ttl).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        let _guard = self.write_lock.lock().await;
+        self.store.set(key, value.clone(), ttl).await?;
+        self.record(key, value).await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        let _guard = self.write_lock.lock().await;
+        self.store.set_many(entries, ttl).await?;
+        for (key, value) in entries {
+            self.record(key, value.clone()).await?;
+        }
+        Ok(())
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        let _guard = self.write_lock.lock().await;
+        self.store
+            .set_with_priority(key, value.clone(), ttl, priority)
+            .await?;
+        self.record(key, value).await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        let _guard = self.write_lock.lock().await;
+        self.store.set_until(key, value.clone(), expires_at).await?;
+        self.record(key, value).await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        let _guard = self.write_lock.lock().await;
+        self.store.set_keep_ttl(key, value.clone()).await?;
+        self.record(key, value).await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let value = self.store.increment(key, delta, ttl).await?;
+        self.record(key, value.into()).await?;
+        Ok(value)
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let written = self.store.set_if_absent(key, value.clone(), ttl).await?;
+        if written {
+            self.record(key, value).await?;
+        }
+        Ok(written)
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        let _guard = self.write_lock.lock().await;
+        let writes: Vec<_> = ops
+            .iter()
+            .map(|op| match op {
+                BatchOp::Set { key, value, .. } => Some((key.clone(), value.clone())),
+                _ => None,
+            })
+            .collect();
+        let results = self.store.execute_batch(ops).await?;
+        for (write, result) in writes.into_iter().zip(&results) {
+            if let (Some((key, value)), Ok(())) = (write, result) {
+                self.record(&key, value).await?;
+            }
+        }
+        Ok(results)
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.store.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.store.remove_many(keys).await
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        self.store.remove_if(key, expected).await
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        self.store.rename(old_key, new_key).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.store.clear().await
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        self.store.usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store.scan_entries(prefix, batch_size)
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store.find_entries(pattern, batch_size)
+    }
+}
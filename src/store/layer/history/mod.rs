@@ -0,0 +1,2 @@
+mod history;
+pub use history::*;
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use super::KeyCodec;
+
+/// Places every key under a namespace, e.g. `users` + `:` + `alice`.
+#[derive(Debug, Clone)]
+pub struct PrefixCodec {
+    namespace: String,
+    separator: String,
+}
+
+impl PrefixCodec {
+    /// Creates a codec prefixing keys with `namespace` and the default `:` separator.
+    pub fn new<N: Into<String>>(namespace: N) -> Self {
+        Self {
+            namespace: namespace.into(),
+            separator: ":".to_string(),
+        }
+    }
+
+    /// Sets the string placed between the namespace and the key.
+    pub fn separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.separator = separator.into();
+        self
+    }
+}
+
+impl KeyCodec for PrefixCodec {
+    fn encode(&self, key: &str) -> String {
+        format!("{}{}{}", self.namespace, self.separator, key)
+    }
+
+    fn decode(&self, key: &str) -> Option<String> {
+        key.strip_prefix(self.namespace.as_str())?
+            .strip_prefix(self.separator.as_str())
+            .map(str::to_string)
+    }
+
+    fn encode_prefix(&self, prefix: &str) -> Option<String> {
+        Some(self.encode(prefix))
+    }
+}
+
+/// Percent-encodes every byte outside the URL unreserved set (`A-Z a-z 0-9 - . _ ~`).
+///
+/// Escaped keys only contain ASCII characters that no backend treats specially, and
+/// compose safely with `PrefixCodec` since separators never appear in them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EscapeCodec;
+
+impl KeyCodec for EscapeCodec {
+    fn encode(&self, key: &str) -> String {
+        let mut escaped = String::with_capacity(key.len());
+        for byte in key.bytes() {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                escaped.push(byte as char);
+            } else {
+                escaped.push_str(&format!("%{:02X}", byte));
+            }
+        }
+        escaped
+    }
+
+    fn decode(&self, key: &str) -> Option<String> {
+        let mut bytes = Vec::with_capacity(key.len());
+        let mut input = key.bytes();
+        while let Some(byte) = input.next() {
+            if byte == b'%' {
+                let hex = [input.next()?, input.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            } else {
+                bytes.push(byte);
+            }
+        }
+        String::from_utf8(bytes).ok()
+    }
+
+    fn encode_prefix(&self, prefix: &str) -> Option<String> {
+        Some(self.encode(prefix))
+    }
+}
+
+/// Lowercases keys so that lookups are case-insensitive.
+///
+/// Decoded keys are returned in their lowercase form.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CaseFoldCodec;
+
+impl KeyCodec for CaseFoldCodec {
+    fn encode(&self, key: &str) -> String {
+        key.to_lowercase()
+    }
+
+    fn decode(&self, key: &str) -> Option<String> {
+        Some(key.to_string())
+    }
+
+    fn encode_prefix(&self, prefix: &str) -> Option<String> {
+        Some(self.encode(prefix))
+    }
+}
+
+/// Applies the first codec, then the second one to its output.
+impl<A: KeyCodec, B: KeyCodec> KeyCodec for (A, B) {
+    fn encode(&self, key: &str) -> String {
+        self.1.encode(&self.0.encode(key))
+    }
+
+    fn decode(&self, key: &str) -> Option<String> {
+        self.0.decode(&self.1.decode(key)?)
+    }
+
+    fn encode_prefix(&self, prefix: &str) -> Option<String> {
+        self.1.encode_prefix(&self.0.encode_prefix(prefix)?)
+    }
+}
+
+/// Applies the codec if there is one, and leaves keys unchanged otherwise.
+impl<C: KeyCodec> KeyCodec for Option<C> {
+    fn encode(&self, key: &str) -> String {
+        match self {
+            Some(codec) => codec.encode(key),
+            None => key.to_string(),
+        }
+    }
+
+    fn decode(&self, key: &str) -> Option<String> {
+        match self {
+            Some(codec) => codec.decode(key),
+            None => Some(key.to_string()),
+        }
+    }
+
+    fn encode_prefix(&self, prefix: &str) -> Option<String> {
+        match self {
+            Some(codec) => codec.encode_prefix(prefix),
+            None => Some(prefix.to_string()),
+        }
+    }
+}
+
+/// Shares a codec between several stores.
+impl<C: KeyCodec + ?Sized> KeyCodec for Arc<C> {
+    fn encode(&self, key: &str) -> String {
+        (**self).encode(key)
+    }
+
+    fn decode(&self, key: &str) -> Option<String> {
+        (**self).decode(key)
+    }
+
+    fn encode_prefix(&self, prefix: &str) -> Option<String> {
+        (**self).encode_prefix(prefix)
+    }
+}
@@ -0,0 +1,353 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use futures::{
+    channel::mpsc,
+    future,
+    stream::{self, BoxStream},
+    SinkExt, StreamExt,
+};
+use serde_json::Value;
+
+use crate::{
+    BatchOp, Capabilities, Change, ChangeFeedBackend, ChangeKind, EvictionPriority, Metadata,
+    QueueBackend, QueueMessage, ScanEntry, Store, StoreError, Usage, Version,
+};
+
+/// Maps the logical keys used by the application to the physical keys written to the
+/// backend.
+///
+/// Codecs are applied by `KeyCodecStore`, or with `Keyv::with_key_codec`, and compose
+/// as tuples: `(EscapeCodec, PrefixCodec::new("app"))` escapes keys, then prefixes them.
+pub trait KeyCodec: Send + Sync {
+    /// Returns the physical key for `key`.
+    fn encode(&self, key: &str) -> String;
+
+    /// Returns the logical key for a physical key, or `None` if the codec is one-way or
+    /// the key was not produced by it.
+    fn decode(&self, key: &str) -> Option<String>;
+
+    /// Returns the physical prefix shared by the keys whose logical key starts with
+    /// `prefix`, or `None` if the codec does not preserve prefixes. Scanning through a
+    /// codec returning `None` is unsupported.
+    fn encode_prefix(&self, _prefix: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Store wrapper encoding every key with a `KeyCodec` before it reaches the backend.
+///
+/// Scanned keys are decoded back to logical keys. `clear` and `usage` are forwarded
+/// unchanged, so they cover every key of the wrapped store, not only those written
+/// through the codec.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore};
+/// # use keyv::layer::key_codec::{EscapeCodec, KeyCodecStore, PrefixCodec};
+/// # async {
+/// let store = KeyCodecStore::new(InMemoryStore::new(), (EscapeCodec, PrefixCodec::new("app")));
+/// let keyv = Keyv::try_new(store).await.unwrap();
+///
+/// // Stored as `app:user%3A42`.
+/// keyv.set("user:42", "profile").await.unwrap();
+/// # };
+/// ```
+#[derive(Debug)]
+pub struct KeyCodecStore<S, C: KeyCodec> {
+    store: S,
+    codec: C,
+}
+
+impl<S, C: KeyCodec> KeyCodecStore<S, C> {
+    /// Wraps `store` so that all keys are encoded with `codec`.
+    pub fn new(store: S, codec: C) -> Self {
+        Self { store, codec }
+    }
+
+    /// Returns a reference to the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the codec applied to keys.
+    pub fn codec(&self) -> &C {
+        &self.codec
+    }
+}
+
+#[async_trait]
+impl<S: Store, C: KeyCodec> Store for KeyCodecStore<S, C> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.store.initialize().await
+    }
+
+    /// Scans are unsupported through codecs that do not preserve prefixes.
+    fn capabilities(&self) -> Capabilities {
+        let capabilities = self.store.capabilities();
+        Capabilities {
+            scan: capabilities.scan && self.codec.encode_prefix("").is_some(),
+            ..capabilities
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.store.get(&self.codec.encode(key)).await
+    }
+
+    async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, StoreError> {
+        self.store.get_ref(&self.codec.encode(key)).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let encoded_keys: Vec<String> = keys.iter().map(|key| self.codec.encode(key)).collect();
+        let encoded_keys: Vec<&str> = encoded_keys.iter().map(String::as_str).collect();
+        self.store.get_many(&encoded_keys).await
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.store.get_with_metadata(&self.codec.encode(key)).await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.store.get_stale(&self.codec.encode(key)).await
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        self.store.get_versions(&self.codec.encode(key)).await
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        self.store.get_and_touch(&self.codec.encode(key), ttl).await
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        let encoded_keys: Vec<String> = keys.iter().map(|key| self.codec.encode(key)).collect();
+        let encoded_keys: Vec<&str> = encoded_keys.iter().map(String::as_str).collect();
+        self.store.touch_many(&encoded_keys, ttl).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.store.set(&self.codec.encode(key), value, ttl).await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        let encoded_keys: Vec<String> = entries
+            .iter()
+            .map(|(key, _)| self.codec.encode(key))
+            .collect();
+        let entries: Vec<(&str, Value)> = encoded_keys
+            .iter()
+            .zip(entries)
+            .map(|(encoded_key, (_, value))| (encoded_key.as_str(), value.clone()))
+            .collect();
+        self.store.set_many(&entries, ttl).await
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        self.store
+            .set_with_priority(&self.codec.encode(key), value, ttl, priority)
+            .await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        self.store
+            .set_until(&self.codec.encode(key), value, expires_at)
+            .await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        self.store
+            .set_keep_ttl(&self.codec.encode(key), value)
+            .await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        self.store
+            .increment(&self.codec.encode(key), delta, ttl)
+            .await
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        self.store
+            .set_if_absent(&self.codec.encode(key), value, ttl)
+            .await
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        let ops = ops
+            .into_iter()
+            .map(|op| op.map_key(|key| self.codec.encode(key)))
+            .collect();
+        self.store.execute_batch(ops).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.store.remove(&self.codec.encode(key)).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        let encoded_keys: Vec<String> = keys.iter().map(|key| self.codec.encode(key)).collect();
+        let encoded_keys: Vec<&str> = encoded_keys.iter().map(String::as_str).collect();
+        self.store.remove_many(&encoded_keys).await
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        self.store
+            .remove_if(&self.codec.encode(key), expected)
+            .await
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        self.store
+            .rename(&self.codec.encode(old_key), &self.codec.encode(new_key))
+            .await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.store.clear().await
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        self.store.usage().await
+    }
+
+    /// Keys that the codec cannot decode are skipped.
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        let Some(encoded_prefix) = self.codec.encode_prefix(prefix.unwrap_or_default()) else {
+            return Box::pin(stream::once(async {
+                Err(StoreError::Unsupported(
+                    "scan_entries through a codec that does not preserve prefixes",
+                ))
+            }));
+        };
+        // The wrapped stream borrows the encoded prefix, so it is driven from a future
+        // owning both and its batches are handed over through a channel.
+        let (mut sender, receiver) = mpsc::channel(0);
+        let driver = async move {
+            let mut batches = self.store.scan_entries(Some(&encoded_prefix), batch_size);
+            while let Some(batch) = batches.next().await {
+                let batch = batch.map(|batch| {
+                    batch
+                        .into_iter()
+                        .filter_map(|entry| {
+                            Some(ScanEntry {
+                                key: self.codec.decode(&entry.key)?,
+                                ..entry
+                            })
+                        })
+                        .collect()
+                });
+                if sender.send(batch).await.is_err() {
+                    break;
+                }
+            }
+        };
+        Box::pin(stream::select(
+            receiver,
+            stream::once(driver).filter_map(|()| future::ready(None)),
+        ))
+    }
+}
+
+#[async_trait]
+impl<S: ChangeFeedBackend, C: KeyCodec> ChangeFeedBackend for KeyCodecStore<S, C> {
+    async fn append_change(
+        &self,
+        key: Option<&str>,
+        kind: ChangeKind,
+    ) -> Result<String, StoreError> {
+        let key = key.map(|key| self.codec.encode(key));
+        self.store.append_change(key.as_deref(), kind).await
+    }
+
+    /// Changes to keys that the codec cannot decode are skipped. Reading continues past
+    /// them, so a page of foreign keys is not mistaken for the end of the feed.
+    async fn read_changes(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Change>, StoreError> {
+        let mut after = after.map(str::to_string);
+        loop {
+            let changes = self.store.read_changes(after.as_deref(), limit).await?;
+            let Some(last) = changes.last() else {
+                return Ok(changes);
+            };
+            after = Some(last.cursor.clone());
+            let decoded: Vec<_> = changes
+                .into_iter()
+                .filter_map(|change| match &change.key {
+                    Some(key) => Some(Change {
+                        key: Some(self.codec.decode(key)?),
+                        ..change
+                    }),
+                    None => Some(change),
+                })
+                .collect();
+            if !decoded.is_empty() {
+                return Ok(decoded);
+            }
+        }
+    }
+}
+
+/// Queue names are encoded like keys, so queues of different namespaces stay apart.
+#[async_trait]
+impl<S: QueueBackend, C: KeyCodec> QueueBackend for KeyCodecStore<S, C> {
+    async fn queue_push(&self, queue: &str, payload: Value) -> Result<String, StoreError> {
+        self.store
+            .queue_push(&self.codec.encode(queue), payload)
+            .await
+    }
+
+    async fn queue_pop(
+        &self,
+        queue: &str,
+        visibility_timeout: Duration,
+    ) -> Result<Option<QueueMessage>, StoreError> {
+        self.store
+            .queue_pop(&self.codec.encode(queue), visibility_timeout)
+            .await
+    }
+
+    async fn queue_ack(&self, queue: &str, id: &str) -> Result<(), StoreError> {
+        self.store.queue_ack(&self.codec.encode(queue), id).await
+    }
+}
@@ -0,0 +1,5 @@
+mod codecs;
+pub use codecs::*;
+
+mod key_codec;
+pub use key_codec::*;
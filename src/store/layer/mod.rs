@@ -0,0 +1,35 @@
+#[cfg(feature = "runtime")]
+pub mod batching;
+
+pub mod change_feed;
+
+#[cfg(feature = "runtime")]
+pub mod concurrency;
+
+pub mod crdt;
+
+pub mod dual_read;
+
+#[cfg(feature = "runtime")]
+pub mod history;
+
+pub mod key_codec;
+
+#[cfg(feature = "runtime")]
+pub mod replicated;
+
+pub mod stats;
+
+pub mod ttl;
+
+#[cfg(feature = "compression")]
+pub mod compression;
+
+#[cfg(feature = "hashed-keys")]
+pub mod hashed_keys;
+
+#[cfg(feature = "opentelemetry")]
+pub mod otel;
+
+#[cfg(feature = "transform")]
+pub mod transform;
@@ -0,0 +1,2 @@
+mod otel;
+pub use otel::*;
@@ -0,0 +1,315 @@
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use opentelemetry::{
+    global::{self, BoxedTracer},
+    metrics::{Counter, Histogram, Unit},
+    trace::{Span, SpanKind, Status, Tracer},
+    KeyValue,
+};
+use serde_json::Value;
+
+use crate::{
+    layer::stats::Operation, BatchOp, Capabilities, EvictionPriority, Metadata, ScanEntry, Store,
+    StoreError, Usage, Version,
+};
+
+const INSTRUMENTATION_NAME: &str = "keyv";
+
+/// Store wrapper that reports every operation to OpenTelemetry.
+///
+/// Each call produces a client span named after the operation with the `db.system`
+/// and `db.operation` attributes, and records:
+///
+/// * `db.client.operation.duration` - histogram of operation durations in seconds.
+/// * `cache.lookups` - counter of keys read by `get`, `get_ref` and `get_many`, with a boolean
+///   `cache.hit` attribute, from which the hit ratio can be derived.
+///
+/// Spans and metrics go through the globally registered tracer and meter providers,
+/// so they are exported by whatever OTLP pipeline the application has configured.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore, layer::otel::OtelStore};
+/// # async {
+/// let store = OtelStore::new(InMemoryStore::new(), "inmemory");
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// # };
+/// ```
+pub struct OtelStore<S: Store> {
+    store: S,
+    system: String,
+    tracer: BoxedTracer,
+    duration: Histogram<f64>,
+    lookups: Counter<u64>,
+}
+
+impl<S: Store> OtelStore<S> {
+    /// Wraps `store`, tagging telemetry with `system` as the `db.system` attribute.
+    pub fn new<N: Into<String>>(store: S, system: N) -> Self {
+        let meter = global::meter(INSTRUMENTATION_NAME);
+        Self {
+            store,
+            system: system.into(),
+            tracer: global::tracer(INSTRUMENTATION_NAME),
+            duration: meter
+                .f64_histogram("db.client.operation.duration")
+                .with_description("Duration of key-value store operations")
+                .with_unit(Unit::new("s"))
+                .init(),
+            lookups: meter
+                .u64_counter("cache.lookups")
+                .with_description("Number of cache lookups, split by the cache.hit attribute")
+                .init(),
+        }
+    }
+
+    /// Returns a reference to the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.store
+    }
+
+    async fn instrument<T, F>(&self, operation: Operation, future: F) -> Result<T, StoreError>
+    where
+        F: Future<Output = Result<T, StoreError>>,
+    {
+        let attributes = vec![
+            KeyValue::new("db.system", self.system.clone()),
+            KeyValue::new("db.operation", operation.as_str()),
+        ];
+        let mut span = self
+            .tracer
+            .span_builder(operation.as_str())
+            .with_kind(SpanKind::Client)
+            .with_attributes(attributes.clone())
+            .start(&self.tracer);
+
+        let started = Instant::now();
+        let result = future.await;
+        self.duration
+            .record(started.elapsed().as_secs_f64(), &attributes);
+
+        if let Err(e) = &result {
+            span.set_status(Status::error(e.to_string()));
+        }
+        span.end();
+        result
+    }
+}
+
+#[async_trait]
+impl<S: Store> Store for OtelStore<S> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.store.initialize().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.store.capabilities()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let result = self.instrument(Operation::Get, self.store.get(key)).await;
+        if let Ok(value) = &result {
+            self.lookups.add(
+                1,
+                &[
+                    KeyValue::new("db.system", self.system.clone()),
+                    KeyValue::new("cache.hit", value.is_some()),
+                ],
+            );
+        }
+        result
+    }
+
+    async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, StoreError> {
+        let result = self
+            .instrument(Operation::Get, self.store.get_ref(key))
+            .await;
+        if let Ok(value) = &result {
+            self.lookups.add(
+                1,
+                &[
+                    KeyValue::new("db.system", self.system.clone()),
+                    KeyValue::new("cache.hit", value.is_some()),
+                ],
+            );
+        }
+        result
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let result = self
+            .instrument(Operation::Get, self.store.get_many(keys))
+            .await;
+        if let Ok(values) = &result {
+            let hits = values.iter().filter(|value| value.is_some()).count() as u64;
+            for (hit, count) in [(true, hits), (false, values.len() as u64 - hits)] {
+                self.lookups.add(
+                    count,
+                    &[
+                        KeyValue::new("db.system", self.system.clone()),
+                        KeyValue::new("cache.hit", hit),
+                    ],
+                );
+            }
+        }
+        result
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.instrument(Operation::Get, self.store.get_with_metadata(key))
+            .await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.instrument(Operation::Get, self.store.get_stale(key))
+            .await
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        self.instrument(Operation::Get, self.store.get_versions(key))
+            .await
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        let result = self
+            .instrument(Operation::Get, self.store.get_and_touch(key, ttl))
+            .await;
+        if let Ok(value) = &result {
+            self.lookups.add(
+                1,
+                &[
+                    KeyValue::new("db.system", self.system.clone()),
+                    KeyValue::new("cache.hit", value.is_some()),
+                ],
+            );
+        }
+        result
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        self.instrument(Operation::Set, self.store.touch_many(keys, ttl))
+            .await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.instrument(Operation::Set, self.store.set(key, value, ttl))
+            .await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        self.instrument(Operation::Set, self.store.set_many(entries, ttl))
+            .await
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        self.instrument(
+            Operation::Set,
+            self.store.set_with_priority(key, value, ttl, priority),
+        )
+        .await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        self.instrument(Operation::Set, self.store.set_until(key, value, expires_at))
+            .await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        self.instrument(Operation::Set, self.store.set_keep_ttl(key, value))
+            .await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        self.instrument(Operation::Set, self.store.increment(key, delta, ttl))
+            .await
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        self.instrument(Operation::Set, self.store.set_if_absent(key, value, ttl))
+            .await
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        self.instrument(Operation::Set, self.store.execute_batch(ops))
+            .await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.instrument(Operation::Remove, self.store.remove(key))
+            .await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.instrument(Operation::Remove, self.store.remove_many(keys))
+            .await
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        self.instrument(Operation::Remove, self.store.remove_if(key, expected))
+            .await
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        self.instrument(Operation::Set, self.store.rename(old_key, new_key))
+            .await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.instrument(Operation::Clear, self.store.clear()).await
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        self.store.usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store.scan_entries(prefix, batch_size)
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store.find_entries(pattern, batch_size)
+    }
+}
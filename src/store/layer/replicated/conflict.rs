@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::Metadata;
+
+/// Decides which copy of an entry wins when the local and remote stores of a
+/// `ReplicatedStore` diverge, see `ReplicatedStore::reconcile`.
+///
+/// `local` and `remote` are the live copies of the entry with their metadata, or
+/// `None` if the store has no value under `key`. At least one of them is present.
+pub trait ConflictResolver: Send + Sync {
+    /// Returns how to settle the divergence of `key`.
+    fn resolve(
+        &self,
+        key: &str,
+        local: Option<&(Value, Metadata)>,
+        remote: Option<&(Value, Metadata)>,
+    ) -> Resolution;
+}
+
+/// The outcome of a `ConflictResolver`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    /// Copy the local entry to the remote store, or remove the remote one if the local
+    /// store has none.
+    KeepLocal,
+    /// Copy the remote entry to the local store, or remove the local one if the remote
+    /// store has none.
+    KeepRemote,
+    /// Write this value to both stores. It expires at the later expiration of the two
+    /// copies, or never if either of them does not expire.
+    Merge(Value),
+}
+
+impl<R: ConflictResolver + ?Sized> ConflictResolver for Arc<R> {
+    fn resolve(
+        &self,
+        key: &str,
+        local: Option<&(Value, Metadata)>,
+        remote: Option<&(Value, Metadata)>,
+    ) -> Resolution {
+        (**self).resolve(key, local, remote)
+    }
+}
+
+/// Keeps the local copy, treating the local store as the primary. The default
+/// `ConflictResolver`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PreferPrimary;
+
+impl ConflictResolver for PreferPrimary {
+    fn resolve(
+        &self,
+        _key: &str,
+        _local: Option<&(Value, Metadata)>,
+        _remote: Option<&(Value, Metadata)>,
+    ) -> Resolution {
+        Resolution::KeepLocal
+    }
+}
+
+/// Keeps the copy with the latest `Metadata::updated_at`.
+///
+/// Only the SQL adapters track `updated_at`; a copy without it loses against one with
+/// it, and the local copy wins ties. Stores keep no trace of removed keys, so a copy
+/// present on one side only always wins over the missing one, which brings back keys
+/// removed while the stores were apart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LastWriterWins;
+
+impl ConflictResolver for LastWriterWins {
+    fn resolve(
+        &self,
+        _key: &str,
+        local: Option<&(Value, Metadata)>,
+        remote: Option<&(Value, Metadata)>,
+    ) -> Resolution {
+        match (local, remote) {
+            (Some((_, local)), Some((_, remote))) if remote.updated_at > local.updated_at => {
+                Resolution::KeepRemote
+            }
+            (None, Some(_)) => Resolution::KeepRemote,
+            _ => Resolution::KeepLocal,
+        }
+    }
+}
+
+/// Merges the two copies with a function of the key, the local value and the remote
+/// value. A copy present on one side only is kept as is.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::layer::replicated::MergeWith;
+/// # use serde_json::{json, Value};
+/// // Keep the largest counter.
+/// let resolver = MergeWith::new(|_key: &str, local: &Value, remote: &Value| {
+///     json!(local.as_i64().max(remote.as_i64()))
+/// });
+/// ```
+pub struct MergeWith<F> {
+    merge: F,
+}
+
+impl<F> MergeWith<F>
+where
+    F: Fn(&str, &Value, &Value) -> Value + Send + Sync,
+{
+    /// Creates a resolver merging diverging values with `merge`.
+    pub fn new(merge: F) -> Self {
+        Self { merge }
+    }
+}
+
+impl<F> ConflictResolver for MergeWith<F>
+where
+    F: Fn(&str, &Value, &Value) -> Value + Send + Sync,
+{
+    fn resolve(
+        &self,
+        key: &str,
+        local: Option<&(Value, Metadata)>,
+        remote: Option<&(Value, Metadata)>,
+    ) -> Resolution {
+        match (local, remote) {
+            (Some((local, _)), Some((remote, _))) => {
+                Resolution::Merge((self.merge)(key, local, remote))
+            }
+            (None, Some(_)) => Resolution::KeepRemote,
+            _ => Resolution::KeepLocal,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod conflict;
+pub use conflict::*;
+
+mod replicated;
+pub use replicated::*;
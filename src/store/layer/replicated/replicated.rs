@@ -0,0 +1,646 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use futures::{stream::BoxStream, TryStreamExt};
+use serde_json::{json, Value};
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use super::{ConflictResolver, PreferPrimary, Resolution};
+use crate::layer::stats::Stats;
+use crate::{
+    BatchOp, Capabilities, EvictionPriority, Metadata, QueueBackend, ScanEntry, SipKeyHasher,
+    Store, StoreError, Usage, Version, DEFAULT_SCAN_BATCH_SIZE,
+};
+
+/// Name of the queue of the local store holding the keys waiting to be replicated.
+pub const REPLICATION_QUEUE: &str = "keyv-replication";
+
+/// Default delay between two checks of an empty outbox.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of buckets keys are hashed into by anti-entropy passes. Only the keys of
+/// buckets whose digests differ between the two stores are compared one by one.
+pub const ANTI_ENTROPY_BUCKETS: usize = 256;
+
+/// The result of an anti-entropy pass run with `ReplicatedStore::reconcile`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReconcileReport {
+    /// Number of buckets whose digests differed between the two stores.
+    pub diverging_buckets: u64,
+    /// Number of distinct keys of the diverging buckets compared one by one.
+    pub checked: u64,
+    /// Number of diverging keys settled with the `ConflictResolver`.
+    pub repaired: u64,
+}
+
+/// State shared between the store and its background replicator.
+struct Replicator<L, R> {
+    local: L,
+    remote: R,
+    remote_initialized: AtomicBool,
+    /// Held while draining the outbox, so that entries are replicated one at a time.
+    drain_lock: Mutex<()>,
+}
+
+impl<L: Store + QueueBackend, R: Store> Replicator<L, R> {
+    /// Replicates the outbox until it is empty, returning the number of entries
+    /// replicated. Stops at the first failure; the failed entry is retried once
+    /// `visibility_timeout` lapses.
+    async fn drain(&self, visibility_timeout: Duration) -> Result<u64, StoreError> {
+        let _guard = self.drain_lock.lock().await;
+        self.drain_locked(visibility_timeout).await
+    }
+
+    /// Does the work of `drain`. Callers must hold `drain_lock`.
+    async fn drain_locked(&self, visibility_timeout: Duration) -> Result<u64, StoreError> {
+        if !self.remote_initialized.load(Ordering::Acquire) {
+            self.remote.initialize().await?;
+            self.remote_initialized.store(true, Ordering::Release);
+        }
+
+        let mut replicated = 0;
+        while let Some(message) = self
+            .local
+            .queue_pop(REPLICATION_QUEUE, visibility_timeout)
+            .await?
+        {
+            self.apply(&message.payload).await?;
+            self.local.queue_ack(REPLICATION_QUEUE, &message.id).await?;
+            replicated += 1;
+        }
+        Ok(replicated)
+    }
+
+    /// Copies the current local state of the entry named by `payload` to the remote
+    /// store.
+    async fn apply(&self, payload: &Value) -> Result<(), StoreError> {
+        let Some(key) = payload.get("key").and_then(Value::as_str) else {
+            return self.remote.clear().await;
+        };
+        let entry = self.local.get_with_metadata(key).await?;
+        write_entry(&self.remote, key, entry).await
+    }
+
+    /// Drains the outbox, then compares both stores bucket by bucket and settles every
+    /// diverging key with `resolver`.
+    async fn reconcile(
+        &self,
+        resolver: &dyn ConflictResolver,
+        visibility_timeout: Duration,
+        stats: Option<&Stats>,
+    ) -> Result<ReconcileReport, StoreError> {
+        let report = self.repair(resolver, visibility_timeout).await?;
+        if let Some(stats) = stats {
+            stats.record_repairs(report.repaired);
+        }
+        Ok(report)
+    }
+
+    async fn repair(
+        &self,
+        resolver: &dyn ConflictResolver,
+        visibility_timeout: Duration,
+    ) -> Result<ReconcileReport, StoreError> {
+        let _guard = self.drain_lock.lock().await;
+        self.drain_locked(visibility_timeout).await?;
+
+        let local = bucket_digests(&self.local).await?;
+        let remote = bucket_digests(&self.remote).await?;
+        let diverging: Vec<bool> = local.iter().zip(&remote).map(|(l, r)| l != r).collect();
+        let mut report = ReconcileReport {
+            diverging_buckets: diverging.iter().filter(|&&d| d).count() as u64,
+            ..ReconcileReport::default()
+        };
+        if report.diverging_buckets == 0 {
+            return Ok(report);
+        }
+
+        // Keys are collected first, so neither store is written while it is scanned.
+        let mut keys = HashSet::new();
+        for store in [&self.local as &dyn Store, &self.remote as &dyn Store] {
+            let mut batches = store.scan_entries(None, DEFAULT_SCAN_BATCH_SIZE);
+            while let Some(batch) = batches.try_next().await? {
+                keys.extend(
+                    batch
+                        .into_iter()
+                        .filter(|entry| diverging[bucket_of(&entry.key)])
+                        .map(|entry| entry.key),
+                );
+            }
+        }
+
+        report.checked = keys.len() as u64;
+        for key in keys {
+            if self.settle(resolver, &key).await? {
+                report.repaired += 1;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Settles a diverging key with `resolver`, returning `false` if both copies
+    /// matched or were gone by the time they were read again.
+    async fn settle(&self, resolver: &dyn ConflictResolver, key: &str) -> Result<bool, StoreError> {
+        let local = self.local.get_with_metadata(key).await?;
+        let remote = self.remote.get_with_metadata(key).await?;
+        let same_value = match (&local, &remote) {
+            (Some((local, _)), Some((remote, _))) => local == remote,
+            (None, None) => true,
+            _ => false,
+        };
+        if same_value {
+            return Ok(false);
+        }
+
+        match resolver.resolve(key, local.as_ref(), remote.as_ref()) {
+            Resolution::KeepLocal => write_entry(&self.remote, key, local).await?,
+            Resolution::KeepRemote => write_entry(&self.local, key, remote).await?,
+            Resolution::Merge(value) => {
+                let expirations: Vec<_> = local
+                    .iter()
+                    .chain(remote.iter())
+                    .map(|(_, metadata)| metadata.expires_at)
+                    .collect();
+                let expires_at = if expirations.contains(&None) {
+                    None
+                } else {
+                    expirations.into_iter().max().flatten()
+                };
+                let metadata = Metadata {
+                    expires_at,
+                    ..Metadata::default()
+                };
+                write_entry(&self.local, key, Some((value.clone(), metadata))).await?;
+                write_entry(&self.remote, key, Some((value, metadata))).await?;
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Returns the bucket of `key` among `ANTI_ENTROPY_BUCKETS`.
+fn bucket_of(key: &str) -> usize {
+    (SipKeyHasher::default().hash_u64(key.as_bytes()) % ANTI_ENTROPY_BUCKETS as u64) as usize
+}
+
+/// Scans `store` and returns a digest of the entries of each bucket. The digest of a
+/// bucket does not depend on the order in which its entries are scanned, so two stores
+/// holding the same values get the same digests.
+async fn bucket_digests<S: Store>(store: &S) -> Result<Vec<u64>, StoreError> {
+    let hasher = SipKeyHasher::default();
+    let mut digests = vec![0u64; ANTI_ENTROPY_BUCKETS];
+    let mut batches = store.scan_entries(None, DEFAULT_SCAN_BATCH_SIZE);
+    while let Some(batch) = batches.try_next().await? {
+        for entry in batch {
+            let bucket = bucket_of(&entry.key);
+            let mut bytes = entry.key.into_bytes();
+            bytes.push(0);
+            bytes.extend_from_slice(entry.value.to_string().as_bytes());
+            digests[bucket] = digests[bucket].wrapping_add(hasher.hash_u64(&bytes));
+        }
+    }
+    Ok(digests)
+}
+
+/// Writes `entry` under `key` with its expiration, or removes `key` if it is `None`.
+async fn write_entry<S: Store>(
+    store: &S,
+    key: &str,
+    entry: Option<(Value, Metadata)>,
+) -> Result<(), StoreError> {
+    match entry {
+        Some((value, metadata)) => match metadata.expires_at {
+            Some(expires_at) => store.set_until(key, value, expires_at).await,
+            None => store.set(key, value, None).await,
+        },
+        None => store.remove(key).await,
+    }
+}
+
+/// Store wrapper that writes to a local store and replicates to a remote store in the
+/// background.
+///
+/// Meant for edge devices that must keep working through connectivity loss: reads
+/// and writes are served by the local store, typically `SqliteStore`, and every write
+/// also pushes the key to an outbox, the `REPLICATION_QUEUE` queue of the local store,
+/// which must therefore be a `QueueBackend`.
+/// A background task started by `initialize()` copies the current local value of each
+/// queued key, with its expiration, to the remote store, and retries with an
+/// exponential backoff while the remote store is unreachable. The remote store is
+/// initialized on the first successful replication.
+///
+/// Because the latest local state is copied rather than the write itself, replaying
+/// an entry is harmless and the remote store converges to the local one. The outbox
+/// is as durable as the local store's queue, which is not the case for
+/// `InMemoryStore`. A write is pushed to the outbox after it succeeded locally, so a
+/// crash in between leaves that key unreplicated until it is written again. Writes
+/// made directly to the remote store may be overwritten, and only one process should
+/// replicate a given local store.
+///
+/// When the two stores may have diverged, for instance after the remote store was
+/// restored from a backup or written by another client, `reconcile` runs an
+/// anti-entropy pass that settles every diverging key with the configured
+/// `ConflictResolver`, `PreferPrimary` by default. `anti_entropy` runs such passes in
+/// the background.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore, layer::replicated::ReplicatedStore};
+/// # async {
+/// let store = ReplicatedStore::new(InMemoryStore::new(), InMemoryStore::new());
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// keyv.set("reading", 21.5).await.unwrap(); // Forwarded to the remote store later
+/// # };
+/// ```
+pub struct ReplicatedStore<L: Store + QueueBackend + 'static, R: Store + 'static> {
+    replicator: Arc<Replicator<L, R>>,
+    poll_interval: Duration,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    resolver: Arc<dyn ConflictResolver>,
+    anti_entropy_interval: Option<Duration>,
+    stats: Option<Arc<Stats>>,
+    worker: std::sync::Mutex<Option<JoinHandle<()>>>,
+    anti_entropy_worker: std::sync::Mutex<Option<JoinHandle<()>>>,
+}
+
+impl<L: Store + QueueBackend + 'static, R: Store + 'static> ReplicatedStore<L, R> {
+    /// Wraps `local`, replicating its writes to `remote`.
+    pub fn new(local: L, remote: R) -> Self {
+        Self {
+            replicator: Arc::new(Replicator {
+                local,
+                remote,
+                remote_initialized: AtomicBool::new(false),
+                drain_lock: Mutex::new(()),
+            }),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            resolver: Arc::new(PreferPrimary),
+            anti_entropy_interval: None,
+            stats: None,
+            worker: std::sync::Mutex::new(None),
+            anti_entropy_worker: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Sets how often an empty outbox is checked for new entries.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Sets the delay before the first retry after a failed replication and the upper
+    /// bound for later delays, which double after every failure.
+    pub fn retry_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Sets how `reconcile` settles keys whose local and remote copies differ.
+    pub fn conflict_resolver(mut self, resolver: impl ConflictResolver + 'static) -> Self {
+        self.resolver = Arc::new(resolver);
+        self
+    }
+
+    /// Runs an anti-entropy pass every `interval` in the background, see `reconcile`.
+    /// Disabled by default.
+    pub fn anti_entropy(mut self, interval: Duration) -> Self {
+        self.anti_entropy_interval = Some(interval);
+        self
+    }
+
+    /// Adds the number of entries repaired by anti-entropy passes to `stats`, see
+    /// `Stats::repairs`.
+    pub fn stats(mut self, stats: Arc<Stats>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Returns a reference to the local store.
+    pub fn local(&self) -> &L {
+        &self.replicator.local
+    }
+
+    /// Returns a reference to the remote store.
+    pub fn remote(&self) -> &R {
+        &self.replicator.remote
+    }
+
+    /// Replicates every entry of the outbox now, for instance before shutting down.
+    ///
+    /// # Returns
+    /// - `Ok(u64)` with the number of entries replicated.
+    /// - `Err(StoreError)` if an entry could not be replicated. It stays in the outbox
+    ///   and is retried by the background task.
+    pub async fn replicate_pending(&self) -> Result<u64, StoreError> {
+        self.replicator.drain(self.initial_backoff).await
+    }
+
+    /// Runs an anti-entropy pass between the local and the remote store.
+    ///
+    /// The outbox is replicated first, then both stores are scanned and their keys
+    /// hashed into `ANTI_ENTROPY_BUCKETS` buckets, each with a digest of its entries.
+    /// Only buckets whose digests differ are scanned again, and every key of them whose
+    /// copies differ, or that only one store holds, is settled with the
+    /// `ConflictResolver`. Stores in sync are thus compared without reading any key one
+    /// by one. Expirations are not compared. Both stores must support
+    /// `Store::scan_entries`. The pass holds off background replication, but writes
+    /// made through the store while it runs may be overwritten by the remote copy, so
+    /// run it when the store is idle, for instance on startup.
+    ///
+    /// # Returns
+    /// - `Ok(ReconcileReport)` with the number of keys compared and settled.
+    /// - `Err(StoreError)` if the outbox could not be replicated or either store fails.
+    pub async fn reconcile(&self) -> Result<ReconcileReport, StoreError> {
+        self.replicator
+            .reconcile(
+                self.resolver.as_ref(),
+                self.initial_backoff,
+                self.stats.as_deref(),
+            )
+            .await
+    }
+
+    async fn enqueue(&self, key: &str) -> Result<(), StoreError> {
+        self.replicator
+            .local
+            .queue_push(REPLICATION_QUEUE, json!({ "key": key }))
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<L: Store + QueueBackend + 'static, R: Store + 'static> Store for ReplicatedStore<L, R> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.replicator.local.initialize().await?;
+
+        let replicator: Weak<Replicator<L, R>> = Arc::downgrade(&self.replicator);
+        let poll_interval = self.poll_interval;
+        let initial_backoff = self.initial_backoff;
+        let max_backoff = self.max_backoff;
+
+        let handle = tokio::spawn(async move {
+            let mut backoff = initial_backoff;
+            loop {
+                let Some(replicator) = replicator.upgrade() else {
+                    break;
+                };
+                // A failed entry stays hidden for `initial_backoff`, so it is visible
+                // again when the retry starts.
+                let delay = match replicator.drain(initial_backoff).await {
+                    Ok(_) => {
+                        backoff = initial_backoff;
+                        poll_interval
+                    }
+                    Err(e) => {
+                        log::warn!("Replication failed, retrying in {:?}: {}", backoff, e);
+                        let delay = backoff;
+                        backoff = (backoff * 2).min(max_backoff);
+                        delay
+                    }
+                };
+                drop(replicator);
+                tokio::time::sleep(delay).await;
+            }
+        });
+
+        if let Some(previous) = self.worker.lock().unwrap().replace(handle) {
+            previous.abort();
+        }
+
+        if let Some(interval) = self.anti_entropy_interval {
+            let replicator = Arc::downgrade(&self.replicator);
+            let resolver = self.resolver.clone();
+            let stats = self.stats.clone();
+            let handle = tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(interval).await;
+                    let Some(replicator) = replicator.upgrade() else {
+                        break;
+                    };
+                    if let Err(e) = replicator
+                        .reconcile(resolver.as_ref(), initial_backoff, stats.as_deref())
+                        .await
+                    {
+                        log::warn!("Anti-entropy pass failed: {}", e);
+                    }
+                }
+            });
+            if let Some(previous) = self.anti_entropy_worker.lock().unwrap().replace(handle) {
+                previous.abort();
+            }
+        }
+        Ok(())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.replicator.local.capabilities()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.replicator.local.get(key).await
+    }
+
+    async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, StoreError> {
+        self.replicator.local.get_ref(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.replicator.local.get_many(keys).await
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.replicator.local.get_with_metadata(key).await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.replicator.local.get_stale(key).await
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        self.replicator.local.get_versions(key).await
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        let value = self.replicator.local.get_and_touch(key, ttl).await?;
+        if value.is_some() {
+            self.enqueue(key).await?;
+        }
+        Ok(value)
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        let touched = self.replicator.local.touch_many(keys, ttl).await?;
+        if touched > 0 {
+            for key in keys {
+                self.enqueue(key).await?;
+            }
+        }
+        Ok(touched)
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.replicator.local.set(key, value, ttl).await?;
+        self.enqueue(key).await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        self.replicator.local.set_many(entries, ttl).await?;
+        for (key, _) in entries {
+            self.enqueue(key).await?;
+        }
+        Ok(())
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        self.replicator
+            .local
+            .set_with_priority(key, value, ttl, priority)
+            .await?;
+        self.enqueue(key).await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        self.replicator
+            .local
+            .set_until(key, value, expires_at)
+            .await?;
+        self.enqueue(key).await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        self.replicator.local.set_keep_ttl(key, value).await?;
+        self.enqueue(key).await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        let value = self.replicator.local.increment(key, delta, ttl).await?;
+        self.enqueue(key).await?;
+        Ok(value)
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        let written = self.replicator.local.set_if_absent(key, value, ttl).await?;
+        if written {
+            self.enqueue(key).await?;
+        }
+        Ok(written)
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        let keys: Vec<String> = ops.iter().map(|op| op.key().to_string()).collect();
+        let results = self.replicator.local.execute_batch(ops).await?;
+        for (key, result) in keys.iter().zip(&results) {
+            if result.is_ok() {
+                self.enqueue(key).await?;
+            }
+        }
+        Ok(results)
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.replicator.local.remove(key).await?;
+        self.enqueue(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.replicator.local.remove_many(keys).await?;
+        for key in keys {
+            self.enqueue(key).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        let removed = self.replicator.local.remove_if(key, expected).await?;
+        if removed {
+            self.enqueue(key).await?;
+        }
+        Ok(removed)
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        self.replicator.local.rename(old_key, new_key).await?;
+        self.enqueue(old_key).await?;
+        self.enqueue(new_key).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.replicator.local.clear().await?;
+        self.replicator
+            .local
+            .queue_push(REPLICATION_QUEUE, json!({ "clear": true }))
+            .await?;
+        Ok(())
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        self.replicator.local.usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.replicator.local.scan_entries(prefix, batch_size)
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.replicator.local.find_entries(pattern, batch_size)
+    }
+}
+
+impl<L: Store + QueueBackend + 'static, R: Store + 'static> Drop for ReplicatedStore<L, R> {
+    fn drop(&mut self) {
+        for worker in [&self.worker, &self.anti_entropy_worker] {
+            if let Some(handle) = worker.lock().unwrap().take() {
+                handle.abort();
+            }
+        }
+    }
+}
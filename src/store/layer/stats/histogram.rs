@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+const SUB_BUCKET_BITS: u32 = 4;
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+const BUCKETS: usize = ((64 - SUB_BUCKET_BITS + 1) as u64 * SUB_BUCKETS) as usize;
+
+/// Latency histogram with logarithmic buckets, in the spirit of HDR histograms.
+///
+/// Values are recorded in microseconds. Every power of two is split in 16 linear
+/// sub-buckets, which keeps the relative error of reported percentiles below ~6%
+/// with a fixed memory footprint and no allocation while recording.
+#[derive(Clone)]
+pub struct Histogram {
+    counts: Vec<u64>,
+    total: u64,
+    max: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            counts: vec![0; BUCKETS],
+            total: 0,
+            max: 0,
+        }
+    }
+
+    /// Records one observation.
+    pub fn record(&mut self, duration: Duration) {
+        let micros = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        self.counts[bucket_index(micros)] += 1;
+        self.total += 1;
+        self.max = self.max.max(micros);
+    }
+
+    /// Returns the number of recorded observations.
+    pub fn count(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the largest recorded observation.
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.max)
+    }
+
+    /// Returns the value below which `quantile` (between 0.0 and 1.0) of the
+    /// observations fall, or `Duration::ZERO` if nothing was recorded.
+    pub fn percentile(&self, quantile: f64) -> Duration {
+        if self.total == 0 {
+            return Duration::ZERO;
+        }
+
+        let rank = ((quantile.clamp(0.0, 1.0) * self.total as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let upper = bucket_upper_bound(index).min(self.max);
+                return Duration::from_micros(upper);
+            }
+        }
+        Duration::from_micros(self.max)
+    }
+}
+
+fn bucket_index(value: u64) -> usize {
+    if value < SUB_BUCKETS {
+        return value as usize;
+    }
+    let exponent = 63 - value.leading_zeros();
+    let sub_bucket = (value >> (exponent - SUB_BUCKET_BITS)) & (SUB_BUCKETS - 1);
+    ((exponent - SUB_BUCKET_BITS + 1) as u64 * SUB_BUCKETS + sub_bucket) as usize
+}
+
+fn bucket_lower_bound(index: usize) -> u64 {
+    let index = index as u64;
+    if index < SUB_BUCKETS {
+        return index;
+    }
+    let group = index / SUB_BUCKETS;
+    let sub_bucket = index % SUB_BUCKETS;
+    (SUB_BUCKETS + sub_bucket) << (group - 1)
+}
+
+fn bucket_upper_bound(index: usize) -> u64 {
+    if index + 1 >= BUCKETS {
+        return u64::MAX;
+    }
+    bucket_lower_bound(index + 1) - 1
+}
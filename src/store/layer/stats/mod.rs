@@ -0,0 +1,5 @@
+mod histogram;
+pub use histogram::*;
+
+mod stats;
+pub use stats::*;
@@ -0,0 +1,473 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use serde_json::Value;
+
+use crate::{
+    BatchOp, Capabilities, EvictionPriority, Metadata, ScanEntry, Store, StoreError, Usage, Version,
+};
+
+use super::Histogram;
+
+/// Store operations tracked by the stats layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Get,
+    Set,
+    Remove,
+    Clear,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 4] = [
+        Operation::Get,
+        Operation::Set,
+        Operation::Remove,
+        Operation::Clear,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Get => "get",
+            Operation::Set => "set",
+            Operation::Remove => "remove",
+            Operation::Clear => "clear",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Latency percentiles for a single operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+/// Latency percentiles for every tracked operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LatencyReport {
+    pub get: LatencySummary,
+    pub set: LatencySummary,
+    pub remove: LatencySummary,
+    pub clear: LatencySummary,
+}
+
+/// Counters and latency histograms collected by `StatsStore`.
+///
+/// `set_many` and `remove_many` count as a single `Set`/`Remove` operation.
+#[derive(Default)]
+pub struct Stats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    errors: AtomicU64,
+    repairs: AtomicU64,
+    original_bytes: AtomicU64,
+    stored_bytes: AtomicU64,
+    histograms: [Mutex<Histogram>; 4],
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of `get` calls that found a value.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of `get` calls that did not find a value.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Number of operations that returned an error.
+    pub fn errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// Number of entries repaired by anti-entropy passes of a `ReplicatedStore` sharing
+    /// these stats.
+    pub fn repairs(&self) -> u64 {
+        self.repairs.load(Ordering::Relaxed)
+    }
+
+    /// Total size of the values written through a `TransformStore` recording into
+    /// these stats, once serialized and before its transformers.
+    pub fn original_bytes(&self) -> u64 {
+        self.original_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Total size, as JSON, of what that `TransformStore` stored for the same values.
+    pub fn stored_bytes(&self) -> u64 {
+        self.stored_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Stored size relative to the original size, or `None` before the first write.
+    ///
+    /// Below 1 the pipeline saves space; above 1 values grew, for instance because
+    /// compressing them did not make up for the base64 envelope.
+    pub fn compression_ratio(&self) -> Option<f64> {
+        let original = self.original_bytes();
+        (original > 0).then(|| self.stored_bytes() as f64 / original as f64)
+    }
+
+    /// Fraction of `get` calls that found a value, or `None` before the first read.
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let hits = self.hits();
+        let total = hits + self.misses();
+        (total > 0).then(|| hits as f64 / total as f64)
+    }
+
+    /// Number of recorded calls for `operation`.
+    pub fn operations(&self, operation: Operation) -> u64 {
+        self.histograms[operation.index()].lock().unwrap().count()
+    }
+
+    /// Returns p50/p95/p99 and max latencies for `operation`.
+    pub fn latency(&self, operation: Operation) -> LatencySummary {
+        let histogram = self.histograms[operation.index()].lock().unwrap();
+        LatencySummary {
+            count: histogram.count(),
+            p50: histogram.percentile(0.50),
+            p95: histogram.percentile(0.95),
+            p99: histogram.percentile(0.99),
+            max: histogram.max(),
+        }
+    }
+
+    /// Returns latency percentiles for every operation.
+    pub fn latency_report(&self) -> LatencyReport {
+        LatencyReport {
+            get: self.latency(Operation::Get),
+            set: self.latency(Operation::Set),
+            remove: self.latency(Operation::Remove),
+            clear: self.latency(Operation::Clear),
+        }
+    }
+
+    /// Resets every counter and histogram.
+    pub fn reset(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+        self.errors.store(0, Ordering::Relaxed);
+        self.repairs.store(0, Ordering::Relaxed);
+        self.original_bytes.store(0, Ordering::Relaxed);
+        self.stored_bytes.store(0, Ordering::Relaxed);
+        for histogram in &self.histograms {
+            *histogram.lock().unwrap() = Histogram::new();
+        }
+    }
+
+    #[cfg(feature = "runtime")]
+    pub(crate) fn record_repairs(&self, count: u64) {
+        self.repairs.fetch_add(count, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "transform")]
+    pub(crate) fn record_bytes(&self, original: usize, stored: usize) {
+        self.original_bytes
+            .fetch_add(original as u64, Ordering::Relaxed);
+        self.stored_bytes
+            .fetch_add(stored as u64, Ordering::Relaxed);
+    }
+
+    fn record<T>(&self, operation: Operation, started: Instant, result: &Result<T, StoreError>) {
+        self.histograms[operation.index()]
+            .lock()
+            .unwrap()
+            .record(started.elapsed());
+        if result.is_err() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Store wrapper that records hit/miss counters and per-operation latencies.
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore, layer::stats::{Stats, StatsStore}};
+/// # async {
+/// let stats = Arc::new(Stats::new());
+/// let store = StatsStore::new(InMemoryStore::new(), stats.clone());
+/// let keyv = Keyv::try_new(store).await.unwrap();
+///
+/// keyv.get("missing").await.unwrap();
+/// assert_eq!(stats.misses(), 1);
+/// println!("{:?}", stats.latency_report().get);
+/// # };
+/// ```
+pub struct StatsStore<S: Store> {
+    store: S,
+    stats: Arc<Stats>,
+}
+
+impl<S: Store> StatsStore<S> {
+    /// Wraps `store`, recording into `stats`.
+    pub fn new(store: S, stats: Arc<Stats>) -> Self {
+        Self { store, stats }
+    }
+
+    /// Returns the collected statistics.
+    pub fn stats(&self) -> &Arc<Stats> {
+        &self.stats
+    }
+
+    /// Returns a reference to the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.store
+    }
+}
+
+#[async_trait]
+impl<S: Store> Store for StatsStore<S> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.store.initialize().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.store.capabilities()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let started = Instant::now();
+        let result = self.store.get(key).await;
+        self.stats.record(Operation::Get, started, &result);
+        match result {
+            Ok(Some(_)) => self.stats.hits.fetch_add(1, Ordering::Relaxed),
+            Ok(None) => self.stats.misses.fetch_add(1, Ordering::Relaxed),
+            Err(_) => 0,
+        };
+        result
+    }
+
+    async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, StoreError> {
+        let started = Instant::now();
+        let result = self.store.get_ref(key).await;
+        self.stats.record(Operation::Get, started, &result);
+        match result {
+            Ok(Some(_)) => self.stats.hits.fetch_add(1, Ordering::Relaxed),
+            Ok(None) => self.stats.misses.fetch_add(1, Ordering::Relaxed),
+            Err(_) => 0,
+        };
+        result
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let started = Instant::now();
+        let result = self.store.get_many(keys).await;
+        self.stats.record(Operation::Get, started, &result);
+        if let Ok(values) = &result {
+            let hits = values.iter().filter(|value| value.is_some()).count() as u64;
+            self.stats.hits.fetch_add(hits, Ordering::Relaxed);
+            self.stats
+                .misses
+                .fetch_add(values.len() as u64 - hits, Ordering::Relaxed);
+        }
+        result
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        let started = Instant::now();
+        let result = self.store.get_with_metadata(key).await;
+        self.stats.record(Operation::Get, started, &result);
+        match result {
+            Ok(Some(_)) => self.stats.hits.fetch_add(1, Ordering::Relaxed),
+            Ok(None) => self.stats.misses.fetch_add(1, Ordering::Relaxed),
+            Err(_) => 0,
+        };
+        result
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        let started = Instant::now();
+        let result = self.store.get_stale(key).await;
+        self.stats.record(Operation::Get, started, &result);
+        result
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        let started = Instant::now();
+        let result = self.store.get_versions(key).await;
+        self.stats.record(Operation::Get, started, &result);
+        result
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        let started = Instant::now();
+        let result = self.store.get_and_touch(key, ttl).await;
+        self.stats.record(Operation::Get, started, &result);
+        match result {
+            Ok(Some(_)) => self.stats.hits.fetch_add(1, Ordering::Relaxed),
+            Ok(None) => self.stats.misses.fetch_add(1, Ordering::Relaxed),
+            Err(_) => 0,
+        };
+        result
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        let started = Instant::now();
+        let result = self.store.touch_many(keys, ttl).await;
+        self.stats.record(Operation::Set, started, &result);
+        result
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        let started = Instant::now();
+        let result = self.store.set(key, value, ttl).await;
+        self.stats.record(Operation::Set, started, &result);
+        result
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        let started = Instant::now();
+        let result = self.store.set_many(entries, ttl).await;
+        self.stats.record(Operation::Set, started, &result);
+        result
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        let started = Instant::now();
+        let result = self
+            .store
+            .set_with_priority(key, value, ttl, priority)
+            .await;
+        self.stats.record(Operation::Set, started, &result);
+        result
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        let started = Instant::now();
+        let result = self.store.set_until(key, value, expires_at).await;
+        self.stats.record(Operation::Set, started, &result);
+        result
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        let started = Instant::now();
+        let result = self.store.set_keep_ttl(key, value).await;
+        self.stats.record(Operation::Set, started, &result);
+        result
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        let started = Instant::now();
+        let result = self.store.increment(key, delta, ttl).await;
+        self.stats.record(Operation::Set, started, &result);
+        result
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        let started = Instant::now();
+        let result = self.store.set_if_absent(key, value, ttl).await;
+        self.stats.record(Operation::Set, started, &result);
+        result
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        let started = Instant::now();
+        let result = self.store.execute_batch(ops).await;
+        self.stats.record(Operation::Set, started, &result);
+        result
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        let started = Instant::now();
+        let result = self.store.remove(key).await;
+        self.stats.record(Operation::Remove, started, &result);
+        result
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        let started = Instant::now();
+        let result = self.store.remove_many(keys).await;
+        self.stats.record(Operation::Remove, started, &result);
+        result
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        let started = Instant::now();
+        let result = self.store.remove_if(key, expected).await;
+        self.stats.record(Operation::Remove, started, &result);
+        result
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        let started = Instant::now();
+        let result = self.store.rename(old_key, new_key).await;
+        self.stats.record(Operation::Set, started, &result);
+        result
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        let started = Instant::now();
+        let result = self.store.clear().await;
+        self.stats.record(Operation::Clear, started, &result);
+        result
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        self.store.usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store.scan_entries(prefix, batch_size)
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store.find_entries(pattern, batch_size)
+    }
+}
@@ -0,0 +1,2 @@
+mod transform;
+pub use transform::*;
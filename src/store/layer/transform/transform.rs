@@ -0,0 +1,481 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::{stream::BoxStream, StreamExt};
+use serde_json::{json, Value};
+
+use crate::{
+    layer::stats::Stats, BatchOp, Capabilities, EvictionPriority, Metadata, ScanEntry, Store,
+    StoreError, Usage, Version,
+};
+
+/// Field of the envelope of a transformed value listing the identifiers of the
+/// transformers applied to it.
+pub const CODEC_FIELD: &str = "__keyv_codec";
+
+/// Field of the envelope of a value written with a serializer other than JSON, holding
+/// the identifier of the serializer.
+pub const FORMAT_FIELD: &str = "__keyv_format";
+
+/// Identifier of `Json`, the default serializer, which is not recorded in envelopes.
+const JSON_FORMAT: &str = "json";
+
+/// Converts values to the bytes `TransformStore` passes to its transformers.
+///
+/// The identifier is recorded in the envelope of every value written with a
+/// serializer other than `Json`, so it must stay the same across releases and services.
+pub trait Serializer: Send + Sync {
+    /// Returns the identifier recorded in the envelope, such as `"msgpack"`.
+    fn id(&self) -> &str;
+
+    /// Serializes a value being written.
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, StoreError>;
+
+    /// Reverts `serialize`.
+    fn deserialize(&self, data: &[u8]) -> Result<Value, StoreError>;
+}
+
+impl<T: Serializer + ?Sized> Serializer for Arc<T> {
+    fn id(&self) -> &str {
+        (**self).id()
+    }
+
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, StoreError> {
+        (**self).serialize(value)
+    }
+
+    fn deserialize(&self, data: &[u8]) -> Result<Value, StoreError> {
+        (**self).deserialize(data)
+    }
+}
+
+/// Serializes values to JSON, the default `Serializer`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Json;
+
+impl Serializer for Json {
+    fn id(&self) -> &str {
+        JSON_FORMAT
+    }
+
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, StoreError> {
+        serde_json::to_vec(value).map_err(|e| StoreError::SerializationError { source: e })
+    }
+
+    fn deserialize(&self, data: &[u8]) -> Result<Value, StoreError> {
+        serde_json::from_slice(data).map_err(|e| StoreError::SerializationError { source: e })
+    }
+}
+
+/// A reversible transformation of serialized values, such as compression or
+/// encryption, applied by `TransformStore`.
+///
+/// The identifier is recorded in the envelope of every value the transformer was
+/// applied to, so it must stay the same across releases and services, be unique
+/// within a pipeline and not contain commas.
+pub trait Transformer: Send + Sync {
+    /// Returns the identifier recorded in the envelope, such as `"gzip"`.
+    fn id(&self) -> &str;
+
+    /// Transforms the bytes of a value being written, or returns `None` to leave them
+    /// unchanged, for instance when compressing would not make them smaller.
+    fn apply(&self, data: &[u8]) -> Result<Option<Vec<u8>>, StoreError>;
+
+    /// Reverts `apply`.
+    fn revert(&self, data: &[u8]) -> Result<Vec<u8>, StoreError>;
+}
+
+impl<T: Transformer + ?Sized> Transformer for Arc<T> {
+    fn id(&self) -> &str {
+        (**self).id()
+    }
+
+    fn apply(&self, data: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        (**self).apply(data)
+    }
+
+    fn revert(&self, data: &[u8]) -> Result<Vec<u8>, StoreError> {
+        (**self).revert(data)
+    }
+}
+
+/// Store wrapper passing values through an ordered pipeline of `Transformer`s.
+///
+/// On writes, a value is serialized, to JSON unless another `Serializer` is set, then
+/// each transformer is applied in turn. If any of them transformed it, the value is
+/// stored as the envelope `{"__keyv_codec": "<ids>", "data": "<base64>"}`, `<ids>`
+/// listing the transformers applied, comma separated and in order; otherwise it is
+/// stored as is. Values written with another serializer are always enveloped, with its
+/// identifier under `__keyv_format`. Reads revert the transformers named by the
+/// envelope in reverse order, and return values written without an envelope unchanged,
+/// so services with different pipelines can share a backend as long as each knows the
+/// transformers the others apply.
+///
+/// Counters must stay plain JSON for `increment` to work, which transformers that skip
+/// small values, such as `Gzip`, ensure.
+///
+/// With `stats`, the size of every written value before and after the pipeline is
+/// added to `Stats::original_bytes` and `Stats::stored_bytes`, to check whether a
+/// cache benefits from compression and tune thresholds such as `Gzip::min_size`.
+///
+/// # Examples
+///
+/// ```
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore, layer::transform::TransformStore};
+/// # use keyv::layer::compression::Gzip;
+/// # async {
+/// let store = TransformStore::new(InMemoryStore::new()).transformer(Gzip::new());
+/// let keyv = Keyv::try_new(store).await.unwrap();
+/// keyv.set("report", "x".repeat(10_000)).await.unwrap(); // Stored gzipped
+/// # };
+/// ```
+pub struct TransformStore<S: Store> {
+    store: S,
+    serializer: Arc<dyn Serializer>,
+    transformers: Vec<Arc<dyn Transformer>>,
+    stats: Option<Arc<Stats>>,
+}
+
+impl<S: Store> TransformStore<S> {
+    /// Wraps `store` with an empty pipeline.
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            serializer: Arc::new(Json),
+            transformers: Vec::new(),
+            stats: None,
+        }
+    }
+
+    /// Replaces the `Json` serializer with `serializer`.
+    pub fn serializer(mut self, serializer: impl Serializer + 'static) -> Self {
+        self.serializer = Arc::new(serializer);
+        self
+    }
+
+    /// Appends `transformer` to the pipeline, to be applied after the previous ones.
+    pub fn transformer(self, transformer: impl Transformer + 'static) -> Self {
+        self.transformers(std::iter::once(
+            Arc::new(transformer) as Arc<dyn Transformer>
+        ))
+    }
+
+    /// Appends `transformers` to the pipeline, in order.
+    pub fn transformers(
+        mut self,
+        transformers: impl IntoIterator<Item = Arc<dyn Transformer>>,
+    ) -> Self {
+        self.transformers.extend(transformers);
+        self
+    }
+
+    /// Records the size of written values before and after the pipeline into `stats`.
+    pub fn stats(mut self, stats: Arc<Stats>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Returns a reference to the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the value to store for `value`.
+    fn encode(&self, value: Value) -> Result<Value, StoreError> {
+        let format = self.serializer.id();
+        let mut data = self.serializer.serialize(&value)?;
+        let original = data.len();
+        let mut applied = Vec::new();
+        for transformer in &self.transformers {
+            if let Some(transformed) = transformer.apply(&data)? {
+                data = transformed;
+                applied.push(transformer.id());
+            }
+        }
+        let mut envelope = json!({ CODEC_FIELD: applied.join(","), "data": STANDARD.encode(data) });
+        if format != JSON_FORMAT {
+            envelope[FORMAT_FIELD] = json!(format);
+        } else if applied.is_empty() {
+            if let Some(stats) = &self.stats {
+                stats.record_bytes(original, original);
+            }
+            return Ok(value);
+        }
+        if let Some(stats) = &self.stats {
+            stats.record_bytes(original, envelope.to_string().len());
+        }
+        Ok(envelope)
+    }
+
+    /// Returns the value held by a stored value, reverting the transformers named by
+    /// its envelope, or the stored value unchanged if it has no envelope.
+    ///
+    /// # Returns
+    /// - `Ok(Value)` with the original value.
+    /// - `Err(StoreError)` if the envelope names a transformer missing from the
+    ///   pipeline or another serializer, or its data is corrupt.
+    pub fn decode(&self, value: Value) -> Result<Value, StoreError> {
+        let Value::Object(envelope) = &value else {
+            return Ok(value);
+        };
+        let format = match envelope.get(FORMAT_FIELD) {
+            None => JSON_FORMAT,
+            Some(Value::String(format)) => format.as_str(),
+            Some(_) => return Ok(value),
+        };
+        let fields = if envelope.contains_key(FORMAT_FIELD) {
+            3
+        } else {
+            2
+        };
+        let (Some(Value::String(ids)), Some(Value::String(data)), true) = (
+            envelope.get(CODEC_FIELD),
+            envelope.get("data"),
+            envelope.len() == fields,
+        ) else {
+            return Ok(value);
+        };
+        if format != self.serializer.id() {
+            return Err(StoreError::QueryError(format!(
+                "Unsupported value format `{}`",
+                format
+            )));
+        }
+
+        let mut data = STANDARD
+            .decode(data)
+            .map_err(|e| StoreError::QueryError(format!("Invalid transformed value: {}", e)))?;
+        for id in ids.rsplit(',').filter(|id| !id.is_empty()) {
+            let transformer = self
+                .transformers
+                .iter()
+                .find(|transformer| transformer.id() == id)
+                .ok_or_else(|| {
+                    StoreError::QueryError(format!("Unsupported value codec `{}`", id))
+                })?;
+            data = transformer.revert(&data)?;
+        }
+        self.serializer.deserialize(&data)
+    }
+
+    fn decode_entries(&self, batch: Vec<ScanEntry>) -> Result<Vec<ScanEntry>, StoreError> {
+        batch
+            .into_iter()
+            .map(|entry| {
+                Ok(ScanEntry {
+                    value: self.decode(entry.value)?,
+                    ..entry
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<S: Store> Store for TransformStore<S> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.store.initialize().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.store.capabilities()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.store
+            .get(key)
+            .await?
+            .map(|value| self.decode(value))
+            .transpose()
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        self.store
+            .get_many(keys)
+            .await?
+            .into_iter()
+            .map(|value| value.map(|value| self.decode(value)).transpose())
+            .collect()
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.store
+            .get_with_metadata(key)
+            .await?
+            .map(|(value, metadata)| Ok((self.decode(value)?, metadata)))
+            .transpose()
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.store
+            .get_stale(key)
+            .await?
+            .map(|(value, metadata)| Ok((self.decode(value)?, metadata)))
+            .transpose()
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        self.store
+            .get_versions(key)
+            .await?
+            .into_iter()
+            .map(|version| {
+                Ok(Version {
+                    value: self.decode(version.value)?,
+                    ..version
+                })
+            })
+            .collect()
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        self.store
+            .get_and_touch(key, ttl)
+            .await?
+            .map(|value| self.decode(value))
+            .transpose()
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        self.store.touch_many(keys, ttl).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.store.set(key, self.encode(value)?, ttl).await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        let entries = entries
+            .iter()
+            .map(|(key, value)| Ok((*key, self.encode(value.clone())?)))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        self.store.set_many(&entries, ttl).await
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        self.store
+            .set_with_priority(key, self.encode(value)?, ttl, priority)
+            .await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        self.store
+            .set_until(key, self.encode(value)?, expires_at)
+            .await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        self.store.set_keep_ttl(key, self.encode(value)?).await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        self.store.increment(key, delta, ttl).await
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        self.store
+            .set_if_absent(key, self.encode(value)?, ttl)
+            .await
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        let ops = ops
+            .into_iter()
+            .map(|op| match op {
+                BatchOp::Set { key, value, ttl } => Ok(BatchOp::Set {
+                    key,
+                    value: self.encode(value)?,
+                    ttl,
+                }),
+                op => Ok(op),
+            })
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        self.store.execute_batch(ops).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.store.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.store.remove_many(keys).await
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        // The stored value may be transformed, so it is compared once decoded and then
+        // removed if it is still the one that was read.
+        let Some(stored) = self.store.get(key).await? else {
+            return Ok(false);
+        };
+        if self.decode(stored.clone())? != *expected {
+            return Ok(false);
+        }
+        self.store.remove_if(key, &stored).await
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        self.store.rename(old_key, new_key).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.store.clear().await
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        self.store.usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store
+            .scan_entries(prefix, batch_size)
+            .map(move |batch| batch.and_then(|batch| self.decode_entries(batch)))
+            .boxed()
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        self.store
+            .find_entries(pattern, batch_size)
+            .map(move |batch| batch.and_then(|batch| self.decode_entries(batch)))
+            .boxed()
+    }
+}
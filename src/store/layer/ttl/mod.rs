@@ -0,0 +1,2 @@
+mod ttl;
+pub use ttl::*;
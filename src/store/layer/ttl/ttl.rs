@@ -0,0 +1,293 @@
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use futures::{stream::BoxStream, TryStreamExt};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    store::expiry::{expires_at_millis, millis_since_epoch, now_millis, system_time_from_millis},
+    Capabilities, EvictionPriority, Metadata, ScanEntry, Store, StoreError, Usage,
+};
+
+const VALUE_FIELD: &str = "value";
+const EXPIRES_AT_FIELD: &str = "expires_at";
+
+/// Store wrapper that implements TTLs on top of backends without native expiration.
+///
+/// Every value is written inside an envelope `{"value": ..., "expires_at": ...}`, with
+/// the expiration in milliseconds since the Unix epoch, and the wrapped store never
+/// receives a TTL. Reads unwrap the envelope and remove entries that have expired, so
+/// any third-party `Store` gains TTL semantics. Values written without the wrapper are
+/// returned as is.
+///
+/// Expired entries are only purged when they are read, with `remove_if` so that a value
+/// written concurrently is kept, and only if the wrapped store supports it. `usage()`
+/// may count them.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use keyv::{Keyv, adapter::inmemory::InMemoryStore};
+/// # use keyv::layer::ttl::TtlStore;
+/// # async {
+/// let keyv = Keyv::try_new(TtlStore::new(InMemoryStore::new())).await.unwrap();
+/// keyv.set_for("otp", "123456", Duration::from_secs(300)).await.unwrap();
+/// # };
+/// ```
+pub struct TtlStore<S: Store> {
+    store: S,
+}
+
+impl<S: Store> TtlStore<S> {
+    /// Wraps `store`, storing expirations alongside the values.
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    /// Returns a reference to the wrapped store.
+    pub fn inner(&self) -> &S {
+        &self.store
+    }
+
+    fn wrap(value: Value, expires_at: Option<i64>) -> Value {
+        json!({ VALUE_FIELD: value, EXPIRES_AT_FIELD: expires_at })
+    }
+
+    /// Splits an envelope into the value and its expiration, or hands `value` back if
+    /// it was not written by this layer.
+    fn unwrap(value: Value) -> Result<(Value, Option<i64>), Value> {
+        match value {
+            Value::Object(mut envelope) if is_envelope(&envelope) => {
+                let expires_at = envelope.remove(EXPIRES_AT_FIELD).and_then(|e| e.as_i64());
+                let value = envelope.remove(VALUE_FIELD).unwrap_or(Value::Null);
+                Ok((value, expires_at))
+            }
+            value => Err(value),
+        }
+    }
+
+    /// Reads `key`, removing it if it has expired. Returns the value and its expiration.
+    async fn get_live(&self, key: &str) -> Result<Option<(Value, Option<i64>)>, StoreError> {
+        let Some(value) = self.store.get(key).await? else {
+            return Ok(None);
+        };
+        if is_expired(&value) {
+            self.purge(key, &value).await?;
+            return Ok(None);
+        }
+        match Self::unwrap(value) {
+            Ok(entry) => Ok(Some(entry)),
+            Err(value) => Ok(Some((value, None))),
+        }
+    }
+
+    /// Removes the expired envelope `stored` from `key`, unless another value has been
+    /// written since it was read. Stores without `remove_if` keep the entry until it is
+    /// overwritten; reads keep treating it as expired.
+    async fn purge(&self, key: &str, stored: &Value) -> Result<(), StoreError> {
+        match self.store.remove_if(key, stored).await {
+            Ok(_) | Err(StoreError::Unsupported(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Unwraps the envelopes of a scanned batch, dropping expired entries.
+    fn unwrap_batch(batch: Vec<ScanEntry>) -> Vec<ScanEntry> {
+        let now = now_millis();
+        batch
+            .into_iter()
+            .filter_map(|entry| match Self::unwrap(entry.value) {
+                Ok((_, Some(expires_at))) if expires_at <= now => None,
+                Ok((value, expires_at)) => Some(ScanEntry {
+                    key: entry.key,
+                    value,
+                    expires_at: expires_at.map(system_time_from_millis),
+                }),
+                Err(value) => Some(ScanEntry { value, ..entry }),
+            })
+            .collect()
+    }
+}
+
+/// Returns whether `value` is an envelope whose expiration has passed.
+fn is_expired(value: &Value) -> bool {
+    match value {
+        Value::Object(envelope) if is_envelope(envelope) => envelope
+            .get(EXPIRES_AT_FIELD)
+            .and_then(Value::as_i64)
+            .is_some_and(|expires_at| expires_at <= now_millis()),
+        _ => false,
+    }
+}
+
+fn is_envelope(envelope: &Map<String, Value>) -> bool {
+    envelope.len() == 2
+        && envelope.contains_key(VALUE_FIELD)
+        && envelope
+            .get(EXPIRES_AT_FIELD)
+            .is_some_and(|expires_at| expires_at.is_null() || expires_at.is_i64())
+}
+
+#[async_trait]
+impl<S: Store> Store for TtlStore<S> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.store.initialize().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            native_ttl: true,
+            atomic_increment: false,
+            transactions: false,
+            ..self.store.capabilities()
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        Ok(self.get_live(key).await?.map(|(value, _)| value))
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        let Some((value, metadata)) = self.store.get_with_metadata(key).await? else {
+            return Ok(None);
+        };
+        if is_expired(&value) {
+            self.purge(key, &value).await?;
+            return Ok(None);
+        }
+        match Self::unwrap(value) {
+            Ok((value, expires_at)) => Ok(Some((
+                value,
+                Metadata {
+                    expires_at: expires_at.map(system_time_from_millis),
+                    ..metadata
+                },
+            ))),
+            Err(value) => Ok(Some((value, metadata))),
+        }
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        let Some((value, metadata)) = self.store.get_stale(key).await? else {
+            return Ok(None);
+        };
+        match Self::unwrap(value) {
+            Ok((value, expires_at)) => Ok(Some((
+                value,
+                Metadata {
+                    expires_at: expires_at.map(system_time_from_millis),
+                    ..metadata
+                },
+            ))),
+            Err(value) => Ok(Some((value, metadata))),
+        }
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.store
+            .set(key, Self::wrap(value, expires_at_millis(ttl)), None)
+            .await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        let expires_at = expires_at_millis(ttl);
+        let entries: Vec<(&str, Value)> = entries
+            .iter()
+            .map(|(key, value)| (*key, Self::wrap(value.clone(), expires_at)))
+            .collect();
+        self.store.set_many(&entries, None).await
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        self.store
+            .set_with_priority(
+                key,
+                Self::wrap(value, expires_at_millis(ttl)),
+                None,
+                priority,
+            )
+            .await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        let expires_at = millis_since_epoch(expires_at);
+        if expires_at <= now_millis() {
+            return self.store.remove(key).await;
+        }
+        self.store
+            .set(key, Self::wrap(value, Some(expires_at)), None)
+            .await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        // Not atomic: a concurrent write between the read and the write loses its TTL.
+        let expires_at = self
+            .get_live(key)
+            .await?
+            .and_then(|(_, expires_at)| expires_at);
+        self.store
+            .set(key, Self::wrap(value, expires_at), None)
+            .await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.store.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.store.remove_many(keys).await
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        // The envelope moves with the value, so the expiration is kept.
+        self.store.rename(old_key, new_key).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.store.clear().await
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        self.store.usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        Box::pin(
+            self.store
+                .scan_entries(prefix, batch_size)
+                .map_ok(Self::unwrap_batch),
+        )
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        Box::pin(
+            self.store
+                .find_entries(pattern, batch_size)
+                .map_ok(Self::unwrap_batch),
+        )
+    }
+}
@@ -0,0 +1,106 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::StoreError;
+
+/// A message claimed from a queue with `QueueBackend::queue_pop`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueMessage {
+    /// Identifies the message when acknowledging it.
+    pub id: String,
+    /// The payload the message was pushed with.
+    pub payload: Value,
+}
+
+/// A backend storing the messages of `queue::Queue`.
+///
+/// Implemented by the stores that can hold queues next to their entries: Redis keeps
+/// them in lists, the SQL stores in a `<table>_queue` table and `InMemoryStore` in
+/// memory. Apart from `KeyCodecStore`, which encodes queue names with its codec, layers
+/// do not implement it, so a queue is built on the adapter itself.
+#[async_trait]
+pub trait QueueBackend: Send + Sync {
+    /// Appends `payload` to the queue named `queue`.
+    ///
+    /// # Arguments
+    /// - `queue`: The name of the queue.
+    /// - `payload`: The message payload, represented as a `serde_json::Value`.
+    ///
+    /// # Returns
+    /// - `Ok(String)` with the id of the new message.
+    /// - `Err(StoreError)` if the message cannot be stored.
+    async fn queue_push(&self, queue: &str, payload: Value) -> Result<String, StoreError>;
+
+    /// Claims the oldest available message of `queue`.
+    ///
+    /// The message stays in the queue but is hidden from other consumers for
+    /// `visibility_timeout`. Unless it is acknowledged with `queue_ack` by then, it
+    /// becomes available again, so a crashed consumer does not lose it.
+    ///
+    /// # Arguments
+    /// - `queue`: The name of the queue.
+    /// - `visibility_timeout`: How long the message is hidden once claimed.
+    ///
+    /// # Returns
+    /// - `Ok(Some(QueueMessage))` with the claimed message.
+    /// - `Ok(None)` if no message is available.
+    /// - `Err(StoreError)` if the queue cannot be read.
+    async fn queue_pop(
+        &self,
+        queue: &str,
+        visibility_timeout: Duration,
+    ) -> Result<Option<QueueMessage>, StoreError>;
+
+    /// Removes a processed message from `queue`.
+    ///
+    /// Acknowledging a message that no longer exists is not an error.
+    ///
+    /// # Arguments
+    /// - `queue`: The name of the queue.
+    /// - `id`: The id of the message, as returned by `queue_pop`.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the message has been removed.
+    /// - `Err(StoreError)` if the message cannot be removed.
+    async fn queue_ack(&self, queue: &str, id: &str) -> Result<(), StoreError>;
+}
+
+#[async_trait]
+impl<B: QueueBackend + ?Sized> QueueBackend for Arc<B> {
+    async fn queue_push(&self, queue: &str, payload: Value) -> Result<String, StoreError> {
+        (**self).queue_push(queue, payload).await
+    }
+
+    async fn queue_pop(
+        &self,
+        queue: &str,
+        visibility_timeout: Duration,
+    ) -> Result<Option<QueueMessage>, StoreError> {
+        (**self).queue_pop(queue, visibility_timeout).await
+    }
+
+    async fn queue_ack(&self, queue: &str, id: &str) -> Result<(), StoreError> {
+        (**self).queue_ack(queue, id).await
+    }
+}
+
+#[async_trait]
+impl<B: QueueBackend + ?Sized> QueueBackend for &B {
+    async fn queue_push(&self, queue: &str, payload: Value) -> Result<String, StoreError> {
+        (**self).queue_push(queue, payload).await
+    }
+
+    async fn queue_pop(
+        &self,
+        queue: &str,
+        visibility_timeout: Duration,
+    ) -> Result<Option<QueueMessage>, StoreError> {
+        (**self).queue_pop(queue, visibility_timeout).await
+    }
+
+    async fn queue_ack(&self, queue: &str, id: &str) -> Result<(), StoreError> {
+        (**self).queue_ack(queue, id).await
+    }
+}
@@ -0,0 +1,30 @@
+use std::time::{Duration, SystemTime};
+
+/// Bookkeeping information about a stored entry, returned by
+/// `Store::get_with_metadata`.
+///
+/// Fields are `None` when the backend does not track them, or for entries written
+/// before it started to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Metadata {
+    /// When the key was first written.
+    pub created_at: Option<SystemTime>,
+    /// When the value was last written.
+    pub updated_at: Option<SystemTime>,
+    /// When the entry expires, or `None` if it does not.
+    pub expires_at: Option<SystemTime>,
+}
+
+impl Metadata {
+    /// Returns how long the entry has left to live, or `None` if it does not expire.
+    ///
+    /// Computed from `expires_at` against the local clock, so it is the same for every
+    /// backend. An entry that expired after it was read reports `Duration::ZERO`.
+    pub fn expires_in(&self) -> Option<Duration> {
+        self.expires_at.map(|expires_at| {
+            expires_at
+                .duration_since(SystemTime::now())
+                .unwrap_or(Duration::ZERO)
+        })
+    }
+}
@@ -4,4 +4,74 @@ pub use store::*;
 mod errors;
 pub use errors::*;
 
+pub(crate) mod expiry;
+
+pub(crate) mod glob;
+
+mod key;
+pub use key::*;
+
+mod key_hasher;
+pub use key_hasher::*;
+
+mod generator;
+pub use generator::*;
+
+mod clock;
+pub use clock::*;
+
+mod usage;
+pub use usage::*;
+
+mod capabilities;
+pub use capabilities::*;
+
+mod metadata;
+pub use metadata::*;
+
+mod version;
+pub use version::*;
+
+mod scan;
+pub use scan::*;
+
+mod message;
+pub use message::*;
+
+mod change;
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+pub(crate) use change::change_from_row;
+#[cfg(feature = "runtime")]
+pub(crate) use change::parse_sequence_cursor;
+pub use change::{Change, ChangeFeedBackend, ChangeKind, DEFAULT_CHANGES_LIMIT};
+
+mod batch;
+pub(crate) use batch::execute_sequentially;
+#[cfg(any(
+    feature = "postgres",
+    feature = "mysql",
+    feature = "sqlite",
+    feature = "redis"
+))]
+pub(crate) use batch::set_many_in_batch;
+pub use batch::BatchOp;
+
+mod priority;
+pub use priority::*;
+
+mod retry;
+pub use retry::*;
+
+mod serialization;
+pub use serialization::*;
+
+mod redact;
+pub use redact::*;
+
+mod verify;
+pub use verify::*;
+
+#[cfg(feature = "runtime")]
 pub mod adapter;
+
+pub mod layer;
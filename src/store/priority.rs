@@ -0,0 +1,15 @@
+/// Hint telling capacity-bounded stores which entries to evict first.
+///
+/// When a bounded store is full it evicts the least recently used entry of the lowest
+/// priority present, so cheap-to-recompute entries make room before expensive ones.
+/// Stores without a capacity limit ignore the hint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EvictionPriority {
+    /// Evicted before any other entry.
+    Low,
+    /// The priority of entries written with `set`.
+    #[default]
+    Normal,
+    /// Evicted only when no lower-priority entry is left.
+    High,
+}
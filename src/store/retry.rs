@@ -0,0 +1,98 @@
+use std::time::Duration;
+#[cfg(feature = "runtime")]
+use std::{future::Future, time::Instant};
+
+#[cfg(feature = "runtime")]
+use super::StoreError;
+
+/// Retry policy used while a store connects to and initializes its backend.
+///
+/// Attempts are spaced with an exponential backoff, starting at `initial_backoff`
+/// and doubling up to `max_backoff`. Retrying stops once `max_attempts` have been
+/// made or, if set, when the next attempt would start after `deadline`.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use keyv::RetryPolicy;
+/// let policy = RetryPolicy::new(10)
+///     .backoff(Duration::from_millis(200), Duration::from_secs(5))
+///     .deadline(Duration::from_secs(60));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy making at most `max_attempts` attempts (at least one).
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            deadline: None,
+        }
+    }
+
+    /// Sets the delay before the first retry and the upper bound for later delays.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Sets the total time after which no new attempt is started.
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Runs `operation` until it succeeds or the policy is exhausted, returning the
+    /// last error in the latter case.
+    #[cfg(feature = "runtime")]
+    pub async fn run<T, F, Fut>(&self, mut operation: F) -> Result<T, StoreError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, StoreError>>,
+    {
+        let started = Instant::now();
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 1;
+
+        loop {
+            let error = match operation().await {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+
+            let out_of_time = self
+                .deadline
+                .is_some_and(|deadline| started.elapsed() + backoff > deadline);
+            if attempt >= self.max_attempts || out_of_time {
+                return Err(error);
+            }
+
+            log::warn!(
+                "Store initialization attempt {}/{} failed, retrying in {:?}: {}",
+                attempt,
+                self.max_attempts,
+                backoff,
+                error
+            );
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(self.max_backoff);
+            attempt += 1;
+        }
+    }
+}
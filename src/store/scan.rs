@@ -0,0 +1,28 @@
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value;
+
+/// An entry yielded by `Store::scan_entries`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanEntry {
+    /// The key, without any namespace the store adds.
+    pub key: String,
+    /// The stored value.
+    pub value: Value,
+    /// When the entry expires, or `None` if it never does.
+    pub expires_at: Option<SystemTime>,
+}
+
+impl ScanEntry {
+    /// Returns how long the entry has left to live, or `None` if it does not expire.
+    pub fn expires_in(&self) -> Option<Duration> {
+        self.expires_at.map(|expires_at| {
+            expires_at
+                .duration_since(SystemTime::now())
+                .unwrap_or(Duration::ZERO)
+        })
+    }
+}
+
+/// Number of entries fetched per batch when no batch size is given.
+pub const DEFAULT_SCAN_BATCH_SIZE: usize = 1000;
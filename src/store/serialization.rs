@@ -0,0 +1,133 @@
+use std::{sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{ScanEntry, Store, StoreError};
+
+/// What a store does with a stored value that cannot be deserialized.
+///
+/// Rows corrupted by a bad deploy or edited by hand would otherwise read as misses
+/// without a trace. The policy applies to reads and scans alike.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SerializationFailurePolicy {
+    /// Fails the read with `StoreError::SerializationError`.
+    Error,
+    /// Logs a warning and reports the key as missing.
+    #[default]
+    LogAndMiss,
+    /// Logs a warning, removes the entry and reports the key as missing.
+    DeleteAndMiss,
+    /// Logs a warning, moves the entry with its raw payload to the store's quarantine
+    /// table, notifies the quarantine listener and reports the key as missing.
+    Quarantine,
+}
+
+/// An entry moved to quarantine because its value could not be deserialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuarantinedEntry {
+    /// The key the entry was stored under.
+    pub key: String,
+    /// The stored payload, exactly as read from the backend.
+    pub raw: String,
+    /// Why deserializing the payload failed.
+    pub error: String,
+}
+
+/// Callback notified of every entry moved to quarantine.
+pub type QuarantineListener = Arc<dyn Fn(&QuarantinedEntry) + Send + Sync>;
+
+/// Stores able to set undeserializable entries aside.
+#[async_trait]
+pub(crate) trait Quarantine: Store {
+    /// Moves `entries` out of the store into its quarantine table.
+    async fn quarantine(&self, entries: &[QuarantinedEntry]) -> Result<(), StoreError>;
+
+    /// Returns the callback to notify once entries have been quarantined.
+    fn quarantine_listener(&self) -> Option<&QuarantineListener>;
+}
+
+impl SerializationFailurePolicy {
+    /// Deserializes `raw`, the value stored under `key` in `store`.
+    ///
+    /// Returns `Ok(None)` if the value is undecodable and the policy treats it as a miss.
+    pub(crate) async fn decode<S: Quarantine + ?Sized>(
+        self,
+        store: &S,
+        key: &str,
+        raw: &str,
+    ) -> Result<Option<Value>, StoreError> {
+        match serde_json::from_str(raw) {
+            Ok(value) => Ok(Some(value)),
+            Err(source) if self == Self::Error => Err(StoreError::SerializationError { source }),
+            Err(error) => {
+                log::warn!("Undecodable value stored under '{}': {}", key, error);
+                let corrupt = [QuarantinedEntry {
+                    key: key.to_string(),
+                    raw: raw.to_string(),
+                    error: error.to_string(),
+                }];
+                self.set_aside(store, &corrupt).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Deserializes a batch of scanned `(key, raw value, expires_at)` rows, dropping the
+    /// undecodable ones the policy treats as misses.
+    pub(crate) async fn decode_entries<S: Quarantine + ?Sized>(
+        self,
+        store: &S,
+        rows: Vec<(String, String, Option<SystemTime>)>,
+    ) -> Result<Vec<ScanEntry>, StoreError> {
+        let mut entries = Vec::with_capacity(rows.len());
+        let mut corrupt = Vec::new();
+        for (key, raw, expires_at) in rows {
+            match serde_json::from_str(&raw) {
+                Ok(value) => entries.push(ScanEntry {
+                    key,
+                    value,
+                    expires_at,
+                }),
+                Err(source) if self == Self::Error => {
+                    return Err(StoreError::SerializationError { source })
+                }
+                Err(error) => {
+                    log::warn!("Undecodable value stored under '{}': {}", key, error);
+                    corrupt.push(QuarantinedEntry {
+                        key,
+                        raw,
+                        error: error.to_string(),
+                    });
+                }
+            }
+        }
+
+        if !corrupt.is_empty() {
+            self.set_aside(store, &corrupt).await?;
+        }
+        Ok(entries)
+    }
+
+    /// Removes or quarantines the `corrupt` entries, as the policy requires.
+    async fn set_aside<S: Quarantine + ?Sized>(
+        self,
+        store: &S,
+        corrupt: &[QuarantinedEntry],
+    ) -> Result<(), StoreError> {
+        match self {
+            Self::Error | Self::LogAndMiss => {}
+            Self::DeleteAndMiss => {
+                let keys: Vec<&str> = corrupt.iter().map(|entry| entry.key.as_str()).collect();
+                store.remove_many(&keys).await?;
+            }
+            Self::Quarantine => {
+                store.quarantine(corrupt).await?;
+                if let Some(listener) = store.quarantine_listener() {
+                    corrupt.iter().for_each(|entry| listener(entry));
+                }
+            }
+        }
+        Ok(())
+    }
+}
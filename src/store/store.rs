@@ -1,7 +1,19 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
 use async_trait::async_trait;
+use futures::{
+    stream::{self, BoxStream},
+    TryStreamExt,
+};
 use serde_json::Value;
 
-use super::StoreError;
+use super::{
+    execute_sequentially, glob, BatchOp, Capabilities, EvictionPriority, Metadata, ScanEntry,
+    StoreError, Usage, Version,
+};
 
 #[async_trait]
 pub trait Store: Send + Sync {
@@ -14,6 +26,14 @@ pub trait Store: Send + Sync {
     /// - `Err(StoreError)` if initialisation fails.
     async fn initialize(&self) -> Result<(), StoreError>;
 
+    /// Reports which optional features the store supports.
+    ///
+    /// The default implementation reports none of them. Adapters and layers should
+    /// override it to describe what they actually provide.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
     /// Retrieves a value associated with a given key from the store.
     ///
     /// # Arguments
@@ -25,17 +45,311 @@ pub trait Store: Send + Sync {
     /// - `Err(StoreError)` if there is an error retrieving the value.
     async fn get(&self, key: &str) -> Result<Option<Value>, StoreError>;
 
+    /// Retrieves a value as a shared reference, without copying it when the store keeps
+    /// values in memory.
+    ///
+    /// The default implementation calls `get` and wraps the value. `InMemoryStore`
+    /// overrides it to hand out the value it holds, so reading a large JSON tree costs a
+    /// reference count increment rather than a deep clone.
+    ///
+    /// # Arguments
+    /// - `key`: A string slice that holds the key for the value to be retrieved.
+    ///
+    /// # Returns
+    /// - `Ok(Some(Arc<Value>))` if the key exists and the value is successfully retrieved.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(StoreError)` if there is an error retrieving the value.
+    async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, StoreError> {
+        Ok(self.get(key).await?.map(Arc::new))
+    }
+
+    /// Retrieves the values of several keys.
+    ///
+    /// The default implementation calls `get` for every key. Adapters should override it
+    /// when the backend can read several keys in one round trip.
+    ///
+    /// # Arguments
+    /// - `keys`: The keys whose values should be retrieved.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<Option<Value>>)` with one element per key, in the order of `keys`.
+    /// - `Err(StoreError)` if there is an error retrieving any of the values.
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(self.get(key).await?);
+        }
+        Ok(values)
+    }
+
+    /// Retrieves a value together with its creation and last update times.
+    ///
+    /// The default implementation calls `get` and reports no timestamps. Adapters
+    /// should override it when the backend records them.
+    ///
+    /// # Arguments
+    /// - `key`: A string slice that holds the key for the value to be retrieved.
+    ///
+    /// # Returns
+    /// - `Ok(Some((Value, Metadata)))` if the key exists.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(StoreError)` if there is an error retrieving the value.
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        Ok(self
+            .get(key)
+            .await?
+            .map(|value| (value, Metadata::default())))
+    }
+
+    /// Retrieves an entry even if it has expired, as long as the backend still holds
+    /// it, without removing it.
+    ///
+    /// Lets callers decide themselves whether an expired value is still good enough,
+    /// e.g. to serve it while it is refreshed. `Metadata::expires_at` tells expired
+    /// entries apart from live ones. The default implementation calls
+    /// `get_with_metadata`, so stores that drop expired entries on their own, such as
+    /// Redis, only return live ones.
+    ///
+    /// # Arguments
+    /// - `key`: A string slice that holds the key for the value to be retrieved.
+    ///
+    /// # Returns
+    /// - `Ok(Some((Value, Metadata)))` if the store holds the key, expired or not.
+    /// - `Ok(None)` if the key does not exist or its entry was already purged.
+    /// - `Err(StoreError)` if there is an error retrieving the value.
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        self.get_with_metadata(key).await
+    }
+
+    /// Retrieves the values previously written under a key, newest first.
+    ///
+    /// Only stores that keep a history implement it, such as
+    /// `layer::history::HistoryStore`. The default implementation returns
+    /// `StoreError::Unsupported`.
+    ///
+    /// # Arguments
+    /// - `key`: A string slice that holds the key whose history is retrieved.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<Version>)` with the recorded values, empty if none were recorded.
+    /// - `Err(StoreError)` if there is an error retrieving the history.
+    async fn get_versions(&self, _key: &str) -> Result<Vec<Version>, StoreError> {
+        Err(StoreError::Unsupported("get_versions"))
+    }
+
+    /// Retrieves a value and resets its expiration to `ttl` from now.
+    ///
+    /// Implements sliding expiration, e.g. for sessions that stay alive while in use.
+    /// The default implementation calls `get` and then `set`, which is not atomic: a
+    /// write landing in between is overwritten with the value read, and the entry's
+    /// `updated_at` moves on every read. Adapters should override it with a single
+    /// command or transaction; all the bundled adapters do.
+    ///
+    /// # Arguments
+    /// - `key`: A string slice that holds the key for the value to be retrieved.
+    /// - `ttl`: The `Duration` after which the value expires, counted from now.
+    ///
+    /// # Returns
+    /// - `Ok(Some(Value))` if the key exists; its expiration has been reset.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(StoreError)` if there is an error retrieving the value or extending it.
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        let value = self.get(key).await?;
+        if let Some(value) = &value {
+            self.set(key, value.clone(), Some(ttl)).await?;
+        }
+        Ok(value)
+    }
+
+    /// Resets the expiration of several keys to `ttl` from now.
+    ///
+    /// Keys that do not exist are skipped. The default implementation calls
+    /// `get_and_touch` for every key. Adapters should override it when the backend can
+    /// update many expirations in one round trip.
+    ///
+    /// # Arguments
+    /// - `keys`: The keys whose expiration should be reset.
+    /// - `ttl`: The `Duration` after which the keys expire, counted from now.
+    ///
+    /// # Returns
+    /// - `Ok(u64)` with the number of keys that existed and were extended.
+    /// - `Err(StoreError)` if there is an error extending any of the keys.
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        let mut touched = 0;
+        for key in keys {
+            if self.get_and_touch(key, ttl).await?.is_some() {
+                touched += 1;
+            }
+        }
+        Ok(touched)
+    }
+
     /// Sets a value for a given key in the store, with an optional time-to-live (TTL).
     ///
     /// # Arguments
     /// - `key`: The key under which the value is stored.
     /// - `value`: The value to set, represented as a `serde_json::Value`.
-    /// - `ttl`: An optional u64 representing the time-to-live in seconds.
+    /// - `ttl`: An optional `Duration` after which the value expires.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the value is successfully set.
+    /// - `Err(StoreError)` if there is an error setting the value.
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError>;
+
+    /// Sets multiple key-value pairs in the store, sharing an optional time-to-live (TTL).
+    ///
+    /// The default implementation calls `set` for every entry. Adapters should override it
+    /// when the backend supports multi-row inserts or pipelining.
+    ///
+    /// # Arguments
+    /// - `entries`: A slice of key-value pairs to store.
+    /// - `ttl`: An optional `Duration` after which the value expires, applied to every entry.
+    ///
+    /// # Returns
+    /// - `Ok(())` if all values are successfully set.
+    /// - `Err(StoreError)` if there is an error setting any of the values.
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        for (key, value) in entries {
+            self.set(key, value.clone(), ttl).await?;
+        }
+        Ok(())
+    }
+
+    /// Sets a value for a given key, tagging it with an eviction priority.
+    ///
+    /// The priority is a hint for capacity-bounded stores. The default implementation
+    /// ignores it and calls `set`.
+    ///
+    /// # Arguments
+    /// - `key`: The key under which the value is stored.
+    /// - `value`: The value to set, represented as a `serde_json::Value`.
+    /// - `ttl`: An optional `Duration` after which the value expires.
+    /// - `priority`: How reluctantly the entry should be evicted.
     ///
     /// # Returns
     /// - `Ok(())` if the value is successfully set.
     /// - `Err(StoreError)` if there is an error setting the value.
-    async fn set(&self, key: &str, value: Value, ttl: Option<u64>) -> Result<(), StoreError>;
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        _priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        self.set(key, value, ttl).await
+    }
+
+    /// Sets a value for a given key that expires at an absolute point in time.
+    ///
+    /// The default implementation converts `expires_at` to a TTL relative to now and
+    /// calls `set`, removing the key instead if `expires_at` has already passed. Adapters
+    /// should override it when the backend can store absolute expirations.
+    ///
+    /// # Arguments
+    /// - `key`: The key under which the value is stored.
+    /// - `value`: The value to set, represented as a `serde_json::Value`.
+    /// - `expires_at`: The wall-clock time at which the value expires.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the value is successfully set.
+    /// - `Err(StoreError)` if there is an error setting the value.
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        match expires_at.duration_since(SystemTime::now()) {
+            Ok(ttl) if !ttl.is_zero() => self.set(key, value, Some(ttl)).await,
+            _ => self.remove(key).await,
+        }
+    }
+
+    /// Sets a value for a given key, keeping the expiration the key already has.
+    ///
+    /// Refreshing a value this way does not reset a carefully chosen TTL. Keys that do
+    /// not exist, or have already expired, are written without an expiration. Adapters
+    /// must override it; the default implementation returns `StoreError::Unsupported`.
+    ///
+    /// # Arguments
+    /// - `key`: The key under which the value is stored.
+    /// - `value`: The value to set, represented as a `serde_json::Value`.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the value is successfully set.
+    /// - `Err(StoreError)` if there is an error setting the value.
+    async fn set_keep_ttl(&self, _key: &str, _value: Value) -> Result<(), StoreError> {
+        Err(StoreError::Unsupported("set_keep_ttl"))
+    }
+
+    /// Atomically adds `delta` to the integer stored under `key` and returns the result.
+    ///
+    /// A missing or expired key counts as zero and is created with `ttl`; the expiration
+    /// of an existing key is left untouched, so fixed windows end on time however often
+    /// they are incremented. The default implementation returns `StoreError::Unsupported`.
+    ///
+    /// # Arguments
+    /// - `key`: The key holding the counter.
+    /// - `delta`: The amount to add, which may be negative.
+    /// - `ttl`: An optional `Duration` after which a newly created counter expires.
+    ///
+    /// # Returns
+    /// - `Ok(i64)` with the value after the increment.
+    /// - `Err(StoreError)` if the stored value is not an integer or the update fails.
+    async fn increment(
+        &self,
+        _key: &str,
+        _delta: i64,
+        _ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        Err(StoreError::Unsupported("increment"))
+    }
+
+    /// Sets `value` under `key` only if the key is missing or has expired.
+    ///
+    /// The check and the write are atomic, so of several callers racing to claim the
+    /// same key exactly one succeeds. The default implementation returns
+    /// `StoreError::Unsupported`.
+    ///
+    /// # Arguments
+    /// - `key`: The key under which the value is stored.
+    /// - `value`: The value to set, represented as a `serde_json::Value`.
+    /// - `ttl`: An optional `Duration` after which the value expires.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if the value was set.
+    /// - `Ok(false)` if the key already held a live value, which is left untouched.
+    /// - `Err(StoreError)` if there is an error setting the value.
+    async fn set_if_absent(
+        &self,
+        _key: &str,
+        _value: Value,
+        _ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        Err(StoreError::Unsupported("set_if_absent"))
+    }
+
+    /// Runs several operations in as few round trips as the backend allows.
+    ///
+    /// Adapters map the batch to a Redis pipeline or a SQL transaction. The default
+    /// implementation runs the operations one after the other.
+    ///
+    /// # Arguments
+    /// - `ops`: The operations to run, in order.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<Result<(), StoreError>>)` with the result of each operation, in order.
+    /// - `Err(StoreError)` if the batch as a whole could not be run.
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        Ok(execute_sequentially(self, ops).await)
+    }
 
     /// Removes a value associated with a given key from the store.
     ///
@@ -57,10 +371,266 @@ pub trait Store: Send + Sync {
     /// - `Err(StoreError)` if there is an error removing the values.
     async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError>;
 
+    /// Removes `key` only if it holds `expected`.
+    ///
+    /// The check and the removal are atomic, so a lock holder releasing its lock cannot
+    /// delete a value another writer has stored since. The default implementation
+    /// returns `StoreError::Unsupported`.
+    ///
+    /// # Arguments
+    /// - `key`: The key of the value to remove.
+    /// - `expected`: The value the key must hold to be removed.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if the key held `expected` and was removed.
+    /// - `Ok(false)` if the key is missing or holds another value, which is left untouched.
+    /// - `Err(StoreError)` if there is an error removing the value.
+    async fn remove_if(&self, _key: &str, _expected: &Value) -> Result<bool, StoreError> {
+        Err(StoreError::Unsupported("remove_if"))
+    }
+
+    /// Moves the value under `old_key` to `new_key`, keeping its expiration.
+    ///
+    /// A value already stored under `new_key` is overwritten. The default
+    /// implementation reads the value with `get_with_metadata`, writes it under the new
+    /// key and removes the old one, so it is not atomic. Adapters should override it
+    /// when the backend can move a key in one operation.
+    ///
+    /// # Arguments
+    /// - `old_key`: The key the value is currently stored under.
+    /// - `new_key`: The key to move the value to.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the value was moved.
+    /// - `Err(StoreError::NotFound)` if there is no value under `old_key`.
+    /// - `Err(StoreError)` if there is an error moving the value.
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        let (value, metadata) = self
+            .get_with_metadata(old_key)
+            .await?
+            .ok_or(StoreError::NotFound)?;
+        if old_key == new_key {
+            return Ok(());
+        }
+        match metadata.expires_at {
+            Some(expires_at) => self.set_until(new_key, value, expires_at).await?,
+            None => self.set(new_key, value, None).await?,
+        }
+        self.remove(old_key).await
+    }
+
     /// Clears all values from the store.
     ///
     /// # Returns
     /// - `Ok(())` if the store is successfully cleared.
     /// - `Err(StoreError)` if there is an error clearing the store.
     async fn clear(&self) -> Result<(), StoreError>;
+
+    /// Reports how many entries the store holds and roughly how much space they use.
+    ///
+    /// The default implementation returns `StoreError::Unsupported`.
+    ///
+    /// # Returns
+    /// - `Ok(Usage)` with the entry count and approximate size.
+    /// - `Err(StoreError)` if the usage cannot be determined.
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        Err(StoreError::Unsupported("usage"))
+    }
+
+    /// Streams the live entries whose key starts with `prefix`, in batches.
+    ///
+    /// Meant for exports, migrations and custom garbage collection: only one batch is
+    /// held in memory at a time. Batches hold about `batch_size` entries; entries
+    /// written or removed during the scan may or may not be included. The default
+    /// implementation yields a single `StoreError::Unsupported`.
+    ///
+    /// # Arguments
+    /// - `prefix`: Only keys starting with this prefix are returned, or all keys if `None`.
+    /// - `batch_size`: The number of entries to fetch per round trip.
+    ///
+    /// # Returns
+    /// A stream of entry batches, ending after the last batch or the first error.
+    fn scan_entries<'a>(
+        &'a self,
+        _prefix: Option<&'a str>,
+        _batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        Box::pin(stream::once(async {
+            Err(StoreError::Unsupported("scan_entries"))
+        }))
+    }
+
+    /// Streams the live entries whose key matches a glob `pattern`, in batches.
+    ///
+    /// Patterns support `*` for any sequence of characters, `?` for a single character
+    /// and `\` to match the next character literally. The default implementation scans
+    /// the literal prefix of the pattern with `scan_entries` and filters the keys
+    /// client-side; adapters override it to match on the backend.
+    ///
+    /// # Arguments
+    /// - `pattern`: The glob pattern keys must match.
+    /// - `batch_size`: The number of entries to fetch per round trip.
+    ///
+    /// # Returns
+    /// A stream of entry batches, ending after the last batch or the first error.
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        let prefix = Some(glob::literal_prefix(pattern)).filter(|prefix| !prefix.is_empty());
+        Box::pin(
+            self.scan_entries(prefix, batch_size)
+                .map_ok(move |batch| {
+                    batch
+                        .into_iter()
+                        .filter(|entry| glob::matches(pattern, &entry.key))
+                        .collect::<Vec<_>>()
+                })
+                .try_filter(|batch| futures::future::ready(!batch.is_empty())),
+        )
+    }
+}
+
+#[async_trait]
+impl<S: Store + ?Sized> Store for Arc<S> {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        (**self).initialize().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        (**self).capabilities()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        (**self).get(key).await
+    }
+
+    async fn get_ref(&self, key: &str) -> Result<Option<Arc<Value>>, StoreError> {
+        (**self).get_ref(key).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Value>>, StoreError> {
+        (**self).get_many(keys).await
+    }
+
+    async fn get_with_metadata(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        (**self).get_with_metadata(key).await
+    }
+
+    async fn get_stale(&self, key: &str) -> Result<Option<(Value, Metadata)>, StoreError> {
+        (**self).get_stale(key).await
+    }
+
+    async fn get_versions(&self, key: &str) -> Result<Vec<Version>, StoreError> {
+        (**self).get_versions(key).await
+    }
+
+    async fn get_and_touch(&self, key: &str, ttl: Duration) -> Result<Option<Value>, StoreError> {
+        (**self).get_and_touch(key, ttl).await
+    }
+
+    async fn touch_many(&self, keys: &[&str], ttl: Duration) -> Result<u64, StoreError> {
+        (**self).touch_many(keys, ttl).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        (**self).set(key, value, ttl).await
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, Value)],
+        ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        (**self).set_many(entries, ttl).await
+    }
+
+    async fn set_with_priority(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+        priority: EvictionPriority,
+    ) -> Result<(), StoreError> {
+        (**self).set_with_priority(key, value, ttl, priority).await
+    }
+
+    async fn set_until(
+        &self,
+        key: &str,
+        value: Value,
+        expires_at: SystemTime,
+    ) -> Result<(), StoreError> {
+        (**self).set_until(key, value, expires_at).await
+    }
+
+    async fn set_keep_ttl(&self, key: &str, value: Value) -> Result<(), StoreError> {
+        (**self).set_keep_ttl(key, value).await
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        delta: i64,
+        ttl: Option<Duration>,
+    ) -> Result<i64, StoreError> {
+        (**self).increment(key, delta, ttl).await
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: Value,
+        ttl: Option<Duration>,
+    ) -> Result<bool, StoreError> {
+        (**self).set_if_absent(key, value, ttl).await
+    }
+
+    async fn execute_batch(
+        &self,
+        ops: Vec<BatchOp>,
+    ) -> Result<Vec<Result<(), StoreError>>, StoreError> {
+        (**self).execute_batch(ops).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        (**self).remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        (**self).remove_many(keys).await
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        (**self).remove_if(key, expected).await
+    }
+
+    async fn rename(&self, old_key: &str, new_key: &str) -> Result<(), StoreError> {
+        (**self).rename(old_key, new_key).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        (**self).clear().await
+    }
+
+    async fn usage(&self) -> Result<Usage, StoreError> {
+        (**self).usage().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        (**self).scan_entries(prefix, batch_size)
+    }
+
+    fn find_entries<'a>(
+        &'a self,
+        pattern: &'a str,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        (**self).find_entries(pattern, batch_size)
+    }
 }
@@ -0,0 +1,12 @@
+/// Keyspace usage reported by `Store::usage`.
+///
+/// Both figures are meant for capacity dashboards rather than exact accounting: backends
+/// compute them from sampling or table statistics, and may include entries that have
+/// expired but not been purged yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Usage {
+    /// Number of entries in the store.
+    pub entries: u64,
+    /// Approximate number of bytes used by the entries, if the backend can report it.
+    pub bytes: Option<u64>,
+}
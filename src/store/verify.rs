@@ -0,0 +1,160 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    time::{Duration, SystemTime},
+};
+
+use futures::TryStreamExt;
+use serde_json::Value;
+
+use super::{Store, StoreError, DEFAULT_SCAN_BATCH_SIZE};
+
+/// How far apart the expirations of the two copies of an entry may be before they are
+/// reported. Both stores are read at slightly different times and round expirations
+/// differently, so exact equality would flag every entry with a TTL.
+pub const VERIFY_EXPIRATION_TOLERANCE: Duration = Duration::from_secs(1);
+
+/// The result of comparing two stores with `verify`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VerifyReport {
+    /// Number of entries of the source store that were compared.
+    pub checked: u64,
+    /// Entries whose copy in the destination store differs.
+    pub divergences: Vec<Divergence>,
+}
+
+impl VerifyReport {
+    /// Returns `true` if every compared entry matched.
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// An entry that differs between the source and the destination store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// The key of the entry.
+    pub key: String,
+    /// How the destination copy differs.
+    pub kind: DivergenceKind,
+}
+
+/// How the destination copy of an entry differs from the source.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DivergenceKind {
+    /// The destination has no live value under the key.
+    Missing,
+    /// The destination holds another value.
+    Value { expected: Value, actual: Value },
+    /// The values match but the expirations differ by more than
+    /// `VERIFY_EXPIRATION_TOLERANCE`.
+    Expiration {
+        expected: Option<SystemTime>,
+        actual: Option<SystemTime>,
+    },
+}
+
+/// Compares a sample of the entries of `src` with their copies in `dst`.
+///
+/// Meant for validating a migration or a mirror before cutting over to it. The source is
+/// scanned, and each key is compared with probability `sample_rate`: the value, and the
+/// expiration within `VERIFY_EXPIRATION_TOLERANCE`. Keys are sampled by hash rather than
+/// at random, so repeated runs check the same keys. Keys present only in `dst` are not
+/// reported.
+///
+/// The source must be able to scan its keys, and the destination must report
+/// expirations through `get_with_metadata`, as every bundled adapter does.
+///
+/// # Arguments
+/// - `src`: The store holding the expected entries.
+/// - `dst`: The store expected to hold the same entries.
+/// - `sample_rate`: The fraction of keys to compare, greater than 0 and at most 1.
+///
+/// # Returns
+/// - `Ok(VerifyReport)` listing the divergences found.
+/// - `Err(StoreError)` if `sample_rate` is out of range or either store fails.
+///
+/// # Examples
+///
+/// ```rust
+/// # use keyv::{adapter::inmemory::InMemoryStore, verify, Store};
+/// # async {
+/// let src = InMemoryStore::new();
+/// let dst = InMemoryStore::new();
+/// src.set("user:1", "alice".into(), None).await.unwrap();
+///
+/// let report = verify(&src, &dst, 1.0).await.unwrap();
+/// assert_eq!(report.divergences.len(), 1);
+/// # };
+/// ```
+pub async fn verify<S, D>(src: &S, dst: &D, sample_rate: f64) -> Result<VerifyReport, StoreError>
+where
+    S: Store + ?Sized,
+    D: Store + ?Sized,
+{
+    if !(sample_rate > 0.0 && sample_rate <= 1.0) {
+        return Err(StoreError::invalid_configuration(
+            "sample_rate",
+            "must be greater than 0 and at most 1",
+        ));
+    }
+
+    let mut report = VerifyReport::default();
+    let mut batches = src.scan_entries(None, DEFAULT_SCAN_BATCH_SIZE);
+    while let Some(batch) = batches.try_next().await? {
+        for entry in batch {
+            if !is_sampled(&entry.key, sample_rate) {
+                continue;
+            }
+            report.checked += 1;
+
+            let kind = match dst.get_with_metadata(&entry.key).await? {
+                None => Some(DivergenceKind::Missing),
+                Some((actual, _)) if actual != entry.value => Some(DivergenceKind::Value {
+                    expected: entry.value,
+                    actual,
+                }),
+                Some((_, metadata))
+                    if !expirations_match(entry.expires_at, metadata.expires_at) =>
+                {
+                    Some(DivergenceKind::Expiration {
+                        expected: entry.expires_at,
+                        actual: metadata.expires_at,
+                    })
+                }
+                Some(_) => None,
+            };
+            if let Some(kind) = kind {
+                report.divergences.push(Divergence {
+                    key: entry.key,
+                    kind,
+                });
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Returns whether `key` falls in the sample, deterministically.
+fn is_sampled(key: &str, sample_rate: f64) -> bool {
+    if sample_rate >= 1.0 {
+        return true;
+    }
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    (hasher.finish() as f64 / u64::MAX as f64) < sample_rate
+}
+
+fn expirations_match(expected: Option<SystemTime>, actual: Option<SystemTime>) -> bool {
+    match (expected, actual) {
+        (None, None) => true,
+        (Some(expected), Some(actual)) => {
+            let difference = expected
+                .duration_since(actual)
+                .or_else(|_| actual.duration_since(expected))
+                .unwrap_or(Duration::ZERO);
+            difference <= VERIFY_EXPIRATION_TOLERANCE
+        }
+        _ => false,
+    }
+}
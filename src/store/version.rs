@@ -0,0 +1,15 @@
+use std::time::SystemTime;
+
+use serde_json::Value;
+
+/// A value previously written under a key, returned by `Store::get_versions`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Version {
+    /// The id the version was recorded under, or `None` if it was recorded before
+    /// versions had ids.
+    pub id: Option<String>,
+    /// The value that was written.
+    pub value: Value,
+    /// When the value was written.
+    pub recorded_at: SystemTime,
+}
@@ -0,0 +1,141 @@
+use std::{ops::Deref, sync::Arc};
+
+use testcontainers::{runners::AsyncRunner, ContainerAsync, Image};
+#[cfg(feature = "mongo")]
+use testcontainers_modules::mongo::Mongo;
+#[cfg(feature = "mysql")]
+use testcontainers_modules::mysql::Mysql;
+#[cfg(feature = "postgres")]
+use testcontainers_modules::postgres::Postgres;
+#[cfg(feature = "redis")]
+use testcontainers_modules::redis::{Redis, REDIS_PORT};
+
+#[cfg(feature = "mongo")]
+use crate::adapter::mongodb::{MongoStore, MongoStoreBuilder};
+#[cfg(feature = "mysql")]
+use crate::adapter::mysql::{MySqlStore, MySqlStoreBuilder};
+#[cfg(feature = "postgres")]
+use crate::adapter::postgres::{PostgresStore, PostgresStoreBuilder};
+#[cfg(feature = "redis")]
+use crate::adapter::redis::{NamespacedRedisStore, RedisStoreBuilder};
+use crate::{Keyv, KeyvError, Store, StoreError};
+
+/// A store connected to a throwaway container, returned by `start_redis` and the other
+/// `start_*` helpers.
+///
+/// The container is stopped and removed when this value is dropped, so keep it alive
+/// for as long as the store, or a `Keyv` built on it, is used.
+pub struct ContainerStore<S, I: Image> {
+    store: Arc<S>,
+    url: String,
+    _container: ContainerAsync<I>,
+}
+
+impl<S: Store + 'static, I: Image> ContainerStore<S, I> {
+    /// Returns a shared handle on the store, e.g. to wrap it in layers.
+    pub fn store(&self) -> Arc<S> {
+        self.store.clone()
+    }
+
+    /// Returns the URL of the backend, to connect more stores or clients to it.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns a `Keyv` on the store.
+    pub async fn keyv(&self) -> Result<Keyv, KeyvError> {
+        Keyv::try_new(self.store.clone()).await
+    }
+}
+
+impl<S, I: Image> Deref for ContainerStore<S, I> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.store
+    }
+}
+
+/// Starts `image` and returns the container with the host and port `port` is
+/// published on.
+async fn start<I: Image>(image: I, port: u16) -> Result<(ContainerAsync<I>, String), StoreError> {
+    let container = image.start().await.map_err(StoreError::connection)?;
+    let host = container.get_host().await.map_err(StoreError::connection)?;
+    let port = container
+        .get_host_port_ipv4(port)
+        .await
+        .map_err(StoreError::connection)?;
+    Ok((container, format!("{}:{}", host, port)))
+}
+
+/// Starts a Redis container and returns a store connected to it.
+///
+/// Requires a Docker daemon; the image is pulled on first use.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use keyv::testing::start_redis;
+/// # async {
+/// let redis = start_redis().await.unwrap();
+/// let keyv = redis.keyv().await.unwrap();
+/// keyv.set("key", "value").await.unwrap();
+/// # };
+/// ```
+#[cfg(feature = "redis")]
+pub async fn start_redis() -> Result<ContainerStore<NamespacedRedisStore, Redis>, StoreError> {
+    let (container, address) = start(Redis::default(), REDIS_PORT).await?;
+    let url = format!("redis://{}", address);
+    let store = RedisStoreBuilder::new().uri(&url).build().await?;
+    Ok(ContainerStore {
+        store: Arc::new(store),
+        url,
+        _container: container,
+    })
+}
+
+/// Starts a PostgreSQL container and returns a store connected to its `postgres`
+/// database.
+///
+/// Requires a Docker daemon; the image is pulled on first use.
+#[cfg(feature = "postgres")]
+pub async fn start_postgres() -> Result<ContainerStore<PostgresStore, Postgres>, StoreError> {
+    let (container, address) = start(Postgres::default(), 5432).await?;
+    let url = format!("postgres://postgres:postgres@{}/postgres", address);
+    let store = PostgresStoreBuilder::new().uri(&url).build().await?;
+    Ok(ContainerStore {
+        store: Arc::new(store),
+        url,
+        _container: container,
+    })
+}
+
+/// Starts a MySQL container and returns a store connected to its `test` database.
+///
+/// Requires a Docker daemon; the image is pulled on first use.
+#[cfg(feature = "mysql")]
+pub async fn start_mysql() -> Result<ContainerStore<MySqlStore, Mysql>, StoreError> {
+    let (container, address) = start(Mysql::default(), 3306).await?;
+    let url = format!("mysql://root@{}/test", address);
+    let store = MySqlStoreBuilder::new().uri(&url).build().await?;
+    Ok(ContainerStore {
+        store: Arc::new(store),
+        url,
+        _container: container,
+    })
+}
+
+/// Starts a MongoDB container and returns a store connected to it.
+///
+/// Requires a Docker daemon; the image is pulled on first use.
+#[cfg(feature = "mongo")]
+pub async fn start_mongo() -> Result<ContainerStore<MongoStore, Mongo>, StoreError> {
+    let (container, address) = start(Mongo::default(), 27017).await?;
+    let url = format!("mongodb://{}", address);
+    let store = MongoStoreBuilder::new().uri(&url).build().await?;
+    Ok(ContainerStore {
+        store: Arc::new(store),
+        url,
+        _container: container,
+    })
+}
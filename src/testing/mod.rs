@@ -0,0 +1,7 @@
+mod ttl;
+pub use ttl::*;
+
+#[cfg(feature = "containers")]
+mod containers;
+#[cfg(feature = "containers")]
+pub use containers::*;
@@ -0,0 +1,185 @@
+use std::{collections::HashMap, time::Duration};
+
+use proptest::{
+    prelude::*,
+    test_runner::{Config, TestCaseError, TestRunner},
+};
+use serde_json::Value;
+
+use crate::{ManualClock, Store, StoreError};
+
+/// Keys the generated operations act on, few enough that operations often hit the same
+/// entry.
+const KEYS: [&str; 3] = ["ttl:a", "ttl:b", "ttl:c"];
+
+/// Longest sequence of operations generated for a single case.
+const MAX_OPS: usize = 32;
+
+/// An operation of a sequence checked by `run_ttl_ops`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TtlOp {
+    /// Sets `key` to a value unique to the operation, expiring after `ttl` if any.
+    Set {
+        key: &'static str,
+        ttl: Option<Duration>,
+    },
+    /// Reads `key`.
+    Get { key: &'static str },
+    /// Reads `key` with `get_and_touch`, restarting its TTL.
+    Touch { key: &'static str, ttl: Duration },
+    /// Removes `key`.
+    Remove { key: &'static str },
+    /// Moves the clock forward, then reads every key.
+    Advance(Duration),
+}
+
+/// Durations clustered around whole seconds, where truncating or rounding to the second
+/// shows, plus the odd arbitrary one.
+fn durations() -> impl Strategy<Value = Duration> {
+    prop_oneof![
+        prop::sample::select(vec![1u64, 999, 1_000, 1_001, 1_999, 2_000, 2_001]),
+        1u64..5_000,
+    ]
+    .prop_map(Duration::from_millis)
+}
+
+fn op() -> impl Strategy<Value = TtlOp> {
+    let key = || prop::sample::select(KEYS.to_vec());
+    prop_oneof![
+        (key(), prop::option::weighted(0.8, durations()))
+            .prop_map(|(key, ttl)| TtlOp::Set { key, ttl }),
+        key().prop_map(|key| TtlOp::Get { key }),
+        (key(), durations()).prop_map(|(key, ttl)| TtlOp::Touch { key, ttl }),
+        key().prop_map(|key| TtlOp::Remove { key }),
+        durations().prop_map(TtlOp::Advance),
+    ]
+}
+
+/// Returns a strategy generating sequences of interleaved `TtlOp`s, for adapter authors
+/// driving `run_ttl_ops` from their own `proptest!` blocks.
+pub fn ttl_ops() -> impl Strategy<Value = Vec<TtlOp>> {
+    prop::collection::vec(op(), 1..=MAX_OPS)
+}
+
+/// The expected contents of the store: values and the elapsed clock time at which they
+/// expire.
+#[derive(Default)]
+struct Model {
+    entries: HashMap<&'static str, (Value, Option<Duration>)>,
+}
+
+impl Model {
+    /// Returns the value of `key` if it has not expired at `now`. An entry expires the
+    /// moment its TTL has elapsed, not a millisecond later.
+    fn get(&mut self, key: &str, now: Duration) -> Option<Value> {
+        let (value, expires_at) = self.entries.get(key)?;
+        if expires_at.is_some_and(|expires_at| expires_at <= now) {
+            self.entries.remove(key);
+            return None;
+        }
+        Some(value.clone())
+    }
+}
+
+/// Applies `ops` to `store`, whose TTLs must be measured against `clock`, and checks
+/// every read against a model of the expected expirations.
+///
+/// The store must start empty and must never move `clock` itself. Values are read back
+/// exactly, so a store returning an entry one tick after its TTL elapsed, or dropping it
+/// one tick early, fails with the operation and the time at which it diverged.
+pub async fn run_ttl_ops<S: Store + ?Sized>(
+    store: &S,
+    clock: &ManualClock,
+    ops: &[TtlOp],
+) -> Result<(), TestCaseError> {
+    let mut model = Model::default();
+    let query = |op: &TtlOp, e: StoreError| TestCaseError::fail(format!("{:?}: {}", op, e));
+    for (i, op) in ops.iter().enumerate() {
+        let now = clock.elapsed();
+        match op {
+            TtlOp::Set { key, ttl } => {
+                let value = Value::from(i);
+                store
+                    .set(key, value.clone(), *ttl)
+                    .await
+                    .map_err(|e| query(op, e))?;
+                model.entries.insert(key, (value, ttl.map(|ttl| now + ttl)));
+            }
+            TtlOp::Get { key } => {
+                let actual = store.get(key).await.map_err(|e| query(op, e))?;
+                let expected = model.get(key, now);
+                prop_assert_eq!(actual, expected, "{:?} after {:?}", op, now);
+            }
+            TtlOp::Touch { key, ttl } => {
+                let actual = store
+                    .get_and_touch(key, *ttl)
+                    .await
+                    .map_err(|e| query(op, e))?;
+                let expected = model.get(key, now);
+                prop_assert_eq!(&actual, &expected, "{:?} after {:?}", op, now);
+                if let Some(value) = expected {
+                    model.entries.insert(key, (value, Some(now + *ttl)));
+                }
+            }
+            TtlOp::Remove { key } => {
+                store.remove(key).await.map_err(|e| query(op, e))?;
+                model.entries.remove(key);
+            }
+            TtlOp::Advance(duration) => {
+                clock.advance(*duration);
+                let now = clock.elapsed();
+                let actual = store.get_many(&KEYS).await.map_err(|e| query(op, e))?;
+                let expected: Vec<Option<Value>> =
+                    KEYS.iter().map(|key| model.get(key, now)).collect();
+                prop_assert_eq!(actual, expected, "get_many of {:?} after {:?}", KEYS, now);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the stores built by `new_store` honour TTLs exactly, on random sequences
+/// of `set`, `get`, `get_and_touch`, `remove` and clock advances.
+///
+/// `new_store` is called once per case with a fresh `ManualClock` and must return an
+/// empty store measuring TTLs against it. Failing sequences are shrunk to a minimal one
+/// before panicking with it. The number of cases defaults to 256 and can be changed with
+/// the `PROPTEST_CASES` environment variable.
+///
+/// The check runs on its own Tokio runtime, so call it from a plain `#[test]` rather
+/// than from `#[tokio::test]`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use keyv::{adapter::inmemory::InMemoryStore, testing::check_ttl_semantics};
+/// check_ttl_semantics(|clock| InMemoryStore::new().clock(clock));
+/// ```
+pub fn check_ttl_semantics<S, F>(new_store: F)
+where
+    S: Store,
+    F: Fn(ManualClock) -> S,
+{
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to start a Tokio runtime");
+    let mut runner = TestRunner::new(Config {
+        failure_persistence: None,
+        ..Config::default()
+    });
+    let result = runner.run(&ttl_ops(), |ops| {
+        let clock = ManualClock::new();
+        let store = new_store(clock.clone());
+        runtime.block_on(async {
+            store
+                .initialize()
+                .await
+                .map_err(|e| TestCaseError::fail(format!("initialize: {}", e)))?;
+            run_ttl_ops(&store, &clock, &ops).await
+        })
+    });
+    if let Err(e) = result {
+        panic!("TTL semantics violated: {}", e);
+    }
+}
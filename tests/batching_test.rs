@@ -0,0 +1,46 @@
+#![cfg(feature = "runtime")]
+
+use std::time::Duration;
+
+use keyv::{adapter::inmemory::InMemoryStore, layer::batching::BatchingStore, Store};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_batching_coalesces_writes() {
+    let store = BatchingStore::new(InMemoryStore::new(), Duration::from_secs(3600));
+    store.initialize().await.unwrap();
+
+    store.set("counter", json!(1), None).await.unwrap();
+    store.set("counter", json!(2), None).await.unwrap();
+    store.set("other", json!("value"), None).await.unwrap();
+
+    // Buffered writes are visible through the layer but not yet in the backend.
+    assert_eq!(store.get("counter").await.unwrap(), Some(json!(2)));
+    assert_eq!(store.inner().get("counter").await.unwrap(), None);
+
+    store.flush().await.unwrap();
+    assert_eq!(store.inner().get("counter").await.unwrap(), Some(json!(2)));
+    assert_eq!(
+        store.inner().get("other").await.unwrap(),
+        Some(json!("value"))
+    );
+
+    store.set("counter", json!(3), None).await.unwrap();
+    store.remove("counter").await.unwrap();
+    store.flush().await.unwrap();
+    assert_eq!(store.get("counter").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_batching_flushes_when_full() {
+    let store =
+        BatchingStore::new(InMemoryStore::new(), Duration::from_secs(3600)).max_batch_size(2);
+    store.initialize().await.unwrap();
+
+    store.set("a", json!(1), None).await.unwrap();
+    assert_eq!(store.inner().get("a").await.unwrap(), None);
+
+    store.set("b", json!(2), None).await.unwrap();
+    assert_eq!(store.inner().get("a").await.unwrap(), Some(json!(1)));
+    assert_eq!(store.inner().get("b").await.unwrap(), Some(json!(2)));
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "runtime")]
+
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use keyv::{adapter::inmemory::InMemoryStore, Keyv, RetryPolicy, Store, StoreError};
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_builder_applies_settings() {
+    let store = Arc::new(InMemoryStore::new());
+    let keyv = Keyv::builder()
+        .store(store.clone())
+        .namespace("app")
+        .default_ttl(Duration::from_millis(50))
+        .stats()
+        .build()
+        .await
+        .unwrap();
+
+    keyv.set("key", "value").await.unwrap();
+    assert_eq!(store.get("app:key").await.unwrap(), Some(json!("value")));
+    assert_eq!(keyv.get("key").await.unwrap(), Some(json!("value")));
+    assert_eq!(keyv.stats().unwrap().hits(), 1);
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(keyv.get("key").await.unwrap().is_none());
+}
+
+/// Fails to initialize until it has been asked `failures` times.
+struct FlakyStore {
+    inner: InMemoryStore,
+    failures: u32,
+    attempts: AtomicU32,
+}
+
+#[async_trait]
+impl Store for FlakyStore {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+            return Err(StoreError::ConnectionError("not ready".to_string()));
+        }
+        self.inner.initialize().await
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.inner.set(key, value, ttl).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.inner.remove_many(keys).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+}
+
+#[tokio::test]
+async fn test_builder_retries_initialization() {
+    let flaky = || FlakyStore {
+        inner: InMemoryStore::new(),
+        failures: 2,
+        attempts: AtomicU32::new(0),
+    };
+
+    assert!(Keyv::builder().store(flaky()).build().await.is_err());
+
+    let keyv = Keyv::builder()
+        .store(flaky())
+        .retry(RetryPolicy::new(3).backoff(Duration::from_millis(1), Duration::from_millis(1)))
+        .build()
+        .await
+        .unwrap();
+    keyv.set("key", "value").await.unwrap();
+}
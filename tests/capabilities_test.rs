@@ -0,0 +1,90 @@
+#![cfg(feature = "runtime")]
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use keyv::{
+    adapter::inmemory::InMemoryStore, layer::ttl::TtlStore, Capabilities, Keyv, Store, StoreError,
+};
+use serde_json::Value;
+
+/// Store implementing only the required methods.
+#[derive(Default)]
+struct Minimal(InMemoryStore);
+
+#[async_trait]
+impl Store for Minimal {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.0.get(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, _ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.0.set(key, value, None).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.0.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.0.remove_many(keys).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.0.clear().await
+    }
+}
+
+#[tokio::test]
+async fn test_inmemory_capabilities() {
+    let keyv = Keyv::default().with_stats();
+    let capabilities = keyv.capabilities();
+    assert!(capabilities.native_ttl && capabilities.atomic_increment);
+    assert!(capabilities.scan && capabilities.watch);
+    assert!(!capabilities.transactions);
+}
+
+#[tokio::test]
+async fn test_ttl_layer_adds_expiration() {
+    assert_eq!(Minimal::default().capabilities(), Capabilities::default());
+
+    let keyv = Keyv::try_new(TtlStore::new(Minimal::default()))
+        .await
+        .unwrap();
+    assert_eq!(
+        keyv.capabilities(),
+        Capabilities {
+            native_ttl: true,
+            ..Capabilities::default()
+        }
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_capabilities() {
+    use keyv::adapter::sqlite::SqliteStoreBuilder;
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let capabilities = store.capabilities();
+    assert!(capabilities.transactions && capabilities.atomic_increment);
+    assert!(capabilities.scan && capabilities.watch);
+}
+
+#[cfg(feature = "hashed-keys")]
+#[tokio::test]
+async fn test_hashed_keys_cannot_scan() {
+    use keyv::layer::hashed_keys::{HashedKeyStore, HmacCodec};
+
+    let store = HashedKeyStore::new(InMemoryStore::new(), HmacCodec::new("secret"));
+    assert!(!store.capabilities().scan);
+    assert!(store.capabilities().native_ttl);
+}
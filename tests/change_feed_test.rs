@@ -0,0 +1,240 @@
+#![cfg(feature = "runtime")]
+
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use keyv::{
+    adapter::inmemory::InMemoryStore, layer::change_feed::ChangeFeedStore, Change,
+    ChangeFeedBackend, ChangeKind, Keyv, KeyvError, Store, StoreError,
+};
+use serde_json::{json, Value};
+
+/// Store whose writes always fail, keeping its change feed in memory.
+#[derive(Default)]
+struct FailingWrites {
+    inner: InMemoryStore,
+}
+
+#[async_trait]
+impl Store for FailingWrites {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get(key).await
+    }
+
+    async fn set(
+        &self,
+        _key: &str,
+        _value: Value,
+        _ttl: Option<Duration>,
+    ) -> Result<(), StoreError> {
+        Err(StoreError::QueryError("write rejected".to_string()))
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.inner.remove_many(keys).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+}
+
+#[async_trait]
+impl ChangeFeedBackend for FailingWrites {
+    async fn append_change(
+        &self,
+        key: Option<&str>,
+        kind: ChangeKind,
+    ) -> Result<String, StoreError> {
+        self.inner.append_change(key, kind).await
+    }
+
+    async fn read_changes(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Change>, StoreError> {
+        self.inner.read_changes(after, limit).await
+    }
+}
+
+fn summary(changes: &[Change]) -> Vec<(Option<&str>, ChangeKind)> {
+    changes
+        .iter()
+        .map(|change| (change.key.as_deref(), change.kind))
+        .collect()
+}
+
+/// Returns a handle recording its mutations in the change feed it reads.
+async fn recording_keyv() -> Keyv {
+    let backend = Arc::new(InMemoryStore::new());
+    Keyv::try_new(ChangeFeedStore::new(backend.clone()))
+        .await
+        .unwrap()
+        .with_change_feed(backend)
+}
+
+#[tokio::test]
+async fn test_change_feed_records_mutations_in_order() {
+    let keyv = recording_keyv().await;
+
+    keyv.set("a", 1).await.unwrap();
+    keyv.set("b", 2).await.unwrap();
+    keyv.set("c", 3).await.unwrap();
+    keyv.remove_many(&["a"]).await.unwrap();
+    keyv.rename("b", "d").await.unwrap();
+    keyv.clear().await.unwrap();
+
+    let changes = keyv.changes(None).await.unwrap();
+    assert_eq!(
+        summary(&changes),
+        [
+            (Some("a"), ChangeKind::Set),
+            (Some("b"), ChangeKind::Set),
+            (Some("c"), ChangeKind::Set),
+            (Some("a"), ChangeKind::Remove),
+            (Some("b"), ChangeKind::Remove),
+            (Some("d"), ChangeKind::Set),
+            (None, ChangeKind::Clear),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_change_feed_resumes_after_cursor() {
+    let store = ChangeFeedStore::new(InMemoryStore::new());
+    for key in ["a", "b", "c"] {
+        store.set(key, json!(key), None).await.unwrap();
+    }
+    let store = store.inner();
+
+    let first = store.read_changes(None, 2).await.unwrap();
+    assert_eq!(first.len(), 2);
+    let rest = store.read_changes(Some(&first[1].cursor), 2).await.unwrap();
+    assert_eq!(summary(&rest), [(Some("c"), ChangeKind::Set)]);
+    assert!(store
+        .read_changes(Some(&rest[0].cursor), 2)
+        .await
+        .unwrap()
+        .is_empty());
+
+    assert!(matches!(
+        store.read_changes(Some("not-a-cursor"), 2).await,
+        Err(StoreError::QueryError(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_failed_write_is_not_recorded() {
+    let store = ChangeFeedStore::new(FailingWrites::default());
+
+    assert!(store.set("a", json!(1), None).await.is_err());
+    store.remove("a").await.unwrap();
+
+    let changes = store.inner().read_changes(None, 10).await.unwrap();
+    assert_eq!(summary(&changes), [(Some("a"), ChangeKind::Remove)]);
+}
+
+#[tokio::test]
+async fn test_conditional_writes_are_recorded_when_applied() {
+    let store = ChangeFeedStore::new(InMemoryStore::new());
+
+    assert!(store.set_if_absent("a", json!(1), None).await.unwrap());
+    assert!(!store.set_if_absent("a", json!(2), None).await.unwrap());
+    assert!(!store.remove_if("a", &json!(2)).await.unwrap());
+
+    let changes = store.inner().read_changes(None, 10).await.unwrap();
+    assert_eq!(summary(&changes), [(Some("a"), ChangeKind::Set)]);
+}
+
+#[tokio::test]
+async fn test_namespaced_changes_skip_other_namespaces() {
+    let keyv = recording_keyv().await;
+    let users = keyv.namespace("users");
+    let orders = keyv.namespace("orders");
+
+    users.set("alice", 1).await.unwrap();
+    orders.set("1", 2).await.unwrap();
+    users.remove("alice").await.unwrap();
+
+    let changes = users.changes(None).await.unwrap();
+    assert_eq!(
+        summary(&changes),
+        [
+            (Some("alice"), ChangeKind::Set),
+            (Some("alice"), ChangeKind::Remove),
+        ]
+    );
+    let changes = orders.changes(None).await.unwrap();
+    assert_eq!(summary(&changes), [(Some("1"), ChangeKind::Set)]);
+}
+
+#[tokio::test]
+async fn test_writes_are_not_recorded_without_layer() {
+    let backend = Arc::new(InMemoryStore::new());
+    let keyv = Keyv::try_new(backend.clone())
+        .await
+        .unwrap()
+        .with_change_feed(backend);
+    keyv.set("a", 1).await.unwrap();
+
+    assert!(keyv.changes(None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_changes_without_feed_are_unsupported() {
+    let keyv = Keyv::try_new(ChangeFeedStore::new(InMemoryStore::new()))
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        keyv.changes(None).await,
+        Err(KeyvError::StoreError(StoreError::Unsupported(_)))
+    ));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_change_feed() {
+    use keyv::adapter::sqlite::SqliteStoreBuilder;
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let backend = Arc::new(store);
+    let keyv = Keyv::try_new(ChangeFeedStore::new(backend.clone()))
+        .await
+        .unwrap()
+        .with_change_feed(backend);
+
+    keyv.set("config", json!({ "rps": 10 })).await.unwrap();
+    keyv.remove("config").await.unwrap();
+
+    let changes = keyv.changes(None).await.unwrap();
+    assert_eq!(
+        summary(&changes),
+        [
+            (Some("config"), ChangeKind::Set),
+            (Some("config"), ChangeKind::Remove),
+        ]
+    );
+    assert!(keyv
+        .changes(Some(&changes[1].cursor))
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(matches!(
+        keyv.changes(Some("-1")).await,
+        Err(KeyvError::StoreError(StoreError::QueryError(_)))
+    ));
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "runtime")]
+
+use keyv::{ClearPolicy, Keyv, KeyvError};
+
+#[tokio::test]
+async fn test_clear_requires_confirmation() {
+    let keyv = Keyv::default().with_clear_policy(ClearPolicy::RequireConfirmation);
+    keyv.set("a", 1).await.unwrap();
+    keyv.set("b", 2).await.unwrap();
+
+    assert!(matches!(
+        keyv.clear().await,
+        Err(KeyvError::ClearNotConfirmed)
+    ));
+    let preview = keyv.clear_dry_run().await.unwrap();
+    assert_eq!(preview.entries, Some(2));
+    assert!(keyv.get("a").await.unwrap().is_some());
+
+    keyv.clear_confirmed(preview.token).await.unwrap();
+    assert!(keyv.get("a").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_confirm_token_is_bound_to_its_handle() {
+    let production = Keyv::default().with_clear_policy(ClearPolicy::RequireConfirmation);
+    let staging = Keyv::default();
+    production.set("a", 1).await.unwrap();
+
+    let token = staging.clear_dry_run().await.unwrap().token;
+    assert!(matches!(
+        production.clear_confirmed(token).await,
+        Err(KeyvError::InvalidConfirmToken)
+    ));
+    assert!(production.get("a").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_clear_denied() {
+    let keyv = Keyv::default().with_clear_policy(ClearPolicy::Deny);
+    keyv.set("a", 1).await.unwrap();
+
+    assert!(matches!(keyv.clear().await, Err(KeyvError::ClearDisabled)));
+    let preview = keyv.clear_dry_run().await.unwrap();
+    assert!(matches!(
+        keyv.clear_confirmed(preview.token).await,
+        Err(KeyvError::ClearDisabled)
+    ));
+    assert!(keyv.get("a").await.unwrap().is_some());
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "runtime")]
+
+#[cfg(feature = "compression")]
+use keyv::{
+    adapter::inmemory::InMemoryStore,
+    layer::{
+        compression::Gzip,
+        stats::Stats,
+        transform::{TransformStore, Transformer, CODEC_FIELD},
+    },
+    Keyv, Store, StoreError,
+};
+#[cfg(feature = "compression")]
+use serde_json::json;
+#[cfg(feature = "compression")]
+use std::sync::Arc;
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn test_large_values_are_compressed() {
+    let backend = Arc::new(InMemoryStore::new());
+    let store = TransformStore::new(backend.clone()).transformer(Gzip::new().min_size(100));
+    let report = json!({ "rows": vec!["repeated row"; 100] });
+
+    store.set("report", report.clone(), None).await.unwrap();
+    store.set("small", json!("tiny"), None).await.unwrap();
+
+    let stored = backend.get("report").await.unwrap().unwrap();
+    assert_eq!(stored[CODEC_FIELD], json!("gzip"));
+    assert!(stored.to_string().len() < report.to_string().len());
+    assert_eq!(store.get("report").await.unwrap(), Some(report.clone()));
+    assert_eq!(backend.get("small").await.unwrap(), Some(json!("tiny")));
+
+    assert!(!store.remove_if("report", &json!("other")).await.unwrap());
+    assert!(store.remove_if("report", &report).await.unwrap());
+    assert!(backend.get("report").await.unwrap().is_none());
+}
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn test_reads_values_written_without_compression() {
+    let backend = Arc::new(InMemoryStore::new());
+    let plain = json!({ "rows": vec!["written by a service without compression"; 100] });
+    backend.set("shared", plain.clone(), None).await.unwrap();
+
+    let store = TransformStore::new(backend.clone()).transformer(Gzip::new());
+    assert_eq!(store.get("shared").await.unwrap(), Some(plain));
+    assert_eq!(store.increment("hits", 2, None).await.unwrap(), 2);
+}
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn test_compression_stats() {
+    let stats = Arc::new(Stats::new());
+    let store = TransformStore::new(InMemoryStore::new())
+        .transformer(Gzip::new().min_size(100))
+        .stats(stats.clone());
+    assert_eq!(stats.compression_ratio(), None);
+
+    store.set("small", json!("tiny"), None).await.unwrap();
+    assert_eq!(stats.original_bytes(), 6);
+    assert_eq!(stats.stored_bytes(), 6);
+    assert_eq!(stats.compression_ratio(), Some(1.0));
+
+    let report = json!({ "rows": vec!["repeated row"; 100] });
+    store.set("report", report.clone(), None).await.unwrap();
+    assert_eq!(stats.original_bytes(), 6 + report.to_string().len() as u64);
+    assert!(stats.stored_bytes() < stats.original_bytes());
+    assert!(stats.compression_ratio().unwrap() < 0.5);
+
+    stats.reset();
+    assert_eq!(stats.compression_ratio(), None);
+}
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn test_compression_stats_per_namespace() {
+    let gzip: Arc<dyn Transformer> = Arc::new(Gzip::new().min_size(100));
+    let keyv = Keyv::default().with_transformers([gzip]).with_stats();
+    let reports = keyv.namespace("reports").with_stats();
+    let report = json!({ "rows": vec!["repeated row"; 100] });
+
+    reports.set("daily", report.clone()).await.unwrap();
+    keyv.set("hits", 1).await.unwrap();
+
+    let stats = reports.stats().unwrap();
+    assert_eq!(stats.original_bytes(), report.to_string().len() as u64);
+    assert!(stats.compression_ratio().unwrap() < 0.5);
+    let stats = keyv.stats().unwrap();
+    assert_eq!(stats.original_bytes(), 1);
+    assert_eq!(stats.compression_ratio(), Some(1.0));
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn test_decode_rejects_unknown_codecs() {
+    let store = TransformStore::new(InMemoryStore::new()).transformer(Gzip::new());
+    let value = json!({ CODEC_FIELD: "zstd", "data": "AAAA" });
+    assert!(matches!(
+        store.decode(value),
+        Err(StoreError::QueryError(_))
+    ));
+    let lookalike = json!({ CODEC_FIELD: "gzip", "data": "AAAA", "extra": true });
+    assert_eq!(store.decode(lookalike.clone()).unwrap(), lookalike);
+}
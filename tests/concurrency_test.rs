@@ -0,0 +1,228 @@
+#![cfg(feature = "runtime")]
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures::{future, stream::BoxStream, Future, StreamExt, TryStreamExt};
+use keyv::{
+    adapter::inmemory::InMemoryStore,
+    layer::concurrency::{ConcurrencyLimitStore, ConcurrencyMode},
+    ScanEntry, Store, StoreError,
+};
+use serde_json::{json, Value};
+use tokio::sync::Semaphore;
+
+/// Store whose writes block until the test releases them.
+struct Gated {
+    inner: InMemoryStore,
+    gate: Semaphore,
+    started: AtomicUsize,
+}
+
+impl Gated {
+    fn new() -> Self {
+        Self {
+            inner: InMemoryStore::new(),
+            gate: Semaphore::new(0),
+            started: AtomicUsize::new(0),
+        }
+    }
+
+    /// Waits until `count` writes have reached the store.
+    async fn wait_started(&self, count: usize) {
+        while self.started.load(Ordering::SeqCst) < count {
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
+#[async_trait]
+impl Store for Gated {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.inner.get(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.started.fetch_add(1, Ordering::SeqCst);
+        self.gate.acquire().await.unwrap().forget();
+        self.inner.set(key, value, ttl).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.inner.remove_many(keys).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+}
+
+/// Store counting the calls in flight, including the batches of scans.
+#[derive(Default)]
+struct Tracked {
+    inner: InMemoryStore,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+impl Tracked {
+    async fn track<T>(&self, call: impl Future<Output = T>) -> T {
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        // Lets the other calls start while this one is in flight.
+        tokio::task::yield_now().await;
+        let result = call.await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        result
+    }
+}
+
+#[async_trait]
+impl Store for Tracked {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.track(self.inner.get(key)).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.inner.set(key, value, ttl).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.inner.remove_many(keys).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+
+    fn scan_entries<'a>(
+        &'a self,
+        prefix: Option<&'a str>,
+        batch_size: usize,
+    ) -> BoxStream<'a, Result<Vec<ScanEntry>, StoreError>> {
+        Box::pin(
+            self.inner
+                .scan_entries(prefix, batch_size)
+                .then(move |batch| self.track(async move { batch })),
+        )
+    }
+}
+
+fn spawn_sets(
+    store: &Arc<ConcurrencyLimitStore<Arc<Gated>>>,
+    count: usize,
+) -> Vec<tokio::task::JoinHandle<Result<(), StoreError>>> {
+    (0..count)
+        .map(|i| {
+            let store = store.clone();
+            tokio::spawn(async move { store.set(&format!("key{}", i), json!(i), None).await })
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_concurrency_limit_fail_fast() {
+    let gated = Arc::new(Gated::new());
+    let store = Arc::new(ConcurrencyLimitStore::new(
+        gated.clone(),
+        2,
+        ConcurrencyMode::FailFast,
+    ));
+
+    let handles = spawn_sets(&store, 2);
+    gated.wait_started(2).await;
+    assert_eq!(store.available_permits(), 0);
+
+    // Bounded, so that a write let through to the blocked store fails the test
+    // instead of hanging it.
+    let rejected = tokio::time::timeout(
+        Duration::from_secs(1),
+        store.set("rejected", json!(0), None),
+    );
+    match rejected.await {
+        Ok(Err(StoreError::ConcurrencyLimitExceeded(2))) => {}
+        other => panic!("Expected the limit to be enforced, got {:?}", other),
+    }
+    assert!(gated.get("rejected").await.unwrap().is_none());
+
+    gated.gate.add_permits(2);
+    for handle in handles {
+        handle.await.unwrap().unwrap();
+    }
+    assert_eq!(store.available_permits(), 2);
+    assert_eq!(gated.started.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_concurrency_limit_queue() {
+    let gated = Arc::new(Gated::new());
+    let store = Arc::new(ConcurrencyLimitStore::new(
+        gated.clone(),
+        2,
+        ConcurrencyMode::Queue,
+    ));
+
+    let handles = spawn_sets(&store, 6);
+    gated.wait_started(2).await;
+
+    // The other writes wait for a permit instead of reaching the store.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(gated.started.load(Ordering::SeqCst), 2);
+    assert_eq!(store.available_permits(), 0);
+
+    gated.gate.add_permits(6);
+    for handle in handles {
+        handle.await.unwrap().unwrap();
+    }
+    assert_eq!(gated.started.load(Ordering::SeqCst), 6);
+    assert_eq!(store.available_permits(), 2);
+    for i in 0..6 {
+        assert_eq!(
+            store.get(&format!("key{}", i)).await.unwrap(),
+            Some(json!(i))
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_concurrency_limit_covers_scans() {
+    let tracked = Arc::new(Tracked::default());
+    for i in 0..20 {
+        tracked
+            .set(&format!("key{}", i), json!(i), None)
+            .await
+            .unwrap();
+    }
+    let store = &ConcurrencyLimitStore::new(tracked.clone(), 2, ConcurrencyMode::Queue);
+
+    let gets =
+        future::join_all((0..20).map(|i| async move { store.get(&format!("key{}", i)).await }));
+    let scan = store.scan_entries(None, 1).try_concat();
+    let (gets, scanned) = tokio::join!(gets, scan);
+
+    assert!(gets.into_iter().all(|value| value.unwrap().is_some()));
+    assert_eq!(scanned.unwrap().len(), 20);
+    assert_eq!(tracked.max_in_flight.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "runtime")]
+
+use std::{collections::HashMap, time::Duration};
+
+use keyv::{Keyv, KeyvError, StoreError};
+use serde_json::json;
+
+type Limits = HashMap<String, u32>;
+
+#[tokio::test]
+async fn test_config_hot_reload() {
+    let keyv = Keyv::default();
+    keyv.set("app:limits", json!({ "workers": 4 }))
+        .await
+        .unwrap();
+
+    let mut limits = keyv
+        .config_with_poll_interval::<Limits>("app:limits", Duration::from_millis(20))
+        .await
+        .unwrap();
+    assert_eq!(limits.borrow()["workers"], 4);
+
+    keyv.set("app:limits", json!({ "workers": 8 }))
+        .await
+        .unwrap();
+    tokio::time::timeout(Duration::from_secs(1), limits.changed())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(limits.borrow_and_update()["workers"], 8);
+
+    // Invalid or missing configurations keep the last good one.
+    keyv.set("app:limits", "not a map").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    keyv.remove("app:limits").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!limits.has_changed().unwrap());
+    assert_eq!(limits.borrow()["workers"], 8);
+}
+
+#[tokio::test]
+async fn test_config_must_exist_and_deserialize() {
+    let keyv = Keyv::default();
+    assert!(matches!(
+        keyv.config::<Limits>("app:limits").await,
+        Err(KeyvError::StoreError(StoreError::NotFound))
+    ));
+
+    keyv.set("app:limits", "not a map").await.unwrap();
+    assert!(matches!(
+        keyv.config::<Limits>("app:limits").await,
+        Err(KeyvError::StoreError(StoreError::SerializationError { .. }))
+    ));
+}
@@ -0,0 +1,23 @@
+#[cfg(feature = "containers")]
+use serde_json::json;
+
+#[cfg(all(feature = "containers", feature = "redis"))]
+#[tokio::test]
+async fn test_start_redis() {
+    let redis = keyv::testing::start_redis().await.unwrap();
+    let keyv = redis.keyv().await.unwrap();
+
+    keyv.set("key", "value").await.unwrap();
+    assert_eq!(keyv.get("key").await.unwrap(), Some(json!("value")));
+    assert!(redis.url().starts_with("redis://"));
+}
+
+#[cfg(all(feature = "containers", feature = "postgres"))]
+#[tokio::test]
+async fn test_start_postgres() {
+    let postgres = keyv::testing::start_postgres().await.unwrap();
+    let keyv = postgres.keyv().await.unwrap();
+
+    keyv.set("key", "value").await.unwrap();
+    assert_eq!(keyv.get("key").await.unwrap(), Some(json!("value")));
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "runtime")]
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use keyv::{
+    adapter::inmemory::InMemoryStore,
+    layer::crdt::{LwwStore, SyncReport},
+    Keyv, Store,
+};
+use serde_json::json;
+
+fn replica(node: &str) -> LwwStore<InMemoryStore> {
+    LwwStore::new(InMemoryStore::new(), node)
+}
+
+#[tokio::test]
+async fn test_sync_converges_on_last_writer() {
+    let laptop = replica("laptop");
+    let server = replica("server");
+
+    laptop.set("title", json!("draft"), None).await.unwrap();
+    server.set("owner", json!("alice"), None).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    server.set("title", json!("final"), None).await.unwrap();
+
+    let report = laptop.sync(&server).await.unwrap();
+    assert_eq!(
+        report,
+        SyncReport {
+            pulled: 2,
+            pushed: 0
+        }
+    );
+    for store in [&laptop, &server] {
+        assert_eq!(store.get("title").await.unwrap(), Some(json!("final")));
+        assert_eq!(store.get("owner").await.unwrap(), Some(json!("alice")));
+    }
+
+    assert_eq!(laptop.sync(&server).await.unwrap(), SyncReport::default());
+}
+
+#[tokio::test]
+async fn test_removal_wins_over_older_writes() {
+    let laptop = replica("laptop");
+    let server = replica("server");
+
+    server.set("note", json!("old"), None).await.unwrap();
+    laptop.sync(&server).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    laptop.remove("note").await.unwrap();
+
+    let report = laptop.sync(&server).await.unwrap();
+    assert_eq!(report.pushed, 1);
+    assert_eq!(server.get("note").await.unwrap(), None);
+    assert_eq!(laptop.get("note").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_write_after_sync_wins_over_skewed_remote() {
+    let laptop = replica("laptop");
+    let server = replica("server");
+
+    // Written by a server whose clock runs an hour ahead.
+    let ahead = SystemTime::now() + Duration::from_secs(3600);
+    let ahead = ahead.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+    server
+        .inner()
+        .set(
+            "setting",
+            json!({ "value": "server", "timestamp": [ahead, 0, "server"], "deleted": false }),
+            None,
+        )
+        .await
+        .unwrap();
+    laptop.sync(&server).await.unwrap();
+    laptop.set("setting", json!("laptop"), None).await.unwrap();
+
+    laptop.sync(&server).await.unwrap();
+    assert_eq!(server.get("setting").await.unwrap(), Some(json!("laptop")));
+}
+
+#[tokio::test]
+async fn test_clear_writes_tombstones() {
+    let laptop = replica("laptop");
+    let server = replica("server");
+    let keyv = Keyv::try_new(replica("phone")).await.unwrap();
+
+    laptop.set("a", json!(1), None).await.unwrap();
+    laptop.sync(&server).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    laptop.clear().await.unwrap();
+    laptop.sync(&server).await.unwrap();
+
+    assert_eq!(server.get("a").await.unwrap(), None);
+    assert_eq!(laptop.get("a").await.unwrap(), None);
+
+    keyv.set("b", 2).await.unwrap();
+    keyv.clear().await.unwrap();
+    assert_eq!(keyv.get("b").await.unwrap(), None);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_replica_syncs_with_server() {
+    use keyv::adapter::sqlite::SqliteStoreBuilder;
+
+    let local = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let laptop = LwwStore::new(local, "laptop");
+    laptop.initialize().await.unwrap();
+    let server = replica("server");
+
+    laptop
+        .set("prefs", json!({ "theme": "dark" }), None)
+        .await
+        .unwrap();
+    server.set("inbox", json!(3), None).await.unwrap();
+
+    let report = laptop.sync(&server).await.unwrap();
+    assert_eq!(
+        report,
+        SyncReport {
+            pulled: 1,
+            pushed: 1
+        }
+    );
+    assert_eq!(laptop.get("inbox").await.unwrap(), Some(json!(3)));
+    assert_eq!(
+        server.get("prefs").await.unwrap(),
+        Some(json!({ "theme": "dark" }))
+    );
+}
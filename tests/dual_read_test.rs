@@ -0,0 +1,76 @@
+#![cfg(feature = "runtime")]
+
+use std::{sync::Arc, time::Duration};
+
+use futures::TryStreamExt;
+use keyv::{adapter::inmemory::InMemoryStore, layer::dual_read::DualReadStore, Keyv, Store};
+use serde_json::json;
+
+async fn migration() -> (Arc<InMemoryStore>, Arc<InMemoryStore>, Keyv) {
+    let new = Arc::new(InMemoryStore::new());
+    let old = Arc::new(InMemoryStore::new());
+    old.set("a", json!(1), None).await.unwrap();
+    old.set("b", json!(2), Some(Duration::from_secs(60)))
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(DualReadStore::new(new.clone(), old.clone()))
+        .await
+        .unwrap();
+    (new, old, keyv)
+}
+
+#[tokio::test]
+async fn test_reads_fall_back_and_backfill() {
+    let (new, old, keyv) = migration().await;
+
+    assert!(new.get("b").await.unwrap().is_none());
+    assert_eq!(keyv.get("b").await.unwrap(), Some(json!(2)));
+    let (value, metadata) = new.get_with_metadata("b").await.unwrap().unwrap();
+    assert_eq!(value, json!(2));
+    assert!(metadata.expires_in().unwrap() > Duration::from_secs(50));
+    assert!(keyv.get("missing").await.unwrap().is_none());
+
+    keyv.set("a", 10).await.unwrap();
+    assert_eq!(keyv.get("a").await.unwrap(), Some(json!(10)));
+    assert_eq!(old.get("a").await.unwrap(), Some(json!(1)));
+}
+
+#[tokio::test]
+async fn test_writes_depending_on_old_entries() {
+    let (new, old, keyv) = migration().await;
+    let store = DualReadStore::new(new.clone(), old.clone());
+
+    assert!(!store.set_if_absent("a", json!(5), None).await.unwrap());
+    assert_eq!(store.increment("a", 2, None).await.unwrap(), 3);
+    assert_eq!(new.get("a").await.unwrap(), Some(json!(3)));
+
+    keyv.remove("b").await.unwrap();
+    assert!(keyv.get("b").await.unwrap().is_none());
+    assert!(old.get("b").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_scan_lists_both_stores_once() {
+    let (new, old, _keyv) = migration().await;
+    new.set("a", json!("migrated"), None).await.unwrap();
+    new.set("c", json!(3), None).await.unwrap();
+    let store = DualReadStore::new(new, old);
+
+    let mut entries: Vec<_> = store
+        .scan_entries(None, 10)
+        .try_concat()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|entry| (entry.key, entry.value))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        entries,
+        [
+            ("a".to_string(), json!("migrated")),
+            ("b".to_string(), json!(2)),
+            ("c".to_string(), json!(3)),
+        ]
+    );
+}
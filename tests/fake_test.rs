@@ -0,0 +1,53 @@
+#![cfg(feature = "runtime")]
+
+use std::time::{Duration, Instant};
+
+use keyv::{
+    adapter::fake::{FakeStore, Latency},
+    layer::stats::Operation,
+    Keyv,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_fake_store_delays_configured_operations() {
+    let store = FakeStore::builder()
+        .operation_latency(Operation::Get, Latency::Fixed(Duration::from_millis(40)))
+        .build();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("key", "value").await.unwrap();
+    let started = Instant::now();
+    assert_eq!(keyv.get("key").await.unwrap(), Some(json!("value")));
+    assert!(started.elapsed() >= Duration::from_millis(40));
+}
+
+#[test]
+fn test_latency_samples_are_deterministic() {
+    let latency = Latency::Uniform {
+        min: Duration::from_millis(5),
+        max: Duration::from_millis(20),
+    };
+
+    let first: Vec<Duration> = latency.samples(7).take(100).collect();
+    assert_eq!(first, latency.samples(7).take(100).collect::<Vec<_>>());
+    assert_ne!(first, latency.samples(8).take(100).collect::<Vec<_>>());
+    assert!(first
+        .iter()
+        .all(|delay| (Duration::from_millis(5)..=Duration::from_millis(20)).contains(delay)));
+}
+
+#[test]
+fn test_exponential_latency_averages_its_mean() {
+    let latency = Latency::Exponential {
+        min: Duration::from_millis(2),
+        mean: Duration::from_millis(10),
+    };
+
+    let delays: Vec<Duration> = latency.samples(1).take(10_000).collect();
+    let average = delays.iter().sum::<Duration>() / delays.len() as u32;
+    assert!(delays
+        .iter()
+        .all(|delay| *delay >= Duration::from_millis(2)));
+    assert!(average > Duration::from_millis(11) && average < Duration::from_millis(13));
+}
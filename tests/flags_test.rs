@@ -0,0 +1,90 @@
+#![cfg(feature = "runtime")]
+
+use std::time::Duration;
+
+use keyv::{flags::Flag, Keyv};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_flag_shapes() {
+    let keyv = Keyv::default();
+    keyv.set("flags:on", true).await.unwrap();
+    keyv.set("flags:off", false).await.unwrap();
+    keyv.set("flags:nobody", json!({ "rollout": 0.0 }))
+        .await
+        .unwrap();
+    keyv.set("flags:everybody", json!({ "rollout": 100.0 }))
+        .await
+        .unwrap();
+    keyv.set("flags:color", "blue").await.unwrap();
+    keyv.set("flags:malformed", 42).await.unwrap();
+    keyv.set("other:on", false).await.unwrap();
+
+    let flags = keyv.flags("flags");
+    for context in ["user:1", "user:2", "user:3"] {
+        assert!(flags.is_enabled("on", context).await.unwrap());
+        assert!(!flags.is_enabled("off", context).await.unwrap());
+        assert!(!flags.is_enabled("nobody", context).await.unwrap());
+        assert!(flags.is_enabled("everybody", context).await.unwrap());
+        assert!(!flags.is_enabled("missing", context).await.unwrap());
+        assert!(!flags.is_enabled("malformed", context).await.unwrap());
+        assert_eq!(
+            flags.variant("color", context).await.unwrap().as_deref(),
+            Some("blue")
+        );
+    }
+    assert_eq!(flags.variant("on", "user:1").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_rollout_is_stable_and_proportional() {
+    let keyv = Keyv::default();
+    let flags = keyv.flags("flags");
+    flags.set("beta", Flag::Rollout(25.0)).await.unwrap();
+    flags
+        .set(
+            "layout",
+            Flag::Variants(vec![("a".to_string(), 1), ("b".to_string(), 3)]),
+        )
+        .await
+        .unwrap();
+
+    let mut enabled = 0;
+    let mut layout_a = 0;
+    for i in 0..2000 {
+        let context = format!("user:{}", i);
+        let first = flags.is_enabled("beta", &context).await.unwrap();
+        assert_eq!(flags.is_enabled("beta", &context).await.unwrap(), first);
+        enabled += first as usize;
+        if flags.variant("layout", &context).await.unwrap().as_deref() == Some("a") {
+            layout_a += 1;
+        }
+    }
+    assert!((400..600).contains(&enabled), "{} enabled", enabled);
+    assert!((400..600).contains(&layout_a), "{} in a", layout_a);
+
+    assert_eq!(
+        keyv.get("flags:layout").await.unwrap(),
+        Some(json!({ "variants": { "a": 1, "b": 3 } }))
+    );
+}
+
+#[tokio::test]
+async fn test_flags_refresh() {
+    let keyv = Keyv::default();
+    keyv.set("flags:checkout", false).await.unwrap();
+    let flags = keyv
+        .flags("flags")
+        .refresh_interval(Duration::from_millis(100));
+    assert!(!flags.is_enabled("checkout", "user:1").await.unwrap());
+
+    // Changes made elsewhere show up once the local copy is refreshed.
+    keyv.set("flags:checkout", true).await.unwrap();
+    assert!(!flags.is_enabled("checkout", "user:1").await.unwrap());
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(flags.is_enabled("checkout", "user:1").await.unwrap());
+
+    keyv.set("flags:checkout", false).await.unwrap();
+    flags.invalidate().await;
+    assert!(!flags.is_enabled("checkout", "user:1").await.unwrap());
+}
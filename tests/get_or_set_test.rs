@@ -0,0 +1,66 @@
+#![cfg(feature = "runtime")]
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use keyv::{Keyv, KeyvError, StoreError};
+
+#[tokio::test]
+async fn test_get_or_set_computes_misses_once() {
+    let keyv = Keyv::default();
+    let computes = AtomicU64::new(0);
+    let compute = || async {
+        computes.fetch_add(1, Ordering::SeqCst);
+        Ok::<_, std::io::Error>(vec!["admin".to_string()])
+    };
+
+    let roles: Vec<String> = keyv.get_or_set("roles:1", compute).await.unwrap();
+    assert_eq!(roles, vec!["admin"]);
+    let roles: Vec<String> = keyv.get_or_set("roles:1", compute).await.unwrap();
+    assert_eq!(roles, vec!["admin"]);
+    assert_eq!(computes.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_get_or_set_for_expires_the_computed_value() {
+    let keyv = Keyv::default();
+
+    let token: String = keyv
+        .get_or_set_for("token", Duration::from_millis(50), || async {
+            Ok::<_, std::io::Error>("first".to_string())
+        })
+        .await
+        .unwrap();
+    assert_eq!(token, "first");
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let token: String = keyv
+        .get_or_set_for("token", Duration::from_millis(50), || async {
+            Ok::<_, std::io::Error>("second".to_string())
+        })
+        .await
+        .unwrap();
+    assert_eq!(token, "second");
+}
+
+#[tokio::test]
+async fn test_get_or_set_surfaces_errors() {
+    let keyv = Keyv::default();
+
+    let computed = keyv
+        .get_or_set("user:1", || async { Err::<String, _>("backend down") })
+        .await;
+    assert!(matches!(computed, Err(KeyvError::LoaderError(_))));
+    assert!(keyv.get("user:1").await.unwrap().is_none());
+
+    keyv.set("user:2", "alice").await.unwrap();
+    let cached = keyv
+        .get_or_set("user:2", || async { Ok::<u64, std::io::Error>(2) })
+        .await;
+    assert!(matches!(
+        cached,
+        Err(KeyvError::StoreError(StoreError::SerializationError { .. }))
+    ));
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "runtime")]
+
+#[cfg(feature = "hashed-keys")]
+use keyv::{
+    adapter::inmemory::InMemoryStore,
+    layer::hashed_keys::{HashedKeyStore, HmacCodec},
+    Store,
+};
+
+#[cfg(feature = "hashed-keys")]
+#[tokio::test]
+async fn test_hashed_keys() {
+    let store = HashedKeyStore::new(InMemoryStore::new(), HmacCodec::new("secret"));
+
+    store
+        .set("user:alice", serde_json::json!("profile"), None)
+        .await
+        .unwrap();
+
+    let value = store.get("user:alice").await.unwrap();
+    assert_eq!(value, Some(serde_json::json!("profile")));
+
+    // The plaintext key never reaches the wrapped store.
+    assert_eq!(store.inner().get("user:alice").await.unwrap(), None);
+    let hashed = store.hash_key("user:alice");
+    assert_eq!(hashed.len(), 64);
+    assert_eq!(
+        store.inner().get(&hashed).await.unwrap(),
+        Some(serde_json::json!("profile"))
+    );
+
+    // Different secrets produce different physical keys.
+    let other = HashedKeyStore::new(InMemoryStore::new(), HmacCodec::new("other-secret"));
+    assert_ne!(other.hash_key("user:alice"), hashed);
+
+    store.remove_many(&["user:alice"]).await.unwrap();
+    assert_eq!(store.get("user:alice").await.unwrap(), None);
+}
+
+#[cfg(feature = "hashed-keys")]
+#[tokio::test]
+async fn test_hashed_keys_cannot_find() {
+    use futures::TryStreamExt;
+
+    let store = HashedKeyStore::new(InMemoryStore::new(), HmacCodec::new("secret"));
+    store
+        .set("user:alice", serde_json::json!("profile"), None)
+        .await
+        .unwrap();
+
+    let found = store.find_entries("*", 10).try_collect::<Vec<_>>().await;
+    assert!(matches!(found, Err(keyv::StoreError::Unsupported(_))));
+}
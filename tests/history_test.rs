@@ -0,0 +1,157 @@
+#![cfg(feature = "runtime")]
+
+use std::time::{Duration, SystemTime};
+
+use keyv::{
+    adapter::inmemory::InMemoryStore, layer::history::HistoryStore, BatchOp, Keyv, KeyvError,
+    SequentialGenerator, Store, StoreError,
+};
+use serde_json::json;
+
+fn history_store() -> HistoryStore<InMemoryStore, InMemoryStore> {
+    HistoryStore::new(InMemoryStore::new(), InMemoryStore::new(), 3)
+}
+
+#[tokio::test]
+async fn test_history_records_versions_newest_first() {
+    let before = SystemTime::now() - Duration::from_secs(1);
+    let keyv = Keyv::try_new(history_store()).await.unwrap();
+
+    assert!(keyv.get_versions("config").await.unwrap().is_empty());
+    for value in ["v1", "v2", "v3", "v4"] {
+        keyv.set("config", value).await.unwrap();
+    }
+
+    let versions = keyv.get_versions("config").await.unwrap();
+    let values: Vec<_> = versions.iter().map(|version| &version.value).collect();
+    assert_eq!(values, [&json!("v4"), &json!("v3"), &json!("v2")]);
+    assert!(versions
+        .iter()
+        .all(|version| version.recorded_at >= before && version.recorded_at <= SystemTime::now()));
+    assert_eq!(keyv.get("config").await.unwrap(), Some(json!("v4")));
+}
+
+#[tokio::test]
+async fn test_history_records_every_write_path() {
+    let store = history_store();
+
+    store
+        .set_many(&[("a", json!(1)), ("b", json!(2))], None)
+        .await
+        .unwrap();
+    store.increment("a", 5, None).await.unwrap();
+    assert!(!store.set_if_absent("b", json!(3), None).await.unwrap());
+    store.set_keep_ttl("b", json!(4)).await.unwrap();
+    store
+        .execute_batch(vec![
+            BatchOp::Set {
+                key: "c".to_string(),
+                value: json!("batched"),
+                ttl: None,
+            },
+            BatchOp::Touch {
+                key: "missing".to_string(),
+                ttl: Duration::from_secs(1),
+            },
+        ])
+        .await
+        .unwrap();
+
+    let values = |key: &'static str| {
+        let store = &store;
+        async move {
+            store
+                .get_versions(key)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|version| version.value)
+                .collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(values("a").await, [json!(6), json!(1)]);
+    assert_eq!(values("b").await, [json!(4), json!(2)]);
+    assert_eq!(values("c").await, [json!("batched")]);
+    assert!(values("missing").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_history_survives_remove() {
+    let keyv = Keyv::try_new(history_store()).await.unwrap();
+
+    keyv.set("config", "v1").await.unwrap();
+    keyv.remove("config").await.unwrap();
+
+    assert_eq!(keyv.get("config").await.unwrap(), None);
+    assert_eq!(keyv.get_versions("config").await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_history_versions_have_ids() {
+    let store = history_store().generator(SequentialGenerator::new());
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("config", "v1").await.unwrap();
+    keyv.set("config", "v2").await.unwrap();
+    let ids: Vec<_> = keyv
+        .get_versions("config")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|version| version.id)
+        .collect();
+    assert_eq!(ids, [Some("2".to_string()), Some("1".to_string())]);
+
+    // Versions recorded before ids were introduced have none.
+    let store = history_store();
+    store
+        .history()
+        .set("legacy", json!([{ "value": "v0", "recorded_at": 0 }]), None)
+        .await
+        .unwrap();
+    assert_eq!(store.get_versions("legacy").await.unwrap()[0].id, None);
+}
+
+#[tokio::test]
+async fn test_get_versions_unsupported_without_history() {
+    let keyv = Keyv::default();
+
+    assert!(matches!(
+        keyv.get_versions("config").await,
+        Err(KeyvError::StoreError(StoreError::Unsupported(
+            "get_versions"
+        )))
+    ));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_history_in_sibling_table() {
+    use std::sync::Arc;
+
+    use keyv::adapter::sqlite::SqliteStoreBuilder;
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .table_name("config")
+        .build()
+        .await
+        .unwrap();
+    let history = SqliteStoreBuilder::new()
+        .pool(Arc::new(store.pool().clone()))
+        .table_name("config_history")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(HistoryStore::new(store, history, 10))
+        .await
+        .unwrap();
+
+    keyv.set("limits", json!({ "rps": 10 })).await.unwrap();
+    keyv.set("limits", json!({ "rps": 20 })).await.unwrap();
+
+    let versions = keyv.get_versions("limits").await.unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0].value, json!({ "rps": 20 }));
+    assert_eq!(versions[1].value, json!({ "rps": 10 }));
+}
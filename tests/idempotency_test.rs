@@ -0,0 +1,70 @@
+#![cfg(feature = "runtime")]
+
+use std::time::Duration;
+
+#[cfg(feature = "sqlite")]
+use keyv::adapter::sqlite::SqliteStoreBuilder;
+use keyv::{idempotency::IdempotencyState, Keyv};
+use serde_json::json;
+
+async fn check_set_if_absent(keyv: &Keyv) {
+    assert!(keyv.set_if_absent("lock", "first", None).await.unwrap());
+    assert!(!keyv.set_if_absent("lock", "second", None).await.unwrap());
+    assert_eq!(keyv.get("lock").await.unwrap(), Some(json!("first")));
+
+    // An expired key counts as absent.
+    keyv.set_for("lease", "stale", Duration::from_millis(50))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(keyv
+        .set_if_absent("lease", "fresh", Some(Duration::from_secs(60)))
+        .await
+        .unwrap());
+    assert_eq!(keyv.get("lease").await.unwrap(), Some(json!("fresh")));
+}
+
+async fn check_idempotency(keyv: &Keyv) {
+    let requests = keyv
+        .idempotency(Duration::from_secs(60))
+        .reservation_timeout(Duration::from_millis(100));
+
+    assert_eq!(requests.lookup("a").await.unwrap(), None);
+    assert_eq!(requests.begin("a").await.unwrap(), None);
+    assert_eq!(
+        requests.begin("a").await.unwrap(),
+        Some(IdempotencyState::InProgress)
+    );
+
+    requests.complete("a", json!({ "id": 7 })).await.unwrap();
+    let completed = Some(IdempotencyState::Completed(json!({ "id": 7 })));
+    assert_eq!(requests.begin("a").await.unwrap(), completed);
+    assert_eq!(requests.lookup("a").await.unwrap(), completed);
+
+    // A reservation that is never completed lapses, and a released one is free at once.
+    assert_eq!(requests.begin("b").await.unwrap(), None);
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(requests.begin("b").await.unwrap(), None);
+    requests.release("b").await.unwrap();
+    assert_eq!(requests.begin("b").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_inmemory_idempotency() {
+    let keyv = Keyv::default();
+    check_set_if_absent(&keyv).await;
+    check_idempotency(&keyv).await;
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_idempotency() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    check_set_if_absent(&keyv).await;
+    check_idempotency(&keyv).await;
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "runtime")]
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use keyv::{
+    adapter::inmemory::InMemoryStore,
+    import::{decode_node_entry, import_node_entries, ImportReport, NODE_KEYV_PREFIX},
+    Store,
+};
+use serde_json::json;
+
+fn millis_from_now(offset: Duration) -> u128 {
+    (SystemTime::now() + offset)
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis()
+}
+
+#[test]
+fn test_decode_node_entry() {
+    let entry = decode_node_entry(
+        NODE_KEYV_PREFIX,
+        "keyv:user:1",
+        r#"{"value":{"name":"::colon","tags":[":base64:AQI="]},"expires":1700000000000}"#,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(entry.key, "user:1");
+    assert_eq!(
+        entry.value,
+        json!({"name": ":colon", "tags": [":base64:AQI="]})
+    );
+    assert_eq!(
+        entry.expires_at,
+        Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_000))
+    );
+
+    assert!(decode_node_entry(NODE_KEYV_PREFIX, "other:user:1", "{}")
+        .unwrap()
+        .is_none());
+    assert!(decode_node_entry(NODE_KEYV_PREFIX, "keyv:bare", "42").is_err());
+}
+
+#[tokio::test]
+async fn test_import_node_entries_preserves_expirations() {
+    let target = InMemoryStore::new();
+    let live = format!(
+        r#"{{"value":"fresh","expires":{}}}"#,
+        millis_from_now(Duration::from_secs(60))
+    );
+    let expired = format!(
+        r#"{{"value":"stale","expires":{}}}"#,
+        millis_from_now(Duration::ZERO) - 1_000
+    );
+    let rows = vec![
+        ("keyv:session".to_string(), live),
+        ("keyv:old".to_string(), expired),
+        ("keyv:config".to_string(), r#"{"value":[1,2]}"#.to_string()),
+        ("keyv:broken".to_string(), "{not json".to_string()),
+        ("namespace:keyv".to_string(), "[]".to_string()),
+    ];
+
+    let report = import_node_entries(&target, NODE_KEYV_PREFIX, rows)
+        .await
+        .unwrap();
+    assert_eq!(
+        report,
+        ImportReport {
+            imported: 2,
+            expired: 1,
+            invalid: 1
+        }
+    );
+    let (value, metadata) = target.get_with_metadata("session").await.unwrap().unwrap();
+    assert_eq!(value, json!("fresh"));
+    assert!(metadata.expires_in().unwrap() > Duration::from_secs(50));
+    assert_eq!(target.get("config").await.unwrap(), Some(json!([1, 2])));
+    assert!(target.get("old").await.unwrap().is_none());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_import_node_sqlite() {
+    use keyv::{
+        adapter::sqlite::SqlitePoolOptions,
+        import::{import_node_sqlite, NODE_KEYV_SQLITE_TABLE},
+    };
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::query("CREATE TABLE keyv (key VARCHAR(255) PRIMARY KEY, value TEXT)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    for i in 0..1_200 {
+        sqlx::query("INSERT INTO keyv (key, value) VALUES (?, ?)")
+            .bind(format!("keyv:item:{i}"))
+            .bind(format!(r#"{{"value":{i},"expires":null}}"#))
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let target = InMemoryStore::new();
+    let report = import_node_sqlite(&pool, NODE_KEYV_SQLITE_TABLE, NODE_KEYV_PREFIX, &target)
+        .await
+        .unwrap();
+    assert_eq!(report.imported, 1_200);
+    assert_eq!(target.get("item:999").await.unwrap(), Some(json!(999)));
+}
@@ -1,4 +1,9 @@
-use keyv::Keyv;
+#![cfg(feature = "runtime")]
+
+use std::sync::Arc;
+
+use keyv::{adapter::inmemory::InMemoryStore, Entry, EvictionPriority, Keyv};
+use serde_json::json;
 
 #[tokio::test]
 async fn test_keyv() {
@@ -13,7 +18,7 @@ async fn test_keyv() {
             let number: i32 = serde_json::from_value(number).unwrap();
             assert_eq!(number, 10);
         }
-        None => assert!(false),
+        None => panic!("Expected data not found"),
     }
 
     match keyv.get("string").await.unwrap() {
@@ -21,7 +26,7 @@ async fn test_keyv() {
             let string: String = serde_json::from_value(string).unwrap();
             assert_eq!(string, "life long");
         }
-        None => assert!(false),
+        None => panic!("Expected data not found"),
     }
 
     match keyv.get("array").await.unwrap() {
@@ -29,11 +34,292 @@ async fn test_keyv() {
             let array: Vec<String> = serde_json::from_value(array).unwrap();
             assert_eq!(array, vec!["hola".to_string(), "test".to_string()])
         }
-        None => assert!(false),
+        None => panic!("Expected data not found"),
     }
 
     match keyv.remove_many(&["number", "string"]).await {
         Ok(_) => {}
-        Err(_) => assert!(false),
+        Err(e) => panic!("remove_many failed: {e}"),
+    }
+}
+
+#[tokio::test]
+async fn test_get_ref_shares_the_stored_value() {
+    let keyv = Keyv::default().with_stats();
+    keyv.set("catalog", vec!["book"; 1_000]).await.unwrap();
+
+    let first = keyv.get_ref("catalog").await.unwrap().unwrap();
+    let second = keyv.get_ref("catalog").await.unwrap().unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(first.as_array().unwrap().len(), 1_000);
+    assert_eq!(keyv.stats().unwrap().hits(), 2);
+
+    keyv.set("catalog", "replaced").await.unwrap();
+    assert_eq!(first.as_array().unwrap().len(), 1_000);
+    assert_eq!(
+        keyv.get_ref("catalog").await.unwrap().as_deref(),
+        Some(&json!("replaced"))
+    );
+    assert!(keyv.get_ref("missing").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_max_entries_evicts_least_recently_used() {
+    let keyv = Keyv::try_new(InMemoryStore::new().max_entries(2))
+        .await
+        .unwrap();
+    keyv.set("a", 1).await.unwrap();
+    keyv.set("b", 2).await.unwrap();
+    keyv.get("a").await.unwrap();
+    keyv.set("c", 3).await.unwrap();
+
+    assert!(keyv.get("a").await.unwrap().is_some());
+    assert!(keyv.get("b").await.unwrap().is_none());
+    assert!(keyv.get("c").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_eviction_prefers_low_priority() {
+    let keyv = Keyv::try_new(InMemoryStore::new().max_entries(2))
+        .await
+        .unwrap();
+    keyv.set_with_priority("report", 1, None, EvictionPriority::High)
+        .await
+        .unwrap();
+    keyv.set_with_priority("page", 2, None, EvictionPriority::Low)
+        .await
+        .unwrap();
+    keyv.set("user", 3).await.unwrap();
+    keyv.set("session", 4).await.unwrap();
+
+    assert!(keyv.get("report").await.unwrap().is_some());
+    assert!(keyv.get("page").await.unwrap().is_none());
+    assert!(keyv.get("user").await.unwrap().is_none());
+    assert!(keyv.get("session").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_max_bytes_evicts_by_weight() {
+    let store = InMemoryStore::new()
+        .max_bytes(10)
+        .weigher(|_, value| value.as_str().map_or(1, |value| value.len() as u64));
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("a", "12345").await.unwrap();
+    keyv.set("b", "1234").await.unwrap();
+    keyv.set("c", "123").await.unwrap();
+
+    assert!(keyv.get("a").await.unwrap().is_none());
+    assert!(keyv.get("b").await.unwrap().is_some());
+    assert!(keyv.get("c").await.unwrap().is_some());
+
+    keyv.set("huge", "12345678901").await.unwrap();
+    assert!(keyv.get("huge").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_on_evict_reports_reason() {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use keyv::adapter::inmemory::EvictionReason;
+
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let listener = evicted.clone();
+    let store = InMemoryStore::new()
+        .max_entries(2)
+        .on_evict(move |key, value, reason| {
+            listener
+                .lock()
+                .unwrap()
+                .push((key.to_string(), value.clone(), reason))
+        });
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("a", 1).await.unwrap();
+    keyv.set("b", 2).await.unwrap();
+    keyv.set("c", 3).await.unwrap();
+    keyv.remove("b").await.unwrap();
+    keyv.set_for("d", 4, Duration::from_millis(10))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(keyv.get("d").await.unwrap().is_none());
+    keyv.clear().await.unwrap();
+
+    let evicted = evicted.lock().unwrap();
+    assert_eq!(
+        *evicted,
+        [
+            ("a".to_string(), 1.into(), EvictionReason::Capacity),
+            ("b".to_string(), 2.into(), EvictionReason::Removed),
+            ("d".to_string(), 4.into(), EvictionReason::Expired),
+            ("c".to_string(), 3.into(), EvictionReason::Removed),
+        ]
+    );
+}
+
+fn journal_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("keyv-{}-{}.journal", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn test_journal_replays_on_initialize() {
+    let path = journal_path("replay");
+    {
+        let keyv = Keyv::try_new(InMemoryStore::new().journal(&path))
+            .await
+            .unwrap();
+        keyv.set("kept", "value").await.unwrap();
+        keyv.set("removed", 1).await.unwrap();
+        keyv.set_with_ttl("expired", 2, 0).await.unwrap();
+        keyv.remove("removed").await.unwrap();
     }
+
+    let keyv = Keyv::try_new(InMemoryStore::new().journal(&path))
+        .await
+        .unwrap();
+    assert_eq!(
+        keyv.get("kept").await.unwrap(),
+        Some(serde_json::json!("value"))
+    );
+    assert!(keyv.get("removed").await.unwrap().is_none());
+    assert!(keyv.get("expired").await.unwrap().is_none());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_journal_compaction() {
+    let path = journal_path("compaction");
+    let keyv = Keyv::try_new(
+        InMemoryStore::new()
+            .journal(&path)
+            .compact_journal_after(10),
+    )
+    .await
+    .unwrap();
+    for i in 0..100 {
+        keyv.set("counter", i).await.unwrap();
+    }
+    let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+    assert!(lines <= 10, "journal holds {} records", lines);
+
+    let keyv = Keyv::try_new(InMemoryStore::new().journal(&path))
+        .await
+        .unwrap();
+    assert_eq!(
+        keyv.get("counter").await.unwrap(),
+        Some(serde_json::json!(99))
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_get_entry_distinguishes_null() {
+    let keyv = Keyv::default();
+    keyv.set("null", None::<i32>).await.unwrap();
+    keyv.set("value", 1).await.unwrap();
+
+    assert_eq!(keyv.get_entry("null").await.unwrap(), Entry::Null);
+    assert_eq!(
+        keyv.get_entry("value").await.unwrap(),
+        Entry::Value(serde_json::json!(1))
+    );
+    assert!(keyv.get_entry("missing").await.unwrap().is_missing());
+}
+
+#[tokio::test]
+async fn test_inmemory_metadata_reports_expiration() {
+    use std::time::Duration;
+
+    let keyv = Keyv::default();
+    keyv.set_for("key", "value", Duration::from_secs(30))
+        .await
+        .unwrap();
+    keyv.set("forever", "value").await.unwrap();
+
+    let (_, metadata) = keyv.get_with_metadata("key").await.unwrap().unwrap();
+    let expires_in = metadata.expires_in().unwrap();
+    assert!(expires_in > Duration::from_secs(25) && expires_in <= Duration::from_secs(30));
+
+    let (_, metadata) = keyv.get_with_metadata("forever").await.unwrap().unwrap();
+    assert_eq!(metadata.expires_at, None);
+}
+
+#[tokio::test]
+async fn test_inmemory_rename_keeps_the_expiration() {
+    use keyv::{KeyvError, StoreError};
+    use std::time::Duration;
+
+    let keyv = Keyv::default();
+    keyv.set_for("user_42", "alice", Duration::from_secs(30))
+        .await
+        .unwrap();
+    keyv.set("user:42", "stale").await.unwrap();
+
+    keyv.rename("user_42", "user:42").await.unwrap();
+    assert!(keyv.get("user_42").await.unwrap().is_none());
+    let (value, metadata) = keyv.get_with_metadata("user:42").await.unwrap().unwrap();
+    assert_eq!(value, serde_json::json!("alice"));
+    assert!(metadata.expires_in().unwrap() > Duration::from_secs(25));
+
+    assert!(matches!(
+        keyv.rename("user_42", "user:43").await,
+        Err(KeyvError::StoreError(StoreError::NotFound))
+    ));
+    keyv.rename("user:42", "user:42").await.unwrap();
+    assert!(keyv.get("user:42").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_inmemory_remove_if() {
+    let keyv = Keyv::default();
+    keyv.set("lock", serde_json::json!({ "owner": "worker-1" }))
+        .await
+        .unwrap();
+
+    assert!(!keyv
+        .remove_if("lock", serde_json::json!({ "owner": "worker-2" }))
+        .await
+        .unwrap());
+    assert!(keyv.get("lock").await.unwrap().is_some());
+    assert!(keyv
+        .remove_if("lock", serde_json::json!({ "owner": "worker-1" }))
+        .await
+        .unwrap());
+    assert!(keyv.get("lock").await.unwrap().is_none());
+    assert!(!keyv.remove_if("lock", "anything").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_inmemory_snapshot_and_iter() {
+    use keyv::Store;
+    use std::time::Duration;
+
+    let store = InMemoryStore::new();
+    store.set("b", serde_json::json!(2), None).await.unwrap();
+    store.set("a", serde_json::json!(1), None).await.unwrap();
+    store
+        .set(
+            "expired",
+            serde_json::json!(3),
+            Some(Duration::from_millis(10)),
+        )
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(30)).await;
+
+    let entries: Vec<_> = store.iter().await.collect();
+    assert_eq!(
+        entries,
+        vec![
+            ("a".to_string(), serde_json::json!(1)),
+            ("b".to_string(), serde_json::json!(2)),
+        ]
+    );
+    let snapshot = store.snapshot().await;
+    assert_eq!(snapshot.len(), 2);
+    assert_eq!(snapshot["b"], serde_json::json!(2));
 }
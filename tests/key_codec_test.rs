@@ -0,0 +1,91 @@
+#![cfg(feature = "runtime")]
+
+use futures::TryStreamExt;
+use keyv::{
+    adapter::inmemory::InMemoryStore,
+    layer::key_codec::{EscapeCodec, KeyCodec, KeyCodecStore, PrefixCodec},
+    Keyv, Store,
+};
+use std::sync::Arc;
+
+#[test]
+fn test_escape_codec_round_trip() {
+    let key = "user:42 café\n%";
+    let encoded = EscapeCodec.encode(key);
+    assert_eq!(encoded, "user%3A42%20caf%C3%A9%0A%25");
+    assert_eq!(EscapeCodec.decode(&encoded).as_deref(), Some(key));
+    assert_eq!(EscapeCodec.decode("%G1"), None);
+}
+
+#[test]
+fn test_codecs_compose_left_to_right() {
+    let codec = (EscapeCodec, PrefixCodec::new("app").separator("/"));
+    assert_eq!(codec.encode("a:b"), "app/a%3Ab");
+    assert_eq!(codec.decode("app/a%3Ab").as_deref(), Some("a:b"));
+    assert_eq!(codec.decode("other/a"), None);
+}
+
+#[test]
+fn test_optional_codec() {
+    let codec = Some(PrefixCodec::new("app"));
+    assert_eq!(codec.encode("a"), "app:a");
+    assert_eq!(codec.decode("app:a").as_deref(), Some("a"));
+    assert_eq!(codec.encode_prefix("us").as_deref(), Some("app:us"));
+
+    let none: Option<PrefixCodec> = None;
+    assert_eq!(none.encode("a"), "a");
+    assert_eq!(none.decode("app:a").as_deref(), Some("app:a"));
+    assert_eq!(none.encode_prefix("us").as_deref(), Some("us"));
+}
+
+#[tokio::test]
+async fn test_key_codec_store_scan_decodes_keys() {
+    let backend = Arc::new(InMemoryStore::new());
+    backend
+        .set("unrelated", serde_json::json!(0), None)
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(KeyCodecStore::new(backend.clone(), PrefixCodec::new("app")))
+        .await
+        .unwrap();
+    keyv.set("user:1", 1).await.unwrap();
+    keyv.set("user:2", 2).await.unwrap();
+    keyv.set("order:1", 3).await.unwrap();
+
+    assert!(backend.get("app:user:1").await.unwrap().is_some());
+
+    let entries = keyv
+        .scan(Some("user:"), 1)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap()
+        .concat();
+    let keys: Vec<_> = entries.iter().map(|entry| entry.key.as_str()).collect();
+    assert_eq!(keys, ["user:1", "user:2"]);
+
+    let all = keyv.scan(None, 10).try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(all.concat().len(), 3);
+}
+
+#[tokio::test]
+async fn test_key_codec_store_namespaces_queues() {
+    use std::time::Duration;
+
+    use keyv::queue::Queue;
+
+    let backend = Arc::new(InMemoryStore::new());
+    let app = Queue::new(
+        KeyCodecStore::new(backend.clone(), PrefixCodec::new("app")),
+        "jobs",
+    );
+    let other = Queue::new(
+        KeyCodecStore::new(backend.clone(), PrefixCodec::new("other")),
+        "jobs",
+    );
+    app.push("resize").await.unwrap();
+
+    assert!(other.pop(Duration::from_secs(30)).await.unwrap().is_none());
+    let message = app.pop(Duration::from_secs(30)).await.unwrap().unwrap();
+    assert_eq!(message.payload, serde_json::json!("resize"));
+    app.ack(&message.id).await.unwrap();
+}
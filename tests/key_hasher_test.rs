@@ -0,0 +1,55 @@
+#![cfg(feature = "runtime")]
+
+use keyv::{KeyHasher, Keyv, SipKeyHasher};
+use serde_json::json;
+
+#[test]
+fn test_sip_key_hasher_matches_reference_vectors() {
+    // Test vectors from the SipHash paper: key 00..0f, messages 00..(n-1).
+    let hasher = SipKeyHasher::with_keys(0x0706050403020100, 0x0f0e0d0c0b0a0908);
+    let message: Vec<u8> = (0..15).collect();
+    assert_eq!(hasher.hash_u64(&[]), 0x726fdb47dd0e0e31);
+    assert_eq!(hasher.hash_u64(&message[..8]), 0x93f5f5799a932462);
+    assert_eq!(hasher.hash_u64(&message), 0xa129ca6149be45e5);
+    assert_eq!(hasher.hash(&message), "a129ca6149be45e5");
+}
+
+#[test]
+fn test_key_for_is_stable_and_ignores_field_order() {
+    let keyv = Keyv::default();
+    let key = keyv
+        .key_for(&json!({ "query": "rust", "page": 2 }))
+        .unwrap();
+    assert_eq!(key.len(), 16);
+    assert_eq!(
+        keyv.key_for(&json!({ "page": 2, "query": "rust" }))
+            .unwrap(),
+        key
+    );
+    assert_ne!(
+        keyv.key_for(&json!({ "query": "rust", "page": 3 }))
+            .unwrap(),
+        key
+    );
+
+    let keyed = Keyv::default().with_key_hasher(SipKeyHasher::with_keys(1, 2));
+    assert_ne!(
+        keyed
+            .key_for(&json!({ "query": "rust", "page": 2 }))
+            .unwrap(),
+        key
+    );
+}
+
+#[cfg(feature = "hashed-keys")]
+#[test]
+fn test_sha256_key_hasher() {
+    use keyv::Sha256KeyHasher;
+
+    assert_eq!(
+        Sha256KeyHasher.hash(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    let keyv = Keyv::default().with_key_hasher(Sha256KeyHasher);
+    assert_eq!(keyv.key_for(&json!([1, 2])).unwrap().len(), 64);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "runtime")]
+
+use std::time::Duration;
+
+use keyv::Keyv;
+
+#[tokio::test]
+async fn test_single_leader() {
+    let keyv = Keyv::default();
+    let first = keyv.campaign("scheduler", Duration::from_secs(60));
+    let second = keyv.campaign("scheduler", Duration::from_secs(60));
+
+    assert_eq!(first.campaign().await.unwrap(), Some(1));
+    assert_eq!(second.campaign().await.unwrap(), None);
+    assert_eq!(first.campaign().await.unwrap(), Some(1));
+    assert!(first.is_leader());
+    assert!(!second.is_leader());
+
+    // Resigning hands the lease over right away, under a greater fencing token.
+    first.resign().await.unwrap();
+    assert!(!first.is_leader());
+    assert_eq!(second.campaign().await.unwrap(), Some(2));
+    assert_eq!(first.campaign().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_expired_lease() {
+    let keyv = Keyv::default();
+    let first = keyv.campaign("scheduler", Duration::from_millis(100));
+    let second = keyv.campaign("scheduler", Duration::from_millis(100));
+
+    assert_eq!(first.campaign().await.unwrap(), Some(1));
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(!first.is_leader());
+    assert_eq!(second.campaign().await.unwrap(), Some(2));
+    assert_eq!(first.campaign().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_heartbeat_keeps_leadership() {
+    let keyv = Keyv::default();
+    let first = keyv.campaign("scheduler", Duration::from_millis(150));
+    let second = keyv.campaign("scheduler", Duration::from_millis(150));
+
+    first.start_heartbeat();
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(first.token(), Some(1));
+    assert_eq!(second.campaign().await.unwrap(), None);
+
+    // Once the leader is gone, its lease runs out and another replica takes over.
+    drop(first);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(second.campaign().await.unwrap(), Some(2));
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "runtime")]
+
+use std::time::Duration;
+
+use keyv::{Keyv, SequentialGenerator};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_lease_renews_while_held() {
+    let keyv = Keyv::default();
+    let lease = keyv
+        .lease("workers:1", "idle", Duration::from_millis(150))
+        .await
+        .unwrap();
+    assert_eq!(lease.key(), "workers:1");
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(keyv.get("workers:1").await.unwrap(), Some(json!("idle")));
+
+    // Dropping the guard stops the renewals and lets the entry expire.
+    drop(lease);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(keyv.get("workers:1").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_lease_release() {
+    let keyv = Keyv::default();
+    let lease = keyv
+        .lease("workers:2", "idle", Duration::from_secs(60))
+        .await
+        .unwrap();
+    lease.release().await.unwrap();
+    assert!(keyv.get("workers:2").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_lease_tokens_come_from_the_generator() {
+    let keyv = Keyv::default().with_generator(SequentialGenerator::new());
+    let first = keyv
+        .lease("workers:3", "busy", Duration::from_secs(1))
+        .await
+        .unwrap();
+    let second = keyv
+        .lease("workers:3", "busy", Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert_eq!((first.token(), second.token()), ("1", "2"));
+
+    let lease = Keyv::default()
+        .lease("workers:4", "busy", Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert_eq!(lease.token().len(), 36);
+}
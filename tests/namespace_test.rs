@@ -0,0 +1,97 @@
+#![cfg(feature = "runtime")]
+
+use std::{sync::Arc, time::Duration};
+
+use keyv::{adapter::inmemory::InMemoryStore, Keyv, Store};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_namespaces_share_the_store() {
+    let keyv = Keyv::default();
+    let emails = keyv
+        .namespace("emails")
+        .with_default_ttl(Duration::from_millis(50));
+    let users = keyv.namespace("users");
+
+    emails.set("alice", "queued").await.unwrap();
+    users.set("alice", "profile").await.unwrap();
+
+    assert_eq!(emails.get("alice").await.unwrap(), Some(json!("queued")));
+    assert_eq!(users.get("alice").await.unwrap(), Some(json!("profile")));
+    assert_eq!(
+        keyv.get("users:alice").await.unwrap(),
+        Some(json!("profile"))
+    );
+
+    // Only the emails handle carries a default TTL.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(emails.get("alice").await.unwrap(), None);
+    assert_eq!(users.get("alice").await.unwrap(), Some(json!("profile")));
+}
+
+#[tokio::test]
+async fn test_namespace_clear_keeps_other_keys() {
+    let keyv = Keyv::default();
+    let sessions = keyv.namespace("sessions");
+
+    sessions.set("a", 1).await.unwrap();
+    sessions.set("b", 2).await.unwrap();
+    keyv.set("config", 3).await.unwrap();
+
+    let preview = sessions.clear_dry_run().await.unwrap();
+    assert_eq!(preview.entries, Some(2));
+
+    sessions.clear().await.unwrap();
+    assert_eq!(sessions.get("a").await.unwrap(), None);
+    assert_eq!(keyv.get("config").await.unwrap(), Some(json!(3)));
+}
+
+#[tokio::test]
+async fn test_copy_between_namespaces_keeps_expirations() {
+    let keyv = Keyv::default();
+    let blue = keyv.namespace("blue");
+    let green = keyv
+        .namespace("green")
+        .with_default_ttl(Duration::from_millis(50));
+
+    blue.set_for("page:home", "home", Duration::from_secs(60))
+        .await
+        .unwrap();
+    blue.set("page:about", "about").await.unwrap();
+    blue.set("asset:logo", "logo").await.unwrap();
+
+    assert!(blue.copy("asset:logo", &green).await.unwrap());
+    assert!(!blue.copy("missing", &green).await.unwrap());
+    assert_eq!(blue.copy_prefix("page:", &green).await.unwrap(), 2);
+
+    // The target's default TTL does not replace the copied expirations.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(green.get("asset:logo").await.unwrap(), Some(json!("logo")));
+    assert_eq!(green.get("page:about").await.unwrap(), Some(json!("about")));
+    let (_, metadata) = green.get_with_metadata("page:home").await.unwrap().unwrap();
+    assert!(metadata.expires_in().unwrap() > Duration::from_secs(50));
+    assert_eq!(blue.get("page:home").await.unwrap(), Some(json!("home")));
+}
+
+#[tokio::test]
+async fn test_try_new_with_namespace_scopes_keys() {
+    let store = Arc::new(InMemoryStore::new());
+    let sessions = Keyv::try_new_with_namespace(store.clone(), "sessions")
+        .await
+        .unwrap();
+    let carts = Keyv::try_new_with_namespace(store.clone(), "carts")
+        .await
+        .unwrap();
+
+    sessions.set("alice", "token").await.unwrap();
+    carts.set("alice", vec!["book"]).await.unwrap();
+    assert_eq!(sessions.get("alice").await.unwrap(), Some(json!("token")));
+    assert_eq!(
+        store.get("carts:alice").await.unwrap(),
+        Some(json!(["book"]))
+    );
+
+    sessions.clear().await.unwrap();
+    assert_eq!(sessions.get("alice").await.unwrap(), None);
+    assert_eq!(carts.get("alice").await.unwrap(), Some(json!(["book"])));
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "runtime")]
+
+#[cfg(feature = "opentelemetry")]
+use keyv::{adapter::inmemory::InMemoryStore, layer::otel::OtelStore, Keyv};
+#[cfg(feature = "opentelemetry")]
+use opentelemetry::{global, trace::SpanKind, KeyValue, Value};
+#[cfg(feature = "opentelemetry")]
+use opentelemetry_sdk::{
+    metrics::{
+        data::{Histogram, Metric, ResourceMetrics, Sum},
+        PeriodicReader, SdkMeterProvider,
+    },
+    runtime,
+    testing::{metrics::InMemoryMetricsExporter, trace::InMemorySpanExporter},
+    trace::TracerProvider,
+    AttributeSet,
+};
+
+/// Returns the value of `key` in an exported attribute set.
+#[cfg(feature = "opentelemetry")]
+fn attribute<'a>(attributes: &'a AttributeSet, key: &str) -> Option<&'a Value> {
+    attributes
+        .iter()
+        .find(|(k, _)| k.as_str() == key)
+        .map(|(_, v)| v)
+}
+
+/// Finds the last exported metric named `name`.
+#[cfg(feature = "opentelemetry")]
+fn metric<'a>(exported: &'a [ResourceMetrics], name: &str) -> &'a Metric {
+    exported
+        .iter()
+        .rev()
+        .flat_map(|resource| &resource.scope_metrics)
+        .flat_map(|scope| &scope.metrics)
+        .find(|metric| metric.name == name)
+        .unwrap_or_else(|| panic!("metric {} was not exported", name))
+}
+
+// The periodic reader exports from a background task, which needs a second worker
+// thread for `force_flush` to complete.
+#[cfg(feature = "opentelemetry")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_keyv_otel() {
+    let spans = InMemorySpanExporter::default();
+    let tracer_provider = TracerProvider::builder()
+        .with_simple_exporter(spans.clone())
+        .build();
+    global::set_tracer_provider(tracer_provider.clone());
+    let metrics = InMemoryMetricsExporter::default();
+    let meter_provider = SdkMeterProvider::builder()
+        .with_reader(PeriodicReader::builder(metrics.clone(), runtime::Tokio).build())
+        .build();
+    global::set_meter_provider(meter_provider.clone());
+
+    let store = OtelStore::new(InMemoryStore::new(), "inmemory");
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("key", "value").await.unwrap();
+    let value: String = serde_json::from_value(keyv.get("key").await.unwrap().unwrap()).unwrap();
+    assert_eq!(value, "value");
+    assert!(keyv.get("missing").await.unwrap().is_none());
+
+    // One client span per operation, named after it.
+    for result in tracer_provider.force_flush() {
+        result.unwrap();
+    }
+    let finished = spans.get_finished_spans().unwrap();
+    let names: Vec<&str> = finished.iter().map(|span| span.name.as_ref()).collect();
+    assert_eq!(names, ["set", "get", "get"]);
+    for span in &finished {
+        assert_eq!(span.span_kind, SpanKind::Client);
+        assert!(span
+            .attributes
+            .contains(&KeyValue::new("db.system", "inmemory")));
+        assert!(span
+            .attributes
+            .contains(&KeyValue::new("db.operation", span.name.to_string())));
+    }
+
+    meter_provider.force_flush().unwrap();
+    let exported = metrics.get_finished_metrics().unwrap();
+
+    // Latency is recorded once per operation, split by operation.
+    let duration = metric(&exported, "db.client.operation.duration");
+    let duration = duration
+        .data
+        .as_any()
+        .downcast_ref::<Histogram<f64>>()
+        .unwrap();
+    let count = |operation: &'static str| {
+        duration
+            .data_points
+            .iter()
+            .filter(|point| {
+                attribute(&point.attributes, "db.operation") == Some(&Value::from(operation))
+            })
+            .map(|point| point.count)
+            .sum::<u64>()
+    };
+    assert_eq!(count("set"), 1);
+    assert_eq!(count("get"), 2);
+
+    // The hit and the miss are counted apart.
+    let lookups = metric(&exported, "cache.lookups");
+    let lookups = lookups.data.as_any().downcast_ref::<Sum<u64>>().unwrap();
+    let lookups_with = |hit: bool| {
+        lookups
+            .data_points
+            .iter()
+            .filter(|point| attribute(&point.attributes, "cache.hit") == Some(&Value::from(hit)))
+            .map(|point| point.value)
+            .sum::<u64>()
+    };
+    assert_eq!(lookups_with(true), 1);
+    assert_eq!(lookups_with(false), 1);
+    assert!(lookups.data_points.iter().all(|point| {
+        attribute(&point.attributes, "db.system") == Some(&Value::from("inmemory"))
+    }));
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "runtime")]
+
+use std::time::Duration;
+
+#[cfg(feature = "sqlite")]
+use keyv::adapter::sqlite::SqliteStoreBuilder;
+use keyv::{Keyv, KeyvError, StoreError};
+use serde_json::json;
+
+async fn check_pipeline(keyv: &Keyv) {
+    keyv.set("b", "stale").await.unwrap();
+    keyv.set_for("c", "session", Duration::from_millis(100))
+        .await
+        .unwrap();
+
+    let results = keyv
+        .pipeline()
+        .set("a", 1)
+        .remove("b")
+        .touch("c", 60)
+        .touch("missing", 60)
+        .set_for("d", 2, Duration::from_millis(100))
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 5);
+    assert!(results[..3].iter().all(Result::is_ok));
+    assert!(matches!(
+        results[3],
+        Err(KeyvError::StoreError(StoreError::NotFound))
+    ));
+    assert!(results[4].is_ok());
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(keyv.get("a").await.unwrap(), Some(json!(1)));
+    assert!(keyv.get("b").await.unwrap().is_none());
+    assert_eq!(keyv.get("c").await.unwrap(), Some(json!("session")));
+    assert!(keyv.get("d").await.unwrap().is_none());
+
+    assert!(keyv.pipeline().set("", 1).execute().await.is_err());
+}
+
+#[tokio::test]
+async fn test_inmemory_pipeline() {
+    check_pipeline(&Keyv::default()).await;
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_pipeline() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    check_pipeline(&keyv).await;
+}
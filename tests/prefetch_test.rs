@@ -0,0 +1,56 @@
+#![cfg(feature = "runtime")]
+
+use std::collections::HashMap;
+
+use futures::StreamExt;
+use keyv::Keyv;
+
+#[tokio::test]
+async fn test_prefetch_yields_every_key() {
+    let keyv = Keyv::default();
+    keyv.set("a", 1).await.unwrap();
+    keyv.set("b", 2).await.unwrap();
+
+    let results: HashMap<String, _> = keyv
+        .prefetch(["a", "b", "missing"], 2)
+        .map(|(key, result)| (key, result.unwrap()))
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results["a"], Some(serde_json::json!(1)));
+    assert_eq!(results["b"], Some(serde_json::json!(2)));
+    assert_eq!(results["missing"], None);
+}
+
+#[tokio::test]
+async fn test_get_many_stream_preserves_order_across_batches() {
+    use futures::TryStreamExt;
+
+    let keyv = Keyv::default();
+    for i in (0..10).step_by(2) {
+        keyv.set(&format!("key{}", i), i).await.unwrap();
+    }
+
+    let keys: Vec<String> = (0..10).map(|i| format!("key{}", i)).collect();
+    let results: Vec<(String, Option<serde_json::Value>)> =
+        keyv.get_many_stream(&keys, 3).try_collect().await.unwrap();
+
+    assert_eq!(results.len(), 10);
+    for (i, (key, value)) in results.into_iter().enumerate() {
+        assert_eq!(key, format!("key{}", i));
+        assert_eq!(value, (i % 2 == 0).then(|| serde_json::json!(i)));
+    }
+}
+
+#[tokio::test]
+async fn test_get_many_stream_stops_at_the_first_error() {
+    use futures::StreamExt;
+
+    let keyv = Keyv::default();
+    let results: Vec<_> = keyv.get_many_stream(["ok", "", "later"], 1).collect().await;
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "runtime")]
+
+use std::time::Duration;
+
+#[cfg(feature = "sqlite")]
+use keyv::adapter::sqlite::SqliteStoreBuilder;
+use keyv::{adapter::inmemory::InMemoryStore, queue::Queue, QueueBackend};
+use serde_json::json;
+
+async fn check_queue<B: QueueBackend + Clone>(backend: B) {
+    let jobs = Queue::new(backend.clone(), "jobs");
+    let first = jobs.push("first").await.unwrap();
+    jobs.push("second").await.unwrap();
+    Queue::new(backend.clone(), "other")
+        .push("elsewhere")
+        .await
+        .unwrap();
+
+    // Messages come out in push order and stay hidden while claimed.
+    let message = jobs.pop(Duration::from_millis(100)).await.unwrap().unwrap();
+    assert_eq!(message.id, first);
+    assert_eq!(message.payload, json!("first"));
+    let second = jobs.pop(Duration::from_secs(60)).await.unwrap().unwrap();
+    assert_eq!(second.payload, json!("second"));
+    assert!(jobs.pop(Duration::from_secs(60)).await.unwrap().is_none());
+
+    // An unacknowledged message is delivered again once its claim lapses.
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    let redelivered = jobs.pop(Duration::from_secs(60)).await.unwrap().unwrap();
+    assert_eq!(redelivered, message);
+
+    jobs.ack(&redelivered.id).await.unwrap();
+    jobs.ack(&second.id).await.unwrap();
+    jobs.ack("unknown").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(jobs.pop(Duration::from_secs(60)).await.unwrap().is_none());
+
+    let other = Queue::new(backend, "other")
+        .pop(Duration::from_secs(60))
+        .await;
+    assert_eq!(other.unwrap().unwrap().payload, json!("elsewhere"));
+}
+
+#[tokio::test]
+async fn test_inmemory_queue() {
+    check_queue(&InMemoryStore::new()).await;
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_queue() {
+    use std::sync::Arc;
+
+    use keyv::Keyv;
+
+    let store = Arc::new(
+        SqliteStoreBuilder::new()
+            .uri("sqlite::memory:")
+            .build()
+            .await
+            .unwrap(),
+    );
+    let keyv = Keyv::try_new(store.clone()).await.unwrap();
+    check_queue(store).await;
+
+    // Queue messages are kept apart from the key-value entries.
+    assert_eq!(keyv.get("jobs").await.unwrap(), None);
+}
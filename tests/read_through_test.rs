@@ -0,0 +1,74 @@
+#![cfg(feature = "runtime")]
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use keyv::{Keyv, KeyvError};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_read_through_loads_misses_once() {
+    let keyv = Keyv::default();
+    let loads = Arc::new(AtomicU64::new(0));
+    let counter = loads.clone();
+    let users = keyv.read_through(Duration::from_secs(60), move |key| {
+        let load = counter.fetch_add(1, Ordering::SeqCst) + 1;
+        async move {
+            Ok::<_, std::io::Error>((key != "missing").then(|| json!({ "key": key, "load": load })))
+        }
+    });
+
+    let expected = json!({ "key": "user:1", "load": 1 });
+    assert_eq!(users.get("user:1").await.unwrap(), Some(expected.clone()));
+    assert_eq!(users.get("user:1").await.unwrap(), Some(expected.clone()));
+    assert_eq!(keyv.get("user:1").await.unwrap(), Some(expected));
+    assert_eq!(users.get("missing").await.unwrap(), None);
+    assert_eq!(loads.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_read_through_surfaces_loader_errors() {
+    let keyv = Keyv::default();
+    let users = keyv.read_through(Duration::from_secs(60), |_| async {
+        Err::<Option<serde_json::Value>, _>("backend down")
+    });
+
+    assert!(matches!(
+        users.get("user:1").await,
+        Err(KeyvError::LoaderError(_))
+    ));
+    assert!(keyv.get("user:1").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_refresh_ahead_refreshes_in_background() {
+    let keyv = Keyv::default();
+    let loads = Arc::new(AtomicU64::new(0));
+    let counter = loads.clone();
+    let users = keyv
+        .read_through(Duration::from_millis(200), move |_| {
+            let load = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move { Ok::<_, std::io::Error>(Some(json!(load))) }
+        })
+        .refresh_ahead(0.5);
+
+    assert_eq!(users.get("hot").await.unwrap(), Some(json!(1)));
+    assert_eq!(users.get("hot").await.unwrap(), Some(json!(1)));
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    tokio::time::sleep(Duration::from_millis(120)).await;
+    // The stale value is served while the refresh runs.
+    assert_eq!(users.get("hot").await.unwrap(), Some(json!(1)));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(loads.load(Ordering::SeqCst), 2);
+    assert_eq!(keyv.get("hot").await.unwrap(), Some(json!(2)));
+
+    // The refreshed entry outlives the original TTL.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(users.get("hot").await.unwrap(), Some(json!(2)));
+}
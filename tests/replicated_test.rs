@@ -0,0 +1,278 @@
+#![cfg(feature = "runtime")]
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use keyv::{
+    adapter::inmemory::InMemoryStore,
+    layer::replicated::{MergeWith, ReconcileReport, ReplicatedStore, REPLICATION_QUEUE},
+    layer::stats::Stats,
+    Keyv, QueueBackend, Store, StoreError,
+};
+use serde_json::{json, Value};
+
+/// Remote store that fails every call while it is offline.
+#[derive(Default)]
+struct Remote {
+    inner: InMemoryStore,
+    offline: AtomicBool,
+}
+
+impl Remote {
+    fn check(&self) -> Result<(), StoreError> {
+        if self.offline.load(Ordering::SeqCst) {
+            return Err(StoreError::ConnectionError("offline".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Store for Remote {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        self.check()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        self.check()?;
+        self.inner.get(key).await
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.check()?;
+        self.inner.set(key, value, ttl).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.check()?;
+        self.inner.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.check()?;
+        self.inner.remove_many(keys).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.check()?;
+        self.inner.clear().await
+    }
+}
+
+#[tokio::test]
+async fn test_writes_are_replicated_on_demand() {
+    let store = ReplicatedStore::new(InMemoryStore::new(), InMemoryStore::new());
+
+    store.set("a", json!(1), None).await.unwrap();
+    store.set("b", json!(2), None).await.unwrap();
+    store.remove("b").await.unwrap();
+    assert_eq!(store.local().get("a").await.unwrap(), Some(json!(1)));
+    assert!(store.remote().get("a").await.unwrap().is_none());
+
+    assert_eq!(store.replicate_pending().await.unwrap(), 3);
+    assert_eq!(store.remote().get("a").await.unwrap(), Some(json!(1)));
+    assert!(store.remote().get("b").await.unwrap().is_none());
+    assert_eq!(store.replicate_pending().await.unwrap(), 0);
+
+    store.clear().await.unwrap();
+    store.replicate_pending().await.unwrap();
+    assert!(store.remote().get("a").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_outbox_survives_remote_outage() {
+    let remote = Arc::new(Remote::default());
+    remote.offline.store(true, Ordering::SeqCst);
+    let store = ReplicatedStore::new(InMemoryStore::new(), remote.clone())
+        .retry_backoff(Duration::from_millis(20), Duration::from_millis(20));
+
+    store.set("reading", json!(21.5), None).await.unwrap();
+    assert_eq!(store.get("reading").await.unwrap(), Some(json!(21.5)));
+    assert!(matches!(
+        store.replicate_pending().await,
+        Err(StoreError::ConnectionError(_))
+    ));
+
+    remote.offline.store(false, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(store.replicate_pending().await.unwrap(), 1);
+    assert_eq!(
+        remote.inner.get("reading").await.unwrap(),
+        Some(json!(21.5))
+    );
+    assert!(store
+        .local()
+        .queue_pop(REPLICATION_QUEUE, Duration::from_secs(1))
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_background_replication_retries() {
+    let remote = Arc::new(Remote::default());
+    remote.offline.store(true, Ordering::SeqCst);
+    let store = ReplicatedStore::new(InMemoryStore::new(), remote.clone())
+        .poll_interval(Duration::from_millis(10))
+        .retry_backoff(Duration::from_millis(10), Duration::from_millis(40));
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("reading", 21.5).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(remote.inner.get("reading").await.unwrap().is_none());
+
+    remote.offline.store(false, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(
+        remote.inner.get("reading").await.unwrap(),
+        Some(json!(21.5))
+    );
+}
+
+#[tokio::test]
+async fn test_reconcile_prefers_primary_by_default() {
+    let store = ReplicatedStore::new(InMemoryStore::new(), InMemoryStore::new());
+    store.set("shared", json!("local"), None).await.unwrap();
+    store.set("same", json!(1), None).await.unwrap();
+    store.replicate_pending().await.unwrap();
+    store
+        .remote()
+        .set("shared", json!("remote"), None)
+        .await
+        .unwrap();
+    store
+        .remote()
+        .set("stray", json!(true), None)
+        .await
+        .unwrap();
+
+    let report = store.reconcile().await.unwrap();
+    assert_eq!(report.repaired, 2);
+    assert!(report.diverging_buckets >= 1 && report.diverging_buckets <= 2);
+    assert_eq!(
+        store.remote().get("shared").await.unwrap(),
+        Some(json!("local"))
+    );
+    assert!(store.remote().get("stray").await.unwrap().is_none());
+    assert_eq!(store.reconcile().await.unwrap(), ReconcileReport::default());
+}
+
+#[tokio::test]
+async fn test_reconcile_merges_with_custom_function() {
+    let store = ReplicatedStore::new(InMemoryStore::new(), InMemoryStore::new()).conflict_resolver(
+        MergeWith::new(|_key: &str, local: &Value, remote: &Value| {
+            json!(local.as_i64().max(remote.as_i64()))
+        }),
+    );
+    store
+        .local()
+        .set("visits", json!(3), Some(Duration::from_secs(60)))
+        .await
+        .unwrap();
+    store
+        .remote()
+        .set("visits", json!(7), Some(Duration::from_secs(120)))
+        .await
+        .unwrap();
+    store
+        .remote()
+        .set("only-remote", json!(1), None)
+        .await
+        .unwrap();
+
+    assert_eq!(store.reconcile().await.unwrap().repaired, 2);
+    for copy in [store.local(), store.remote()] {
+        let (value, metadata) = copy.get_with_metadata("visits").await.unwrap().unwrap();
+        assert_eq!(value, json!(7));
+        assert!(metadata.expires_in().unwrap() > Duration::from_secs(60));
+    }
+    assert_eq!(
+        store.local().get("only-remote").await.unwrap(),
+        Some(json!(1))
+    );
+}
+
+#[tokio::test]
+async fn test_background_anti_entropy_reports_repairs() {
+    let stats = Arc::new(Stats::new());
+    let store = ReplicatedStore::new(InMemoryStore::new(), Arc::new(InMemoryStore::new()))
+        .anti_entropy(Duration::from_millis(20))
+        .stats(stats.clone());
+    let remote = store.remote().clone();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("config", "v1").await.unwrap();
+    remote.set("config", json!("stale"), None).await.unwrap();
+    remote.set("leftover", json!(1), None).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(remote.get("config").await.unwrap(), Some(json!("v1")));
+    assert!(remote.get("leftover").await.unwrap().is_none());
+    // `config` is fixed by the outbox replicated at the start of the pass.
+    assert_eq!(stats.repairs(), 1);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_reconcile_last_writer_wins() {
+    use keyv::{adapter::sqlite::SqliteStoreBuilder, layer::replicated::LastWriterWins};
+
+    let sqlite = || async {
+        SqliteStoreBuilder::new()
+            .uri("sqlite::memory:")
+            .build()
+            .await
+            .unwrap()
+    };
+    let store =
+        ReplicatedStore::new(sqlite().await, sqlite().await).conflict_resolver(LastWriterWins);
+    store.initialize().await.unwrap();
+    store.remote().initialize().await.unwrap();
+
+    store.local().set("a", json!("old"), None).await.unwrap();
+    store.remote().set("b", json!("old"), None).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    store.remote().set("a", json!("new"), None).await.unwrap();
+    store.local().set("b", json!("new"), None).await.unwrap();
+
+    assert_eq!(store.reconcile().await.unwrap().repaired, 2);
+    for key in ["a", "b"] {
+        assert_eq!(store.local().get(key).await.unwrap(), Some(json!("new")));
+        assert_eq!(store.remote().get(key).await.unwrap(), Some(json!("new")));
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_local_store() {
+    use keyv::adapter::sqlite::SqliteStoreBuilder;
+
+    let local = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let store = ReplicatedStore::new(local, InMemoryStore::new());
+    store.initialize().await.unwrap();
+
+    store
+        .set("session", json!("abc"), Some(Duration::from_secs(60)))
+        .await
+        .unwrap();
+    store.replicate_pending().await.unwrap();
+
+    let (value, metadata) = store
+        .remote()
+        .get_with_metadata("session")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(value, json!("abc"));
+    assert!(metadata.expires_at.is_some());
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "runtime")]
+
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use keyv::{RetryPolicy, StoreError};
+
+#[tokio::test]
+async fn test_retry_policy() {
+    let attempts = AtomicU32::new(0);
+    let policy = RetryPolicy::new(5).backoff(Duration::from_millis(1), Duration::from_millis(4));
+
+    let result = policy
+        .run(|| async {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(StoreError::ConnectionError("not ready".to_string()))
+            } else {
+                Ok("ready")
+            }
+        })
+        .await;
+    assert_eq!(result.unwrap(), "ready");
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+    let attempts = AtomicU32::new(0);
+    let result: Result<(), StoreError> = policy
+        .run(|| async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(StoreError::ConnectionError("down".to_string()))
+        })
+        .await;
+    assert!(matches!(result, Err(StoreError::ConnectionError(_))));
+    assert_eq!(attempts.load(Ordering::SeqCst), 5);
+}
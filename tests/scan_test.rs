@@ -0,0 +1,49 @@
+#![cfg(feature = "runtime")]
+
+use futures::TryStreamExt;
+use keyv::Keyv;
+
+#[tokio::test]
+async fn test_inmemory_scan() {
+    let keyv = Keyv::default();
+    for i in 0..5 {
+        keyv.set(&format!("user:{}", i), i).await.unwrap();
+    }
+    keyv.set("order:1", 1).await.unwrap();
+
+    let batches: Vec<_> = keyv.scan(Some("user:"), 2).try_collect().await.unwrap();
+    assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), [2, 2, 1]);
+
+    let entries = batches.concat();
+    assert_eq!(entries[0].key, "user:0");
+    assert_eq!(entries[4].value, serde_json::json!(4));
+    assert!(entries.iter().all(|entry| entry.expires_at.is_none()));
+
+    let all: Vec<_> = keyv.scan(None, 100).try_collect().await.unwrap();
+    assert_eq!(all.concat().len(), 6);
+}
+
+#[tokio::test]
+async fn test_inmemory_find() {
+    let keyv = Keyv::default();
+    keyv.set("user:1:profile", 1).await.unwrap();
+    keyv.set("user:22:profile", 2).await.unwrap();
+    keyv.set("user:1:settings", 3).await.unwrap();
+    keyv.set("user:*", 4).await.unwrap();
+    keyv.set("user:é", 5).await.unwrap();
+
+    let mut keys: Vec<String> = keyv
+        .find("user:*:profile")
+        .map_ok(|(key, _)| key)
+        .try_collect()
+        .await
+        .unwrap();
+    keys.sort();
+    assert_eq!(keys, ["user:1:profile", "user:22:profile"]);
+
+    let found: Vec<_> = keyv.find("user:\\*").try_collect().await.unwrap();
+    assert_eq!(found, [("user:*".to_string(), serde_json::json!(4))]);
+
+    let found: Vec<_> = keyv.find("user:?").try_collect().await.unwrap();
+    assert_eq!(found.len(), 2);
+}
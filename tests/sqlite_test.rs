@@ -1,5 +1,5 @@
 #[cfg(feature = "sqlite")]
-use keyv::{adapter::sqlite::SqliteStoreBuilder, Keyv};
+use keyv::{adapter::sqlite::SqliteStoreBuilder, Keyv, StoreError};
 
 #[cfg(feature = "sqlite")]
 #[tokio::test]
@@ -26,7 +26,7 @@ async fn test_keyv_sqlite() {
             let number: i32 = serde_json::from_value(number).unwrap(); // Deserialize
             assert_eq!(number, 10);
         }
-        None => assert!(false, "Expected data not found"),
+        None => panic!("Expected data not found"),
     }
 
     match keyv.get("string").await.unwrap() {
@@ -34,7 +34,7 @@ async fn test_keyv_sqlite() {
             let string: String = serde_json::from_value(string).unwrap(); // Deserialize
             assert_eq!(string, "test value");
         }
-        None => assert!(false, "Expected data not found"),
+        None => panic!("Expected data not found"),
     }
 
     match keyv.get("array").await.unwrap() {
@@ -42,6 +42,662 @@ async fn test_keyv_sqlite() {
             let array: Vec<String> = serde_json::from_value(array).unwrap(); // Deserialize
             assert_eq!(array, vec!["hello".to_string(), "world".to_string()]);
         }
-        None => assert!(false, "Expected data not found"),
+        None => panic!("Expected data not found"),
     }
 }
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_builder_without_uri_is_invalid() {
+    let builder = SqliteStoreBuilder::new();
+    assert!(matches!(
+        builder.validate(),
+        Err(StoreError::InvalidConfiguration { .. })
+    ));
+    assert!(matches!(
+        builder.build().await,
+        Err(StoreError::InvalidConfiguration { .. })
+    ));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_builder_rejects_misconfiguration() {
+    let invalid_field = |builder: SqliteStoreBuilder| match builder.validate() {
+        Err(StoreError::InvalidConfiguration { field, .. }) => field,
+        other => panic!("expected an invalid configuration, got {:?}", other),
+    };
+
+    assert_eq!(
+        invalid_field(SqliteStoreBuilder::new().uri("postgres://localhost/db")),
+        "uri"
+    );
+    assert_eq!(
+        invalid_field(
+            SqliteStoreBuilder::new()
+                .uri("sqlite::memory:")
+                .table_name("")
+        ),
+        "table_name"
+    );
+
+    let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+    assert_eq!(
+        invalid_field(
+            SqliteStoreBuilder::new()
+                .uri("sqlite::memory:")
+                .pool(std::sync::Arc::new(pool))
+        ),
+        "pool"
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_pool_is_shared() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+
+    let pool = store.pool().clone();
+    assert!(!pool.is_closed());
+    assert!(pool.acquire().await.is_ok());
+    assert!(pool.size() >= 1);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_entry_timestamps() {
+    use std::time::{Duration, SystemTime};
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    let before = SystemTime::now() - Duration::from_millis(1);
+    keyv.set("key", "v1").await.unwrap();
+    let (_, first) = keyv.get_with_metadata("key").await.unwrap().unwrap();
+    let created_at = first.created_at.unwrap();
+    assert!(created_at >= before);
+    assert_eq!(first.updated_at, Some(created_at));
+
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    keyv.set("key", "v2").await.unwrap();
+    let (value, second) = keyv.get_with_metadata("key").await.unwrap().unwrap();
+    assert_eq!(value, serde_json::json!("v2"));
+    assert_eq!(second.created_at, Some(created_at));
+    assert!(second.updated_at.unwrap() > created_at);
+    assert_eq!(second.expires_at, None);
+    assert_eq!(second.expires_in(), None);
+
+    keyv.set_for("key", "v3", Duration::from_secs(60))
+        .await
+        .unwrap();
+    let (_, third) = keyv.get_with_metadata("key").await.unwrap().unwrap();
+    let expires_in = third.expires_in().unwrap();
+    assert!(expires_in > Duration::from_secs(55) && expires_in <= Duration::from_secs(60));
+
+    assert!(keyv.get_with_metadata("missing").await.unwrap().is_none());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_get_many() {
+    use std::time::Duration;
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("session:1", "alice").await.unwrap();
+    keyv.set("session:2", "bob").await.unwrap();
+    keyv.set_for("session:3", "carol", Duration::from_millis(10))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let values = keyv
+        .get_many(&[
+            "session:2",
+            "missing",
+            "session:3",
+            "session:1",
+            "session:2",
+        ])
+        .await
+        .unwrap();
+    assert_eq!(
+        values,
+        vec![
+            Some(serde_json::json!("bob")),
+            None,
+            None,
+            Some(serde_json::json!("alice")),
+            Some(serde_json::json!("bob")),
+        ]
+    );
+    assert!(keyv.get_many::<&str>(&[]).await.unwrap().is_empty());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_set_many() {
+    use std::time::Duration;
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("user:1", "old").await.unwrap();
+
+    let users: Vec<(String, String)> = (1..=3)
+        .map(|i| (format!("user:{}", i), format!("name-{}", i)))
+        .collect();
+    keyv.set_many(&users, Some(Duration::from_secs(60)))
+        .await
+        .unwrap();
+    for (key, name) in &users {
+        let (value, metadata) = keyv.get_with_metadata(key).await.unwrap().unwrap();
+        assert_eq!(value, serde_json::json!(name));
+        assert!(metadata.expires_at.is_some());
+    }
+
+    keyv.set_many::<&str, i32>(&[], None).await.unwrap();
+    assert!(keyv.set_many(&[("", 1)], None).await.is_err());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_scan_entries() {
+    use futures::TryStreamExt;
+    use keyv::Store;
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    store.initialize().await.unwrap();
+    for i in 0..5 {
+        store
+            .set(&format!("user:{}", i), serde_json::json!(i), None)
+            .await
+            .unwrap();
+    }
+    store
+        .set("User:5", serde_json::json!(5), None)
+        .await
+        .unwrap();
+    store
+        .set("order:1", serde_json::json!(1), None)
+        .await
+        .unwrap();
+
+    let batches: Vec<_> = store
+        .scan_entries(Some("user:"), 2)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), [2, 2, 1]);
+    let keys: Vec<String> = batches.into_iter().flatten().map(|e| e.key).collect();
+    assert_eq!(keys, ["user:0", "user:1", "user:2", "user:3", "user:4"]);
+
+    let all: Vec<_> = store.scan_entries(None, 100).try_collect().await.unwrap();
+    assert_eq!(all.concat().len(), 7);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_find() {
+    use futures::TryStreamExt;
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("user:1:profile", 1).await.unwrap();
+    keyv.set("User:2:profile", 2).await.unwrap();
+    keyv.set("user:3:settings", 3).await.unwrap();
+    keyv.set("user:[4]:profile", 4).await.unwrap();
+
+    let keys: Vec<String> = keyv
+        .find("user:*:profile")
+        .map_ok(|(key, _)| key)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(keys, ["user:1:profile", "user:[4]:profile"]);
+
+    let keys: Vec<String> = keyv
+        .find("user:[4]:*")
+        .map_ok(|(key, _)| key)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(keys, ["user:[4]:profile"]);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_stores_null() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set("null", ()).await.unwrap();
+
+    assert_eq!(keyv.get_entry("null").await.unwrap(), keyv::Entry::Null);
+    assert!(keyv.get_entry("missing").await.unwrap().is_missing());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_serialization_failure_policy() {
+    use keyv::{SerializationFailurePolicy, Store};
+
+    for policy in [
+        SerializationFailurePolicy::Error,
+        SerializationFailurePolicy::LogAndMiss,
+        SerializationFailurePolicy::DeleteAndMiss,
+    ] {
+        let store = SqliteStoreBuilder::new()
+            .uri("sqlite::memory:")
+            .on_serialization_failure(policy)
+            .build()
+            .await
+            .unwrap();
+        store.initialize().await.unwrap();
+        sqlx::query("INSERT INTO keyv (key, value) VALUES ('corrupt', '{not json')")
+            .execute(store.pool())
+            .await
+            .unwrap();
+
+        let result = store.get("corrupt").await;
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM keyv")
+            .fetch_one(store.pool())
+            .await
+            .unwrap();
+        match policy {
+            SerializationFailurePolicy::Error => {
+                assert!(matches!(result, Err(StoreError::SerializationError { .. })));
+                assert_eq!(count, 1);
+            }
+            SerializationFailurePolicy::LogAndMiss => {
+                assert!(result.unwrap().is_none());
+                assert_eq!(count, 1);
+            }
+            SerializationFailurePolicy::DeleteAndMiss => {
+                assert!(result.unwrap().is_none());
+                assert_eq!(count, 0);
+            }
+            SerializationFailurePolicy::Quarantine => unreachable!(),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_quarantine() {
+    use std::sync::{Arc, Mutex};
+
+    use keyv::{SerializationFailurePolicy, Store};
+
+    let quarantined = Arc::new(Mutex::new(Vec::new()));
+    let listener = quarantined.clone();
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .on_serialization_failure(SerializationFailurePolicy::Quarantine)
+        .on_quarantine(move |entry| listener.lock().unwrap().push(entry.clone()))
+        .build()
+        .await
+        .unwrap();
+    store.initialize().await.unwrap();
+    sqlx::query("INSERT INTO keyv (key, value) VALUES ('corrupt', '{not json')")
+        .execute(store.pool())
+        .await
+        .unwrap();
+
+    assert!(store.get("corrupt").await.unwrap().is_none());
+
+    let rows: Vec<(String, String)> = sqlx::query_as("SELECT key, value FROM keyv_quarantine")
+        .fetch_all(store.pool())
+        .await
+        .unwrap();
+    assert_eq!(rows, [("corrupt".to_string(), "{not json".to_string())]);
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM keyv")
+        .fetch_one(store.pool())
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+
+    let quarantined = quarantined.lock().unwrap();
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].key, "corrupt");
+    assert_eq!(quarantined[0].raw, "{not json");
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_memory_modes() {
+    use keyv::{adapter::sqlite::SqliteMemoryMode, Store};
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(store.pool().options().get_max_connections(), 1);
+    assert_eq!(store.pool().options().get_idle_timeout(), None);
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .memory_mode(SqliteMemoryMode::SharedCache)
+        .build()
+        .await
+        .unwrap();
+    store.initialize().await.unwrap();
+    store
+        .set("key", serde_json::json!("value"), None)
+        .await
+        .unwrap();
+
+    // Every connection of the pool sees the same database.
+    let mut first = store.pool().acquire().await.unwrap();
+    let mut second = store.pool().acquire().await.unwrap();
+    for conn in [&mut first, &mut second] {
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM keyv")
+            .fetch_one(&mut **conn)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_concurrent_writes_queue() {
+    use std::sync::Arc;
+
+    use keyv::Store;
+
+    let path = std::env::temp_dir().join(format!("keyv-writes-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let store = SqliteStoreBuilder::new()
+        .uri(format!("sqlite://{}?mode=rwc", path.display()))
+        .build()
+        .await
+        .unwrap();
+    store.initialize().await.unwrap();
+    let store = Arc::new(store);
+
+    let writes: Vec<_> = (0..64)
+        .map(|i| {
+            let store = store.clone();
+            tokio::spawn(async move {
+                store
+                    .set(&format!("key:{}", i), serde_json::json!(i), None)
+                    .await
+            })
+        })
+        .collect();
+    for write in writes {
+        write.await.unwrap().unwrap();
+    }
+
+    assert_eq!(store.usage().await.unwrap().entries, 64);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_existing_table_with_custom_columns() {
+    use std::time::Duration;
+
+    use keyv::Store;
+
+    let pool = std::sync::Arc::new(
+        sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap(),
+    );
+    sqlx::query(
+        "CREATE TABLE http_cache (cache_key TEXT PRIMARY KEY, payload TEXT NOT NULL, expires INTEGER)",
+    )
+    .execute(&*pool)
+    .await
+    .unwrap();
+    sqlx::query("INSERT INTO http_cache (cache_key, payload) VALUES ('legacy', '\"kept\"')")
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+    let strict = SqliteStoreBuilder::new()
+        .pool(pool.clone())
+        .table_name("http_cache")
+        .key_column("cache_key")
+        .value_column("payload")
+        .ttl_column("expires")
+        .create_tables(false)
+        .build()
+        .await
+        .unwrap();
+    // No DDL runs, so the missing timestamp columns are reported.
+    assert!(matches!(
+        strict.initialize().await,
+        Err(StoreError::InvalidConfiguration { .. })
+    ));
+    assert_eq!(
+        strict.get("legacy").await.unwrap(),
+        Some(serde_json::json!("kept"))
+    );
+
+    let store = SqliteStoreBuilder::new()
+        .pool(pool.clone())
+        .table_name("http_cache")
+        .key_column("cache_key")
+        .value_column("payload")
+        .ttl_column("expires")
+        .build()
+        .await
+        .unwrap();
+    store.initialize().await.unwrap();
+    store
+        .set("key", serde_json::json!(1), Some(Duration::from_secs(60)))
+        .await
+        .unwrap();
+    assert_eq!(store.get("key").await.unwrap(), Some(serde_json::json!(1)));
+    assert_eq!(store.increment("key", 2, None).await.unwrap(), 3);
+    assert_eq!(store.usage().await.unwrap().entries, 2);
+
+    let (expires,): (Option<i64>,) =
+        sqlx::query_as("SELECT expires FROM http_cache WHERE cache_key = 'key'")
+            .fetch_one(&*pool)
+            .await
+            .unwrap();
+    assert!(expires.is_some());
+
+    assert!(matches!(
+        SqliteStoreBuilder::new()
+            .uri("sqlite::memory:")
+            .key_column("key; DROP TABLE keyv")
+            .validate(),
+        Err(StoreError::InvalidConfiguration { .. })
+    ));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_existing_table_without_timestamps() {
+    use std::time::Duration;
+
+    use keyv::Store;
+
+    let pool = std::sync::Arc::new(
+        sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap(),
+    );
+    sqlx::query(
+        "CREATE TABLE sessions (key TEXT PRIMARY KEY, value TEXT NOT NULL, expires_at INTEGER)",
+    )
+    .execute(&*pool)
+    .await
+    .unwrap();
+
+    let store = SqliteStoreBuilder::new()
+        .pool(pool.clone())
+        .table_name("sessions")
+        .created_at_column(None)
+        .updated_at_column(None)
+        .create_tables(false)
+        .build()
+        .await
+        .unwrap();
+    store.initialize().await.unwrap();
+
+    store
+        .set(
+            "key",
+            serde_json::json!("first"),
+            Some(Duration::from_secs(60)),
+        )
+        .await
+        .unwrap();
+    store
+        .set("key", serde_json::json!("second"), None)
+        .await
+        .unwrap();
+    store
+        .set_keep_ttl("key", serde_json::json!("third"))
+        .await
+        .unwrap();
+    assert!(store
+        .set_if_absent("other", serde_json::json!(0), None)
+        .await
+        .unwrap());
+    assert_eq!(store.increment("other", 2, None).await.unwrap(), 2);
+
+    // The table records no timestamps, so none are returned.
+    let (value, metadata) = store.get_with_metadata("key").await.unwrap().unwrap();
+    assert_eq!(value, serde_json::json!("third"));
+    assert!(metadata.created_at.is_none());
+    assert!(metadata.updated_at.is_none());
+    assert!(metadata.expires_at.is_none());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_without_ddl_verifies_the_table() {
+    use keyv::Store;
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .create_tables(false)
+        .build()
+        .await
+        .unwrap();
+    assert!(matches!(
+        store.initialize().await,
+        Err(StoreError::InvalidConfiguration { .. })
+    ));
+
+    sqlx::query(
+        "CREATE TABLE keyv (key TEXT PRIMARY KEY, value TEXT NOT NULL, expires_at INTEGER, created_at INTEGER, updated_at INTEGER)",
+    )
+    .execute(store.pool())
+    .await
+    .unwrap();
+    store.initialize().await.unwrap();
+    store.set("key", serde_json::json!(1), None).await.unwrap();
+    assert_eq!(store.get("key").await.unwrap(), Some(serde_json::json!(1)));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_rename() {
+    use std::time::Duration;
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set_for("user_42", "alice", Duration::from_secs(30))
+        .await
+        .unwrap();
+    keyv.set("user:42", "stale").await.unwrap();
+
+    keyv.rename("user_42", "user:42").await.unwrap();
+    assert!(keyv.get("user_42").await.unwrap().is_none());
+    let (value, metadata) = keyv.get_with_metadata("user:42").await.unwrap().unwrap();
+    assert_eq!(value, serde_json::json!("alice"));
+    assert!(metadata.expires_in().unwrap() > Duration::from_secs(25));
+
+    // A failed rename leaves the target untouched.
+    keyv.set("other", "kept").await.unwrap();
+    assert!(matches!(
+        keyv.rename("user_42", "other").await,
+        Err(keyv::KeyvError::StoreError(StoreError::NotFound))
+    ));
+    assert_eq!(
+        keyv.get("other").await.unwrap(),
+        Some(serde_json::json!("kept"))
+    );
+
+    keyv.rename("user:42", "user:42").await.unwrap();
+    assert!(keyv.get("user:42").await.unwrap().is_some());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_remove_if() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set(
+        "lock",
+        serde_json::json!({ "owner": "worker-1", "fence": 7 }),
+    )
+    .await
+    .unwrap();
+
+    assert!(!keyv
+        .remove_if(
+            "lock",
+            serde_json::json!({ "owner": "worker-2", "fence": 8 })
+        )
+        .await
+        .unwrap());
+    assert!(keyv.get("lock").await.unwrap().is_some());
+    assert!(keyv
+        .remove_if(
+            "lock",
+            serde_json::json!({ "fence": 7, "owner": "worker-1" })
+        )
+        .await
+        .unwrap());
+    assert!(keyv.get("lock").await.unwrap().is_none());
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "runtime")]
+
+use std::time::Duration;
+
+use keyv::{adapter::inmemory::InMemoryStore, layer::ttl::TtlStore, Keyv, KeyvError, StaleRead};
+use serde_json::json;
+
+const TTL: Duration = Duration::from_millis(20);
+
+async fn expire() {
+    tokio::time::sleep(TTL * 2).await;
+}
+
+#[tokio::test]
+async fn test_stale_values_are_returned_without_being_purged() {
+    let keyv = Keyv::default();
+    keyv.set_for("rates", "1.08", TTL).await.unwrap();
+    keyv.set("config", "v1").await.unwrap();
+    assert_eq!(
+        keyv.get_allow_stale("rates").await.unwrap(),
+        StaleRead::Fresh(json!("1.08"))
+    );
+
+    expire().await;
+    let StaleRead::Stale(value, expired_by) = keyv.get_allow_stale("rates").await.unwrap() else {
+        panic!("expected a stale value");
+    };
+    assert_eq!(value, json!("1.08"));
+    assert!(expired_by >= TTL / 2 && expired_by < Duration::from_secs(1));
+    assert!(keyv
+        .get_allow_stale("rates")
+        .await
+        .unwrap()
+        .into_value()
+        .is_some());
+    assert!(keyv.get_allow_stale("config").await.unwrap().is_fresh());
+
+    // A regular read still treats the value as missing and purges it.
+    assert!(keyv.get("rates").await.unwrap().is_none());
+    assert_eq!(
+        keyv.get_allow_stale("rates").await.unwrap(),
+        StaleRead::Missing
+    );
+}
+
+#[tokio::test]
+async fn test_within_rejects_values_too_old() {
+    let keyv = Keyv::default();
+    keyv.set_for("rates", "1.08", TTL).await.unwrap();
+    expire().await;
+
+    let read = keyv.get_allow_stale("rates").await.unwrap();
+    assert_eq!(
+        read.clone().within(Duration::from_secs(60)).unwrap(),
+        Some(json!("1.08"))
+    );
+    assert!(matches!(
+        read.within(Duration::ZERO),
+        Err(KeyvError::Expired(_))
+    ));
+    assert_eq!(StaleRead::Missing.within(Duration::ZERO).unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_stale_reads_through_layers() {
+    let keyv = Keyv::try_new(TtlStore::new(InMemoryStore::new()))
+        .await
+        .unwrap()
+        .namespace("fx");
+    keyv.set_for("rates", "1.08", TTL).await.unwrap();
+    expire().await;
+
+    assert!(matches!(
+        keyv.get_allow_stale("rates").await.unwrap(),
+        StaleRead::Stale(_, _)
+    ));
+    assert!(keyv.get("rates").await.unwrap().is_none());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_stale_reads() {
+    use keyv::adapter::sqlite::SqliteStoreBuilder;
+
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+    keyv.set_for("rates", "1.08", TTL).await.unwrap();
+    expire().await;
+
+    assert!(keyv.get("rates").await.unwrap().is_none());
+    let StaleRead::Stale(value, _) = keyv.get_allow_stale("rates").await.unwrap() else {
+        panic!("expected a stale value");
+    };
+    assert_eq!(value, json!("1.08"));
+    assert_eq!(
+        keyv.get_allow_stale("missing").await.unwrap(),
+        StaleRead::Missing
+    );
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "runtime")]
+
+use keyv::{layer::stats::Operation, Keyv};
+
+#[tokio::test]
+async fn test_stats() {
+    let keyv = Keyv::default().with_stats();
+
+    keyv.set("key", "value").await.unwrap();
+    keyv.get("key").await.unwrap();
+    keyv.get("missing").await.unwrap();
+    keyv.remove_many(&["key"]).await.unwrap();
+
+    let stats = keyv.stats().unwrap();
+    assert_eq!(stats.hits(), 1);
+    assert_eq!(stats.misses(), 1);
+    assert_eq!(stats.hit_ratio(), Some(0.5));
+    assert_eq!(stats.operations(Operation::Get), 2);
+    assert_eq!(stats.operations(Operation::Remove), 1);
+
+    let report = keyv.latency_report().unwrap();
+    assert_eq!(report.get.count, 2);
+    assert!(report.get.p50 <= report.get.p99);
+    assert!(report.get.p99 <= report.get.max);
+    assert_eq!(report.clear.count, 0);
+
+    assert!(Keyv::default().latency_report().is_none());
+}
+
+#[tokio::test]
+async fn test_stats_count_every_key_of_get_many() {
+    let keyv = Keyv::default().with_stats();
+
+    keyv.set("a", 1).await.unwrap();
+    keyv.set("b", 2).await.unwrap();
+    let found = keyv.get_map(&["a", "b", "c"]).await.unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found["b"], serde_json::json!(2));
+
+    let stats = keyv.stats().unwrap();
+    assert_eq!(stats.hits(), 2);
+    assert_eq!(stats.misses(), 1);
+    assert_eq!(stats.operations(Operation::Get), 1);
+}
@@ -0,0 +1,177 @@
+#![cfg(feature = "runtime")]
+
+#[cfg(feature = "transform")]
+use keyv::{
+    adapter::inmemory::InMemoryStore,
+    layer::transform::{Serializer, TransformStore, Transformer, CODEC_FIELD, FORMAT_FIELD},
+    Keyv, Store, StoreError,
+};
+#[cfg(feature = "transform")]
+use serde_json::{json, Value};
+#[cfg(feature = "transform")]
+use std::sync::Arc;
+
+/// Toy cipher flipping every bit with a key byte.
+#[cfg(feature = "transform")]
+struct Xor(u8);
+
+#[cfg(feature = "transform")]
+impl Transformer for Xor {
+    fn id(&self) -> &str {
+        "xor"
+    }
+
+    fn apply(&self, data: &[u8]) -> Result<Option<Vec<u8>>, StoreError> {
+        Ok(Some(data.iter().map(|byte| byte ^ self.0).collect()))
+    }
+
+    fn revert(&self, data: &[u8]) -> Result<Vec<u8>, StoreError> {
+        Ok(data.iter().map(|byte| byte ^ self.0).collect())
+    }
+}
+
+/// Stores strings as their bare UTF-8 bytes.
+#[cfg(feature = "transform")]
+struct Text;
+
+#[cfg(feature = "transform")]
+impl Serializer for Text {
+    fn id(&self) -> &str {
+        "text"
+    }
+
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, StoreError> {
+        match value {
+            Value::String(text) => Ok(text.as_bytes().to_vec()),
+            _ => Err(StoreError::QueryError("not a string".to_string())),
+        }
+    }
+
+    fn deserialize(&self, data: &[u8]) -> Result<Value, StoreError> {
+        Ok(json!(String::from_utf8_lossy(data)))
+    }
+}
+
+#[cfg(feature = "transform")]
+#[tokio::test]
+async fn test_pipeline_records_transformers_in_order() {
+    let backend = Arc::new(InMemoryStore::new());
+    let keyv = Keyv::builder()
+        .store(backend.clone())
+        .transformer(Xor(0x5a))
+        .build()
+        .await
+        .unwrap();
+
+    keyv.set("secret", json!({ "token": "abc" })).await.unwrap();
+    let stored = backend.get("secret").await.unwrap().unwrap();
+    assert_eq!(stored[CODEC_FIELD], json!("xor"));
+    assert!(!stored.to_string().contains("abc"));
+    assert_eq!(
+        keyv.get("secret").await.unwrap(),
+        Some(json!({ "token": "abc" }))
+    );
+}
+
+#[cfg(feature = "transform")]
+#[tokio::test]
+async fn test_reads_plain_values_and_rejects_unknown_transformers() {
+    let backend = Arc::new(InMemoryStore::new());
+    let writer = TransformStore::new(backend.clone()).transformer(Xor(1));
+    let reader = TransformStore::new(backend.clone());
+
+    backend.set("plain", json!("visible"), None).await.unwrap();
+    writer.set("hidden", json!("value"), None).await.unwrap();
+
+    assert_eq!(writer.get("plain").await.unwrap(), Some(json!("visible")));
+    assert!(matches!(
+        reader.get("hidden").await,
+        Err(StoreError::QueryError(_))
+    ));
+}
+
+#[cfg(feature = "transform")]
+#[tokio::test]
+async fn test_custom_serializer_is_recorded() {
+    let backend = Arc::new(InMemoryStore::new());
+    let json_reader = TransformStore::new(backend.clone());
+    let store = TransformStore::new(backend.clone()).serializer(Text);
+
+    store.set("page", json!("<p>hi</p>"), None).await.unwrap();
+    let stored = backend.get("page").await.unwrap().unwrap();
+    assert_eq!(stored[FORMAT_FIELD], json!("text"));
+    assert_eq!(stored[CODEC_FIELD], json!(""));
+    assert_eq!(store.get("page").await.unwrap(), Some(json!("<p>hi</p>")));
+    assert!(matches!(
+        json_reader.get("page").await,
+        Err(StoreError::QueryError(_))
+    ));
+}
+
+#[cfg(feature = "transform")]
+#[tokio::test]
+async fn test_namespaces_override_the_pipeline() {
+    let backend = Arc::new(InMemoryStore::new());
+    let keyv = Keyv::builder()
+        .store(backend.clone())
+        .transformer(Xor(1))
+        .build()
+        .await
+        .unwrap();
+    let sessions = keyv
+        .namespace("sessions")
+        .with_transformers([Arc::new(Xor(2)) as Arc<dyn Transformer>]);
+    let pages = keyv.namespace("pages").with_serializer(Text);
+    let counters = keyv.namespace("counters").with_raw_values();
+    let inherited = keyv.namespace("inherited");
+
+    sessions.set("alice", "token").await.unwrap();
+    pages.set("home", "<h1>home</h1>").await.unwrap();
+    inherited.set("key", "value").await.unwrap();
+    assert_eq!(counters.increment("visits", 3, None).await.unwrap(), 3);
+
+    assert_eq!(sessions.get("alice").await.unwrap(), Some(json!("token")));
+    assert_eq!(
+        pages.get("home").await.unwrap(),
+        Some(json!("<h1>home</h1>"))
+    );
+    assert_eq!(inherited.get("key").await.unwrap(), Some(json!("value")));
+
+    let session = backend.get("sessions:alice").await.unwrap().unwrap();
+    assert_eq!(session[CODEC_FIELD], json!("xor"));
+    assert_eq!(
+        backend.get("counters:visits").await.unwrap(),
+        Some(json!(3))
+    );
+    assert_eq!(
+        backend.get("pages:home").await.unwrap().unwrap()[FORMAT_FIELD],
+        json!("text")
+    );
+    // The namespace's pipeline replaces the inherited one instead of stacking on it.
+    let sessions_reader = TransformStore::new(backend.clone()).transformer(Xor(2));
+    assert_eq!(
+        sessions_reader.get("sessions:alice").await.unwrap(),
+        Some(json!("token"))
+    );
+    assert_eq!(
+        keyv.get("inherited:key").await.unwrap(),
+        Some(json!("value"))
+    );
+}
+
+#[cfg(feature = "compression")]
+#[tokio::test]
+async fn test_compress_then_encrypt() {
+    use keyv::layer::compression::Gzip;
+
+    let backend = Arc::new(InMemoryStore::new());
+    let store = TransformStore::new(backend.clone())
+        .transformer(Gzip::new().min_size(0))
+        .transformer(Xor(0x7f));
+    let value = json!(vec!["row"; 500]);
+
+    store.set("report", value.clone(), None).await.unwrap();
+    let stored = backend.get("report").await.unwrap().unwrap();
+    assert_eq!(stored[CODEC_FIELD], json!("gzip,xor"));
+    assert_eq!(store.get("report").await.unwrap(), Some(value));
+}
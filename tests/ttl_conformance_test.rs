@@ -0,0 +1,20 @@
+#[cfg(feature = "testing")]
+use keyv::{
+    adapter::inmemory::InMemoryStore,
+    layer::key_codec::{KeyCodecStore, PrefixCodec},
+    testing::check_ttl_semantics,
+};
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_inmemory_ttl_semantics() {
+    check_ttl_semantics(|clock| InMemoryStore::new().clock(clock));
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_inmemory_ttl_semantics_through_a_layer() {
+    check_ttl_semantics(|clock| {
+        KeyCodecStore::new(InMemoryStore::new().clock(clock), PrefixCodec::new("app"))
+    });
+}
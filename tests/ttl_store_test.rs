@@ -0,0 +1,120 @@
+#![cfg(feature = "runtime")]
+
+use std::{sync::Mutex, time::Duration};
+
+use async_trait::async_trait;
+use keyv::{adapter::inmemory::InMemoryStore, layer::ttl::TtlStore, Store, StoreError};
+use serde_json::{json, Value};
+
+/// Store where another writer stores `fresh` under a key right after it is read.
+#[derive(Default)]
+struct RacingWriter {
+    inner: InMemoryStore,
+    fresh: Mutex<Option<Value>>,
+}
+
+#[async_trait]
+impl Store for RacingWriter {
+    async fn initialize(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Value>, StoreError> {
+        let value = self.inner.get(key).await?;
+        let fresh = self.fresh.lock().unwrap().take();
+        if let Some(fresh) = fresh {
+            self.inner.set(key, fresh, None).await?;
+        }
+        Ok(value)
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<(), StoreError> {
+        self.inner.set(key, value, ttl).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.remove(key).await
+    }
+
+    async fn remove_many(&self, keys: &[&str]) -> Result<(), StoreError> {
+        self.inner.remove_many(keys).await
+    }
+
+    async fn remove_if(&self, key: &str, expected: &Value) -> Result<bool, StoreError> {
+        self.inner.remove_if(key, expected).await
+    }
+
+    async fn clear(&self) -> Result<(), StoreError> {
+        self.inner.clear().await
+    }
+}
+
+#[tokio::test]
+async fn test_ttl_store_expires_entries() {
+    let store = TtlStore::new(InMemoryStore::new());
+
+    store
+        .set("short", json!("lived"), Some(Duration::from_millis(50)))
+        .await
+        .unwrap();
+    store.set("forever", json!({ "a": 1 }), None).await.unwrap();
+    assert_eq!(store.get("short").await.unwrap(), Some(json!("lived")));
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(store.get("short").await.unwrap(), None);
+    assert_eq!(store.get("forever").await.unwrap(), Some(json!({ "a": 1 })));
+
+    // The expired entry was purged from the wrapped store on read.
+    assert_eq!(store.inner().get("short").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_ttl_store_keeps_value_written_during_purge() {
+    let store = TtlStore::new(RacingWriter::default());
+
+    store
+        .set("session", json!("old"), Some(Duration::from_millis(10)))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    *store.inner().fresh.lock().unwrap() = Some(json!("new"));
+
+    assert_eq!(store.get("session").await.unwrap(), None);
+    assert_eq!(store.get("session").await.unwrap(), Some(json!("new")));
+}
+
+#[tokio::test]
+async fn test_ttl_store_reads_unwrapped_values() {
+    let store = TtlStore::new(InMemoryStore::new());
+
+    store.inner().set("legacy", json!(42), None).await.unwrap();
+    assert_eq!(store.get("legacy").await.unwrap(), Some(json!(42)));
+}
+
+#[tokio::test]
+async fn test_ttl_store_keep_ttl() {
+    let store = TtlStore::new(InMemoryStore::new());
+
+    store
+        .set("session", json!("v1"), Some(Duration::from_millis(50)))
+        .await
+        .unwrap();
+    store.set_keep_ttl("session", json!("v2")).await.unwrap();
+    assert_eq!(store.get("session").await.unwrap(), Some(json!("v2")));
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(store.get("session").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_ttl_store_reports_expiration_in_metadata() {
+    let store = TtlStore::new(InMemoryStore::new());
+
+    store
+        .set("key", json!(1), Some(Duration::from_secs(30)))
+        .await
+        .unwrap();
+    let (_, metadata) = store.get_with_metadata("key").await.unwrap().unwrap();
+    let expires_in = metadata.expires_in().unwrap();
+    assert!(expires_in > Duration::from_secs(25) && expires_in <= Duration::from_secs(30));
+}
@@ -0,0 +1,248 @@
+#![cfg(feature = "runtime")]
+
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "sqlite")]
+use keyv::adapter::sqlite::SqliteStoreBuilder;
+use keyv::Keyv;
+
+#[tokio::test]
+async fn test_inmemory_sub_second_ttl() {
+    let keyv = Keyv::default();
+
+    keyv.set_for("short", "lived", Duration::from_millis(50))
+        .await
+        .unwrap();
+    keyv.set("forever", "value").await.unwrap();
+    assert!(keyv.get("short").await.unwrap().is_some());
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(keyv.get("short").await.unwrap().is_none());
+    assert!(keyv.get("forever").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_inmemory_set_until() {
+    let keyv = Keyv::default();
+
+    let expires_at = SystemTime::now() + Duration::from_millis(50);
+    keyv.set_until("campaign", "spring", expires_at)
+        .await
+        .unwrap();
+    assert!(keyv.get("campaign").await.unwrap().is_some());
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(keyv.get("campaign").await.unwrap().is_none());
+
+    keyv.set("ended", "value").await.unwrap();
+    keyv.set_until("ended", "value", SystemTime::UNIX_EPOCH)
+        .await
+        .unwrap();
+    assert!(keyv.get("ended").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_inmemory_get_and_touch() {
+    let keyv = Keyv::default();
+
+    keyv.set_for("session", "data", Duration::from_millis(100))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let session = keyv
+        .get_and_touch("session", Duration::from_millis(100))
+        .await
+        .unwrap();
+    assert_eq!(session, Some(serde_json::json!("data")));
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(keyv.get("session").await.unwrap().is_some());
+    assert!(keyv
+        .get_and_touch("missing", Duration::from_millis(100))
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_sub_second_ttl() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set_for("short", "lived", Duration::from_millis(50))
+        .await
+        .unwrap();
+    assert!(keyv.get("short").await.unwrap().is_some());
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(keyv.get("short").await.unwrap().is_none());
+
+    // Overwriting without a TTL clears the previous expiration.
+    keyv.set("short", "again").await.unwrap();
+    assert!(keyv.get("short").await.unwrap().is_some());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_get_and_touch() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set_for("session", "data", Duration::from_millis(100))
+        .await
+        .unwrap();
+    let (_, written) = keyv.get_with_metadata("session").await.unwrap().unwrap();
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let session = keyv
+        .get_and_touch("session", Duration::from_millis(100))
+        .await
+        .unwrap();
+    assert_eq!(session, Some(serde_json::json!("data")));
+
+    // Touching extends the expiration without rewriting the entry.
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let (_, touched) = keyv.get_with_metadata("session").await.unwrap().unwrap();
+    assert_eq!(touched.updated_at, written.updated_at);
+    assert!(touched.expires_at > written.expires_at);
+    assert!(keyv
+        .get_and_touch("missing", Duration::from_millis(100))
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_set_until() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    let expires_at = SystemTime::now() + Duration::from_millis(50);
+    keyv.set_until("campaign", "spring", expires_at)
+        .await
+        .unwrap();
+    assert!(keyv.get("campaign").await.unwrap().is_some());
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(keyv.get("campaign").await.unwrap().is_none());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_touch_many() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set_for("session:1", "alice", Duration::from_millis(100))
+        .await
+        .unwrap();
+    keyv.set_for("session:2", "bob", Duration::from_millis(100))
+        .await
+        .unwrap();
+    keyv.set_for("session:3", "carol", Duration::from_millis(10))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(60)).await;
+
+    // The expired session is not brought back.
+    let touched = keyv
+        .touch_many(
+            &["session:1", "session:2", "session:3", "missing"],
+            Duration::from_millis(100),
+        )
+        .await
+        .unwrap();
+    assert_eq!(touched, 2);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(keyv.get("session:1").await.unwrap().is_some());
+    assert!(keyv.get("session:2").await.unwrap().is_some());
+    assert!(keyv.get("session:3").await.unwrap().is_none());
+    assert_eq!(
+        keyv.touch_many::<&str>(&[], Duration::from_secs(1))
+            .await
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_inmemory_set_keep_ttl() {
+    let keyv = Keyv::default();
+
+    keyv.set_for("session", "v1", Duration::from_millis(100))
+        .await
+        .unwrap();
+    keyv.set_keep_ttl("session", "v2").await.unwrap();
+    assert_eq!(
+        keyv.get("session").await.unwrap(),
+        Some(serde_json::json!("v2"))
+    );
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(keyv.get("session").await.unwrap().is_none());
+
+    // Expired or missing keys are written without an expiration.
+    keyv.set_keep_ttl("session", "v3").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(keyv.get("session").await.unwrap().is_some());
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_set_keep_ttl() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set_for("session", "v1", Duration::from_millis(100))
+        .await
+        .unwrap();
+    keyv.set_keep_ttl("session", "v2").await.unwrap();
+    assert_eq!(
+        keyv.get("session").await.unwrap(),
+        Some(serde_json::json!("v2"))
+    );
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(keyv.get("session").await.unwrap().is_none());
+
+    keyv.set_keep_ttl("session", "v3").await.unwrap();
+    assert!(keyv.get("session").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_time_to_idle_extends_on_read() {
+    use keyv::ExpiryPolicy;
+
+    let keyv =
+        Keyv::default().with_expiry_policy(ExpiryPolicy::TimeToIdle(Duration::from_millis(150)));
+    keyv.set("active", "a").await.unwrap();
+    keyv.set("idle", "b").await.unwrap();
+
+    for _ in 0..3 {
+        tokio::time::sleep(Duration::from_millis(75)).await;
+        assert!(keyv.get("active").await.unwrap().is_some());
+    }
+    assert!(keyv.get("idle").await.unwrap().is_none());
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "runtime")]
+
+#[cfg(feature = "sqlite")]
+use keyv::adapter::sqlite::SqliteStoreBuilder;
+use keyv::Keyv;
+
+#[tokio::test]
+async fn test_inmemory_usage() {
+    let keyv = Keyv::default();
+    assert_eq!(keyv.usage().await.unwrap().entries, 0);
+
+    keyv.set("a", "value").await.unwrap();
+    keyv.set("b", vec![1, 2, 3]).await.unwrap();
+
+    let usage = keyv.usage().await.unwrap();
+    assert_eq!(usage.entries, 2);
+    // "a" + "\"value\"" and "b" + "[1,2,3]"
+    assert_eq!(usage.bytes, Some(8 + 8));
+}
+
+#[tokio::test]
+async fn test_namespaced_usage() {
+    let keyv = Keyv::default();
+    let emails = keyv.namespace("emails");
+
+    keyv.set("a", "value").await.unwrap();
+    emails.set("alice", "queued").await.unwrap();
+
+    let usage = emails.usage().await.unwrap();
+    assert_eq!(usage.entries, 1);
+    assert_eq!(
+        usage.entries,
+        emails.clear_dry_run().await.unwrap().entries.unwrap()
+    );
+    assert_eq!(keyv.usage().await.unwrap().entries, 2);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_usage() {
+    let store = SqliteStoreBuilder::new()
+        .uri("sqlite::memory:")
+        .build()
+        .await
+        .unwrap();
+    let keyv = Keyv::try_new(store).await.unwrap();
+
+    keyv.set("a", "value").await.unwrap();
+    keyv.set("b", "value").await.unwrap();
+    keyv.remove("b").await.unwrap();
+
+    let usage = keyv.usage().await.unwrap();
+    assert_eq!(usage.entries, 1);
+    assert_eq!(usage.bytes, Some(8));
+}
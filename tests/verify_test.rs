@@ -0,0 +1,74 @@
+#![cfg(feature = "runtime")]
+
+use std::time::Duration;
+
+use keyv::{adapter::inmemory::InMemoryStore, verify, DivergenceKind, Store, StoreError};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_verify_reports_divergences() {
+    let src = InMemoryStore::new();
+    let dst = InMemoryStore::new();
+
+    src.set("same", json!(1), Some(Duration::from_secs(60)))
+        .await
+        .unwrap();
+    dst.set("same", json!(1), Some(Duration::from_secs(60)))
+        .await
+        .unwrap();
+    src.set("missing", json!(2), None).await.unwrap();
+    src.set("changed", json!("new"), None).await.unwrap();
+    dst.set("changed", json!("old"), None).await.unwrap();
+    src.set("ttl", json!(3), Some(Duration::from_secs(60)))
+        .await
+        .unwrap();
+    dst.set("ttl", json!(3), None).await.unwrap();
+    dst.set("extra", json!(4), None).await.unwrap();
+
+    let report = verify(&src, &dst, 1.0).await.unwrap();
+    assert_eq!(report.checked, 4);
+    assert!(!report.is_consistent());
+
+    let mut divergences: Vec<_> = report
+        .divergences
+        .iter()
+        .map(|divergence| (divergence.key.as_str(), &divergence.kind))
+        .collect();
+    divergences.sort_by_key(|(key, _)| *key);
+    assert_eq!(divergences.len(), 3);
+    assert!(matches!(
+        divergences[0],
+        ("changed", DivergenceKind::Value { .. })
+    ));
+    assert!(matches!(
+        divergences[1],
+        ("missing", DivergenceKind::Missing)
+    ));
+    assert!(matches!(
+        divergences[2],
+        ("ttl", DivergenceKind::Expiration { actual: None, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_verify_samples_keys() {
+    let src = InMemoryStore::new();
+    for i in 0..200 {
+        src.set(&format!("key:{i}"), json!(i), None).await.unwrap();
+    }
+
+    let report = verify(&src, &src, 0.25).await.unwrap();
+    assert!(report.is_consistent());
+    assert!(report.checked > 20 && report.checked < 100);
+    assert_eq!(
+        verify(&src, &src, 0.25).await.unwrap().checked,
+        report.checked
+    );
+
+    for sample_rate in [0.0, 1.5, f64::NAN] {
+        assert!(matches!(
+            verify(&src, &src, sample_rate).await,
+            Err(StoreError::InvalidConfiguration { .. })
+        ));
+    }
+}